@@ -0,0 +1,170 @@
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::source::snippet;
+use rustc_hir::hir_id::ItemLocalId;
+use rustc_hir::{BorrowKind, Expr, ExprKind, Local, PatKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::middle::region::{Scope, ScopeData, ScopeTree};
+use rustc_middle::ty::adjustment::Adjust;
+use rustc_middle::ty::subst::GenericArgKind;
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `let` bindings which borrow from a temporary value, either
+    /// relying on temporary lifetime extension through a field or index
+    /// projection (`let x = &make().field;`), or borrowing from a temporary
+    /// which is dropped at the end of the `let` statement
+    /// (`let it = vec![1, 2].iter();`).
+    ///
+    /// ### Why is this bad?
+    /// Temporary lifetime extension only applies to a few syntactic forms. The
+    /// owner of the borrowed value is invisible in the code, and seemingly
+    /// harmless refactorings like `let x = make().field.as_ref();` make the
+    /// temporary be dropped at the end of the statement instead, which then
+    /// fails to compile or leaves a binding which can't be used at all.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # struct Config { name: String }
+    /// # fn load() -> Config { Config { name: String::new() } }
+    /// let name = &load().name;
+    /// # let _ = name;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// # struct Config { name: String }
+    /// # fn load() -> Config { Config { name: String::new() } }
+    /// let config = load();
+    /// let name = &config.name;
+    /// # let _ = name;
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub BORROWED_TEMPORARY,
+    pedantic,
+    "`let` binding borrowing from a temporary value"
+}
+
+declare_lint_pass!(BorrowedTemporary => [BORROWED_TEMPORARY]);
+
+impl<'tcx> LateLintPass<'tcx> for BorrowedTemporary {
+    fn check_local(&mut self, cx: &LateContext<'tcx>, local: &'tcx Local<'tcx>) {
+        if in_external_macro(cx.sess(), local.span)
+            || local.span.from_expansion()
+            || matches!(local.pat.kind, PatKind::Wild)
+        {
+            return;
+        }
+        let Some(init) = local.init else { return };
+
+        let owner = cx.tcx.hir().enclosing_body_owner(local.hir_id);
+        let scope_tree = cx.tcx.region_scope_tree(owner.to_def_id());
+
+        match init.kind {
+            // `let x = &make().field;`
+            ExprKind::AddrOf(BorrowKind::Ref, _, place) => {
+                let temporary = peel_projections(place);
+                if temporary.hir_id != place.hir_id
+                    && is_temporary_value(cx, temporary)
+                    && is_extended(cx, scope_tree, temporary.hir_id.local_id)
+                {
+                    lint(
+                        cx,
+                        init,
+                        temporary,
+                        "this borrows from a temporary whose lifetime is implicitly extended",
+                        "the temporary is dropped at the end of the enclosing block, \
+                        but refactoring this expression can make it be dropped right after the `let`",
+                    );
+                }
+            },
+            // `let it = vec![1, 2].iter();`
+            ExprKind::MethodCall(_, receiver, ..) => {
+                let temporary = peel_projections(receiver);
+                if is_temporary_value(cx, temporary)
+                    && !is_extended(cx, scope_tree, temporary.hir_id.local_id)
+                    && is_autoref(cx, receiver)
+                    && returns_borrow(cx, init)
+                {
+                    lint(
+                        cx,
+                        init,
+                        temporary,
+                        "this binding borrows from a temporary which is dropped at the end of the statement",
+                        "the temporary is dropped at the end of the `let` statement, \
+                        so the binding can't be used afterwards",
+                    );
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+fn lint(cx: &LateContext<'_>, init: &Expr<'_>, temporary: &Expr<'_>, msg: &str, note: &str) {
+    let temporary_span = temporary.span.source_callsite();
+    span_lint_and_then(cx, BORROWED_TEMPORARY, init.span, msg, |diag| {
+        diag.span_note(temporary_span, note);
+        diag.help(format!(
+            "bind the owner to a variable first: `let owner = {};`",
+            snippet(cx, temporary_span, "..")
+        ));
+    });
+}
+
+/// Peels field accesses and indexing from a place expression.
+fn peel_projections<'a, 'tcx>(mut expr: &'a Expr<'tcx>) -> &'a Expr<'tcx> {
+    while let ExprKind::Field(base, _) | ExprKind::Index(base, _) = expr.kind {
+        expr = base;
+    }
+    expr
+}
+
+/// Whether the expression produces an owned value which is stored in a temporary when borrowed.
+/// Other kinds of expressions may instead be places, or be promoted to constants.
+fn is_temporary_value(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    matches!(expr.kind, ExprKind::Call(..) | ExprKind::MethodCall(..)) && !cx.typeck_results().expr_ty(expr).is_ref()
+}
+
+/// Whether the temporary created for `expr_id` lives longer than it would without temporary
+/// lifetime extension.
+fn is_extended(cx: &LateContext<'_>, scope_tree: &ScopeTree, expr_id: ItemLocalId) -> bool {
+    let temporary_scope = cx.typeck_results().rvalue_scopes.temporary_scope(scope_tree, expr_id);
+    temporary_scope != enclosing_terminating_scope(scope_tree, expr_id)
+}
+
+/// The scope at the end of which a temporary created for `expr_id` is dropped without lifetime
+/// extension, i.e. the innermost scope enclosing it which is followed by a destruction scope.
+fn enclosing_terminating_scope(scope_tree: &ScopeTree, expr_id: ItemLocalId) -> Option<Scope> {
+    let mut scope = Scope {
+        id: expr_id,
+        data: ScopeData::Node,
+    };
+    while let Some(&(parent, _)) = scope_tree.parent_map.get(&scope) {
+        if parent.data == ScopeData::Destruction {
+            return Some(scope);
+        }
+        scope = parent;
+    }
+    None
+}
+
+fn is_autoref(cx: &LateContext<'_>, receiver: &Expr<'_>) -> bool {
+    cx.typeck_results()
+        .expr_adjustments(receiver)
+        .iter()
+        .any(|adjustment| matches!(adjustment.kind, Adjust::Borrow(_)))
+}
+
+/// Whether the method called by `expr` returns a type which can hold a non-`'static` borrow.
+fn returns_borrow(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    let Some(def_id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) else { return false };
+    cx.tcx
+        .fn_sig(def_id)
+        .subst_identity()
+        .skip_binder()
+        .output()
+        .walk()
+        .any(|arg| matches!(arg.unpack(), GenericArgKind::Lifetime(region) if !region.is_static()))
+}
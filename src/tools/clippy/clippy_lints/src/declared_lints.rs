@@ -60,6 +60,7 @@
     crate::booleans::NONMINIMAL_BOOL_INFO,
     crate::booleans::OVERLY_COMPLEX_BOOL_EXPR_INFO,
     crate::borrow_deref_ref::BORROW_DEREF_REF_INFO,
+    crate::borrowed_temporary::BORROWED_TEMPORARY_INFO,
     crate::box_default::BOX_DEFAULT_INFO,
     crate::cargo::CARGO_COMMON_METADATA_INFO,
     crate::cargo::MULTIPLE_CRATE_VERSIONS_INFO,
@@ -115,6 +116,7 @@
     crate::derive::DERIVE_ORD_XOR_PARTIAL_ORD_INFO,
     crate::derive::DERIVE_PARTIAL_EQ_WITHOUT_EQ_INFO,
     crate::derive::EXPL_IMPL_CLONE_ON_COPY_INFO,
+    crate::derive::HASH_EQ_WITH_UNSTABLE_FIELDS_INFO,
     crate::derive::UNSAFE_DERIVE_DESERIALIZE_INFO,
     crate::disallowed_macros::DISALLOWED_MACROS_INFO,
     crate::disallowed_methods::DISALLOWED_METHODS_INFO,
@@ -155,9 +157,13 @@
     crate::exhaustive_items::EXHAUSTIVE_ENUMS_INFO,
     crate::exhaustive_items::EXHAUSTIVE_STRUCTS_INFO,
     crate::exit::EXIT_INFO,
+    crate::expensive_log_arguments::EXPENSIVE_LOG_ARGUMENTS_INFO,
     crate::explicit_write::EXPLICIT_WRITE_INFO,
     crate::extra_unused_type_parameters::EXTRA_UNUSED_TYPE_PARAMETERS_INFO,
     crate::fallible_impl_from::FALLIBLE_IMPL_FROM_INFO,
+    crate::ffi::EXTERN_TYPE_WITHOUT_REPR_C_INFO,
+    crate::ffi::FFI_UNSAFE_EXTERN_TYPES_INFO,
+    crate::ffi::REPR_C_FIELD_PADDING_INFO,
     crate::float_literal::EXCESSIVE_PRECISION_INFO,
     crate::float_literal::LOSSY_FLOAT_LITERAL_INFO,
     crate::floating_point_arithmetic::IMPRECISE_FLOPS_INFO,
@@ -267,6 +273,9 @@
     crate::manual_strip::MANUAL_STRIP_INFO,
     crate::map_unit_fn::OPTION_MAP_UNIT_FN_INFO,
     crate::map_unit_fn::RESULT_MAP_UNIT_FN_INFO,
+    crate::match_ergonomics::EXPLICIT_BINDING_DEREF_INFO,
+    crate::match_ergonomics::NEEDLESS_REFERENCE_PATTERN_INFO,
+    crate::match_ergonomics::NEEDLESS_REF_BINDING_INFO,
     crate::match_result_ok::MATCH_RESULT_OK_INFO,
     crate::matches::COLLAPSIBLE_MATCH_INFO,
     crate::matches::INFALLIBLE_DESTRUCTURING_MATCH_INFO,
@@ -433,6 +442,7 @@
     crate::mutable_debug_assertion::DEBUG_ASSERT_WITH_MUT_CALL_INFO,
     crate::mutex_atomic::MUTEX_ATOMIC_INFO,
     crate::mutex_atomic::MUTEX_INTEGER_INFO,
+    crate::narrowing_return_cast::NARROWING_RETURN_CAST_INFO,
     crate::needless_arbitrary_self_type::NEEDLESS_ARBITRARY_SELF_TYPE_INFO,
     crate::needless_bool::BOOL_COMPARISON_INFO,
     crate::needless_bool::NEEDLESS_BOOL_INFO,
@@ -544,12 +554,15 @@
     crate::significant_drop_tightening::SIGNIFICANT_DROP_TIGHTENING_INFO,
     crate::single_char_lifetime_names::SINGLE_CHAR_LIFETIME_NAMES_INFO,
     crate::single_component_path_imports::SINGLE_COMPONENT_PATH_IMPORTS_INFO,
+    crate::size_hint::INCONSISTENT_SIZE_HINT_INFO,
+    crate::size_hint::MISSING_EXACT_SIZE_ITERATOR_INFO,
     crate::size_of_in_element_count::SIZE_OF_IN_ELEMENT_COUNT_INFO,
     crate::size_of_ref::SIZE_OF_REF_INFO,
     crate::slow_vector_initialization::SLOW_VECTOR_INITIALIZATION_INFO,
     crate::std_instead_of_core::ALLOC_INSTEAD_OF_CORE_INFO,
     crate::std_instead_of_core::STD_INSTEAD_OF_ALLOC_INFO,
     crate::std_instead_of_core::STD_INSTEAD_OF_CORE_INFO,
+    crate::string_push_sequence::STRING_PUSH_SEQUENCE_INFO,
     crate::strings::STRING_ADD_INFO,
     crate::strings::STRING_ADD_ASSIGN_INFO,
     crate::strings::STRING_FROM_UTF8_AS_BYTES_INFO,
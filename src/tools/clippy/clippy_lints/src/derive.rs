@@ -1,26 +1,30 @@
 use clippy_utils::diagnostics::{span_lint_and_help, span_lint_and_note, span_lint_and_sugg, span_lint_and_then};
 use clippy_utils::paths;
 use clippy_utils::ty::{implements_trait, implements_trait_with_env, is_copy};
-use clippy_utils::{is_lint_allowed, match_def_path};
+use clippy_utils::visitors::for_each_expr;
+use clippy_utils::{get_parent_expr, is_lint_allowed, match_def_path, path_to_local};
+use core::ops::ControlFlow;
 use if_chain::if_chain;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::Applicability;
+use rustc_hir::def::Res;
 use rustc_hir::def_id::DefId;
-use rustc_hir::intravisit::{walk_expr, walk_fn, walk_item, FnKind, Visitor};
+use rustc_hir::intravisit::{walk_expr, walk_fn, walk_item, walk_pat, FnKind, Visitor};
 use rustc_hir::{
-    self as hir, BlockCheckMode, BodyId, Constness, Expr, ExprKind, FnDecl, Impl, Item, ItemKind, UnsafeSource,
-    Unsafety,
+    self as hir, BlockCheckMode, BodyId, Constness, Expr, ExprKind, FnDecl, HirId, Impl, Item, ItemKind, Pat, PatKind,
+    QPath, UnsafeSource, Unsafety,
 };
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::hir::nested_filter;
 use rustc_middle::traits::Reveal;
 use rustc_middle::ty::{
-    self, Binder, BoundConstness, Clause, GenericArgKind, GenericParamDefKind, ImplPolarity, ParamEnv, PredicateKind,
-    TraitPredicate, Ty, TyCtxt,
+    self, AdtDef, Binder, BoundConstness, Clause, GenericArgKind, GenericParamDefKind, ImplPolarity, ParamEnv,
+    PredicateKind, TraitPredicate, Ty, TyCtxt, TypeckResults, VariantDef,
 };
 use rustc_session::{declare_lint_pass, declare_tool_lint};
 use rustc_span::def_id::LocalDefId;
 use rustc_span::source_map::Span;
-use rustc_span::sym;
+use rustc_span::{sym, Symbol};
 
 declare_clippy_lint! {
     /// ### What it does
@@ -196,12 +200,76 @@
     "deriving `PartialEq` on a type that can implement `Eq`, without implementing `Eq`"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `Hash` implementations of structs and enums which hash fields with interior
+    /// mutability, and for `Eq` implementations of hashable types over a `PartialEq` which
+    /// compares floating-point fields.
+    ///
+    /// ### Why is this bad?
+    /// Hash maps and sets rely on the hash and equality of their keys staying the same while
+    /// they are stored. A field behind a `Cell` or `RefCell` can be changed through a shared
+    /// reference, silently changing the hash of a stored key. Floating-point values are not
+    /// reflexive, since `NaN != NaN`, so a key containing `NaN` can never be found again.
+    ///
+    /// ### Known problems
+    /// Fields which a manual `Hash` implementation reads explicitly, like through `Cell::get`,
+    /// are assumed to be hashed on purpose and are not linted.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # use std::hash::{Hash, Hasher};
+    /// #[derive(PartialEq)]
+    /// struct Point {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// impl Eq for Point {}
+    ///
+    /// impl Hash for Point {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.x.to_bits().hash(state);
+    ///         self.y.to_bits().hash(state);
+    ///     }
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```rust
+    /// # use std::hash::{Hash, Hasher};
+    /// struct Point {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// impl PartialEq for Point {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.x.to_bits() == other.x.to_bits() && self.y.to_bits() == other.y.to_bits()
+    ///     }
+    /// }
+    ///
+    /// impl Eq for Point {}
+    ///
+    /// impl Hash for Point {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.x.to_bits().hash(state);
+    ///         self.y.to_bits().hash(state);
+    ///     }
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub HASH_EQ_WITH_UNSTABLE_FIELDS,
+    correctness,
+    "`Hash` or `Eq` implementations relying on interior-mutable or floating-point fields"
+}
+
 declare_lint_pass!(Derive => [
     EXPL_IMPL_CLONE_ON_COPY,
     DERIVED_HASH_WITH_MANUAL_EQ,
     DERIVE_ORD_XOR_PARTIAL_ORD,
     UNSAFE_DERIVE_DESERIALIZE,
-    DERIVE_PARTIAL_EQ_WITHOUT_EQ
+    DERIVE_PARTIAL_EQ_WITHOUT_EQ,
+    HASH_EQ_WITH_UNSTABLE_FIELDS
 ]);
 
 impl<'tcx> LateLintPass<'tcx> for Derive {
@@ -216,6 +284,7 @@ fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'_>) {
 
             check_hash_peq(cx, item.span, trait_ref, ty, is_automatically_derived);
             check_ord_partial_ord(cx, item.span, trait_ref, ty, is_automatically_derived);
+            check_unstable_fields(cx, item, trait_ref, ty, is_automatically_derived);
 
             if is_automatically_derived {
                 check_unsafe_derive_deserialize(cx, item, trait_ref, ty);
@@ -274,6 +343,228 @@ fn check_hash_peq<'tcx>(
     }
 }
 
+/// Implementation of the `HASH_EQ_WITH_UNSTABLE_FIELDS` lint.
+fn check_unstable_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    item: &Item<'_>,
+    trait_ref: &hir::TraitRef<'_>,
+    ty: Ty<'tcx>,
+    is_automatically_derived: bool,
+) {
+    let Some(trait_def_id) = trait_ref.trait_def_id() else { return };
+    let ty::Adt(adt, substs) = *ty.kind() else { return };
+    if adt.is_union() {
+        return;
+    }
+
+    if cx.tcx.is_diagnostic_item(sym::Hash, trait_def_id) {
+        // A derived `Hash` hashes every field, a manual one only the fields it uses. Fields read
+        // explicitly, like through `Cell::get`, are assumed to be hashed on purpose.
+        let hashed = if is_automatically_derived {
+            None
+        } else if let Some(hashed) = used_fields(cx, item.owner_id.to_def_id(), sym::hash, ty, |e| {
+            !is_read_explicitly(cx, e)
+        }) {
+            Some(hashed)
+        } else {
+            return;
+        };
+        let unstable: Vec<_> = adt
+            .all_fields()
+            .filter(|field| hashed.as_ref().map_or(true, |hashed| hashed.contains(&field.did)))
+            .filter(|field| is_unfrozen(cx, field.ty(cx.tcx, substs)))
+            .collect();
+        if unstable.is_empty() {
+            return;
+        }
+        span_lint_and_then(
+            cx,
+            HASH_EQ_WITH_UNSTABLE_FIELDS,
+            item.span,
+            "this `Hash` implementation hashes fields with interior mutability",
+            |diag| {
+                for field in unstable {
+                    diag.span_note(
+                        cx.tcx.def_span(field.did),
+                        format!(
+                            "`{}` can be changed through a shared reference, changing the hash of a stored key",
+                            field.name
+                        ),
+                    );
+                }
+                diag.help("leave these fields out of both the `Hash` and the `PartialEq` implementation");
+            },
+        );
+    } else if cx.tcx.is_diagnostic_item(sym::Eq, trait_def_id) {
+        // Only keys of hash maps and sets have to be equal to themselves.
+        let Some(hash_trait_def_id) = cx.tcx.get_diagnostic_item(sym::Hash) else { return };
+        if !implements_trait(cx, ty, hash_trait_def_id, &[]) {
+            return;
+        }
+        let Some(peq_trait_def_id) = cx.tcx.lang_items().eq_trait() else { return };
+        cx.tcx.for_each_relevant_impl(peq_trait_def_id, ty, |impl_id| {
+            let peq_trait_ref = cx.tcx.impl_trait_ref(impl_id).expect("must be a trait implementation");
+            if peq_trait_ref.subst_identity().substs.type_at(1) != ty {
+                return;
+            }
+            // A derived `PartialEq` compares every field, a manual one only the fields it uses.
+            // Fields compared through `to_bits` or `total_cmp` are fine.
+            let compared = if cx.tcx.has_attr(impl_id, sym::automatically_derived) {
+                None
+            } else if let Some(compared) = used_fields(cx, impl_id, sym::eq, ty, |e| !is_compared_totally(cx, e)) {
+                Some(compared)
+            } else {
+                return;
+            };
+            let floats: Vec<_> = adt
+                .all_fields()
+                .filter(|field| compared.as_ref().map_or(true, |compared| compared.contains(&field.did)))
+                .filter(|field| field.ty(cx.tcx, substs).is_floating_point())
+                .collect();
+            if floats.is_empty() {
+                return;
+            }
+            span_lint_and_then(
+                cx,
+                HASH_EQ_WITH_UNSTABLE_FIELDS,
+                item.span,
+                "this `Eq` implementation relies on the `PartialEq` of floating-point fields",
+                |diag| {
+                    for field in floats {
+                        diag.span_note(
+                            cx.tcx.def_span(field.did),
+                            format!("`{}` is not equal to itself when it is `NaN`", field.name),
+                        );
+                    }
+                    diag.help(
+                        "compare and hash the fields' `to_bits()` instead, or use a wrapper with total equality",
+                    );
+                },
+            );
+        });
+    }
+}
+
+fn is_unfrozen<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    // `is_freeze` reports generic types as `!Freeze`, so only look at types with a known layout.
+    cx.tcx.layout_of(cx.param_env.and(ty)).is_ok() && !ty.is_freeze(cx.tcx, cx.param_env)
+}
+
+/// Whether `e` is the receiver of a method call other than `hash`, like `Cell::get`.
+fn is_read_explicitly(cx: &LateContext<'_>, e: &Expr<'_>) -> bool {
+    matches!(
+        get_parent_expr(cx, e),
+        Some(Expr { kind: ExprKind::MethodCall(segment, receiver, ..), .. })
+            if receiver.hir_id == e.hir_id && segment.ident.name != sym::hash
+    )
+}
+
+/// Whether `e` is the receiver of `to_bits` or `total_cmp`.
+fn is_compared_totally(cx: &LateContext<'_>, e: &Expr<'_>) -> bool {
+    matches!(
+        get_parent_expr(cx, e),
+        Some(Expr { kind: ExprKind::MethodCall(segment, receiver, ..), .. })
+            if receiver.hir_id == e.hir_id && matches!(segment.ident.as_str(), "to_bits" | "total_cmp")
+    )
+}
+
+/// Returns the fields of `self_ty` used in the method `method` of the local impl `impl_id`, either
+/// through a field access or through a binding of a pattern, skipping the uses for which `filter`
+/// returns `false`.
+fn used_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    impl_id: DefId,
+    method: Symbol,
+    self_ty: Ty<'tcx>,
+    filter: impl Fn(&Expr<'_>) -> bool,
+) -> Option<FxHashSet<DefId>> {
+    let ty::Adt(adt, _) = *self_ty.kind() else { return None };
+    let method = cx.tcx.associated_items(impl_id).filter_by_name_unhygienic(method).next()?;
+    let body_id = cx.tcx.hir().maybe_body_owned_by(method.def_id.as_local()?)?;
+    let typeck = cx.tcx.typeck_body(body_id);
+    let body = cx.tcx.hir().body(body_id);
+
+    let mut visitor = FieldBindings {
+        typeck,
+        adt,
+        self_ty,
+        bindings: FxHashMap::default(),
+    };
+    visitor.visit_body(body);
+    let bindings = visitor.bindings;
+
+    let mut fields = FxHashSet::default();
+    for_each_expr(body.value, |e| {
+        let field = match e.kind {
+            ExprKind::Field(receiver, _)
+                if adt.is_struct() && typeck.expr_ty_adjusted(receiver).peel_refs() == self_ty =>
+            {
+                Some(adt.non_enum_variant().fields[typeck.field_index(e.hir_id)].did)
+            },
+            _ => path_to_local(e).and_then(|id| bindings.get(&id).copied()),
+        };
+        if let Some(field) = field
+            && filter(e)
+        {
+            fields.insert(field);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    Some(fields)
+}
+
+/// Collects the bindings of the fields of `self_ty` in struct and tuple struct patterns.
+struct FieldBindings<'a, 'tcx> {
+    typeck: &'a TypeckResults<'tcx>,
+    adt: AdtDef<'tcx>,
+    self_ty: Ty<'tcx>,
+    bindings: FxHashMap<HirId, DefId>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FieldBindings<'a, 'tcx> {
+    fn visit_pat(&mut self, pat: &'tcx Pat<'tcx>) {
+        if self.typeck.pat_ty(pat).peel_refs() == self.self_ty {
+            match pat.kind {
+                PatKind::Struct(ref qpath, fields, _) => {
+                    if let Some(variant) = self.variant(qpath, pat.hir_id) {
+                        for field in fields {
+                            if let PatKind::Binding(_, id, ..) = field.pat.kind {
+                                let index = self.typeck.field_index(field.hir_id);
+                                self.bindings.insert(id, variant.fields[index].did);
+                            }
+                        }
+                    }
+                },
+                PatKind::TupleStruct(ref qpath, pats, dotdot) => {
+                    if let Some(variant) = self.variant(qpath, pat.hir_id) {
+                        for (i, sub_pat) in pats.iter().enumerate() {
+                            // The patterns after `..` match the last fields.
+                            let index = match dotdot.as_opt_usize() {
+                                Some(pos) if i >= pos => i + variant.fields.len() - pats.len(),
+                                _ => i,
+                            };
+                            if let PatKind::Binding(_, id, ..) = sub_pat.kind {
+                                self.bindings.insert(id, variant.fields[index].did);
+                            }
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+        walk_pat(self, pat);
+    }
+}
+
+impl<'a, 'tcx> FieldBindings<'a, 'tcx> {
+    fn variant(&self, qpath: &QPath<'_>, id: HirId) -> Option<&'tcx VariantDef> {
+        match self.typeck.qpath_res(qpath, id) {
+            Res::Err => None,
+            res => Some(self.adt.variant_of_res(res)),
+        }
+    }
+}
+
 /// Implementation of the `DERIVE_ORD_XOR_PARTIAL_ORD` lint.
 fn check_ord_partial_ord<'tcx>(
     cx: &LateContext<'tcx>,
@@ -0,0 +1,191 @@
+use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::macros::{macro_backtrace, root_macro_call_first_node, FormatArgsExpn};
+use clippy_utils::visitors::{for_each_expr, Descend};
+use clippy_utils::{def_path_def_ids, is_trait_method, path_res, peel_hir_expr_refs};
+use core::ops::ControlFlow;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_hir::{Expr, ExprKind, Node};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::hygiene::ExpnId;
+use rustc_span::sym;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for arguments of logging macros which are formatted into a `String` with
+    /// `format!` or `to_string`, and for function calls or assignments in the arguments of
+    /// logging macros which evaluate them before checking whether the level is enabled.
+    ///
+    /// The checked macros can be configured with `log-macros`, by default they are the level
+    /// macros of the `log` and `tracing` crates.
+    ///
+    /// ### Why is this bad?
+    /// The logging macro formats its arguments itself, so the `String` is allocated only to be
+    /// formatted again. Logging macros which evaluate their arguments eagerly run the calls and
+    /// side effects in them even when the log level is disabled.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// debug!("{}", format!("{:?}", state));
+    /// debug!("id: {}", id.to_string());
+    /// // `record!` evaluates its arguments eagerly
+    /// record!("stats: {}", cache.stats());
+    /// ```
+    /// Use instead:
+    /// ```rust,ignore
+    /// debug!("{:?}", state);
+    /// debug!("id: {}", id);
+    /// if record_enabled!() {
+    ///     record!("stats: {}", cache.stats());
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub EXPENSIVE_LOG_ARGUMENTS,
+    pedantic,
+    "logging macro arguments which are formatted into a `String` or evaluated when the level is disabled"
+}
+
+pub struct ExpensiveLogArguments {
+    log_macros: Vec<String>,
+    log_macro_def_ids: FxHashSet<DefId>,
+}
+
+impl ExpensiveLogArguments {
+    pub fn new(log_macros: Vec<String>) -> Self {
+        Self {
+            log_macros,
+            log_macro_def_ids: FxHashSet::default(),
+        }
+    }
+}
+
+impl_lint_pass!(ExpensiveLogArguments => [EXPENSIVE_LOG_ARGUMENTS]);
+
+impl<'tcx> LateLintPass<'tcx> for ExpensiveLogArguments {
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        self.log_macro_def_ids.clear();
+        for path in &self.log_macros {
+            let segments: Vec<&str> = path.split("::").collect();
+            self.log_macro_def_ids.extend(def_path_def_ids(cx, &segments));
+        }
+    }
+
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        let Some(macro_call) = root_macro_call_first_node(cx, expr) else { return };
+        if !self.log_macro_def_ids.contains(&macro_call.def_id) {
+            return;
+        }
+        let Some((format_args_expr, format_args)) = find_format_args(cx, expr, macro_call.expn) else { return };
+        let eager = !is_conditional(cx, format_args_expr, expr);
+
+        let call_site = macro_call.span.ctxt();
+        let mut seen = FxHashSet::default();
+        let params = format_args
+            .args
+            .iter()
+            .flat_map(|arg| [Some(arg.param), arg.format.precision.param(), arg.format.width.param()])
+            .flatten();
+        for param in params {
+            if !seen.insert(param.value.hir_id) {
+                continue;
+            }
+            let (value, _) = peel_hir_expr_refs(param.value);
+            if let Some(inner_call) = macro_backtrace(value.span).next()
+                && inner_call.span.ctxt() == call_site
+                && cx.tcx.is_diagnostic_item(sym::format_macro, inner_call.def_id)
+            {
+                span_lint_and_help(
+                    cx,
+                    EXPENSIVE_LOG_ARGUMENTS,
+                    inner_call.span,
+                    "`format!` in the arguments of a logging macro",
+                    None,
+                    "pass the format string and its arguments to the logging macro directly",
+                );
+            } else if value.span.ctxt() == call_site
+                && let ExprKind::MethodCall(path, ..) = value.kind
+                && path.ident.name == sym::to_string
+                && is_trait_method(cx, value, sym::ToString)
+            {
+                span_lint_and_help(
+                    cx,
+                    EXPENSIVE_LOG_ARGUMENTS,
+                    value.span,
+                    "`to_string` in the arguments of a logging macro",
+                    None,
+                    "pass the value to the logging macro directly, it formats it with `Display`",
+                );
+            } else if eager
+                && value.span.ctxt() == call_site
+                && find_side_effect(cx, value).is_some()
+            {
+                let name = cx.tcx.item_name(macro_call.def_id);
+                span_lint_and_help(
+                    cx,
+                    EXPENSIVE_LOG_ARGUMENTS,
+                    value.span,
+                    &format!("`{name}!` evaluates this argument even when its level is disabled"),
+                    None,
+                    "guard the logging call with a check of the level, e.g. `log_enabled!`, \
+                    or pass a value which is only formatted when the message is logged",
+                );
+            }
+        }
+    }
+}
+
+/// Finds the `format_args!` call in the expansion `expn` of a logging macro, which usually calls
+/// other macros of its crate to build it.
+fn find_format_args<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    expn: ExpnId,
+) -> Option<(&'tcx Expr<'tcx>, FormatArgsExpn<'tcx>)> {
+    for_each_expr(expr, |e| {
+        if !e.span.ctxt().outer_expn().is_descendant_of(expn) {
+            ControlFlow::Continue(Descend::No)
+        } else if let Some(args) = FormatArgsExpn::parse(cx, e) {
+            ControlFlow::Break((e, args))
+        } else {
+            ControlFlow::Continue(Descend::Yes)
+        }
+    })
+}
+
+/// Checks whether the expansion of the logging macro `root` only evaluates `format_args` in a
+/// branch of an `if` or `match`, usually after checking the level.
+fn is_conditional(cx: &LateContext<'_>, format_args: &Expr<'_>, root: &Expr<'_>) -> bool {
+    let mut child = format_args.hir_id;
+    for (id, node) in cx.tcx.hir().parent_iter(format_args.hir_id) {
+        match node {
+            Node::Expr(Expr {
+                kind: ExprKind::If(cond, ..),
+                ..
+            }) if cond.hir_id != child => return true,
+            Node::Arm(arm) if arm.body.hir_id == child => return true,
+            _ => {},
+        }
+        if id == root.hir_id {
+            break;
+        }
+        child = id;
+    }
+    false
+}
+
+/// Finds a function call or an assignment in a logging macro argument.
+fn find_side_effect<'tcx>(cx: &LateContext<'tcx>, arg: &'tcx Expr<'tcx>) -> Option<&'tcx Expr<'tcx>> {
+    for_each_expr(arg, |e| match e.kind {
+        // Constructing a tuple struct or variant is cheap.
+        ExprKind::Call(func, _) if matches!(path_res(cx, func), Res::Def(DefKind::Ctor(..), _)) => {
+            ControlFlow::Continue(Descend::Yes)
+        },
+        ExprKind::Call(..) | ExprKind::MethodCall(..) | ExprKind::Assign(..) | ExprKind::AssignOp(..) => {
+            ControlFlow::Break(e)
+        },
+        ExprKind::Closure(..) => ControlFlow::Continue(Descend::No),
+        _ => ControlFlow::Continue(Descend::Yes),
+    })
+}
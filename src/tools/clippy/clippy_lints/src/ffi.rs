@@ -0,0 +1,285 @@
+use clippy_utils::diagnostics::{span_lint_and_help, span_lint_and_note, span_lint_and_then};
+use clippy_utils::ty::is_type_diagnostic_item;
+use itertools::Itertools;
+use rustc_hir::{FnDecl, FnRetTy, ForeignItemKind, Item, ItemKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+use rustc_span::def_id::LocalDefId;
+use rustc_span::{sym, Span};
+use rustc_target::spec::abi::Abi;
+use std::cmp::Reverse;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `String`, `Vec` and pointers to dynamically sized types like `&str`
+    /// or `&[T]` in the signatures of functions with a foreign ABI, both in `extern`
+    /// blocks and in `extern "C" fn` definitions.
+    ///
+    /// ### Why is this bad?
+    /// These types have no stable layout. Pointers to dynamically sized types are
+    /// twice the size of a thin pointer, so the foreign side cannot make sense of them.
+    ///
+    /// ### Known problems
+    /// This mostly overlaps with rustc's `improper_ctypes` and `improper_ctypes_definitions`
+    /// lints, which are enabled by default. It is meant for crates that allow those lints
+    /// because of their false positives, but still want these common mistakes to be caught.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// extern "C" {
+    ///     fn puts(s: &str) -> i32;
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// extern "C" {
+    ///     fn puts(s: *const std::ffi::c_char) -> i32;
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub FFI_UNSAFE_EXTERN_TYPES,
+    pedantic,
+    "types without a stable ABI in the signature of a foreign function"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for structs, enums and unions of the current crate which are passed to or
+    /// returned from a function with a foreign ABI, but lack a `#[repr(C)]` attribute.
+    ///
+    /// ### Why is this bad?
+    /// The layout of a type with the default representation is unspecified and may
+    /// change between compiler versions, so it can't be shared with foreign code.
+    ///
+    /// ### Known problems
+    /// rustc's `improper_ctypes` lint already catches such types in `extern` blocks. This
+    /// lint also checks pointers to them in `extern "C" fn` definitions, which rustc
+    /// considers FFI-safe.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// extern "C" {
+    ///     fn draw(p: *const Point);
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[repr(C)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// extern "C" {
+    ///     fn draw(p: *const Point);
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub EXTERN_TYPE_WITHOUT_REPR_C,
+    pedantic,
+    "type without `#[repr(C)]` in the signature of a foreign function"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `#[repr(C)]` structs whose fields are ordered such that the struct
+    /// contains more padding than necessary.
+    ///
+    /// ### Why is this bad?
+    /// The compiler lays out `#[repr(C)]` structs in declaration order, so every field
+    /// followed by a field with a larger alignment wastes space. Sorting the fields by
+    /// decreasing alignment makes the struct smaller.
+    ///
+    /// ### Known problems
+    /// The field order of a struct shared with foreign code is usually dictated by the
+    /// foreign side and can't be changed.
+    ///
+    /// ### Example
+    /// ```rust
+    /// #[repr(C)]
+    /// struct Header {
+    ///     tag: u8,
+    ///     len: u64,
+    ///     flags: u8,
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// #[repr(C)]
+    /// struct Header {
+    ///     len: u64,
+    ///     tag: u8,
+    ///     flags: u8,
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub REPR_C_FIELD_PADDING,
+    pedantic,
+    "`#[repr(C)]` struct whose field order creates avoidable padding"
+}
+
+declare_lint_pass!(Ffi => [FFI_UNSAFE_EXTERN_TYPES, EXTERN_TYPE_WITHOUT_REPR_C, REPR_C_FIELD_PADDING]);
+
+impl<'tcx> LateLintPass<'tcx> for Ffi {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'_>) {
+        if in_external_macro(cx.sess(), item.span) {
+            return;
+        }
+        match item.kind {
+            ItemKind::ForeignMod { abi, items } if !is_rust_abi(abi) => {
+                for foreign_item in items.iter().map(|it| cx.tcx.hir().foreign_item(it.id)) {
+                    if let ForeignItemKind::Fn(decl, ..) = foreign_item.kind {
+                        check_extern_signature(cx, decl, foreign_item.owner_id.def_id);
+                    }
+                }
+            },
+            ItemKind::Fn(sig, ..) if !is_rust_abi(sig.header.abi) => {
+                check_extern_signature(cx, sig.decl, item.owner_id.def_id);
+            },
+            ItemKind::Struct(..) => check_field_padding(cx, item),
+            _ => {},
+        }
+    }
+}
+
+fn is_rust_abi(abi: Abi) -> bool {
+    matches!(
+        abi,
+        Abi::Rust | Abi::RustCall | Abi::RustCold | Abi::RustIntrinsic | Abi::PlatformIntrinsic
+    )
+}
+
+fn check_extern_signature(cx: &LateContext<'_>, decl: &FnDecl<'_>, def_id: LocalDefId) {
+    let fn_sig = cx.tcx.erase_late_bound_regions(cx.tcx.fn_sig(def_id).subst_identity());
+    let output_span = match decl.output {
+        FnRetTy::Return(ty) => Some(ty.span),
+        FnRetTy::DefaultReturn(_) => None,
+    };
+    let inputs = decl
+        .inputs
+        .iter()
+        .map(|ty| Some(ty.span))
+        .zip(fn_sig.inputs().iter().copied());
+    for (span, ty) in inputs.chain([(output_span, fn_sig.output())]) {
+        if let Some(span) = span {
+            check_extern_type(cx, span, ty);
+        }
+    }
+}
+
+fn check_extern_type<'tcx>(cx: &LateContext<'tcx>, span: Span, ty: Ty<'tcx>) {
+    if is_type_diagnostic_item(cx, ty, sym::String) {
+        span_lint_and_help(
+            cx,
+            FFI_UNSAFE_EXTERN_TYPES,
+            span,
+            "`String` is not FFI-safe",
+            None,
+            "consider passing a `*const c_char` obtained from a `CString` instead",
+        );
+    } else if is_type_diagnostic_item(cx, ty, sym::Vec) {
+        span_lint_and_help(
+            cx,
+            FFI_UNSAFE_EXTERN_TYPES,
+            span,
+            "`Vec` is not FFI-safe",
+            None,
+            "consider passing a pointer to the elements and their number instead",
+        );
+    } else if let ty::Ref(_, pointee, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee, .. }) = *ty.kind() {
+        // Pointers to extern types are thin, like pointers to sized types.
+        if pointee.is_sized(cx.tcx, cx.param_env) || matches!(pointee.kind(), ty::Foreign(_)) {
+            check_repr_c(cx, span, pointee);
+        } else {
+            span_lint_and_help(
+                cx,
+                FFI_UNSAFE_EXTERN_TYPES,
+                span,
+                &format!("`{ty}` is a pointer to a dynamically sized type and is not FFI-safe"),
+                None,
+                "consider passing a thin pointer and a length instead",
+            );
+        }
+    } else {
+        check_repr_c(cx, span, ty);
+    }
+}
+
+/// Lints local ADTs behind any number of pointers which don't have a stable layout.
+fn check_repr_c<'tcx>(cx: &LateContext<'tcx>, span: Span, ty: Ty<'tcx>) {
+    let mut ty = ty;
+    while let ty::Ref(_, pointee, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee, .. }) = *ty.kind() {
+        ty = pointee;
+    }
+    let ty::Adt(adt, _) = ty.kind() else { return };
+    let repr = adt.repr();
+    let has_stable_layout = if adt.is_enum() {
+        repr.c() || repr.int.is_some()
+    } else {
+        repr.c() || repr.transparent()
+    };
+    if has_stable_layout || !adt.did().is_local() {
+        return;
+    }
+    span_lint_and_then(
+        cx,
+        EXTERN_TYPE_WITHOUT_REPR_C,
+        span,
+        &format!("`{ty}` is used in the signature of a foreign function but has no `#[repr(C)]` attribute"),
+        |diag| {
+            diag.span_help(cx.tcx.def_span(adt.did()), "consider adding `#[repr(C)]` to this type");
+        },
+    );
+}
+
+fn check_field_padding(cx: &LateContext<'_>, item: &Item<'_>) {
+    let ty = cx.tcx.type_of(item.owner_id).subst_identity();
+    let ty::Adt(adt, _) = ty.kind() else { return };
+    if !adt.repr().c() || adt.repr().packed() || !ty.is_sized(cx.tcx, cx.param_env) {
+        return;
+    }
+    let Ok(layout) = cx.layout_of(ty) else { return };
+
+    // The name, size and alignment of every field, sorted by decreasing alignment.
+    let mut fields = Vec::new();
+    for field in &adt.non_enum_variant().fields {
+        let Ok(field_layout) = cx.layout_of(cx.tcx.type_of(field.did).subst_identity()) else { return };
+        fields.push((field.name, field_layout.size.bytes(), field_layout.align.abi.bytes()));
+    }
+    fields.sort_by_key(|&(_, _, align)| Reverse(align));
+
+    let mut size = 0;
+    for &(_, field_size, field_align) in &fields {
+        size = align_up(size, field_align) + field_size;
+    }
+    let min_size = align_up(size, layout.align.abi.bytes());
+    if min_size < layout.size.bytes() {
+        let order = fields.iter().map(|(name, _, _)| format!("`{name}`")).join(", ");
+        span_lint_and_note(
+            cx,
+            REPR_C_FIELD_PADDING,
+            item.ident.span,
+            &format!(
+                "this `#[repr(C)]` struct takes {} bytes, but only needs {min_size} with its fields reordered",
+                layout.size.bytes()
+            ),
+            None,
+            &format!("ordering the fields as {order} avoids the extra padding"),
+        );
+    }
+}
+
+/// Rounds `offset` up to a multiple of `align`, which is a power of two.
+fn align_up(offset: u64, align: u64) -> u64 {
+    (offset + align - 1) & !(align - 1)
+}
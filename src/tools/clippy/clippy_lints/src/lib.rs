@@ -81,6 +81,7 @@
 mod bool_to_int_with_if;
 mod booleans;
 mod borrow_deref_ref;
+mod borrowed_temporary;
 mod box_default;
 mod cargo;
 mod casts;
@@ -122,9 +123,11 @@
 mod excessive_bools;
 mod exhaustive_items;
 mod exit;
+mod expensive_log_arguments;
 mod explicit_write;
 mod extra_unused_type_parameters;
 mod fallible_impl_from;
+mod ffi;
 mod float_literal;
 mod floating_point_arithmetic;
 mod fn_null_check;
@@ -183,6 +186,7 @@
 mod manual_string_new;
 mod manual_strip;
 mod map_unit_fn;
+mod match_ergonomics;
 mod match_result_ok;
 mod matches;
 mod mem_forget;
@@ -206,6 +210,7 @@
 mod mut_reference;
 mod mutable_debug_assertion;
 mod mutex_atomic;
+mod narrowing_return_cast;
 mod needless_arbitrary_self_type;
 mod needless_bool;
 mod needless_borrowed_ref;
@@ -270,10 +275,12 @@
 mod significant_drop_tightening;
 mod single_char_lifetime_names;
 mod single_component_path_imports;
+mod size_hint;
 mod size_of_in_element_count;
 mod size_of_ref;
 mod slow_vector_initialization;
 mod std_instead_of_core;
+mod string_push_sequence;
 mod strings;
 mod strlen_on_c_strings;
 mod suspicious_operation_groupings;
@@ -427,6 +434,22 @@ fn is_all(self) -> bool {
         matches!(self, Correctness | Suspicious | Style | Complexity | Perf)
     }
 
+    fn name(self) -> &'static str {
+        match self {
+            Cargo => "cargo",
+            Complexity => "complexity",
+            Correctness => "correctness",
+            Nursery => "nursery",
+            Pedantic => "pedantic",
+            Perf => "perf",
+            Restriction => "restriction",
+            Style => "style",
+            Suspicious => "suspicious",
+            #[cfg(feature = "internal")]
+            Internal => "internal",
+        }
+    }
+
     fn group(self, groups: &mut RegistrationGroups) -> &mut Vec<LintId> {
         match self {
             Cargo => &mut groups.cargo,
@@ -459,6 +482,33 @@ pub fn explain(name: &str) {
     }
 }
 
+/// Metadata of a lint, used to describe lints in the machine readable output of `cargo clippy`.
+pub struct LintMetadata {
+    /// The lint group, e.g. `style`.
+    pub group: &'static str,
+    /// A short description of what the lint checks for.
+    pub description: &'static str,
+    /// The default level, e.g. `warn`.
+    pub default_level: &'static str,
+    /// The lint documentation, in Markdown.
+    pub explanation: &'static str,
+}
+
+/// Looks up a lint by its name, with or without the `clippy::` prefix.
+pub fn lint_metadata(name: &str) -> Option<LintMetadata> {
+    let name = name.strip_prefix("clippy::").unwrap_or(name);
+    let target = format!("clippy::{}", name.to_ascii_uppercase());
+    declared_lints::LINTS
+        .iter()
+        .find(|info| info.lint.name == target)
+        .map(|info| LintMetadata {
+            group: info.category.name(),
+            description: info.lint.desc,
+            default_level: info.lint.default_level.as_str(),
+            explanation: info.explanation,
+        })
+}
+
 fn register_categories(store: &mut rustc_lint::LintStore) {
     let mut groups = RegistrationGroups::default();
 
@@ -619,7 +669,8 @@ pub fn register_plugins(store: &mut rustc_lint::LintStore, sess: &Session, conf:
             msrv(),
         ))
     });
-    store.register_late_pass(|_| Box::<shadow::Shadow>::default());
+    let allowed_shadow_names = conf.allowed_shadow_names.iter().cloned().collect::<FxHashSet<_>>();
+    store.register_late_pass(move |_| Box::new(shadow::Shadow::new(allowed_shadow_names.clone())));
     store.register_late_pass(|_| Box::new(unit_types::UnitTypes));
     store.register_late_pass(|_| Box::new(loops::Loops));
     store.register_late_pass(|_| Box::<main_recursion::MainRecursion>::default());
@@ -746,9 +797,11 @@ pub fn register_plugins(store: &mut rustc_lint::LintStore, sess: &Session, conf:
     store.register_late_pass(|_| Box::new(create_dir::CreateDir));
     store.register_early_pass(|| Box::new(needless_arbitrary_self_type::NeedlessArbitrarySelfType));
     let literal_representation_lint_fraction_readability = conf.unreadable_literal_lint_fractions;
+    let unreadable_literal_threshold = conf.unreadable_literal_threshold;
     store.register_early_pass(move || {
         Box::new(literal_representation::LiteralDigitGrouping::new(
             literal_representation_lint_fraction_readability,
+            unreadable_literal_threshold,
         ))
     });
     let literal_representation_threshold = conf.literal_representation_threshold;
@@ -794,7 +847,13 @@ pub fn register_plugins(store: &mut rustc_lint::LintStore, sess: &Session, conf:
     });
     store.register_early_pass(|| Box::new(option_env_unwrap::OptionEnvUnwrap));
     let warn_on_all_wildcard_imports = conf.warn_on_all_wildcard_imports;
-    store.register_late_pass(move |_| Box::new(wildcard_imports::WildcardImports::new(warn_on_all_wildcard_imports)));
+    let allowed_wildcard_imports = conf.allowed_wildcard_imports.clone();
+    store.register_late_pass(move |_| {
+        Box::new(wildcard_imports::WildcardImports::new(
+            warn_on_all_wildcard_imports,
+            allowed_wildcard_imports.clone(),
+        ))
+    });
     store.register_late_pass(|_| Box::<redundant_pub_crate::RedundantPubCrate>::default());
     store.register_late_pass(|_| Box::new(unnamed_address::UnnamedAddress));
     store.register_late_pass(move |_| Box::new(dereference::Dereferencing::new(msrv())));
@@ -924,6 +983,16 @@ pub fn register_plugins(store: &mut rustc_lint::LintStore, sess: &Session, conf:
         ))
     });
     store.register_late_pass(|_| Box::new(no_mangle_with_rust_abi::NoMangleWithRustAbi));
+    store.register_late_pass(|_| Box::new(narrowing_return_cast::NarrowingReturnCast));
+    store.register_late_pass(|_| Box::new(borrowed_temporary::BorrowedTemporary));
+    store.register_late_pass(move |_| Box::new(string_push_sequence::StringPushSequence::new(msrv())));
+    store.register_late_pass(|_| Box::new(size_hint::SizeHint));
+    store.register_late_pass(|_| Box::new(ffi::Ffi));
+    store.register_late_pass(|_| Box::new(match_ergonomics::MatchErgonomics));
+    let log_macros = conf.log_macros.clone();
+    store.register_late_pass(move |_| {
+        Box::new(expensive_log_arguments::ExpensiveLogArguments::new(log_macros.clone()))
+    });
     // add lints here, do not remove this comment, it's used in `new_lint`
 }
 
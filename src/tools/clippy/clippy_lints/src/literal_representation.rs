@@ -222,6 +222,7 @@ fn display(&self, suggested_format: String, cx: &EarlyContext<'_>, span: rustc_s
 #[derive(Copy, Clone)]
 pub struct LiteralDigitGrouping {
     lint_fraction_readability: bool,
+    unreadable_threshold: usize,
 }
 
 impl_lint_pass!(LiteralDigitGrouping => [
@@ -248,9 +249,10 @@ fn check_expr(&mut self, cx: &EarlyContext<'_>, expr: &Expr) {
 const UUID_GROUP_LENS: [usize; 5] = [8, 4, 4, 4, 12];
 
 impl LiteralDigitGrouping {
-    pub fn new(lint_fraction_readability: bool) -> Self {
+    pub fn new(lint_fraction_readability: bool, unreadable_threshold: usize) -> Self {
         Self {
             lint_fraction_readability,
+            unreadable_threshold,
         }
     }
 
@@ -270,9 +272,9 @@ fn check_lit(self, cx: &EarlyContext<'_>, lit: token::Lit, span: Span) {
 
                 let result = (|| {
 
-                    let integral_group_size = Self::get_group_size(num_lit.integer.split('_'), num_lit.radix, true)?;
+                    let integral_group_size = self.get_group_size(num_lit.integer.split('_'), num_lit.radix, true)?;
                     if let Some(fraction) = num_lit.fraction {
-                        let fractional_group_size = Self::get_group_size(
+                        let fractional_group_size = self.get_group_size(
                             fraction.rsplit('_'),
                             num_lit.radix,
                             self.lint_fraction_readability)?;
@@ -419,6 +421,7 @@ fn parts_consistent(
     /// Returns the size of the digit groups (or None if ungrouped) if successful,
     /// otherwise returns a `WarningType` for linting.
     fn get_group_size<'a>(
+        self,
         groups: impl Iterator<Item = &'a str>,
         radix: Radix,
         lint_unreadable: bool,
@@ -443,7 +446,7 @@ fn get_group_size<'a>(
             } else {
                 Ok(Some(second))
             }
-        } else if first > 5 && lint_unreadable {
+        } else if first > self.unreadable_threshold && lint_unreadable {
             Err(WarningType::UnreadableLiteral)
         } else {
             Ok(None)
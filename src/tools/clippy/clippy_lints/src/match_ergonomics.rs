@@ -0,0 +1,299 @@
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::ty::is_copy;
+use clippy_utils::visitors::for_each_expr_with_closures;
+use clippy_utils::{get_parent_expr, path_to_local};
+use core::ops::ControlFlow;
+use rustc_errors::Applicability;
+use rustc_hir::def::{CtorKind, DefKind, Res};
+use rustc_hir::{BindingAnnotation, ExprKind, HirId, Mutability, Node, Pat, PatKind, UnOp};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty;
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+use rustc_span::Span;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `&` and `&mut` patterns around patterns that don't bind anything, and would
+    /// match through the reference on their own.
+    ///
+    /// ### Why is this bad?
+    /// Since match ergonomics, patterns like `None` or `Foo::Bar { .. }` match references to
+    /// their type as well. The explicit reference pattern only adds noise.
+    ///
+    /// ### Example
+    /// ```rust
+    /// fn is_none(x: &Option<u32>) -> bool {
+    ///     matches!(x, &None)
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```rust
+    /// fn is_none(x: &Option<u32>) -> bool {
+    ///     matches!(x, None)
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub NEEDLESS_REFERENCE_PATTERN,
+    pedantic,
+    "reference patterns around patterns that match through references on their own"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `ref` and `ref mut` bindings in positions where match ergonomics already
+    /// bind by reference.
+    ///
+    /// ### Why is this bad?
+    /// When a pattern matches through a reference, its bindings default to binding by
+    /// reference, so the explicit annotation doesn't change anything.
+    ///
+    /// ### Example
+    /// ```rust
+    /// fn len(x: &Option<String>) -> usize {
+    ///     match x {
+    ///         Some(ref s) => s.len(),
+    ///         None => 0,
+    ///     }
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```rust
+    /// fn len(x: &Option<String>) -> usize {
+    ///     match x {
+    ///         Some(s) => s.len(),
+    ///         None => 0,
+    ///     }
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub NEEDLESS_REF_BINDING,
+    pedantic,
+    "`ref` bindings in positions which already bind by reference"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for bindings of `Copy` values which are bound by reference through match
+    /// ergonomics, but only ever used dereferenced.
+    ///
+    /// ### Why is this bad?
+    /// Matching the reference explicitly with a `&` pattern binds the values directly, and
+    /// removes the need to dereference every use.
+    ///
+    /// ### Example
+    /// ```rust
+    /// fn sum(pairs: &[(u32, u32)]) -> u32 {
+    ///     pairs.iter().map(|(a, b)| *a + *b).sum()
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```rust
+    /// fn sum(pairs: &[(u32, u32)]) -> u32 {
+    ///     pairs.iter().map(|&(a, b)| a + b).sum()
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub EXPLICIT_BINDING_DEREF,
+    pedantic,
+    "bindings of `Copy` values bound by reference, but only used dereferenced"
+}
+
+declare_lint_pass!(MatchErgonomics => [NEEDLESS_REFERENCE_PATTERN, NEEDLESS_REF_BINDING, EXPLICIT_BINDING_DEREF]);
+
+impl<'tcx> LateLintPass<'tcx> for MatchErgonomics {
+    fn check_pat(&mut self, cx: &LateContext<'tcx>, pat: &'tcx Pat<'_>) {
+        if pat.span.from_expansion() {
+            return;
+        }
+        match pat.kind {
+            PatKind::Ref(inner, _) => check_reference_pattern(cx, pat, inner),
+            PatKind::Binding(annotation, _, ident, _) => check_ref_binding(cx, pat, annotation, ident.span),
+            _ => {},
+        }
+        if is_top_level(cx, pat) {
+            check_binding_derefs(cx, pat);
+        }
+    }
+}
+
+/// Returns how a plain binding at the position of `pat` binds, `None` meaning by value.
+///
+/// Every reference matched through by an enclosing pattern switches the default binding mode to
+/// binding by reference, which is `ref` as soon as one of the references is shared. An explicit
+/// reference pattern resets it to binding by value.
+fn default_binding_mode(cx: &LateContext<'_>, pat: &Pat<'_>) -> Option<Mutability> {
+    let mut mode = None;
+    for (_, node) in cx.tcx.hir().parent_iter(pat.hir_id) {
+        let Node::Pat(parent) = node else { break };
+        if matches!(parent.kind, PatKind::Ref(..)) {
+            break;
+        }
+        if let Some(adjustments) = cx.typeck_results().pat_adjustments().get(parent.hir_id) {
+            for ty in adjustments {
+                if let ty::Ref(_, _, mutability) = *ty.kind() {
+                    mode = Some(mode.map_or(mutability, |mode: Mutability| mode.min(mutability)));
+                }
+            }
+        }
+    }
+    mode
+}
+
+fn is_in_or_pattern(cx: &LateContext<'_>, pat: &Pat<'_>) -> bool {
+    cx.tcx
+        .hir()
+        .parent_iter(pat.hir_id)
+        .map_while(|(_, node)| if let Node::Pat(pat) = node { Some(pat) } else { None })
+        .any(|pat| matches!(pat.kind, PatKind::Or(_)))
+}
+
+/// Whether `pat` is the pattern of a `let`, a match arm or a parameter, looking through the
+/// desugaring of `for` loops.
+fn is_top_level(cx: &LateContext<'_>, pat: &Pat<'_>) -> bool {
+    match cx.tcx.hir().find_parent(pat.hir_id) {
+        Some(Node::Pat(parent)) => parent.for_loop_some().map_or(false, |it| it.hir_id == pat.hir_id),
+        _ => true,
+    }
+}
+
+fn check_reference_pattern(cx: &LateContext<'_>, pat: &Pat<'_>, inner: &Pat<'_>) {
+    let matches_through_references = match inner.kind {
+        PatKind::Wild | PatKind::Struct(..) | PatKind::TupleStruct(..) | PatKind::Tuple(..) | PatKind::Slice(..) => {
+            true
+        },
+        // Constants are matched by value, only unit structs and variants match through references.
+        PatKind::Path(ref qpath) => matches!(
+            cx.qpath_res(qpath, inner.hir_id),
+            Res::Def(DefKind::Ctor(_, CtorKind::Const), _) | Res::SelfCtor(_)
+        ),
+        _ => false,
+    };
+    // Removing the reference pattern would turn bindings into references.
+    let has_bindings = !inner.walk_short(|p| !matches!(p.kind, PatKind::Binding(..)));
+    if !matches_through_references || has_bindings || inner.span.from_expansion() {
+        return;
+    }
+    span_lint_and_then(
+        cx,
+        NEEDLESS_REFERENCE_PATTERN,
+        pat.span,
+        "this reference pattern is not needed to match through the reference",
+        |diag| {
+            diag.span_suggestion_verbose(
+                pat.span.until(inner.span),
+                "remove the reference pattern",
+                "",
+                Applicability::MachineApplicable,
+            );
+        },
+    );
+}
+
+fn check_ref_binding(cx: &LateContext<'_>, pat: &Pat<'_>, annotation: BindingAnnotation, ident_span: Span) {
+    let mutability = match annotation {
+        BindingAnnotation::REF => Mutability::Not,
+        BindingAnnotation::REF_MUT => Mutability::Mut,
+        _ => return,
+    };
+    // A `ref` binding in a `ref mut` position reborrows immutably, and the alternatives of an or
+    // pattern have to agree on their binding modes.
+    if default_binding_mode(cx, pat) != Some(mutability) || is_in_or_pattern(cx, pat) {
+        return;
+    }
+    span_lint_and_then(
+        cx,
+        NEEDLESS_REF_BINDING,
+        pat.span,
+        &format!("this binding already binds by `{}`", annotation.prefix_str().trim_end()),
+        |diag| {
+            diag.span_suggestion_verbose(
+                pat.span.until(ident_span),
+                format!("remove the `{}` annotation", annotation.prefix_str().trim_end()),
+                "",
+                Applicability::MachineApplicable,
+            );
+        },
+    );
+}
+
+fn check_binding_derefs<'tcx>(cx: &LateContext<'tcx>, pat: &'tcx Pat<'_>) {
+    // Adding a `&` pattern in front only changes the bindings' mode if the whole pattern matches
+    // through a single shared reference, and nothing within it resets the binding mode.
+    let Some(adjustments) = cx.typeck_results().pat_adjustments().get(pat.hir_id) else {
+        return;
+    };
+    if !matches!(**adjustments, [ty] if matches!(ty.kind(), ty::Ref(_, _, Mutability::Not))) {
+        return;
+    }
+    let mut bindings = Vec::new();
+    let mut is_simple = true;
+    pat.walk(|p| {
+        if p.hir_id != pat.hir_id && cx.typeck_results().pat_adjustments().get(p.hir_id).is_some() {
+            is_simple = false;
+        }
+        match p.kind {
+            PatKind::Binding(BindingAnnotation::NONE, id, ..) => bindings.push(id),
+            PatKind::Binding(..) | PatKind::Ref(..) | PatKind::Or(_) => is_simple = false,
+            _ => {},
+        }
+        is_simple
+    });
+    if !is_simple || bindings.is_empty() {
+        return;
+    }
+    let all_copy = bindings
+        .iter()
+        .all(|&id| matches!(*cx.typeck_results().node_type(id).kind(), ty::Ref(_, ty, _) if is_copy(cx, ty)));
+    if !all_copy {
+        return;
+    }
+    let Some(body_id) = cx.enclosing_body else { return };
+    let Some(deref_spans) = binding_derefs(cx, cx.tcx.hir().body(body_id), &bindings) else {
+        return;
+    };
+    if deref_spans.is_empty() {
+        return;
+    }
+
+    span_lint_and_then(
+        cx,
+        EXPLICIT_BINDING_DEREF,
+        pat.span,
+        "the bindings of this pattern are bound by reference, but only used dereferenced",
+        |diag| {
+            let mut suggestions = vec![(pat.span.shrink_to_lo(), "&".to_owned())];
+            suggestions.extend(deref_spans.into_iter().map(|span| (span, String::new())));
+            diag.multipart_suggestion(
+                "match the reference and bind the values directly",
+                suggestions,
+                Applicability::MachineApplicable,
+            );
+        },
+    );
+}
+
+/// Returns the spans of the `*` operators dereferencing the given bindings, or `None` if any of
+/// them is used without being dereferenced.
+fn binding_derefs<'tcx>(
+    cx: &LateContext<'tcx>,
+    body: &'tcx rustc_hir::Body<'_>,
+    bindings: &[HirId],
+) -> Option<Vec<Span>> {
+    let mut spans = Vec::new();
+    let res = for_each_expr_with_closures(cx, body.value, |e| {
+        let Some(id) = path_to_local(e) else {
+            return ControlFlow::Continue(());
+        };
+        if !bindings.contains(&id) {
+            return ControlFlow::Continue(());
+        }
+        match get_parent_expr(cx, e) {
+            Some(parent) if matches!(parent.kind, ExprKind::Unary(UnOp::Deref, _)) && !parent.span.from_expansion() => {
+                spans.push(parent.span.until(e.span));
+                ControlFlow::Continue(())
+            },
+            _ => ControlFlow::Break(()),
+        }
+    });
+    res.is_none().then_some(spans)
+}
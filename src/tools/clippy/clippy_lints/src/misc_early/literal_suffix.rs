@@ -1,36 +1,46 @@
 use clippy_utils::diagnostics::span_lint_and_sugg;
+use clippy_utils::numeric_literal::{DigitGrouping, LiteralFormat, NumericLiteral, SuffixSeparator};
 use rustc_errors::Applicability;
 use rustc_lint::EarlyContext;
 use rustc_span::Span;
 
 use super::{SEPARATED_LITERAL_SUFFIX, UNSEPARATED_LITERAL_SUFFIX};
 
-pub(super) fn check(cx: &EarlyContext<'_>, lit_span: Span, lit_snip: &str, suffix: &str, sugg_type: &str) {
-    let Some(maybe_last_sep_idx) = lit_snip.len().checked_sub(suffix.len() + 1) else {
-        return; // It's useless so shouldn't lint.
-    };
+pub(super) fn check(cx: &EarlyContext<'_>, lit_span: Span, lit_snip: &str, suffix: &str, float: bool) {
     // Do not lint when literal is unsuffixed.
-    if !suffix.is_empty() {
-        if lit_snip.as_bytes()[maybe_last_sep_idx] == b'_' {
-            span_lint_and_sugg(
-                cx,
-                SEPARATED_LITERAL_SUFFIX,
-                lit_span,
-                &format!("{sugg_type} type suffix should not be separated by an underscore"),
-                "remove the underscore",
-                format!("{}{suffix}", &lit_snip[..maybe_last_sep_idx]),
-                Applicability::MachineApplicable,
-            );
-        } else {
-            span_lint_and_sugg(
-                cx,
-                UNSEPARATED_LITERAL_SUFFIX,
-                lit_span,
-                &format!("{sugg_type} type suffix should be separated by an underscore"),
-                "add an underscore",
-                format!("{}_{suffix}", &lit_snip[..=maybe_last_sep_idx]),
-                Applicability::MachineApplicable,
-            );
-        }
+    if suffix.is_empty() {
+        return;
     }
+    let Some(last_digit_idx) = lit_snip.len().checked_sub(suffix.len() + 1) else {
+        return; // It's useless so shouldn't lint.
+    };
+    let num_lit = NumericLiteral::new(&lit_snip[..=last_digit_idx], Some(suffix), float);
+    let sugg_type = if float { "float" } else { "integer" };
+    let (lint, msg, help, suffix_separator) = if num_lit.suffix_separated {
+        (
+            SEPARATED_LITERAL_SUFFIX,
+            format!("{sugg_type} type suffix should not be separated by an underscore"),
+            "remove the underscore",
+            SuffixSeparator::None,
+        )
+    } else {
+        (
+            UNSEPARATED_LITERAL_SUFFIX,
+            format!("{sugg_type} type suffix should be separated by an underscore"),
+            "add an underscore",
+            SuffixSeparator::Underscore,
+        )
+    };
+    span_lint_and_sugg(
+        cx,
+        lint,
+        lit_span,
+        &msg,
+        help,
+        num_lit.format_with(LiteralFormat {
+            grouping: DigitGrouping::AsWritten,
+            suffix_separator,
+        }),
+        Applicability::MachineApplicable,
+    );
 }
@@ -401,7 +401,7 @@ fn check_lit(cx: &EarlyContext<'_>, lit: token::Lit, span: Span) {
                 LitIntType::Unsigned(ty) => ty.name_str(),
                 LitIntType::Unsuffixed => "",
             };
-            literal_suffix::check(cx, span, &lit_snip, suffix, "integer");
+            literal_suffix::check(cx, span, &lit_snip, suffix, false);
             if lit_snip.starts_with("0x") {
                 mixed_case_hex_literals::check(cx, span, suffix, &lit_snip);
             } else if lit_snip.starts_with("0b") || lit_snip.starts_with("0o") {
@@ -411,7 +411,7 @@ fn check_lit(cx: &EarlyContext<'_>, lit: token::Lit, span: Span) {
             }
         } else if let Ok(LitKind::Float(_, LitFloatType::Suffixed(float_ty))) = lit_kind {
             let suffix = float_ty.name_str();
-            literal_suffix::check(cx, span, &lit_snip, suffix, "float");
+            literal_suffix::check(cx, span, &lit_snip, suffix, true);
         }
     }
 }
@@ -0,0 +1,137 @@
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::visitors::find_all_ret_expressions;
+use clippy_utils::{expr_or_init, return_ty, trait_ref_of_method};
+use if_chain::if_chain;
+use rustc_hir::intravisit::FnKind;
+use rustc_hir::{Body, Expr, ExprKind, FnDecl};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+use rustc_span::def_id::LocalDefId;
+use rustc_span::Span;
+use rustc_target::abi::Integer;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for exported functions returning an integer that is produced by
+    /// narrowing a wider integer with `as` right before it is returned.
+    ///
+    /// ### Why is this bad?
+    /// The function computes values that do not necessarily fit into its declared
+    /// return type, and the `as` cast silently truncates them. Callers of a public
+    /// API have no way to know that the result was cut off.
+    ///
+    /// ### Known problems
+    /// The lint does not know whether the computed value is actually bounded, e.g.
+    /// after a preceding range check.
+    ///
+    /// ### Example
+    /// ```rust
+    /// pub fn checksum(data: &[u8]) -> u8 {
+    ///     let sum: u32 = data.iter().map(|&b| u32::from(b)).sum();
+    ///     sum as u8
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// pub fn checksum(data: &[u8]) -> u32 {
+    ///     data.iter().map(|&b| u32::from(b)).sum()
+    /// }
+    /// ```
+    /// or make the conversion fallible:
+    /// ```rust
+    /// # use std::num::TryFromIntError;
+    /// pub fn checksum(data: &[u8]) -> Result<u8, TryFromIntError> {
+    ///     let sum: u32 = data.iter().map(|&b| u32::from(b)).sum();
+    ///     u8::try_from(sum)
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub NARROWING_RETURN_CAST,
+    pedantic,
+    "exported function truncating its computed value with `as` to fit the declared return type"
+}
+
+declare_lint_pass!(NarrowingReturnCast => [NARROWING_RETURN_CAST]);
+
+impl<'tcx> LateLintPass<'tcx> for NarrowingReturnCast {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        kind: FnKind<'tcx>,
+        decl: &'tcx FnDecl<'_>,
+        body: &'tcx Body<'_>,
+        span: Span,
+        def_id: LocalDefId,
+    ) {
+        if !matches!(kind, FnKind::ItemFn(..) | FnKind::Method(..))
+            || in_external_macro(cx.sess(), span)
+            || !cx.effective_visibilities.is_exported(def_id)
+            // The trait fixes the return type of its implementations.
+            || trait_ref_of_method(cx, def_id).is_some()
+        {
+            return;
+        }
+
+        let hir_id = cx.tcx.hir().local_def_id_to_hir_id(def_id);
+        let ret_ty = return_ty(cx, hir_id.expect_owner());
+        let Some(ret_bits) = int_ty_bits(cx.tcx, ret_ty) else { return };
+
+        let mut narrowing_casts = Vec::new();
+        find_all_ret_expressions(cx, body.value, |ret_expr| {
+            // Follow `let x = y as u8; x` to the cast the returned value comes from.
+            let value = expr_or_init(cx, ret_expr);
+            if_chain! {
+                if !value.span.from_expansion();
+                if let ExprKind::Cast(source, _) = value.kind;
+                let source_ty = cx.typeck_results().expr_ty(source);
+                if let Some(source_bits) = int_ty_bits(cx.tcx, source_ty);
+                if source_bits > ret_bits;
+                then {
+                    narrowing_casts.push((value, source_ty));
+                }
+            }
+            true
+        });
+
+        for (cast, source_ty) in narrowing_casts {
+            lint_narrowing_cast(cx, decl, cast, source_ty, ret_ty);
+        }
+    }
+}
+
+fn lint_narrowing_cast<'tcx>(
+    cx: &LateContext<'tcx>,
+    decl: &FnDecl<'_>,
+    cast: &Expr<'_>,
+    source_ty: Ty<'tcx>,
+    ret_ty: Ty<'tcx>,
+) {
+    span_lint_and_then(
+        cx,
+        NARROWING_RETURN_CAST,
+        cast.span,
+        &format!("casting `{source_ty}` to `{ret_ty}` may truncate the returned value"),
+        |diag| {
+            diag.span_note(
+                decl.output.span(),
+                format!("the function is declared to return `{ret_ty}`"),
+            );
+            diag.help(format!(
+                "consider widening the return type to `{source_ty}`, \
+                or use `{ret_ty}::try_from` and handle the error"
+            ));
+        },
+    );
+}
+
+/// Returns the width in bits of an integer type, or `None` for any other type.
+fn int_ty_bits(tcx: TyCtxt<'_>, ty: Ty<'_>) -> Option<u64> {
+    match ty.kind() {
+        ty::Int(ity) => Some(Integer::from_int_ty(&tcx, *ity).size().bits()),
+        ty::Uint(uty) => Some(Integer::from_uint_ty(&tcx, *uty).size().bits()),
+        _ => None,
+    }
+}
@@ -0,0 +1,259 @@
+use clippy_utils::consts::{constant, Constant};
+use clippy_utils::diagnostics::{span_lint_and_help, span_lint_and_then};
+use clippy_utils::visitors::find_all_ret_expressions;
+use clippy_utils::{get_trait_def_id, is_res_lang_ctor, path_res, paths, peel_blocks, SpanlessEq};
+use rustc_hir::LangItem::{OptionNone, OptionSome};
+use rustc_hir::{Body, Expr, ExprKind, ImplItemKind, ImplItemRef, Item, ItemKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::lint::in_external_macro;
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+use rustc_span::{sym, Span};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for manual `Iterator` implementations whose `size_hint` returns
+    /// constant bounds contradicting the `next` method of the same impl: an
+    /// upper bound of `0` while `next` returns `Some`, a lower bound above `0`
+    /// while `next` only ever returns `None`, or a lower bound greater than the
+    /// upper bound.
+    ///
+    /// ### Why is this bad?
+    /// Adapters and collections trust `size_hint`, e.g. to preallocate memory
+    /// or to compute the hint of an adapter chain. A wrong hint leads to
+    /// surprising results, and the `ExactSizeIterator::len` implementation
+    /// panics if the bounds differ.
+    ///
+    /// ### Example
+    /// ```rust
+    /// struct Once(Option<u32>);
+    ///
+    /// impl Iterator for Once {
+    ///     type Item = u32;
+    ///
+    ///     fn next(&mut self) -> Option<u32> {
+    ///         if let Some(value) = self.0.take() {
+    ///             Some(value)
+    ///         } else {
+    ///             None
+    ///         }
+    ///     }
+    ///
+    ///     fn size_hint(&self) -> (usize, Option<usize>) {
+    ///         (0, Some(0))
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// # struct Once(Option<u32>);
+    /// impl Iterator for Once {
+    ///     // ..
+    /// #   type Item = u32;
+    /// #   fn next(&mut self) -> Option<u32> { self.0.take() }
+    ///     fn size_hint(&self) -> (usize, Option<usize>) {
+    ///         let len = usize::from(self.0.is_some());
+    ///         (len, Some(len))
+    ///     }
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub INCONSISTENT_SIZE_HINT,
+    correctness,
+    "`Iterator::size_hint` returning bounds which contradict `Iterator::next`"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for manual `Iterator` implementations whose `size_hint` returns
+    /// the same value as lower and upper bound, for types which don't implement
+    /// `ExactSizeIterator`.
+    ///
+    /// ### Why is this bad?
+    /// The iterator knows its exact length, but users can't call `len` on it,
+    /// and adapters like `Iterator::rev` on a `Zip` or `Iterator::rposition`
+    /// can't be used.
+    ///
+    /// ### Example
+    /// ```rust
+    /// struct Countdown(usize);
+    ///
+    /// impl Iterator for Countdown {
+    ///     type Item = usize;
+    ///
+    ///     fn next(&mut self) -> Option<usize> {
+    ///         self.0 = self.0.checked_sub(1)?;
+    ///         Some(self.0)
+    ///     }
+    ///
+    ///     fn size_hint(&self) -> (usize, Option<usize>) {
+    ///         (self.0, Some(self.0))
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// # struct Countdown(usize);
+    /// # impl Iterator for Countdown {
+    /// #     type Item = usize;
+    /// #     fn next(&mut self) -> Option<usize> { self.0 = self.0.checked_sub(1)?; Some(self.0) }
+    /// #     fn size_hint(&self) -> (usize, Option<usize>) { (self.0, Some(self.0)) }
+    /// # }
+    /// impl ExactSizeIterator for Countdown {}
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub MISSING_EXACT_SIZE_ITERATOR,
+    pedantic,
+    "`Iterator` with an exact `size_hint` not implementing `ExactSizeIterator`"
+}
+
+declare_lint_pass!(SizeHint => [INCONSISTENT_SIZE_HINT, MISSING_EXACT_SIZE_ITERATOR]);
+
+impl<'tcx> LateLintPass<'tcx> for SizeHint {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'tcx>) {
+        if let ItemKind::Impl(impl_) = item.kind
+            && let Some(trait_ref) = impl_.of_trait
+            && let Some(trait_id) = trait_ref.trait_def_id()
+            && cx.tcx.is_diagnostic_item(sym::Iterator, trait_id)
+            && !in_external_macro(cx.sess(), item.span)
+            && let Some(size_hint) = method_body(cx, impl_.items, "size_hint")
+            && let ExprKind::Tup([lower, upper]) = peel_blocks(size_hint.value).kind
+            && !size_hint.value.span.from_expansion()
+        {
+            let typeck = cx.tcx.typeck_body(size_hint.id());
+            let int_constant = |expr: &Expr<'_>| match constant(cx, typeck, expr) {
+                Some((Constant::Int(value), _)) => Some(value),
+                _ => None,
+            };
+            let upper = match upper.kind {
+                ExprKind::Call(func, [arg]) if is_res_lang_ctor(cx, path_res(cx, func), OptionSome) => Some(arg),
+                _ if is_res_lang_ctor(cx, path_res(cx, upper), OptionNone) => None,
+                _ => return,
+            };
+            let lower_value = int_constant(lower);
+            let upper_value = upper.and_then(int_constant);
+            let hint_span = peel_blocks(size_hint.value).span;
+
+            if let (Some(lower_value), Some(upper_value)) = (lower_value, upper_value)
+                && lower_value > upper_value
+            {
+                span_lint_and_help(
+                    cx,
+                    INCONSISTENT_SIZE_HINT,
+                    hint_span,
+                    &format!("the lower bound `{lower_value}` is greater than the upper bound `{upper_value}`"),
+                    None,
+                    "the lower bound must not be greater than the upper bound",
+                );
+                return;
+            }
+
+            if let Some(next) = method_body(cx, impl_.items, "next") {
+                let returns = next_returns(cx, next);
+                if upper_value == Some(0)
+                    && let NextReturns::Some(some_span) = returns
+                {
+                    lint_inconsistent(
+                        cx,
+                        hint_span,
+                        "the upper bound is `0`, but `next` can return `Some`",
+                        some_span,
+                        "`next` returns `Some` here",
+                    );
+                    return;
+                }
+                if let Some(lower_value) = lower_value
+                    && lower_value > 0
+                    && let NextReturns::OnlyNone = returns
+                {
+                    lint_inconsistent(
+                        cx,
+                        hint_span,
+                        &format!("the lower bound is `{lower_value}`, but `next` always returns `None`"),
+                        next.value.span,
+                        "`next` is implemented here",
+                    );
+                    return;
+                }
+            }
+
+            if let Some(upper) = upper
+                && ((lower_value.is_some() && lower_value == upper_value) || SpanlessEq::new(cx).eq_expr(lower, upper))
+                && !implements_exact_size_iterator(cx, item)
+            {
+                span_lint_and_help(
+                    cx,
+                    MISSING_EXACT_SIZE_ITERATOR,
+                    hint_span,
+                    "this iterator returns an exact `size_hint`, but doesn't implement `ExactSizeIterator`",
+                    None,
+                    "consider implementing `ExactSizeIterator` for it",
+                );
+            }
+        }
+    }
+}
+
+fn lint_inconsistent(cx: &LateContext<'_>, hint_span: Span, msg: &str, next_span: Span, note: &str) {
+    span_lint_and_then(cx, INCONSISTENT_SIZE_HINT, hint_span, msg, |diag| {
+        diag.span_note(next_span, note);
+    });
+}
+
+/// The body of the method with the given name in an impl.
+fn method_body<'tcx>(cx: &LateContext<'tcx>, items: &[ImplItemRef], name: &str) -> Option<&'tcx Body<'tcx>> {
+    let item = items.iter().find(|item| item.ident.name.as_str() == name)?;
+    match cx.tcx.hir().impl_item(item.id).kind {
+        ImplItemKind::Fn(_, body_id) => Some(cx.tcx.hir().body(body_id)),
+        _ => None,
+    }
+}
+
+enum NextReturns {
+    /// `next` can return `Some`, e.g. at the given expression.
+    Some(Span),
+    /// Every returned value is `None`.
+    OnlyNone,
+    Unknown,
+}
+
+fn next_returns(cx: &LateContext<'_>, body: &Body<'_>) -> NextReturns {
+    let typeck = cx.tcx.typeck_body(body.id());
+    let mut some = None;
+    let mut only_none = true;
+    let analyzed = find_all_ret_expressions(cx, body.value, |ret| {
+        match ret.kind {
+            ExprKind::Call(func, [_]) if is_res_lang_ctor(cx, path_res(cx, func), OptionSome) => {
+                if some.is_none() {
+                    some = Some(ret.span);
+                }
+                only_none = false;
+            },
+            _ if is_res_lang_ctor(cx, path_res(cx, ret), OptionNone) => {},
+            // `panic!()` and other diverging expressions don't return anything.
+            _ if typeck.expr_ty(ret).is_never() => {},
+            _ => only_none = false,
+        }
+        true
+    });
+    match (some, analyzed && only_none) {
+        (Some(span), _) => NextReturns::Some(span),
+        (None, true) => NextReturns::OnlyNone,
+        (None, false) => NextReturns::Unknown,
+    }
+}
+
+fn implements_exact_size_iterator(cx: &LateContext<'_>, item: &Item<'_>) -> bool {
+    let Some(exact_size_iterator) = get_trait_def_id(cx, &paths::EXACT_SIZE_ITERATOR) else { return true };
+    let self_ty = cx.tcx.type_of(item.owner_id).subst_identity();
+    // The impl may have different bounds than the `Iterator` impl, so look for any impl for
+    // the same type instead of checking whether the trait is implemented.
+    match self_ty.ty_adt_def() {
+        Some(adt) => cx
+            .tcx
+            .all_impls(exact_size_iterator)
+            .any(|impl_id| cx.tcx.type_of(impl_id).subst_identity().ty_adt_def() == Some(adt)),
+        None => true,
+    }
+}
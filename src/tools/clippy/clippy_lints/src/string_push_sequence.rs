@@ -0,0 +1,419 @@
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::msrvs::{self, Msrv};
+use clippy_utils::source::snippet;
+use clippy_utils::ty::is_type_lang_item;
+use clippy_utils::{higher, is_path_lang_item, path_to_local, path_to_local_id, peel_hir_expr_refs};
+use rustc_ast::ast::{LitKind, RangeLimits};
+use rustc_errors::Applicability;
+use rustc_hir::{BinOpKind, Block, Expr, ExprKind, HirId, LangItem, LoopSource, PatKind, QPath, Stmt, StmtKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::ty;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Span;
+use std::fmt::Write as _;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for three or more consecutive statements appending to the same
+    /// `String`, with `push_str`, `push` or `+=`, and for strings which start
+    /// out empty and are then grown with `+=` or `s = s + ..` inside a loop.
+    ///
+    /// ### Why is this bad?
+    /// A single `format!` or `write!` call shows the shape of the resulting
+    /// string at a glance, and reserves the required capacity once instead of
+    /// potentially reallocating on every push. Strings grown in a loop can be
+    /// preallocated with `String::with_capacity`.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # let name = "world";
+    /// let mut greeting = String::new();
+    /// greeting.push_str("Hello, ");
+    /// greeting.push_str(name);
+    /// greeting.push('!');
+    ///
+    /// let mut line = String::new();
+    /// for _ in 0..80 {
+    ///     line += "-";
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust
+    /// # let name = "world";
+    /// let mut greeting = format!("Hello, {name}!");
+    ///
+    /// let mut line = String::with_capacity(80);
+    /// for _ in 0..80 {
+    ///     line += "-";
+    /// }
+    /// ```
+    #[clippy::version = "1.69.0"]
+    pub STRING_PUSH_SEQUENCE,
+    pedantic,
+    "consecutive pushes to a `String` which could be a single `format!` or `write!`"
+}
+
+pub struct StringPushSequence {
+    msrv: Msrv,
+}
+
+impl StringPushSequence {
+    #[must_use]
+    pub fn new(msrv: Msrv) -> Self {
+        Self { msrv }
+    }
+}
+
+impl_lint_pass!(StringPushSequence => [STRING_PUSH_SEQUENCE]);
+
+/// The number of consecutive pushes from which on the lint triggers.
+const MIN_PUSHES: usize = 3;
+
+impl<'tcx> LateLintPass<'tcx> for StringPushSequence {
+    fn check_block(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        let mut stmts = block.stmts.iter().enumerate().peekable();
+        while let Some((start, stmt)) = stmts.next() {
+            if let Some(local) = string_new_local(cx, stmt)
+                && let Some(&(_, next)) = stmts.peek()
+            {
+                check_loop(cx, local, stmt.span, next);
+            }
+
+            let Some((local, first)) = string_push(cx, stmt) else { continue };
+            let mut pieces = vec![first];
+            let mut last = stmt;
+            while let Some(&(_, next)) = stmts.peek()
+                && let Some((next_local, piece)) = string_push(cx, next)
+                && next_local == local
+            {
+                pieces.push(piece);
+                last = next;
+                stmts.next();
+            }
+
+            if pieces.len() < MIN_PUSHES || in_external_macro(cx.sess(), stmt.span) {
+                continue;
+            }
+            let init = start
+                .checked_sub(1)
+                .filter(|&prev| string_new_local(cx, &block.stmts[prev]) == Some(local))
+                .map(|prev| block.stmts[prev].span);
+            let inline_args = self.msrv.meets(msrvs::FORMAT_ARGS_CAPTURE);
+            lint_sequence(cx, local, init, stmt.span.to(last.span), &pieces, inline_args);
+        }
+    }
+
+    extract_msrv_attr!(LateContext);
+}
+
+/// A value appended to the string.
+enum Piece<'tcx> {
+    /// A string or char literal with its value.
+    Lit(String),
+    Expr(&'tcx Expr<'tcx>),
+}
+
+/// Matches `s.push_str(..);`, `s.push(..);` and `s += ..;` where `s` is a local `String`.
+fn string_push<'tcx>(cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'tcx>) -> Option<(HirId, Piece<'tcx>)> {
+    let StmtKind::Semi(expr) = stmt.kind else { return None };
+    if expr.span.from_expansion() {
+        return None;
+    }
+    let (receiver, arg) = match expr.kind {
+        ExprKind::MethodCall(path, receiver, [arg], _) if matches!(path.ident.as_str(), "push_str" | "push") => {
+            (receiver, arg)
+        },
+        ExprKind::AssignOp(op, receiver, arg) if op.node == BinOpKind::Add => (receiver, arg),
+        _ => return None,
+    };
+    let local = path_to_local(receiver)?;
+    if !is_type_lang_item(cx, cx.typeck_results().expr_ty(receiver), LangItem::String) {
+        return None;
+    }
+    Some((local, piece(arg)))
+}
+
+/// Matches `s += ..` and `s = s + ..` for the given local.
+fn string_concat<'tcx>(expr: &'tcx Expr<'tcx>, local: HirId) -> Option<Piece<'tcx>> {
+    if expr.span.from_expansion() {
+        return None;
+    }
+    match expr.kind {
+        ExprKind::AssignOp(op, lhs, arg) if op.node == BinOpKind::Add && path_to_local_id(lhs, local) => {
+            Some(piece(arg))
+        },
+        ExprKind::Assign(lhs, rhs, _) if path_to_local_id(lhs, local) => match rhs.kind {
+            ExprKind::Binary(op, base, arg) if op.node == BinOpKind::Add && path_to_local_id(base, local) => {
+                Some(piece(arg))
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn piece<'tcx>(arg: &'tcx Expr<'tcx>) -> Piece<'tcx> {
+    let (arg, _) = peel_hir_expr_refs(arg);
+    match arg.kind {
+        ExprKind::Lit(lit) if !arg.span.from_expansion() => match lit.node {
+            LitKind::Str(value, _) => Piece::Lit(value.to_string()),
+            LitKind::Char(value) => Piece::Lit(value.to_string()),
+            _ => Piece::Expr(arg),
+        },
+        _ => Piece::Expr(arg),
+    }
+}
+
+/// Matches `let mut s = String::new();` and returns the bound local.
+fn string_new_local(cx: &LateContext<'_>, stmt: &Stmt<'_>) -> Option<HirId> {
+    if let StmtKind::Local(let_stmt) = stmt.kind
+        && let PatKind::Binding(_, id, _, None) = let_stmt.pat.kind
+        && let Some(init) = let_stmt.init
+        && let ExprKind::Call(func, []) = init.kind
+        && let ExprKind::Path(QPath::TypeRelative(ty, segment)) = func.kind
+        && is_path_lang_item(cx, ty, LangItem::String)
+        && segment.ident.as_str() == "new"
+        && !stmt.span.from_expansion()
+    {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// The number of iterations of a `for` loop over a range with literal bounds.
+fn range_len(arg: &Expr<'_>) -> Option<u128> {
+    let range = higher::Range::hir(arg)?;
+    let bound = |expr: Option<&Expr<'_>>| match expr?.kind {
+        ExprKind::Lit(lit) => match lit.node {
+            LitKind::Int(value, _) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    };
+    let (start, end) = (bound(range.start)?, bound(range.end)?);
+    match range.limits {
+        RangeLimits::HalfOpen => end.checked_sub(start),
+        RangeLimits::Closed => end.checked_sub(start)?.checked_add(1),
+    }
+}
+
+/// Checks whether the loop directly following `let mut s = String::new();` grows `s` by
+/// concatenation, which could be preallocated.
+fn check_loop<'tcx>(cx: &LateContext<'tcx>, local: HirId, init: Span, stmt: &'tcx Stmt<'tcx>) {
+    let (StmtKind::Expr(expr) | StmtKind::Semi(expr)) = stmt.kind else { return };
+    let (body, iterations) = if let Some(for_loop) = higher::ForLoop::hir(expr)
+        && let ExprKind::Block(body, _) = for_loop.body.kind
+    {
+        (body, range_len(for_loop.arg))
+    } else if let Some(while_loop) = higher::While::hir(expr)
+        && let ExprKind::Block(body, _) = while_loop.body.kind
+    {
+        (body, None)
+    } else if let ExprKind::Loop(body, _, LoopSource::Loop, _) = expr.kind
+        && !expr.span.from_expansion()
+    {
+        (body, None)
+    } else {
+        return;
+    };
+    if in_external_macro(cx.sess(), stmt.span) {
+        return;
+    }
+
+    let pieces = body
+        .stmts
+        .iter()
+        .filter_map(|stmt| match stmt.kind {
+            StmtKind::Expr(expr) | StmtKind::Semi(expr) => Some(expr),
+            _ => None,
+        })
+        .chain(body.expr)
+        .filter_map(|expr| string_concat(expr, local))
+        .collect::<Vec<_>>();
+    if pieces.is_empty() {
+        return;
+    }
+
+    let name = cx.tcx.hir().name(local);
+    // The bytes appended by each iteration, not counting pieces of unknown length.
+    let literal_len = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Lit(value) => value.len(),
+            Piece::Expr(_) => 0,
+        })
+        .sum::<usize>();
+    let all_literals = pieces.iter().all(|piece| matches!(piece, Piece::Lit(_)));
+    let capacity = iterations
+        .and_then(|iterations| iterations.checked_mul(literal_len as u128))
+        .filter(|&capacity| capacity > 0);
+
+    span_lint_and_then(
+        cx,
+        STRING_PUSH_SEQUENCE,
+        stmt.span,
+        &format!("`{name}` is grown by concatenation inside a loop"),
+        |diag| match capacity {
+            Some(capacity) if all_literals => {
+                diag.span_suggestion(
+                    init,
+                    "consider preallocating the string",
+                    format!("let mut {name} = String::with_capacity({capacity});"),
+                    Applicability::MaybeIncorrect,
+                );
+            },
+            Some(capacity) => {
+                diag.span_help(
+                    init,
+                    format!(
+                        "at least {capacity} bytes are appended, \
+                        consider preallocating the string with `String::with_capacity`"
+                    ),
+                );
+            },
+            None => {
+                diag.span_help(init, "consider preallocating the string with `String::with_capacity`");
+            },
+        },
+    );
+}
+
+/// The expression computing the length of a piece, if it is cheap and free of side effects.
+fn piece_len(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<String> {
+    if !matches!(expr.kind, ExprKind::Path(_)) {
+        return None;
+    }
+    let ty = cx.typeck_results().expr_ty(expr).peel_refs();
+    let method = match ty.kind() {
+        ty::Str => "len",
+        ty::Char => "len_utf8",
+        _ if is_type_lang_item(cx, ty, LangItem::String) => "len",
+        _ => return None,
+    };
+    Some(format!("{}.{method}()", snippet(cx, expr.span, "..")))
+}
+
+fn lint_sequence(
+    cx: &LateContext<'_>,
+    local: HirId,
+    init: Option<Span>,
+    span: Span,
+    pieces: &[Piece<'_>],
+    inline_args: bool,
+) {
+    let name = cx.tcx.hir().name(local);
+    let mut format_str = String::new();
+    let mut args = Vec::new();
+    for piece in pieces {
+        match piece {
+            Piece::Lit(value) => {
+                for c in value.chars() {
+                    match c {
+                        '{' => format_str.push_str("{{"),
+                        '}' => format_str.push_str("}}"),
+                        _ => format_str.extend(c.escape_debug()),
+                    }
+                }
+            },
+            Piece::Expr(expr) => match path_to_local(expr) {
+                // Inline plain locals into the format string.
+                Some(id) if id != local && inline_args => {
+                    let _ = write!(format_str, "{{{}}}", cx.tcx.hir().name(id));
+                },
+                _ => {
+                    format_str.push_str("{}");
+                    args.push(snippet(cx, expr.span, "..").into_owned());
+                },
+            },
+        }
+    }
+    let args = args.iter().fold(String::new(), |mut acc, arg| {
+        acc.push_str(", ");
+        acc.push_str(arg);
+        acc
+    });
+
+    // The number of bytes of the literals, and the lengths of the other pieces if they are known.
+    let literal_len = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Lit(value) => value.len(),
+            Piece::Expr(_) => 0,
+        })
+        .sum::<usize>();
+    let expr_lens = pieces
+        .iter()
+        .filter_map(|piece| match piece {
+            Piece::Lit(_) => None,
+            Piece::Expr(expr) => Some(piece_len(cx, expr)),
+        })
+        .collect::<Option<Vec<_>>>();
+
+    match init {
+        Some(init) => span_lint_and_then(
+            cx,
+            STRING_PUSH_SEQUENCE,
+            init.to(span),
+            &format!("`{name}` is built with {} consecutive pushes", pieces.len()),
+            |diag| {
+                let sugg = if pieces.iter().all(|piece| matches!(piece, Piece::Lit(_))) {
+                    // Only literals are pushed, the string is known upfront.
+                    let value = pieces.iter().fold(String::new(), |mut acc, piece| {
+                        if let Piece::Lit(value) = piece {
+                            acc.extend(value.chars().flat_map(char::escape_debug));
+                        }
+                        acc
+                    });
+                    format!("let mut {name} = String::from(\"{value}\");")
+                } else {
+                    format!("let mut {name} = format!(\"{format_str}\"{args});")
+                };
+                diag.span_suggestion(
+                    init.to(span),
+                    "consider creating the string at once",
+                    sugg,
+                    Applicability::MaybeIncorrect,
+                );
+            },
+        ),
+        None => span_lint_and_then(
+            cx,
+            STRING_PUSH_SEQUENCE,
+            span,
+            &format!("{} consecutive pushes to `{name}`", pieces.len()),
+            |diag| {
+                diag.span_suggestion(
+                    span,
+                    "consider using `write!`",
+                    format!("let _ = write!({name}, \"{format_str}\"{args});"),
+                    Applicability::MaybeIncorrect,
+                );
+                diag.note("`write!` requires `std::fmt::Write` to be in scope");
+                match expr_lens {
+                    Some(lens) if lens.is_empty() => {
+                        diag.help(format!(
+                            "the pushed strings are {literal_len} bytes long, \
+                            consider reserving them upfront with `{name}.reserve({literal_len})`"
+                        ));
+                    },
+                    Some(lens) => {
+                        diag.help(format!(
+                            "consider reserving the pushed strings upfront with `{name}.reserve({literal_len} + {})`",
+                            lens.join(" + ")
+                        ));
+                    },
+                    None if literal_len > 0 => {
+                        diag.help(format!(
+                            "at least {literal_len} bytes are pushed, \
+                            consider reserving them upfront with `{name}.reserve(..)`"
+                        ));
+                    },
+                    None => {},
+                }
+            },
+        ),
+    }
+}
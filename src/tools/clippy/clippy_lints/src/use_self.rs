@@ -1,7 +1,7 @@
 use clippy_utils::diagnostics::span_lint_and_sugg;
-use clippy_utils::is_from_proc_macro;
 use clippy_utils::msrvs::{self, Msrv};
 use clippy_utils::ty::same_type_and_consts;
+use clippy_utils::{is_from_proc_macro, is_self, path_to_local_id};
 use if_chain::if_chain;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::Applicability;
@@ -10,11 +10,12 @@
     def::{CtorOf, DefKind, Res},
     def_id::LocalDefId,
     intravisit::{walk_inf, walk_ty, Visitor},
-    Expr, ExprKind, FnRetTy, FnSig, GenericArg, HirId, Impl, ImplItemKind, Item, ItemKind, Pat, PatKind, Path, QPath,
-    TyKind,
+    Expr, ExprKind, FnRetTy, FnSig, HirId, Impl, ImplItemKind, Item, ItemKind, Node, Pat, PatKind, Path, QPath, TyKind,
+    UnOp,
 };
 use rustc_hir_analysis::hir_ty_to_ty;
 use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{Ty, TypeVisitableExt};
 use rustc_session::{declare_tool_lint, impl_lint_pass};
 use rustc_span::Span;
 
@@ -99,9 +100,7 @@ fn check_item(&mut self, cx: &LateContext<'tcx>, item: &Item<'tcx>) {
             if let ItemKind::Impl(Impl { self_ty, .. }) = item.kind;
             if let TyKind::Path(QPath::Resolved(_, item_path)) = self_ty.kind;
             let parameters = &item_path.segments.last().expect(SEGMENTS_MSG).args;
-            if parameters.as_ref().map_or(true, |params| {
-                !params.parenthesized && !params.args.iter().any(|arg| matches!(arg, GenericArg::Lifetime(_)))
-            });
+            if parameters.as_ref().map_or(true, |params| !params.parenthesized);
             if !item.span.from_expansion();
             if !is_from_proc_macro(cx, item); // expensive, should be last check
             then {
@@ -213,12 +212,22 @@ fn check_ty(&mut self, cx: &LateContext<'_>, hir_ty: &hir::Ty<'_>) {
                 | Res::Def(DefKind::TyParam, _)
             );
             if !types_to_skip.contains(&hir_ty.hir_id);
+            let impl_ty = cx.tcx.type_of(impl_id).subst_identity();
+            // Regions are erased in bodies, and `same_type_and_consts` ignores them. If the self
+            // type has lifetimes, only types outside of bodies which are exactly the self type can
+            // be replaced, e.g. `Foo<'a>` but neither `Foo<'static>` nor an elided `Foo<'_>`.
+            if !impl_ty.has_free_regions() || in_body == 0;
             let ty = if in_body > 0 {
                 cx.typeck_results().node_type(hir_ty.hir_id)
             } else {
                 hir_ty_to_ty(cx.tcx, hir_ty)
             };
-            if same_type_and_consts(ty, cx.tcx.type_of(impl_id).subst_identity());
+            let is_self_ty = if impl_ty.has_free_regions() {
+                ty == impl_ty
+            } else {
+                same_type_and_consts(ty, impl_ty)
+            };
+            if is_self_ty;
             then {
                 span_lint(cx, hir_ty.span);
             }
@@ -230,7 +239,11 @@ fn check_expr(&mut self, cx: &LateContext<'_>, expr: &Expr<'_>) {
             if !expr.span.from_expansion();
             if self.msrv.meets(msrvs::TYPE_ALIAS_ENUM_VARIANTS);
             if let Some(&StackItem::Check { impl_id, .. }) = self.stack.last();
-            if cx.typeck_results().expr_ty(expr) == cx.tcx.type_of(impl_id).subst_identity();
+            let impl_ty = cx.tcx.type_of(impl_id).subst_identity();
+            if cx.typeck_results().expr_ty(expr) == cx.tcx.erase_regions(impl_ty);
+            // Regions are erased in bodies, so with lifetimes we only know the type of values
+            // returned from a function returning exactly the self type.
+            if !impl_ty.has_free_regions() || is_returned_as(cx, expr, impl_ty);
             then {} else { return; }
         }
         match expr.kind {
@@ -254,7 +267,10 @@ fn check_pat(&mut self, cx: &LateContext<'_>, pat: &Pat<'_>) {
             if let PatKind::Path(QPath::Resolved(_, path))
                  | PatKind::TupleStruct(QPath::Resolved(_, path), _, _)
                  | PatKind::Struct(QPath::Resolved(_, path), _, _) = pat.kind;
-            if cx.typeck_results().pat_ty(pat) == cx.tcx.type_of(impl_id).subst_identity();
+            let impl_ty = cx.tcx.type_of(impl_id).subst_identity();
+            if cx.typeck_results().pat_ty(pat) == cx.tcx.erase_regions(impl_ty);
+            // Likewise, with lifetimes we only know the type of patterns matching on `self`.
+            if !impl_ty.has_free_regions() || matches_self_param(cx, pat, impl_ty);
             then {
                 check_path(cx, path);
             }
@@ -294,6 +310,76 @@ fn span_lint(cx: &LateContext<'_>, span: Span) {
     );
 }
 
+/// Checks whether the expression is the return value of a function whose return type is
+/// `impl_ty`, including its regions.
+fn is_returned_as<'tcx>(cx: &LateContext<'tcx>, expr: &Expr<'_>, impl_ty: Ty<'tcx>) -> bool {
+    let owner = cx.tcx.hir().enclosing_body_owner(expr.hir_id);
+    if !matches!(cx.tcx.def_kind(owner), DefKind::AssocFn)
+        || cx.tcx.fn_sig(owner).subst_identity().output().skip_binder() != impl_ty
+    {
+        return false;
+    }
+    let mut child = expr.hir_id;
+    for (id, node) in cx.tcx.hir().parent_iter(expr.hir_id) {
+        let is_value = match node {
+            Node::Expr(parent) => match parent.kind {
+                ExprKind::Ret(_) => return true,
+                ExprKind::Block(..) | ExprKind::DropTemps(_) => true,
+                _ => false,
+            },
+            Node::Block(block) => block.expr.map_or(false, |tail| tail.hir_id == child),
+            Node::ImplItem(_) => return true,
+            _ => false,
+        };
+        if !is_value {
+            return false;
+        }
+        child = id;
+    }
+    false
+}
+
+/// Checks whether the pattern matches on the `self` parameter, which is of type `impl_ty` or a
+/// reference to it.
+fn matches_self_param<'tcx>(cx: &LateContext<'tcx>, pat: &Pat<'_>, impl_ty: Ty<'tcx>) -> bool {
+    let mut scrutinee = match cx.tcx.hir().get_parent(pat.hir_id) {
+        Node::Arm(arm) => match cx.tcx.hir().get_parent(arm.hir_id) {
+            Node::Expr(Expr {
+                kind: ExprKind::Match(scrutinee, ..),
+                ..
+            }) => scrutinee,
+            _ => return false,
+        },
+        Node::Local(local) => match local.init {
+            Some(init) => init,
+            None => return false,
+        },
+        Node::Expr(Expr {
+            kind: ExprKind::Let(let_expr),
+            ..
+        }) => let_expr.init,
+        _ => return false,
+    };
+    while let ExprKind::Unary(UnOp::Deref, inner) | ExprKind::AddrOf(_, _, inner) = scrutinee.kind {
+        scrutinee = inner;
+    }
+
+    let owner = cx.tcx.hir().enclosing_body_owner(pat.hir_id);
+    if_chain! {
+        if matches!(cx.tcx.def_kind(owner), DefKind::AssocFn);
+        if let Some(body_id) = cx.enclosing_body;
+        if let [self_param, ..] = cx.tcx.hir().body(body_id).params;
+        if is_self(self_param);
+        if path_to_local_id(scrutinee, self_param.pat.hir_id);
+        if let [self_ty, ..] = cx.tcx.fn_sig(owner).subst_identity().inputs().skip_binder();
+        then {
+            self_ty.peel_refs() == impl_ty
+        } else {
+            false
+        }
+    }
+}
+
 fn check_path(cx: &LateContext<'_>, path: &Path<'_>) {
     match path.res {
         Res::Def(DefKind::Ctor(CtorOf::Variant, _) | DefKind::Variant, ..) => {
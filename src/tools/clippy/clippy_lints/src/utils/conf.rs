@@ -2,6 +2,7 @@
 
 #![allow(clippy::module_name_repetitions)]
 
+use rustc_span::edit_distance::edit_distance;
 use serde::de::{Deserializer, IgnoredAny, IntoDeserializer, MapAccess, Visitor};
 use serde::Deserialize;
 use std::error::Error;
@@ -31,6 +32,11 @@
     "CamelCase",
 ];
 const DEFAULT_DISALLOWED_NAMES: &[&str] = &["foo", "baz", "quux"];
+#[rustfmt::skip]
+const DEFAULT_LOG_MACROS: &[&str] = &[
+    "log::trace", "log::debug", "log::info", "log::warn", "log::error",
+    "tracing::trace", "tracing::debug", "tracing::info", "tracing::warn", "tracing::error",
+];
 
 /// Holds information used by `MISSING_ENFORCED_IMPORT_RENAMES` lint.
 #[derive(Clone, Debug, Deserialize)]
@@ -253,7 +259,7 @@ pub(crate) fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     ///
     /// Suppress lints whenever the suggested change would cause breakage for other crates.
     (avoid_breaking_exported_api: bool = true),
-    /// Lint: MANUAL_SPLIT_ONCE, MANUAL_STR_REPEAT, CLONED_INSTEAD_OF_COPIED, REDUNDANT_FIELD_NAMES, REDUNDANT_STATIC_LIFETIMES, FILTER_MAP_NEXT, CHECKED_CONVERSIONS, MANUAL_RANGE_CONTAINS, USE_SELF, MEM_REPLACE_WITH_DEFAULT, MANUAL_NON_EXHAUSTIVE, OPTION_AS_REF_DEREF, MAP_UNWRAP_OR, MATCH_LIKE_MATCHES_MACRO, MANUAL_STRIP, MISSING_CONST_FOR_FN, UNNESTED_OR_PATTERNS, FROM_OVER_INTO, PTR_AS_PTR, IF_THEN_SOME_ELSE_NONE, APPROX_CONSTANT, DEPRECATED_CFG_ATTR, INDEX_REFUTABLE_SLICE, MAP_CLONE, BORROW_AS_PTR, MANUAL_BITS, ERR_EXPECT, CAST_ABS_TO_UNSIGNED, UNINLINED_FORMAT_ARGS, MANUAL_CLAMP, MANUAL_LET_ELSE, UNCHECKED_DURATION_SUBTRACTION, COLLAPSIBLE_STR_REPLACE, SEEK_FROM_CURRENT, SEEK_REWIND, UNNECESSARY_LAZY_EVALUATIONS, TRANSMUTE_PTR_TO_REF, ALMOST_COMPLETE_RANGE, NEEDLESS_BORROW, DERIVABLE_IMPLS, MANUAL_IS_ASCII_CHECK, MANUAL_REM_EUCLID, MANUAL_RETAIN.
+    /// Lint: MANUAL_SPLIT_ONCE, MANUAL_STR_REPEAT, CLONED_INSTEAD_OF_COPIED, REDUNDANT_FIELD_NAMES, REDUNDANT_STATIC_LIFETIMES, FILTER_MAP_NEXT, CHECKED_CONVERSIONS, MANUAL_RANGE_CONTAINS, USE_SELF, MEM_REPLACE_WITH_DEFAULT, MANUAL_NON_EXHAUSTIVE, OPTION_AS_REF_DEREF, MAP_UNWRAP_OR, MATCH_LIKE_MATCHES_MACRO, MANUAL_STRIP, MISSING_CONST_FOR_FN, UNNESTED_OR_PATTERNS, FROM_OVER_INTO, PTR_AS_PTR, IF_THEN_SOME_ELSE_NONE, APPROX_CONSTANT, DEPRECATED_CFG_ATTR, INDEX_REFUTABLE_SLICE, MAP_CLONE, BORROW_AS_PTR, MANUAL_BITS, ERR_EXPECT, CAST_ABS_TO_UNSIGNED, UNINLINED_FORMAT_ARGS, MANUAL_CLAMP, MANUAL_LET_ELSE, UNCHECKED_DURATION_SUBTRACTION, COLLAPSIBLE_STR_REPLACE, SEEK_FROM_CURRENT, SEEK_REWIND, UNNECESSARY_LAZY_EVALUATIONS, TRANSMUTE_PTR_TO_REF, ALMOST_COMPLETE_RANGE, NEEDLESS_BORROW, DERIVABLE_IMPLS, MANUAL_IS_ASCII_CHECK, MANUAL_REM_EUCLID, MANUAL_RETAIN, STRING_PUSH_SEQUENCE.
     ///
     /// The minimum rust version that the project supports
     (msrv: Option<String> = None),
@@ -371,6 +377,10 @@ pub(crate) fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     ///
     /// Should the fraction of a decimal be linted to include separators.
     (unreadable_literal_lint_fractions: bool = true),
+    /// Lint: UNREADABLE_LITERAL.
+    ///
+    /// The maximum number of consecutive digits a literal may have without separators.
+    (unreadable_literal_threshold: usize = 5),
     /// Lint: UPPER_CASE_ACRONYMS.
     ///
     /// Enables verbose mode. Triggers if there is more than one uppercase char next to each other
@@ -459,6 +469,24 @@ pub(crate) fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// Whether to **only** check for missing documentation in items visible within the current
     /// crate. For example, `pub(crate)` items.
     (missing_docs_in_crate_items: bool = false),
+    /// Lint: WILDCARD_IMPORTS.
+    ///
+    /// List of path prefixes for which wildcard imports are always allowed.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// allowed-wildcard-imports = ["utils", "crate::ast"]
+    /// ```
+    (allowed_wildcard_imports: Vec<String> = Vec::new()),
+    /// Lint: SHADOW_SAME, SHADOW_REUSE, SHADOW_UNRELATED.
+    ///
+    /// Binding names which are allowed to shadow a previous binding, e.g. `["guard", "span"]`.
+    (allowed_shadow_names: Vec<String> = Vec::new()),
+    /// Lint: EXPENSIVE_LOG_ARGUMENTS.
+    ///
+    /// The paths of the logging macros whose arguments are checked.
+    (log_macros: Vec<String> = super::DEFAULT_LOG_MACROS.iter().map(ToString::to_string).collect()),
 }
 
 /// Search for the configuration file.
@@ -551,6 +579,10 @@ pub fn format_error(error: Box<dyn Error>) -> String {
             use fmt::Write;
 
             fields.sort_unstable();
+            let suggestion = prefix
+                .strip_prefix("unknown field `")
+                .and_then(|rest| rest.split_once('`'))
+                .and_then(|(unknown, _)| closest_field(unknown, &fields));
 
             let (rows, column_widths) = calculate_dimensions(&fields);
 
@@ -569,6 +601,9 @@ pub fn format_error(error: Box<dyn Error>) -> String {
                 }
             }
             write!(msg, "\n{suffix}").unwrap();
+            if let Some(suggestion) = suggestion {
+                write!(msg, "\nhelp: perhaps you meant `{suggestion}`").unwrap();
+            }
             msg
         } else {
             s
@@ -576,6 +611,17 @@ pub fn format_error(error: Box<dyn Error>) -> String {
     }
 }
 
+/// Returns the known field closest to `unknown` by edit distance, if any is close enough to be
+/// a plausible typo.
+fn closest_field<'a>(unknown: &str, fields: &[&'a str]) -> Option<&'a str> {
+    let limit = cmp::max(unknown.len(), 3) / 3;
+    fields
+        .iter()
+        .filter_map(|field| edit_distance(unknown, field, limit).map(|distance| (distance, *field)))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, field)| field)
+}
+
 // `parse_unknown_field_message` will become unnecessary if
 // https://github.com/alexcrichton/toml-rs/pull/364 is merged.
 fn parse_unknown_field_message(s: &str) -> Option<(&str, Vec<&str>, &str)> {
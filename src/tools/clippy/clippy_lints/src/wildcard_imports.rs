@@ -72,6 +72,9 @@
     ///
     /// These exceptions can be disabled using the `warn-on-all-wildcard-imports` configuration flag.
     ///
+    /// Further paths can be allowed with the `allowed-wildcard-imports` configuration, which takes
+    /// a list of path prefixes such as `["utils", "crate::ast"]`.
+    ///
     /// ### Known problems
     /// If macros are imported through the wildcard, this macro is not included
     /// by the suggestion and has to be added by hand.
@@ -101,13 +104,15 @@
 #[derive(Default)]
 pub struct WildcardImports {
     warn_on_all: bool,
+    allowed_paths: Vec<String>,
     test_modules_deep: u32,
 }
 
 impl WildcardImports {
-    pub fn new(warn_on_all: bool) -> Self {
+    pub fn new(warn_on_all: bool, allowed_paths: Vec<String>) -> Self {
         Self {
             warn_on_all,
+            allowed_paths,
             test_modules_deep: 0,
         }
     }
@@ -127,6 +132,7 @@ fn check_item(&mut self, cx: &LateContext<'_>, item: &Item<'_>) {
         if_chain! {
             if let ItemKind::Use(use_path, UseKind::Glob) = &item.kind;
             if self.warn_on_all || !self.check_exceptions(item, use_path.segments);
+            if !self.is_allowed_path(use_path.segments);
             let used_imports = cx.tcx.names_imported_by_glob_use(item.owner_id.def_id);
             if !used_imports.is_empty(); // Already handled by `unused_imports`
             then {
@@ -209,6 +215,22 @@ fn check_exceptions(&self, item: &Item<'_>, segments: &[PathSegment<'_>]) -> boo
             || is_prelude_import(segments)
             || (is_super_only_import(segments) && self.test_modules_deep > 0)
     }
+
+    // Allow imports from the paths listed in `allowed-wildcard-imports`.
+    fn is_allowed_path(&self, segments: &[PathSegment<'_>]) -> bool {
+        if self.allowed_paths.is_empty() {
+            return false;
+        }
+        let path = segments
+            .iter()
+            .map(|segment| segment.ident.as_str())
+            .collect::<Vec<_>>()
+            .join("::");
+        self.allowed_paths.iter().any(|allowed| {
+            path.strip_prefix(allowed.as_str())
+                .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+        })
+    }
 }
 
 // Allow "...prelude::..::*" imports.
@@ -20,12 +20,56 @@ fn suggest_grouping(self) -> usize {
     }
 }
 
+/// How the digits of a formatted literal are grouped with underscores.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DigitGrouping {
+    /// Keep the underscores as they are written.
+    AsWritten,
+    /// Groups of 4 digits for binary and hexadecimal literals, of 3 digits otherwise.
+    Standard,
+    /// Groups of the given number of digits.
+    Size(usize),
+}
+
+/// How the type suffix of a formatted literal is separated from its digits.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SuffixSeparator {
+    /// Keep the suffix separated or not, as it is written.
+    AsWritten,
+    /// Separate the suffix with an underscore, like `1_000_u64`.
+    Underscore,
+    /// Glue the suffix to the digits, like `1_000u64`.
+    None,
+}
+
+/// The style in which numeric literals are formatted, so that lints suggesting reformatted
+/// literals agree with each other.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct LiteralFormat {
+    pub grouping: DigitGrouping,
+    pub suffix_separator: SuffixSeparator,
+}
+
+impl Default for LiteralFormat {
+    fn default() -> Self {
+        Self {
+            grouping: DigitGrouping::Standard,
+            suffix_separator: SuffixSeparator::Underscore,
+        }
+    }
+}
+
 /// A helper method to format numeric literals with digit grouping.
 /// `lit` must be a valid numeric literal without suffix.
 pub fn format(lit: &str, type_suffix: Option<&str>, float: bool) -> String {
     NumericLiteral::new(lit, type_suffix, float).format()
 }
 
+/// Like [`format`], but in the given style.
+pub fn format_with(lit: &str, type_suffix: Option<&str>, float: bool, style: LiteralFormat) -> String {
+    NumericLiteral::new(lit, type_suffix, float).format_with(style)
+}
+
 #[derive(Debug)]
 pub struct NumericLiteral<'a> {
     /// Which radix the literal was represented in.
@@ -43,6 +87,8 @@ pub struct NumericLiteral<'a> {
 
     /// The type suffix, including preceding underscore if present.
     pub suffix: Option<&'a str>,
+    /// Whether the type suffix is separated from the digits with an underscore.
+    pub suffix_separated: bool,
 }
 
 impl<'a> NumericLiteral<'a> {
@@ -85,7 +131,8 @@ pub fn new(lit: &'a str, suffix: Option<&'a str>, float: bool) -> Self {
             (Some(p), s)
         };
 
-        if suffix.is_some() && sans_prefix.ends_with('_') {
+        let suffix_separated = suffix.is_some() && sans_prefix.ends_with('_');
+        if suffix_separated {
             // The '_' before the suffix isn't part of the digits
             sans_prefix = &sans_prefix[..sans_prefix.len() - 1];
         }
@@ -99,6 +146,7 @@ pub fn new(lit: &'a str, suffix: Option<&'a str>, float: bool) -> Self {
             fraction,
             exponent,
             suffix,
+            suffix_separated,
         }
     }
 
@@ -139,31 +187,52 @@ pub fn split_digit_parts(digits: &str, float: bool) -> (&str, Option<&str>, Opti
 
     /// Returns literal formatted in a sensible way.
     pub fn format(&self) -> String {
+        self.format_with(LiteralFormat::default())
+    }
+
+    /// Returns literal formatted in the given style.
+    pub fn format_with(&self, style: LiteralFormat) -> String {
         let mut output = String::new();
 
         if let Some(prefix) = self.prefix {
             output.push_str(prefix);
         }
 
-        let group_size = self.radix.suggest_grouping();
-
-        Self::group_digits(
-            &mut output,
-            self.integer,
-            group_size,
-            true,
-            self.radix == Radix::Hexadecimal,
-        );
+        let group_size = match style.grouping {
+            DigitGrouping::AsWritten => None,
+            DigitGrouping::Standard => Some(self.radix.suggest_grouping()),
+            DigitGrouping::Size(size) => Some(size),
+        };
 
-        if let Some(fraction) = self.fraction {
-            output.push('.');
-            Self::group_digits(&mut output, fraction, group_size, false, false);
-        }
+        if let Some(group_size) = group_size {
+            Self::group_digits(
+                &mut output,
+                self.integer,
+                group_size,
+                true,
+                self.radix == Radix::Hexadecimal,
+            );
+
+            if let Some(fraction) = self.fraction {
+                output.push('.');
+                Self::group_digits(&mut output, fraction, group_size, false, false);
+            }
 
-        if let Some((separator, exponent)) = self.exponent {
-            if exponent != "0" {
+            if let Some((separator, exponent)) = self.exponent {
+                if exponent != "0" {
+                    output.push_str(separator);
+                    Self::group_digits(&mut output, exponent, group_size, true, false);
+                }
+            }
+        } else {
+            output.push_str(self.integer);
+            if let Some(fraction) = self.fraction {
+                output.push('.');
+                output.push_str(fraction);
+            }
+            if let Some((separator, exponent)) = self.exponent {
                 output.push_str(separator);
-                Self::group_digits(&mut output, exponent, group_size, true, false);
+                output.push_str(exponent);
             }
         }
 
@@ -171,7 +240,14 @@ pub fn format(&self) -> String {
             if output.ends_with('.') {
                 output.push('0');
             }
-            output.push('_');
+            let separated = match style.suffix_separator {
+                SuffixSeparator::AsWritten => self.suffix_separated,
+                SuffixSeparator::Underscore => true,
+                SuffixSeparator::None => false,
+            };
+            if separated {
+                output.push('_');
+            }
             output.push_str(suffix);
         }
 
@@ -2,9 +2,13 @@
 // warn on lints, that are included in `rust-lang/rust`s bootstrap
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
+mod output;
+
+use output::OutputFormat;
 use std::env;
+use std::io::{self, BufReader, Write};
 use std::path::PathBuf;
-use std::process::{self, Command};
+use std::process::{self, Command, Stdio};
 
 const CARGO_CLIPPY_HELP: &str = r#"Checks a package to catch common mistakes and improve your Rust code.
 
@@ -17,6 +21,8 @@
     -h, --help               Print this message
     -V, --version            Print version info and exit
     --explain LINT           Print the documentation for a given lint
+    --output-format FORMAT   Print the lint results to stdout as `json` or as a `sarif` log,
+                             in addition to the terminal diagnostics on stderr
 
 Other options are the same as `cargo check`.
 
@@ -73,18 +79,20 @@ struct ClippyCmd {
     cargo_subcommand: &'static str,
     args: Vec<String>,
     clippy_args: Vec<String>,
+    output_format: OutputFormat,
 }
 
 impl ClippyCmd {
-    fn new<I>(mut old_args: I) -> Self
+    fn new<I>(mut old_args: I) -> Result<Self, String>
     where
         I: Iterator<Item = String>,
     {
         let mut cargo_subcommand = "check";
         let mut args = vec![];
         let mut clippy_args: Vec<String> = vec![];
+        let mut output_format = OutputFormat::Human;
 
-        for arg in old_args.by_ref() {
+        while let Some(arg) = old_args.next() {
             match arg.as_str() {
                 "--fix" => {
                     cargo_subcommand = "fix";
@@ -94,8 +102,18 @@ fn new<I>(mut old_args: I) -> Self
                     clippy_args.push("--no-deps".into());
                     continue;
                 },
+                "--output-format" => {
+                    let format = old_args.next().ok_or("missing value for `--output-format`")?;
+                    output_format = OutputFormat::parse(&format)?;
+                    continue;
+                },
                 "--" => break,
-                _ => {},
+                _ => {
+                    if let Some(format) = arg.strip_prefix("--output-format=") {
+                        output_format = OutputFormat::parse(format)?;
+                        continue;
+                    }
+                },
             }
 
             args.push(arg);
@@ -106,11 +124,12 @@ fn new<I>(mut old_args: I) -> Self
             clippy_args.push("--no-deps".into());
         }
 
-        Self {
+        Ok(Self {
             cargo_subcommand,
             args,
             clippy_args,
-        }
+            output_format,
+        })
     }
 
     fn path() -> PathBuf {
@@ -142,6 +161,10 @@ fn into_std_cmd(self) -> Command {
             .arg(self.cargo_subcommand)
             .args(&self.args);
 
+        if self.output_format != OutputFormat::Human {
+            cmd.arg("--message-format=json").stdout(Stdio::piped());
+        }
+
         cmd
     }
 }
@@ -150,15 +173,35 @@ fn process<I>(old_args: I) -> Result<(), i32>
 where
     I: Iterator<Item = String>,
 {
-    let cmd = ClippyCmd::new(old_args);
+    let cmd = ClippyCmd::new(old_args).map_err(|e| {
+        eprintln!("error: {e}");
+        1
+    })?;
+    let output_format = cmd.output_format;
 
     let mut cmd = cmd.into_std_cmd();
 
-    let exit_status = cmd
-        .spawn()
-        .expect("could not run cargo")
-        .wait()
-        .expect("failed to wait for cargo?");
+    let mut child = cmd.spawn().expect("could not run cargo");
+    if output_format != OutputFormat::Human {
+        let stdout = child.stdout.take().expect("stdout of cargo is piped");
+        let diagnostics = output::collect_diagnostics(BufReader::new(stdout)).expect("failed to read cargo output");
+        for rendered in diagnostics
+            .iter()
+            .filter_map(|diagnostic| diagnostic.rendered.as_deref())
+        {
+            eprint!("{rendered}");
+        }
+        let output = match output_format {
+            OutputFormat::Json => output::to_json(&diagnostics),
+            OutputFormat::Sarif => output::to_sarif(&diagnostics),
+            OutputFormat::Human => unreachable!(),
+        };
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, &output).expect("failed to write output");
+        writeln!(stdout).expect("failed to write output");
+    }
+
+    let exit_status = child.wait().expect("failed to wait for cargo?");
 
     if exit_status.success() {
         Ok(())
@@ -169,12 +212,12 @@ fn process<I>(old_args: I) -> Result<(), i32>
 
 #[cfg(test)]
 mod tests {
-    use super::ClippyCmd;
+    use super::{ClippyCmd, OutputFormat};
 
     #[test]
     fn fix() {
         let args = "cargo clippy --fix".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!("fix", cmd.cargo_subcommand);
         assert!(!cmd.args.iter().any(|arg| arg.ends_with("unstable-options")));
     }
@@ -182,7 +225,7 @@ fn fix() {
     #[test]
     fn fix_implies_no_deps() {
         let args = "cargo clippy --fix".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert!(cmd.clippy_args.iter().any(|arg| arg == "--no-deps"));
     }
 
@@ -191,14 +234,35 @@ fn no_deps_not_duplicated_with_fix() {
         let args = "cargo clippy --fix -- --no-deps"
             .split_whitespace()
             .map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!(cmd.clippy_args.iter().filter(|arg| *arg == "--no-deps").count(), 1);
     }
 
+    #[test]
+    fn output_format() {
+        let args = "cargo clippy --output-format sarif --all-targets -- -D warnings"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!(cmd.output_format, OutputFormat::Sarif);
+        assert!(cmd.args.iter().any(|arg| arg == "--all-targets"));
+        assert!(!cmd.args.iter().any(|arg| arg.contains("sarif")));
+
+        let args = "cargo clippy --output-format=json"
+            .split_whitespace()
+            .map(ToString::to_string);
+        assert_eq!(ClippyCmd::new(args).unwrap().output_format, OutputFormat::Json);
+
+        let args = "cargo clippy --output-format=xml"
+            .split_whitespace()
+            .map(ToString::to_string);
+        assert!(ClippyCmd::new(args).is_err());
+    }
+
     #[test]
     fn check() {
         let args = "cargo clippy".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!("check", cmd.cargo_subcommand);
     }
 }
@@ -0,0 +1,349 @@
+//! Machine readable output formats of `cargo clippy`.
+//!
+//! Cargo is run with `--message-format=json`, and the compiler diagnostics it
+//! emits are collected and converted to either a JSON document or a
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log.
+
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The usual terminal output.
+    Human,
+    /// A JSON document with all diagnostics.
+    Json,
+    /// A SARIF log, e.g. for code scanning dashboards.
+    Sarif,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err(format!(
+                "invalid output format `{s}`, expected one of `human`, `json` or `sarif`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The lint name or error code, e.g. `clippy::enum_glob_use` or `E0308`.
+    pub code: Option<String>,
+    pub level: String,
+    pub message: String,
+    pub spans: Vec<Span>,
+    pub suggestions: Vec<Suggestion>,
+    /// The diagnostic as rendered for the terminal.
+    pub rendered: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Span {
+    pub file: String,
+    pub line_start: u64,
+    pub column_start: u64,
+    pub line_end: u64,
+    pub column_end: u64,
+    pub label: Option<String>,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: String,
+    pub applicability: Option<String>,
+    pub edits: Vec<(Span, String)>,
+}
+
+/// Reads the JSON messages emitted by cargo and collects the compiler diagnostics.
+///
+/// Diagnostics emitted more than once, e.g. for both the library and the test
+/// target of a crate, are only collected once. Diagnostics without a code and
+/// without spans, like "aborting due to previous error", are skipped.
+pub fn collect_diagnostics(reader: impl BufRead) -> io::Result<Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+    let mut seen = HashSet::new();
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(message) = serde_json::from_str::<Value>(&line) else { continue };
+        if message["reason"] != "compiler-message" {
+            continue;
+        }
+        let Some(diagnostic) = parse_diagnostic(&message["message"]) else { continue };
+        if (diagnostic.code.is_some() || !diagnostic.spans.is_empty())
+            && seen.insert((diagnostic.code.clone(), diagnostic.rendered.clone()))
+        {
+            diagnostics.push(diagnostic);
+        }
+    }
+    Ok(diagnostics)
+}
+
+fn parse_diagnostic(message: &Value) -> Option<Diagnostic> {
+    let suggestions = message["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|child| {
+            let edits: Vec<_> = child["spans"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|span| {
+                    let replacement = span["suggested_replacement"].as_str()?;
+                    Some((parse_span(span)?, replacement.to_string()))
+                })
+                .collect();
+            (!edits.is_empty()).then(|| Suggestion {
+                message: child["message"].as_str().unwrap_or_default().to_string(),
+                applicability: child["spans"][0]["suggestion_applicability"]
+                    .as_str()
+                    .map(ToString::to_string),
+                edits,
+            })
+        })
+        .collect();
+
+    Some(Diagnostic {
+        code: message["code"]["code"].as_str().map(ToString::to_string),
+        level: message["level"].as_str()?.to_string(),
+        message: message["message"].as_str()?.to_string(),
+        spans: message["spans"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(parse_span)
+            .collect(),
+        suggestions,
+        rendered: message["rendered"].as_str().map(ToString::to_string),
+    })
+}
+
+fn parse_span(span: &Value) -> Option<Span> {
+    Some(Span {
+        file: span["file_name"].as_str()?.replace('\\', "/"),
+        line_start: span["line_start"].as_u64()?,
+        column_start: span["column_start"].as_u64()?,
+        line_end: span["line_end"].as_u64()?,
+        column_end: span["column_end"].as_u64()?,
+        label: span["label"].as_str().map(ToString::to_string),
+        is_primary: span["is_primary"].as_bool().unwrap_or_default(),
+    })
+}
+
+/// The URL of the documentation of the lint or error code.
+fn docs_url(code: &str) -> Option<String> {
+    if let Some(lint) = code.strip_prefix("clippy::") {
+        Some(format!(
+            "https://rust-lang.github.io/rust-clippy/master/index.html#{lint}"
+        ))
+    } else if code.starts_with('E') && code[1..].bytes().all(|b| b.is_ascii_digit()) {
+        Some(format!("https://doc.rust-lang.org/error_codes/{code}.html"))
+    } else {
+        None
+    }
+}
+
+fn json_span(span: &Span) -> Value {
+    json!({
+        "file": span.file,
+        "line_start": span.line_start,
+        "column_start": span.column_start,
+        "line_end": span.line_end,
+        "column_end": span.column_end,
+        "label": span.label,
+        "is_primary": span.is_primary,
+    })
+}
+
+pub fn to_json(diagnostics: &[Diagnostic]) -> Value {
+    let diagnostics: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let lint = diagnostic.code.as_deref().and_then(clippy_lints::lint_metadata);
+            json!({
+                "code": diagnostic.code,
+                "level": diagnostic.level,
+                "message": diagnostic.message,
+                "group": lint.map(|lint| lint.group),
+                "docs_url": diagnostic.code.as_deref().and_then(docs_url),
+                "spans": diagnostic.spans.iter().map(json_span).collect::<Vec<_>>(),
+                "suggestions": diagnostic.suggestions.iter().map(|suggestion| json!({
+                    "message": suggestion.message,
+                    "applicability": suggestion.applicability,
+                    "edits": suggestion.edits.iter().map(|(span, replacement)| json!({
+                        "span": json_span(span),
+                        "replacement": replacement,
+                    })).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({ "diagnostics": diagnostics })
+}
+
+fn sarif_level(level: &str) -> &'static str {
+    match level {
+        "error" | "deny" | "forbid" => "error",
+        "warning" | "warn" => "warning",
+        "allow" => "none",
+        _ => "note",
+    }
+}
+
+fn sarif_region(span: &Span) -> Value {
+    json!({
+        "startLine": span.line_start,
+        "startColumn": span.column_start,
+        "endLine": span.line_end,
+        "endColumn": span.column_end,
+    })
+}
+
+fn sarif_location(span: &Span) -> Value {
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": span.file },
+            "region": sarif_region(span),
+        },
+    });
+    if let Some(label) = &span.label {
+        location["message"] = json!({ "text": label });
+    }
+    location
+}
+
+fn sarif_rule(code: &str) -> Value {
+    let mut rule = json!({ "id": code });
+    if let Some(url) = docs_url(code) {
+        rule["helpUri"] = json!(url);
+    }
+    if let Some(lint) = clippy_lints::lint_metadata(code) {
+        rule["shortDescription"] = json!({ "text": lint.description });
+        rule["fullDescription"] = json!({ "text": lint.explanation });
+        rule["help"] = json!({ "text": lint.explanation, "markdown": lint.explanation });
+        rule["defaultConfiguration"] = json!({ "level": sarif_level(lint.default_level) });
+        rule["properties"] = json!({ "tags": [lint.group] });
+    }
+    rule
+}
+
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> Value {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut result = json!({
+                "level": sarif_level(&diagnostic.level),
+                "message": { "text": diagnostic.message },
+            });
+            if let Some(code) = diagnostic.code.as_deref() {
+                let index = rule_ids.iter().position(|id| *id == code).unwrap_or_else(|| {
+                    rule_ids.push(code);
+                    rule_ids.len() - 1
+                });
+                result["ruleId"] = json!(code);
+                result["ruleIndex"] = json!(index);
+            }
+            let (primary, related): (Vec<_>, Vec<_>) =
+                diagnostic.spans.iter().partition(|span| span.is_primary);
+            result["locations"] = primary.into_iter().map(sarif_location).collect();
+            if !related.is_empty() {
+                result["relatedLocations"] = related.into_iter().map(sarif_location).collect();
+            }
+            if !diagnostic.suggestions.is_empty() {
+                result["fixes"] = diagnostic
+                    .suggestions
+                    .iter()
+                    .map(|suggestion| {
+                        json!({
+                            "description": { "text": suggestion.message },
+                            "artifactChanges": suggestion.edits.iter().map(|(span, replacement)| json!({
+                                "artifactLocation": { "uri": span.file },
+                                "replacements": [{
+                                    "deletedRegion": sarif_region(span),
+                                    "insertedContent": { "text": replacement },
+                                }],
+                            })).collect::<Vec<_>>(),
+                        })
+                    })
+                    .collect();
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "clippy",
+                    "informationUri": "https://github.com/rust-lang/rust-clippy",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rule_ids.into_iter().map(sarif_rule).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = concat!(
+        r#"{"reason":"compiler-message","message":{"#,
+        r#""message":"usage of wildcard import for enum variants","#,
+        r#""code":{"code":"clippy::enum_glob_use","explanation":null},"level":"warning","#,
+        r#""spans":[{"file_name":"src/main.rs","line_start":3,"line_end":3,"column_start":5,"column_end":16,"#,
+        r#""is_primary":true,"label":null,"suggested_replacement":null,"suggestion_applicability":null}],"#,
+        r#""children":[{"message":"try this","code":null,"level":"help","#,
+        r#""spans":[{"file_name":"src/main.rs","line_start":3,"line_end":3,"column_start":5,"column_end":16,"#,
+        r#""is_primary":true,"label":null,"suggested_replacement":"Color::Red","#,
+        r#""suggestion_applicability":"MachineApplicable"}],"children":[],"rendered":null}],"#,
+        r#""rendered":"warning: usage of wildcard import for enum variants\n"}}"#,
+    );
+
+    #[test]
+    fn collect() {
+        let input = format!("{MESSAGE}\n{MESSAGE}\n{{\"reason\":\"build-finished\",\"success\":true}}\n");
+        let diagnostics = collect_diagnostics(input.as_bytes()).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code.as_deref(), Some("clippy::enum_glob_use"));
+        assert_eq!(diagnostic.spans.len(), 1);
+        assert_eq!(diagnostic.suggestions[0].edits[0].1, "Color::Red");
+    }
+
+    #[test]
+    fn sarif() {
+        let diagnostics = collect_diagnostics(MESSAGE.as_bytes()).unwrap();
+        let sarif = to_sarif(&diagnostics);
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "clippy::enum_glob_use");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["properties"]["tags"][0], "pedantic");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["shortDescription"]["text"],
+            "use items that import all variants of an enum"
+        );
+        assert_eq!(run["results"][0]["ruleIndex"], 0);
+        assert_eq!(run["results"][0]["level"], "warning");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            5
+        );
+        assert_eq!(
+            run["results"][0]["fixes"][0]["artifactChanges"][0]["replacements"][0]["insertedContent"]["text"],
+            "Color::Red"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+#![warn(clippy::shadow_unrelated)]
+
+fn main() {
+    let guard = 1;
+    let guard = 2;
+    let value = guard;
+    let value = 3;
+    let _ = value;
+}
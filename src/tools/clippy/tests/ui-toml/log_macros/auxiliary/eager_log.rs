@@ -0,0 +1,18 @@
+//! A logging crate whose macro evaluates its arguments before checking the level.
+
+pub fn enabled() -> bool {
+    false
+}
+
+pub fn write(args: std::fmt::Arguments<'_>, enabled: bool) {
+    if enabled {
+        println!("{args}");
+    }
+}
+
+#[macro_export]
+macro_rules! record {
+    ($($arg:tt)+) => {
+        $crate::write(format_args!($($arg)+), $crate::enabled())
+    };
+}
@@ -0,0 +1,26 @@
+// aux-build:eager_log.rs
+
+#![warn(clippy::expensive_log_arguments)]
+
+#[macro_use]
+extern crate eager_log;
+
+fn load() -> u32 {
+    1
+}
+
+fn main() {
+    let entries = vec![1, 2, 3];
+    let mut iter = entries.iter();
+
+    record!("loaded: {}", load());
+    record!("next: {:?}", iter.next());
+    record!("{}: {}", entries[0], entries.len() + 1);
+
+    // cheap arguments
+    record!("{} {:?}", entries[0], Some(entries[1]));
+    record!("{entries:?}");
+
+    // not a configured logging macro
+    println!("{}", load());
+}
@@ -0,0 +1,9 @@
+#![warn(clippy::unreadable_literal)]
+
+fn main() {
+    let _pass1 = 1234567;
+    let _pass2 = 0x1234567;
+    let _pass3 = 1.1234567;
+    let _fail1 = 12345678;
+    let _fail2 = 1234.12345678;
+}
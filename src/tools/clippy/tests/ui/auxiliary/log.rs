@@ -0,0 +1,38 @@
+//! A stand-in for the `log` crate, with macros expanding like the real ones.
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+pub fn max_level() -> Level {
+    Level::Info
+}
+
+pub fn __private_api_log(args: std::fmt::Arguments<'_>, _level: Level) {
+    let _ = args;
+}
+
+#[macro_export]
+macro_rules! log {
+    ($lvl:expr, $($arg:tt)+) => {{
+        let lvl = $lvl;
+        if lvl <= $crate::max_level() {
+            $crate::__private_api_log(format_args!($($arg)+), lvl);
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => ($crate::log!($crate::Level::Debug, $($arg)+))
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => ($crate::log!($crate::Level::Info, $($arg)+))
+}
@@ -0,0 +1,39 @@
+#![warn(clippy::borrowed_temporary)]
+#![allow(unused)]
+
+struct Config {
+    name: String,
+    ports: Vec<u16>,
+}
+
+fn load() -> Config {
+    Config {
+        name: String::new(),
+        ports: vec![80],
+    }
+}
+
+fn name() -> &'static str {
+    "name"
+}
+
+fn main() {
+    // lint: extended through a projection
+    let name_ref = &load().name;
+    let port = &load().ports[0];
+
+    // lint: dropped at the end of the statement
+    let iter = vec![1, 2].iter();
+    let trimmed = String::from(" a ").trim();
+
+    // fine: plain borrow of a temporary
+    let config = &load();
+    // fine: the owner is bound first
+    let config = load();
+    let name_ref = &config.name;
+    // fine: no borrow is returned
+    let len = vec![1, 2].len();
+    let first = load().ports.first().copied();
+    // fine: `'static` borrows
+    let s = name().trim_start_matches("n");
+}
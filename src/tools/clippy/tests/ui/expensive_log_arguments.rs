@@ -0,0 +1,35 @@
+// aux-build:log.rs
+
+#![warn(clippy::expensive_log_arguments)]
+
+#[macro_use]
+extern crate log;
+
+struct Cache {
+    entries: Vec<u32>,
+}
+
+impl Cache {
+    fn stats(&self) -> String {
+        format!("{} entries", self.entries.len())
+    }
+}
+
+fn main() {
+    let cache = Cache { entries: vec![1, 2, 3] };
+    let state = (1, "two");
+
+    debug!("{}", format!("{:?}", state));
+    info!("first: {}", cache.entries[0].to_string());
+    debug!("{0} {0}", state.1.to_string());
+
+    // cheap arguments
+    debug!("{:?} {}", state, cache.entries.len());
+    info!("{} {state:?}", cache.entries[0]);
+
+    // `log` evaluates the arguments only if the level is enabled
+    info!("cache: {}", cache.stats());
+
+    // not a logging macro
+    println!("{}", cache.stats());
+}
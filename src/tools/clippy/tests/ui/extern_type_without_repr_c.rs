@@ -0,0 +1,42 @@
+#![warn(clippy::extern_type_without_repr_c)]
+#![allow(dead_code, improper_ctypes, improper_ctypes_definitions)]
+
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[repr(C)]
+pub struct Size {
+    w: i32,
+    h: i32,
+}
+
+#[repr(transparent)]
+pub struct Handle(*mut u8);
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+#[repr(u8)]
+pub enum Color {
+    Red,
+    Green,
+}
+
+extern "C" {
+    fn draw(p: *const Point, s: Size, h: Handle, c: Color);
+    fn shape() -> Shape;
+}
+
+pub extern "C" fn move_point(p: &mut Point) {
+    p.x += 1;
+}
+
+pub fn rust_abi(_p: Point) -> Shape {
+    Shape::Circle
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+#![feature(extern_types)]
+#![warn(clippy::ffi_unsafe_extern_types)]
+#![allow(dead_code, improper_ctypes, improper_ctypes_definitions)]
+
+use std::ffi::c_char;
+
+extern "C" {
+    fn takes_string(s: String);
+    fn takes_str(s: &str);
+    fn returns_slice() -> *const [u8];
+    fn takes_vec(v: Vec<u8>);
+    fn takes_ptr(s: *const c_char, len: usize);
+}
+
+// ok, pointers to extern types are thin
+extern "C" {
+    type Opaque;
+
+    fn takes_opaque(o: *mut Opaque);
+    fn returns_opaque() -> &'static Opaque;
+}
+
+#[no_mangle]
+pub extern "C" fn exported(name: &str) -> usize {
+    name.len()
+}
+
+pub fn rust_abi(name: &str) -> String {
+    name.to_string()
+}
+
+fn main() {}
@@ -0,0 +1,165 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+
+#[derive(PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Eq for Point {}
+
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.to_bits().hash(state);
+        self.y.to_bits().hash(state);
+    }
+}
+
+struct Weighted {
+    id: u32,
+    weight: f32,
+}
+
+impl PartialEq for Weighted {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.weight == other.weight
+    }
+}
+
+impl Eq for Weighted {}
+
+impl Hash for Weighted {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.weight.to_bits().hash(state);
+    }
+}
+
+#[derive(PartialEq)]
+enum Shape {
+    Circle { radius: f64 },
+    Square(u32),
+}
+
+impl Eq for Shape {}
+
+impl Hash for Shape {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Shape::Circle { radius } => radius.to_bits().hash(state),
+            Shape::Square(side) => side.hash(state),
+        }
+    }
+}
+
+// ok, the floats are compared by their bits
+struct Bits {
+    x: f64,
+}
+
+impl PartialEq for Bits {
+    fn eq(&self, other: &Self) -> bool {
+        self.x.to_bits() == other.x.to_bits()
+    }
+}
+
+impl Eq for Bits {}
+
+impl Hash for Bits {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.to_bits().hash(state);
+    }
+}
+
+// ok, the floats are compared by their bits
+enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Value::Int(a) => a.hash(state),
+            Value::Float(a) => a.to_bits().hash(state),
+        }
+    }
+}
+
+// ok, the float isn't compared
+struct Keyed {
+    key: u32,
+    score: f64,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl Hash for Keyed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+// ok, not hashable, so it can't be the key of a hash map
+#[derive(PartialEq)]
+struct Vector {
+    x: f64,
+}
+
+impl Eq for Vector {}
+
+// ok, the cell is read explicitly
+struct Counter(Cell<u32>);
+
+impl Hash for Counter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.get().hash(state);
+    }
+}
+
+#[derive(Hash)]
+struct Entry {
+    name: String,
+    counter: Counter,
+}
+
+#[derive(Hash)]
+enum Slot {
+    Empty,
+    Used(Counter),
+}
+
+// ok, the cache isn't hashed
+struct Cached {
+    key: String,
+    cache: Cell<Option<usize>>,
+}
+
+impl Hash for Cached {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+fn main() {}
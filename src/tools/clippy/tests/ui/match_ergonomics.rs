@@ -0,0 +1,61 @@
+// run-rustfix
+
+#![warn(
+    clippy::needless_reference_pattern,
+    clippy::needless_ref_binding,
+    clippy::explicit_binding_deref
+)]
+#![allow(unused, clippy::needless_borrowed_reference, clippy::redundant_pattern_matching)]
+
+enum Shape {
+    Circle(f64),
+    Square { side: f64 },
+    Point,
+}
+
+const ORIGIN: (i32, i32) = (0, 0);
+
+fn needless_reference_pattern(shape: &Shape, pair: &(i32, i32), opt: &mut Option<String>) {
+    let _ = matches!(shape, &Shape::Point);
+    let _ = matches!(shape, &Shape::Circle(_));
+    let _ = matches!(shape, &Shape::Square { .. });
+    if let &mut None = opt {}
+
+    // Binds by value.
+    if let &Shape::Circle(radius) = shape {}
+    // Constants don't match through references.
+    let _ = matches!(pair, &ORIGIN);
+}
+
+fn needless_ref_binding(opt: &Option<String>, pair: &mut (String, String)) {
+    if let Some(ref s) = opt {}
+    let (ref mut a, ref mut b) = pair;
+
+    // Binds by value otherwise.
+    if let Some(ref s) = *opt {}
+    // Reborrows immutably.
+    let (ref a, _) = pair;
+    // The alternatives of an or pattern have to agree.
+    if let Some(ref s) | Some(ref s) = opt {}
+}
+
+fn explicit_binding_deref(pairs: &[(u32, u32)], opt: &Option<u32>, names: &[(String, u32)]) {
+    let _: u32 = pairs.iter().map(|(a, b)| *a + *b).sum();
+    if let Some(x) = opt {
+        let _ = *x + 1;
+    }
+    for (a, b) in pairs {
+        let _ = *a * *b;
+    }
+
+    // Not `Copy`.
+    for (name, age) in names {
+        let _ = (name.len(), *age);
+    }
+    // Used without being dereferenced.
+    if let Some(x) = opt {
+        let _ = x.count_ones() + *x;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+#![warn(clippy::narrowing_return_cast)]
+#![allow(clippy::cast_possible_truncation, clippy::let_and_return)]
+
+pub fn checksum(data: &[u8]) -> u8 {
+    let sum: u32 = data.iter().map(|&b| u32::from(b)).sum();
+    sum as u8
+}
+
+pub fn early_return(x: u64, early: bool) -> u16 {
+    if early {
+        return x as u16;
+    }
+    0
+}
+
+pub fn through_binding(x: i64) -> i32 {
+    let y = x as i32;
+    y
+}
+
+// widening casts are fine
+pub fn widen(x: u8) -> u32 {
+    x as u32
+}
+
+// private functions are not part of the API
+fn private(x: u32) -> u8 {
+    x as u8
+}
+
+pub fn fallible(x: u32) -> Result<u8, std::num::TryFromIntError> {
+    u8::try_from(x)
+}
+
+// the trait fixes the return type of its implementations
+pub struct Wrapper(pub u64);
+
+impl From<Wrapper> for u16 {
+    fn from(w: Wrapper) -> u16 {
+        w.0 as u16
+    }
+}
+
+fn main() {
+    let _ = private(1);
+}
@@ -0,0 +1,44 @@
+#![warn(clippy::repr_c_field_padding)]
+#![allow(dead_code)]
+
+#[repr(C)]
+struct Header {
+    tag: u8,
+    len: u64,
+    flags: u8,
+}
+
+#[repr(C)]
+struct Entry {
+    used: bool,
+    id: u32,
+    kind: u8,
+}
+
+#[repr(C)]
+struct Sorted {
+    len: u64,
+    count: u32,
+    tag: u8,
+}
+
+// Not `#[repr(C)]`, the compiler already reorders the fields
+struct Unordered {
+    tag: u8,
+    len: u64,
+    flags: u8,
+}
+
+#[repr(C, packed)]
+struct Packed {
+    tag: u8,
+    len: u64,
+}
+
+#[repr(C)]
+struct Generic<T> {
+    tag: u8,
+    value: T,
+}
+
+fn main() {}
@@ -0,0 +1,112 @@
+#![warn(clippy::inconsistent_size_hint, clippy::missing_exact_size_iterator)]
+
+struct Once(Option<u32>);
+
+impl Iterator for Once {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if let Some(value) = self.0.take() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(0))
+    }
+}
+
+struct Never;
+
+impl Iterator for Never {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (1, None)
+    }
+}
+
+struct Inverted;
+
+impl Iterator for Inverted {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (2, Some(1))
+    }
+}
+
+struct Countdown(usize);
+
+impl Iterator for Countdown {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.0 = self.0.checked_sub(1)?;
+        Some(self.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0, Some(self.0))
+    }
+}
+
+// Ok, implements `ExactSizeIterator`
+struct Exact(usize);
+
+impl Iterator for Exact {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.0 = self.0.checked_sub(1)?;
+        Some(self.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0, Some(self.0))
+    }
+}
+
+impl ExactSizeIterator for Exact {}
+
+// Ok, the bounds don't contradict `next`
+struct Empty;
+
+impl Iterator for Empty {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+// Ok, `next` may return `Some`
+struct Inner(std::vec::IntoIter<u32>);
+
+impl Iterator for Inner {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (1, None)
+    }
+}
+
+fn main() {}
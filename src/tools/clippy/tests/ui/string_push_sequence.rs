@@ -0,0 +1,84 @@
+#![warn(clippy::string_push_sequence)]
+#![allow(unused, clippy::assign_op_pattern)]
+
+fn main() {
+    let name = "world";
+
+    let mut greeting = String::new();
+    greeting.push_str("Hello, ");
+    greeting.push_str(name);
+    greeting.push('!');
+
+    let mut header = String::new();
+    header.push_str("GET ");
+    header.push('/');
+    header.push_str(" HTTP/1.1");
+
+    let mut out = String::from("{");
+    out.push_str("\"name\": ");
+    out += &name.to_uppercase();
+    out.push('}');
+
+    let mut sep = String::new();
+    sep.push_str("a");
+    sep.push_str("b");
+    sep.push_str("c");
+
+    // only two pushes
+    let mut short = String::new();
+    short.push_str("a");
+    short.push_str(name);
+
+    // pushes to different strings
+    let mut a = String::new();
+    let mut b = String::new();
+    a.push_str("a");
+    b.push_str("b");
+    a.push_str("a");
+}
+
+fn known_lengths(name: &str, c: char) {
+    let mut tag = String::from("<");
+    tag.push_str(name);
+    tag.push(c);
+    tag.push_str("/>");
+}
+
+#[clippy::msrv = "1.57"]
+fn msrv_1_57(name: &str) {
+    let mut greeting = String::new();
+    greeting.push_str("Hello, ");
+    greeting.push_str(name);
+    greeting.push('!');
+}
+
+fn loops(names: &[&str]) {
+    let mut line = String::new();
+    for _ in 0..80 {
+        line += "-";
+    }
+
+    let mut row = String::new();
+    for _ in 0..=3 {
+        row = row + "| ";
+        row += names[0];
+    }
+
+    let mut joined = String::new();
+    for &name in names {
+        joined += name;
+        joined += ", ";
+    }
+
+    // not preceded by `String::new()`
+    let mut from = String::from("a");
+    for _ in 0..3 {
+        from += "a";
+    }
+
+    // not grown by concatenation
+    let mut pushed = String::new();
+    for &name in names {
+        pushed.push_str(name);
+    }
+}
@@ -69,11 +69,28 @@ fn bar() -> Foo<'static> {
             Foo { foo_str: "foo" }
         }
 
-        // FIXME: the lint does not handle lifetimed struct
-        // `Self` should be applicable here
+        // `Foo<'a>` is exactly the self type, so `Self` can be used
+        // even though the struct has a lifetime parameter
         fn clone(&self) -> Foo<'a> {
             Foo { foo_str: self.foo_str }
         }
+
+        fn get(&self) -> &str {
+            let Foo { foo_str } = self;
+            foo_str
+        }
+
+        fn is_empty(&self) -> bool {
+            match *self {
+                Foo { foo_str } => foo_str.is_empty(),
+            }
+        }
+
+        // cannot replace with `Self`, `other` may have a different lifetime
+        fn same(&self, other: Foo) -> bool {
+            let Foo { foo_str } = other;
+            self.foo_str == foo_str
+        }
     }
 }
 
@@ -6,7 +6,7 @@
 use test_utils::{
     extract_range_or_offset, Fixture, RangeOrOffset, CURSOR_MARKER, ESCAPED_CURSOR_MARKER,
 };
-use tt::token_id::{Leaf, Subtree, TokenTree};
+use tt::token_id::{Delimiter, DelimiterKind, Ident, Leaf, Subtree, TokenTree};
 use vfs::{file_set::FileSet, VfsPath};
 
 use crate::{
@@ -310,7 +310,7 @@ pub fn parse_with_proc_macros(
     }
 }
 
-fn default_test_proc_macros() -> [(String, ProcMacro); 5] {
+fn default_test_proc_macros() -> [(String, ProcMacro); 6] {
     [
         (
             r#"
@@ -342,6 +342,20 @@ pub fn derive_identity(item: TokenStream) -> TokenStream {
         ),
         (
             r#"
+#[proc_macro_derive(DeriveMarker)]
+pub fn derive_marker(item: TokenStream) -> TokenStream {
+    loop {}
+}
+"#
+            .into(),
+            ProcMacro {
+                name: "DeriveMarker".into(),
+                kind: crate::ProcMacroKind::CustomDerive,
+                expander: Arc::new(DeriveMarkerProcMacroExpander),
+            },
+        ),
+        (
+            r#"
 #[proc_macro_attribute]
 pub fn input_replace(attr: TokenStream, _item: TokenStream) -> TokenStream {
     attr
@@ -483,6 +497,43 @@ fn expand(
     }
 }
 
+// Implements a `Marker` trait for the derived item, ignoring its generics
+#[derive(Debug)]
+struct DeriveMarkerProcMacroExpander;
+impl ProcMacroExpander for DeriveMarkerProcMacroExpander {
+    fn expand(
+        &self,
+        input: &Subtree,
+        _: Option<&Subtree>,
+        _: &Env,
+    ) -> Result<Subtree, ProcMacroExpansionError> {
+        let mut idents = input.token_trees.iter().filter_map(|tt| match tt {
+            TokenTree::Leaf(Leaf::Ident(it)) => Some(it),
+            _ => None,
+        });
+        let name = idents
+            .find(|it| matches!(&*it.text, "struct" | "enum" | "union"))
+            .and_then(|_| idents.next())
+            .ok_or_else(|| ProcMacroExpansionError::Panic("Expected an ADT".into()))?;
+        let ident =
+            |text: &str| TokenTree::Leaf(Leaf::Ident(Ident { text: text.into(), span: name.span }));
+        let body = Subtree {
+            delimiter: Delimiter { open: name.span, close: name.span, kind: DelimiterKind::Brace },
+            token_trees: Vec::new(),
+        };
+        Ok(Subtree {
+            delimiter: Delimiter::UNSPECIFIED,
+            token_trees: vec![
+                ident("impl"),
+                ident("Marker"),
+                ident("for"),
+                TokenTree::Leaf(Leaf::Ident(name.clone())),
+                TokenTree::Subtree(body),
+            ],
+        })
+    }
+}
+
 // Pastes the attribute input as its output
 #[derive(Debug)]
 struct AttributeInputReplaceProcMacroExpander;
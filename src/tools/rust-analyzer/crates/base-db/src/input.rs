@@ -33,15 +33,40 @@ pub struct SourceRoot {
     /// optimize salsa's query structure
     pub is_library: bool,
     file_set: FileSet,
+    pub analysis_policy: AnalysisPolicy,
+}
+
+/// Which files of a source root the IDE features analyze.
+///
+/// Files excluded from analysis are still loaded, so that navigating into them works, but no
+/// diagnostics, inlay hints or references are reported for them and edits like renames leave them
+/// alone. Typically this is used for large generated modules.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnalysisPolicy {
+    excluded: NoHashHashSet<FileId>,
+}
+
+impl AnalysisPolicy {
+    pub fn excluding(files: impl IntoIterator<Item = FileId>) -> AnalysisPolicy {
+        AnalysisPolicy { excluded: files.into_iter().collect() }
+    }
+
+    pub fn is_analyzed(&self, file: FileId) -> bool {
+        !self.excluded.contains(&file)
+    }
 }
 
 impl SourceRoot {
     pub fn new_local(file_set: FileSet) -> SourceRoot {
-        SourceRoot { is_library: false, file_set }
+        SourceRoot { is_library: false, file_set, analysis_policy: AnalysisPolicy::default() }
     }
 
     pub fn new_library(file_set: FileSet) -> SourceRoot {
-        SourceRoot { is_library: true, file_set }
+        SourceRoot { is_library: true, file_set, analysis_policy: AnalysisPolicy::default() }
+    }
+
+    pub fn with_analysis_policy(self, analysis_policy: AnalysisPolicy) -> SourceRoot {
+        SourceRoot { analysis_policy, ..self }
     }
 
     pub fn path_for_file(&self, file: &FileId) -> Option<&VfsPath> {
@@ -295,21 +320,40 @@ pub struct Dependency {
     pub crate_id: CrateId,
     pub name: CrateName,
     prelude: bool,
+    kind: DependencyKind,
+}
+
+/// The section of the manifest a dependency was declared in.
+///
+/// This is only used to present the crate graph, the analysis treats all dependencies the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
 }
 
 impl Dependency {
     pub fn new(name: CrateName, crate_id: CrateId) -> Self {
-        Self { name, crate_id, prelude: true }
+        Self { name, crate_id, prelude: true, kind: DependencyKind::Normal }
     }
 
     pub fn with_prelude(name: CrateName, crate_id: CrateId, prelude: bool) -> Self {
-        Self { name, crate_id, prelude }
+        Self { name, crate_id, prelude, kind: DependencyKind::Normal }
+    }
+
+    pub fn with_kind(name: CrateName, crate_id: CrateId, kind: DependencyKind) -> Self {
+        Self { name, crate_id, prelude: true, kind }
     }
 
     /// Whether this dependency is to be added to the depending crate's extern prelude.
     pub fn is_prelude(&self) -> bool {
         self.prelude
     }
+
+    pub fn kind(&self) -> DependencyKind {
+        self.kind
+    }
 }
 
 impl CrateGraph {
@@ -368,6 +412,17 @@ pub fn add_dep(
         Ok(())
     }
 
+    /// Removes the dependency of `from` named `name`, for example when an optional dependency is
+    /// disabled.
+    pub fn remove_dep(&mut self, from: CrateId, name: &CrateName) {
+        self.arena.get_mut(&from).unwrap().dependencies.retain(|dep| dep.name != *name);
+    }
+
+    /// Replaces the enabled cfg options of a crate, for example when its Cargo features change.
+    pub fn set_cfg_options(&mut self, krate: CrateId, cfg_options: CfgOptions) {
+        self.arena.get_mut(&krate).unwrap().cfg_options = cfg_options;
+    }
+
     pub fn is_empty(&self) -> bool {
         self.arena.is_empty()
     }
@@ -0,0 +1,117 @@
+//! This module provides the functionality needed to build the executable of a runnable with cargo
+//! and find its path in cargo's JSON output, for debugging.
+
+use std::{
+    io,
+    path::PathBuf,
+    process::{ChildStderr, ChildStdout, Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use cargo_metadata::Message;
+use command_group::CommandGroup;
+use paths::AbsPathBuf;
+use stdx::process::streaming_output;
+
+use crate::command::JodGroupChild;
+
+/// Runs `cmd`, a cargo command like `cargo build` or `cargo test --no-run`, with JSON messages
+/// and returns the path of the single executable it built. With `tests_only`, only executables
+/// built with the test harness are considered, as `cargo test` also builds the binaries of the
+/// package.
+///
+/// `is_cancelled` is polled while cargo runs. Once it returns `true`, cargo is killed and an error
+/// of kind [`io::ErrorKind::Interrupted`] is returned.
+pub fn build_executable(
+    mut cmd: Command,
+    tests_only: bool,
+    is_cancelled: &dyn Fn() -> bool,
+) -> io::Result<AbsPathBuf> {
+    cmd.arg("--message-format=json");
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::null());
+    tracing::debug!(?cmd, "building executable");
+    let mut child = cmd.group_spawn().map(JodGroupChild)?;
+
+    let stdout = child.0.inner().stdout.take().unwrap();
+    let stderr = child.0.inner().stderr.take().unwrap();
+    let reader = jod_thread::Builder::new()
+        .name("BuildExecutable".to_owned())
+        .spawn(move || read_output(stdout, stderr, tests_only))
+        .expect("failed to spawn thread");
+    let status = loop {
+        if let Some(status) = child.0.try_wait()? {
+            break status;
+        }
+        if is_cancelled() {
+            // Kill cargo before joining the reader, which only finishes once the pipes are closed.
+            drop(child);
+            drop(reader);
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "the build was cancelled"));
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let BuildOutput { mut executables, compiler_messages, error } = reader.join()?;
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("cargo exited with {status}:\n{compiler_messages}{error}"),
+        ));
+    }
+
+    match executables.len() {
+        1 => AbsPathBuf::try_from(executables.pop().unwrap()).map_err(|path| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("cargo reported a relative executable path: {}", path.display()),
+            )
+        }),
+        0 => Err(io::Error::new(io::ErrorKind::Other, "cargo built no executable")),
+        n => Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("cargo built {n} executables, expected one"),
+        )),
+    }
+}
+
+struct BuildOutput {
+    executables: Vec<PathBuf>,
+    compiler_messages: String,
+    error: String,
+}
+
+fn read_output(
+    stdout: ChildStdout,
+    stderr: ChildStderr,
+    tests_only: bool,
+) -> io::Result<BuildOutput> {
+    let mut executables = Vec::new();
+    let mut compiler_messages = String::new();
+    let mut error = String::new();
+    streaming_output(
+        stdout,
+        stderr,
+        &mut |line| match serde_json::from_str::<Message>(line) {
+            Ok(Message::CompilerArtifact(artifact)) => {
+                let Some(executable) = artifact.executable else { return };
+                let is_binary = artifact.target.crate_types.iter().any(|it| it == "bin");
+                let is_build_script = artifact.target.kind.iter().any(|it| it == "custom-build");
+                let is_test = artifact.profile.test;
+                if is_test || (!tests_only && is_binary && !is_build_script) {
+                    executables.push(PathBuf::from(executable));
+                }
+            }
+            Ok(Message::CompilerMessage(msg)) => {
+                if let Some(rendered) = msg.message.rendered {
+                    compiler_messages.push_str(&rendered);
+                }
+            }
+            _ => (),
+        },
+        &mut |line| {
+            error.push_str(line);
+            error.push('\n');
+        },
+    )?;
+    Ok(BuildOutput { executables, compiler_messages, error })
+}
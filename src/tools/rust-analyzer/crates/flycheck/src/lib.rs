@@ -4,24 +4,32 @@
 
 #![warn(rust_2018_idioms, unused_lifetimes, semicolon_in_expressions_from_macros)]
 
-use std::{
-    fmt, io,
-    process::{ChildStderr, ChildStdout, Command, Stdio},
-    time::Duration,
-};
+mod build_executable;
+mod command;
+mod test_runner;
+
+use std::{fmt, io, process::Command, time::Duration};
 
-use command_group::{CommandGroup, GroupChild};
 use crossbeam_channel::{never, select, unbounded, Receiver, Sender};
-use paths::AbsPathBuf;
+use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
-use stdx::process::streaming_output;
 
 pub use cargo_metadata::diagnostic::{
     Applicability, Diagnostic, DiagnosticCode, DiagnosticLevel, DiagnosticSpan,
     DiagnosticSpanMacroExpansion,
 };
 
+use crate::command::{CommandHandle, ParseFromLine};
+
+pub use crate::{
+    build_executable::build_executable,
+    test_runner::{CargoTestHandle, CargoTestMessage, TestState},
+};
+
+/// The argument of custom check commands replaced by the path of the saved file.
+const SAVED_FILE_PLACEHOLDER: &str = "$saved_file";
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum InvocationStrategy {
     Once,
@@ -98,8 +106,16 @@ pub fn spawn(
     }
 
     /// Schedule a re-start of the cargo check worker.
-    pub fn restart(&self) {
-        self.sender.send(Restart::Yes).unwrap();
+    ///
+    /// `saved_file` is the file whose save triggered the check, substituted for `$saved_file`
+    /// in custom commands.
+    pub fn restart(&self, saved_file: Option<AbsPathBuf>) {
+        self.sender.send(Restart::Yes { packages: Vec::new(), saved_file }).unwrap();
+    }
+
+    /// Schedule a re-start of the cargo check worker, only checking the given packages.
+    pub fn restart_for_packages(&self, packages: Vec<String>, saved_file: Option<AbsPathBuf>) {
+        self.sender.send(Restart::Yes { packages, saved_file }).unwrap();
     }
 
     /// Stop this cargo check worker.
@@ -150,7 +166,11 @@ pub enum Progress {
 }
 
 enum Restart {
-    Yes,
+    /// `packages` is empty if the whole workspace should be checked.
+    Yes {
+        packages: Vec<String>,
+        saved_file: Option<AbsPathBuf>,
+    },
     No,
 }
 
@@ -163,12 +183,12 @@ struct FlycheckActor {
     /// Either the workspace root of the workspace we are flychecking,
     /// or the project root of the project.
     root: AbsPathBuf,
-    /// CargoHandle exists to wrap around the communication needed to be able to
+    /// CommandHandle exists to wrap around the communication needed to be able to
     /// run `cargo check` without blocking. Currently the Rust standard library
     /// doesn't provide a way to read sub-process output without blocking, so we
     /// have to wrap sub-processes output handling in a thread and pass messages
     /// back over a channel.
-    cargo_handle: Option<CargoHandle>,
+    cargo_handle: Option<CommandHandle<CargoMessage>>,
 }
 
 enum Event {
@@ -209,32 +229,48 @@ fn run(mut self, inbox: Receiver<Restart>) {
                 Event::Restart(Restart::No) => {
                     self.cancel_check_process();
                 }
-                Event::Restart(Restart::Yes) => {
+                Event::Restart(Restart::Yes { mut packages, mut saved_file }) => {
                     // Cancel the previously spawned process
                     self.cancel_check_process();
                     while let Ok(restart) = inbox.recv_timeout(Duration::from_millis(50)) {
-                        // restart chained with a stop, so just cancel
-                        if let Restart::No = restart {
-                            continue 'event;
+                        match restart {
+                            // restart chained with a stop, so just cancel
+                            Restart::No => continue 'event,
+                            // restarts for different files check the whole workspace, and
+                            // the last saved file is substituted for `$saved_file`
+                            Restart::Yes { packages: next_packages, saved_file: next_file } => {
+                                if next_file.is_some() {
+                                    saved_file = next_file;
+                                }
+                                if packages.is_empty() || next_packages.is_empty() {
+                                    packages.clear();
+                                } else {
+                                    for package in next_packages {
+                                        if !packages.contains(&package) {
+                                            packages.push(package);
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
 
-                    let command = self.check_command();
-                    tracing::debug!(?command, "will restart flycheck");
-                    match CargoHandle::spawn(command) {
+                    let Some(command) = self.check_command(&packages, saved_file.as_deref()) else {
+                        tracing::debug!("flycheck command needs a saved file, skipping");
+                        continue;
+                    };
+                    let formatted_command = format!("{command:?}");
+                    tracing::debug!(command = %formatted_command, "will restart flycheck");
+                    match CommandHandle::spawn(command) {
                         Ok(cargo_handle) => {
-                            tracing::debug!(
-                                command = ?self.check_command(),
-                                "did  restart flycheck"
-                            );
+                            tracing::debug!(command = %formatted_command, "did  restart flycheck");
                             self.cargo_handle = Some(cargo_handle);
                             self.report_progress(Progress::DidStart);
                         }
                         Err(error) => {
                             self.report_progress(Progress::DidFailToRestart(format!(
-                                "Failed to run the following command: {:?} error={}",
-                                self.check_command(),
-                                error
+                                "Failed to run the following command: {} error={}",
+                                formatted_command, error
                             )));
                         }
                     }
@@ -246,10 +282,7 @@ fn run(mut self, inbox: Receiver<Restart>) {
                     let cargo_handle = self.cargo_handle.take().unwrap();
                     let res = cargo_handle.join();
                     if res.is_err() {
-                        tracing::error!(
-                            "Flycheck failed to run the following command: {:?}",
-                            self.check_command()
-                        );
+                        tracing::error!(flycheck_id = self.id, "Flycheck failed to run");
                     }
                     self.report_progress(Progress::DidFinish(res));
                 }
@@ -274,17 +307,18 @@ fn run(mut self, inbox: Receiver<Restart>) {
 
     fn cancel_check_process(&mut self) {
         if let Some(cargo_handle) = self.cargo_handle.take() {
-            tracing::debug!(
-                command = ?self.check_command(),
-                "did  cancel flycheck"
-            );
+            tracing::debug!(flycheck_id = self.id, "did  cancel flycheck");
             cargo_handle.cancel();
             self.report_progress(Progress::DidCancel);
         }
     }
 
-    fn check_command(&self) -> Command {
-        let (mut cmd, args) = match &self.config {
+    /// Only the given `packages` are checked by Cargo commands, unless it is empty.
+    ///
+    /// Returns `None` if the command refers to `$saved_file` and the check was not triggered by
+    /// saving a file.
+    fn check_command(&self, packages: &[String], saved_file: Option<&AbsPath>) -> Option<Command> {
+        let cmd = match &self.config {
             FlycheckConfig::CargoCommand {
                 command,
                 target_triples,
@@ -299,7 +333,13 @@ fn check_command(&self) -> Command {
                 let mut cmd = Command::new(toolchain::cargo());
                 cmd.arg(command);
                 cmd.current_dir(&self.root);
-                cmd.arg("--workspace");
+                if packages.is_empty() {
+                    cmd.arg("--workspace");
+                } else {
+                    for package in packages {
+                        cmd.args(["-p", package]);
+                    }
+                }
 
                 cmd.arg(if *ansi_color_output {
                     "--message-format=json-diagnostic-rendered-ansi"
@@ -328,7 +368,8 @@ fn check_command(&self) -> Command {
                     }
                 }
                 cmd.envs(extra_env);
-                (cmd, extra_args)
+                cmd.args(extra_args);
+                cmd
             }
             FlycheckConfig::CustomCommand {
                 command,
@@ -357,12 +398,17 @@ fn check_command(&self) -> Command {
                     }
                 }
 
-                (cmd, args)
+                for arg in args {
+                    if arg == SAVED_FILE_PLACEHOLDER {
+                        cmd.arg(saved_file?.as_os_str());
+                    } else {
+                        cmd.arg(arg);
+                    }
+                }
+                cmd
             }
         };
-
-        cmd.args(args);
-        cmd
+        Some(cmd)
     }
 
     fn send(&self, check_task: Message) {
@@ -370,132 +416,88 @@ fn send(&self, check_task: Message) {
     }
 }
 
-struct JodGroupChild(GroupChild);
-
-impl Drop for JodGroupChild {
-    fn drop(&mut self) {
-        _ = self.0.kill();
-        _ = self.0.wait();
-    }
-}
-
-/// A handle to a cargo process used for fly-checking.
-struct CargoHandle {
-    /// The handle to the actual cargo process. As we cannot cancel directly from with
-    /// a read syscall dropping and therefore terminating the process is our best option.
-    child: JodGroupChild,
-    thread: jod_thread::JoinHandle<io::Result<(bool, String)>>,
-    receiver: Receiver<CargoMessage>,
+enum CargoMessage {
+    CompilerArtifact(cargo_metadata::Artifact),
+    Diagnostic(Diagnostic),
 }
 
-impl CargoHandle {
-    fn spawn(mut command: Command) -> std::io::Result<CargoHandle> {
-        command.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::null());
-        let mut child = command.group_spawn().map(JodGroupChild)?;
-
-        let stdout = child.0.inner().stdout.take().unwrap();
-        let stderr = child.0.inner().stderr.take().unwrap();
-
-        let (sender, receiver) = unbounded();
-        let actor = CargoActor::new(sender, stdout, stderr);
-        let thread = jod_thread::Builder::new()
-            .name("CargoHandle".to_owned())
-            .spawn(move || actor.run())
-            .expect("failed to spawn thread");
-        Ok(CargoHandle { child, thread, receiver })
-    }
-
-    fn cancel(mut self) {
-        let _ = self.child.0.kill();
-        let _ = self.child.0.wait();
+impl ParseFromLine for CargoMessage {
+    fn from_line(line: &str, _error: &mut String) -> Option<Self> {
+        let mut deserializer = serde_json::Deserializer::from_str(line);
+        deserializer.disable_recursion_limit();
+        if let Ok(message) = JsonMessage::deserialize(&mut deserializer) {
+            return match message {
+                // Skip certain kinds of messages to only spend time on what's useful
+                JsonMessage::Cargo(message) => match message {
+                    cargo_metadata::Message::CompilerArtifact(artifact) if !artifact.fresh => {
+                        Some(CargoMessage::CompilerArtifact(artifact))
+                    }
+                    cargo_metadata::Message::CompilerMessage(msg) => {
+                        Some(CargoMessage::Diagnostic(msg.message))
+                    }
+                    _ => None,
+                },
+                JsonMessage::Rustc(message) => Some(CargoMessage::Diagnostic(message)),
+            };
+        }
+        None
     }
 
-    fn join(mut self) -> io::Result<()> {
-        let _ = self.child.0.kill();
-        let exit_status = self.child.0.wait()?;
-        let (read_at_least_one_message, error) = self.thread.join()?;
-        if read_at_least_one_message || exit_status.success() {
-            Ok(())
-        } else {
-            Err(io::Error::new(io::ErrorKind::Other, format!(
-                "Cargo watcher failed, the command produced no valid metadata (exit code: {exit_status:?}):\n{error}"
-            )))
-        }
+    fn from_eof() -> Option<Self> {
+        None
     }
 }
 
-struct CargoActor {
-    sender: Sender<CargoMessage>,
-    stdout: ChildStdout,
-    stderr: ChildStderr,
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonMessage {
+    Cargo(cargo_metadata::Message),
+    Rustc(Diagnostic),
 }
 
-impl CargoActor {
-    fn new(sender: Sender<CargoMessage>, stdout: ChildStdout, stderr: ChildStderr) -> CargoActor {
-        CargoActor { sender, stdout, stderr }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> AbsPathBuf {
+        AbsPathBuf::assert(if cfg!(windows) { "C:\\workspace" } else { "/workspace" }.into())
     }
 
-    fn run(self) -> io::Result<(bool, String)> {
-        // We manually read a line at a time, instead of using serde's
-        // stream deserializers, because the deserializer cannot recover
-        // from an error, resulting in it getting stuck, because we try to
-        // be resilient against failures.
-        //
-        // Because cargo only outputs one JSON object per line, we can
-        // simply skip a line if it doesn't parse, which just ignores any
-        // erroneous output.
-
-        let mut error = String::new();
-        let mut read_at_least_one_message = false;
-        let output = streaming_output(
-            self.stdout,
-            self.stderr,
-            &mut |line| {
-                read_at_least_one_message = true;
-
-                // Try to deserialize a message from Cargo or Rustc.
-                let mut deserializer = serde_json::Deserializer::from_str(line);
-                deserializer.disable_recursion_limit();
-                if let Ok(message) = JsonMessage::deserialize(&mut deserializer) {
-                    match message {
-                        // Skip certain kinds of messages to only spend time on what's useful
-                        JsonMessage::Cargo(message) => match message {
-                            cargo_metadata::Message::CompilerArtifact(artifact)
-                                if !artifact.fresh =>
-                            {
-                                self.sender.send(CargoMessage::CompilerArtifact(artifact)).unwrap();
-                            }
-                            cargo_metadata::Message::CompilerMessage(msg) => {
-                                self.sender.send(CargoMessage::Diagnostic(msg.message)).unwrap();
-                            }
-                            _ => (),
-                        },
-                        JsonMessage::Rustc(message) => {
-                            self.sender.send(CargoMessage::Diagnostic(message)).unwrap();
-                        }
-                    }
-                }
-            },
-            &mut |line| {
-                error.push_str(line);
-                error.push('\n');
+    fn custom_command_args(args: &[&str], saved_file: Option<&AbsPath>) -> Option<Vec<String>> {
+        let actor = FlycheckActor::new(
+            0,
+            Box::new(|_| ()),
+            FlycheckConfig::CustomCommand {
+                command: "check".to_owned(),
+                args: args.iter().map(|&it| it.to_owned()).collect(),
+                extra_env: FxHashMap::default(),
+                invocation_strategy: InvocationStrategy::PerWorkspace,
+                invocation_location: InvocationLocation::Workspace,
             },
+            root(),
         );
-        match output {
-            Ok(_) => Ok((read_at_least_one_message, error)),
-            Err(e) => Err(io::Error::new(e.kind(), format!("{e:?}: {error}"))),
-        }
+        let command = actor.check_command(&[], saved_file)?;
+        Some(command.get_args().map(|it| it.to_string_lossy().into_owned()).collect())
     }
-}
 
-enum CargoMessage {
-    CompilerArtifact(cargo_metadata::Artifact),
-    Diagnostic(Diagnostic),
-}
+    #[test]
+    fn substitutes_saved_file() {
+        let saved_file = root().join("main.rs");
+        let args =
+            custom_command_args(&["--file", "$saved_file", "--quiet"], Some(saved_file.as_path()));
+        assert_eq!(
+            args.unwrap(),
+            ["--file", &*saved_file.as_os_str().to_string_lossy(), "--quiet"]
+        );
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum JsonMessage {
-    Cargo(cargo_metadata::Message),
-    Rustc(Diagnostic),
+        // Only whole arguments are substituted.
+        let args = custom_command_args(&["--file=$saved_file"], Some(saved_file.as_path()));
+        assert_eq!(args.unwrap(), ["--file=$saved_file"]);
+    }
+
+    #[test]
+    fn skips_check_without_saved_file() {
+        assert_eq!(custom_command_args(&["$saved_file"], None), None);
+        assert_eq!(custom_command_args(&["--workspace"], None).unwrap(), ["--workspace"]);
+    }
 }
@@ -0,0 +1,134 @@
+//! This module provides the functionality needed to run `cargo test` in a background
+//! thread and report the result of each test in a channel.
+
+use std::process::Command;
+
+use crossbeam_channel::Receiver;
+use paths::AbsPath;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+use crate::command::{CommandHandle, ParseFromLine};
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum TestState {
+    Started,
+    Ok,
+    Ignored,
+    Failed {
+        #[serde(default)]
+        stdout: String,
+    },
+}
+
+/// A message of the `--format=json` output of libtest.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CargoTestMessage {
+    Test {
+        /// The path of the test within its test target, like `tests::it_works`.
+        name: String,
+        #[serde(flatten)]
+        state: TestState,
+    },
+    Suite,
+    /// The test process exited.
+    Finished,
+    /// A line of output which isn't a libtest message, like the output of a test run with
+    /// `--nocapture`.
+    Custom {
+        text: String,
+    },
+}
+
+impl ParseFromLine for CargoTestMessage {
+    fn from_line(line: &str, _error: &mut String) -> Option<Self> {
+        let mut deserializer = serde_json::Deserializer::from_str(line);
+        deserializer.disable_recursion_limit();
+        match CargoTestMessage::deserialize(&mut deserializer) {
+            Ok(message) => Some(message),
+            Err(_) => Some(CargoTestMessage::Custom { text: line.to_owned() }),
+        }
+    }
+
+    fn from_eof() -> Option<Self> {
+        Some(CargoTestMessage::Finished)
+    }
+}
+
+/// A running `cargo test` process. The process is killed when this is dropped.
+pub struct CargoTestHandle {
+    handle: CommandHandle<CargoTestMessage>,
+}
+
+impl CargoTestHandle {
+    /// Runs `cargo {cargo_args} --no-fail-fast -- {test_args}` in `root`, with the test output
+    /// in JSON format. `cargo_args` start with the subcommand, usually `test`.
+    pub fn spawn(
+        root: &AbsPath,
+        cargo_args: &[String],
+        test_args: &[String],
+        extra_env: &FxHashMap<String, String>,
+    ) -> std::io::Result<CargoTestHandle> {
+        let mut cmd = Command::new(toolchain::cargo());
+        cmd.current_dir(root);
+        cmd.envs(extra_env);
+        // The JSON output of libtest is unstable.
+        cmd.env("RUSTC_BOOTSTRAP", "1");
+        cmd.args(cargo_args);
+        cmd.arg("--no-fail-fast");
+        cmd.arg("--");
+        cmd.args(test_args);
+        cmd.args(["-Z", "unstable-options", "--format=json"]);
+        tracing::debug!(?cmd, "running tests");
+        Ok(CargoTestHandle { handle: CommandHandle::spawn(cmd)? })
+    }
+
+    /// The messages of the test run, ending with [`CargoTestMessage::Finished`].
+    pub fn receiver(&self) -> &Receiver<CargoTestMessage> {
+        &self.handle.receiver
+    }
+
+    pub fn cancel(self) {
+        self.handle.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> CargoTestMessage {
+        CargoTestMessage::from_line(line, &mut String::new()).unwrap()
+    }
+
+    #[test]
+    fn parses_libtest_messages() {
+        assert_eq!(
+            parse(r#"{ "type": "suite", "event": "started", "test_count": 2 }"#),
+            CargoTestMessage::Suite
+        );
+        assert_eq!(
+            parse(r#"{ "type": "test", "event": "started", "name": "tests::foo" }"#),
+            CargoTestMessage::Test { name: "tests::foo".to_owned(), state: TestState::Started }
+        );
+        assert_eq!(
+            parse(r#"{ "type": "test", "name": "tests::foo", "event": "ok" }"#),
+            CargoTestMessage::Test { name: "tests::foo".to_owned(), state: TestState::Ok }
+        );
+        assert_eq!(
+            parse(
+                r#"{ "type": "test", "name": "tests::bar", "event": "failed", "stdout": "boom\n" }"#
+            ),
+            CargoTestMessage::Test {
+                name: "tests::bar".to_owned(),
+                state: TestState::Failed { stdout: "boom\n".to_owned() }
+            }
+        );
+        assert_eq!(
+            parse("running 2 tests"),
+            CargoTestMessage::Custom { text: "running 2 tests".to_owned() }
+        );
+    }
+}
@@ -21,9 +21,10 @@ pub fn find_path(
     item: ItemInNs,
     from: ModuleId,
     prefer_no_std: bool,
+    prefer_defining_crate: bool,
 ) -> Option<ModPath> {
     let _p = profile::span("find_path");
-    find_path_inner(db, item, from, None, prefer_no_std)
+    find_path_inner(db, item, from, None, prefer_no_std, prefer_defining_crate)
 }
 
 pub fn find_path_prefixed(
@@ -32,9 +33,10 @@ pub fn find_path_prefixed(
     from: ModuleId,
     prefix_kind: PrefixKind,
     prefer_no_std: bool,
+    prefer_defining_crate: bool,
 ) -> Option<ModPath> {
     let _p = profile::span("find_path_prefixed");
-    find_path_inner(db, item, from, Some(prefix_kind), prefer_no_std)
+    find_path_inner(db, item, from, Some(prefix_kind), prefer_no_std, prefer_defining_crate)
 }
 
 const MAX_PATH_LEN: usize = 15;
@@ -74,6 +76,7 @@ fn find_path_inner(
     from: ModuleId,
     prefixed: Option<PrefixKind>,
     prefer_no_std: bool,
+    prefer_defining_crate: bool,
 ) -> Option<ModPath> {
     // - if the item is a builtin, it's in scope
     if let ItemInNs::Types(ModuleDefId::BuiltinType(builtin)) = item {
@@ -95,6 +98,7 @@ fn find_path_inner(
             MAX_PATH_LEN,
             prefixed,
             prefer_no_std || db.crate_supports_no_std(crate_root.krate),
+            prefer_defining_crate,
         );
     }
 
@@ -119,6 +123,7 @@ fn find_path_inner(
             from,
             prefixed,
             prefer_no_std,
+            prefer_defining_crate,
         ) {
             let data = db.enum_data(variant.parent);
             path.push_segment(data.variants[variant.local_id].name.clone());
@@ -141,6 +146,7 @@ fn find_path_inner(
         from,
         prefixed,
         prefer_no_std || db.crate_supports_no_std(crate_root.krate),
+        prefer_defining_crate,
         scope_name,
     )
 }
@@ -155,6 +161,7 @@ fn find_path_for_module(
     max_len: usize,
     prefixed: Option<PrefixKind>,
     prefer_no_std: bool,
+    prefer_defining_crate: bool,
 ) -> Option<ModPath> {
     if max_len == 0 {
         return None;
@@ -220,6 +227,7 @@ fn find_path_for_module(
         from,
         prefixed,
         prefer_no_std,
+        prefer_defining_crate,
         scope_name,
     )
 }
@@ -299,6 +307,7 @@ fn calculate_best_path(
     from: ModuleId,
     mut prefixed: Option<PrefixKind>,
     prefer_no_std: bool,
+    prefer_defining_crate: bool,
     scope_name: Option<Name>,
 ) -> Option<ModPath> {
     if max_len <= 1 {
@@ -326,6 +335,7 @@ fn calculate_best_path(
                 best_path_len - 1,
                 prefixed,
                 prefer_no_std,
+                prefer_defining_crate,
             ) {
                 path.push_segment(name);
 
@@ -342,6 +352,7 @@ fn calculate_best_path(
         // too (unless we can't name it at all). It could *also* be (re)exported by the same crate
         // that wants to import it here, but we always prefer to use the external path here.
 
+        let defining_crate = item.krate(db);
         let crate_graph = db.crate_graph();
         let extern_paths = crate_graph[from.krate].dependencies.iter().filter_map(|dep| {
             let import_map = db.import_map(dep.crate_id);
@@ -358,19 +369,40 @@ fn calculate_best_path(
                     max_len - 1,
                     prefixed,
                     prefer_no_std,
+                    prefer_defining_crate,
                 )?;
                 cov_mark::hit!(partially_imported);
                 path.push_segment(info.path.segments.last()?.clone());
-                Some(path)
+                Some((path, Some(dep.crate_id) == defining_crate))
             })
         });
 
-        for path in extern_paths {
-            let new_path = match best_path {
-                Some(best_path) => select_best_path(best_path, path, prefer_no_std),
-                None => path,
+        let mut best_path_is_defining = false;
+        for (path, is_defining) in extern_paths {
+            let (new_path, new_path_is_defining) = match best_path {
+                // Paths through std, core and alloc are ranked by `prefer_no_std` alone.
+                Some(best_path)
+                    if prefer_defining_crate
+                        && is_defining != best_path_is_defining
+                        && !(is_std_path(&best_path) && is_std_path(&path)) =>
+                {
+                    if is_defining {
+                        cov_mark::hit!(prefer_defining_crate);
+                        (path, true)
+                    } else {
+                        (best_path, true)
+                    }
+                }
+                Some(best_path) => {
+                    let new_path = select_best_path(best_path, path.clone(), prefer_no_std);
+                    let new_path_is_defining =
+                        if new_path == path { is_defining } else { best_path_is_defining };
+                    (new_path, new_path_is_defining)
+                }
+                None => (path, is_defining),
             };
             best_path = Some(new_path);
+            best_path_is_defining = new_path_is_defining;
         }
     }
     if let Some(module) = item.module(db) {
@@ -387,8 +419,13 @@ fn calculate_best_path(
     }
 }
 
+const STD_CRATES: [Name; 3] = [known::std, known::core, known::alloc];
+
+fn is_std_path(path: &ModPath) -> bool {
+    path.segments().first().map_or(false, |first| STD_CRATES.contains(first))
+}
+
 fn select_best_path(old_path: ModPath, new_path: ModPath, prefer_no_std: bool) -> ModPath {
-    const STD_CRATES: [Name; 3] = [known::std, known::core, known::alloc];
     match (old_path.segments().first(), new_path.segments().first()) {
         (Some(old), Some(new)) if STD_CRATES.contains(old) && STD_CRATES.contains(new) => {
             let rank = match prefer_no_std {
@@ -528,7 +565,12 @@ mod tests {
     /// `code` needs to contain a cursor marker; checks that `find_path` for the
     /// item the `path` refers to returns that same path when called from the
     /// module the cursor is in.
-    fn check_found_path_(ra_fixture: &str, path: &str, prefix_kind: Option<PrefixKind>) {
+    fn check_found_path_(
+        ra_fixture: &str,
+        path: &str,
+        prefix_kind: Option<PrefixKind>,
+        prefer_defining_crate: bool,
+    ) {
         let (db, pos) = TestDB::with_position(ra_fixture);
         let module = db.module_at_position(pos);
         let parsed_path_file = syntax::SourceFile::parse(&format!("use {path};"));
@@ -548,8 +590,14 @@ fn check_found_path_(ra_fixture: &str, path: &str, prefix_kind: Option<PrefixKin
             .take_types()
             .unwrap();
 
-        let found_path =
-            find_path_inner(&db, ItemInNs::Types(resolved), module, prefix_kind, false);
+        let found_path = find_path_inner(
+            &db,
+            ItemInNs::Types(resolved),
+            module,
+            prefix_kind,
+            false,
+            prefer_defining_crate,
+        );
         assert_eq!(found_path, Some(mod_path), "{prefix_kind:?}");
     }
 
@@ -560,10 +608,10 @@ fn check_found_path(
         absolute: &str,
         self_prefixed: &str,
     ) {
-        check_found_path_(ra_fixture, unprefixed, None);
-        check_found_path_(ra_fixture, prefixed, Some(PrefixKind::Plain));
-        check_found_path_(ra_fixture, absolute, Some(PrefixKind::ByCrate));
-        check_found_path_(ra_fixture, self_prefixed, Some(PrefixKind::BySelf));
+        check_found_path_(ra_fixture, unprefixed, None, false);
+        check_found_path_(ra_fixture, prefixed, Some(PrefixKind::Plain), false);
+        check_found_path_(ra_fixture, absolute, Some(PrefixKind::ByCrate), false);
+        check_found_path_(ra_fixture, self_prefixed, Some(PrefixKind::BySelf), false);
     }
 
     #[test]
@@ -1077,6 +1125,25 @@ pub mod sync {
         );
     }
 
+    #[test]
+    fn prefer_defining_crate_paths() {
+        let ra_fixture = r#"
+//- /main.rs crate:main deps:facade,inner
+$0
+
+//- /facade.rs crate:facade deps:inner
+pub use inner::nested::S;
+
+//- /inner.rs crate:inner
+pub mod nested {
+    pub struct S;
+}
+"#;
+        check_found_path_(ra_fixture, "facade::S", None, false);
+        cov_mark::check!(prefer_defining_crate);
+        check_found_path_(ra_fixture, "inner::nested::S", None, true);
+    }
+
     #[test]
     fn builtins_are_in_scope() {
         let code = r#"
@@ -19,13 +19,25 @@
     MacroCallLoc, MacroDefId, MacroDefKind, MacroFile, ProcMacroExpander,
 };
 
-/// Total limit on the number of tokens produced by any macro invocation.
-///
-/// If an invocation produces more tokens than this limit, it will not be stored in the database and
-/// an error will be emitted.
-///
-/// Actual max for `analysis-stats .` at some point: 30672.
-static TOKEN_LIMIT: Limit = Limit::new(1_048_576);
+/// Limits guarding macro expansion against pathological inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionLimits {
+    /// Total limit on the number of tokens produced by any macro invocation.
+    ///
+    /// If an invocation produces more tokens than this limit, it will not be stored in the
+    /// database and an error will be emitted.
+    ///
+    /// Actual max for `analysis-stats .` at some point: 30672.
+    pub max_output_tokens: usize,
+    /// Limit on the depth of macro invocations expanding to further macro invocations.
+    pub max_depth: usize,
+}
+
+impl Default for ExpansionLimits {
+    fn default() -> Self {
+        ExpansionLimits { max_output_tokens: 1_048_576, max_depth: 128 }
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TokenExpander {
@@ -86,6 +98,10 @@ pub(crate) fn map_id_up(&self, id: tt::TokenId) -> (tt::TokenId, mbe::Origin) {
 // FIXME: rename to ExpandDatabase
 #[salsa::query_group(AstDatabaseStorage)]
 pub trait AstDatabase: SourceDatabase {
+    /// The limits macro expansion is subject to, see [`ExpansionLimits`].
+    #[salsa::input]
+    fn expansion_limits(&self) -> ExpansionLimits;
+
     fn ast_id_map(&self, file_id: HirFileId) -> Arc<AstIdMap>;
 
     /// Main public API -- parses a hir file, not caring whether it's a real
@@ -452,13 +468,14 @@ fn macro_expand(db: &dyn AstDatabase, id: MacroCallId) -> ExpandResult<Option<Ar
     };
     let ExpandResult { value: mut tt, err } = expander.expand(db, id, &macro_arg.0);
     // Set a hard limit for the expanded tt
+    let token_limit = Limit::new(db.expansion_limits().max_output_tokens);
     let count = tt.count();
-    if TOKEN_LIMIT.check(count).is_err() {
+    if token_limit.check(count).is_err() {
         return ExpandResult::only_err(ExpandError::Other(
             format!(
                 "macro invocation exceeds token limit: produced {} tokens, limit is {}",
                 count,
-                TOKEN_LIMIT.inner(),
+                token_limit.inner(),
             )
             .into(),
         ));
@@ -469,6 +486,22 @@ fn macro_expand(db: &dyn AstDatabase, id: MacroCallId) -> ExpandResult<Option<Ar
     ExpandResult { value: Some(Arc::new(tt)), err }
 }
 
+/// Expands the given macro call like [`AstDatabase::macro_expand`] does, but bypasses the memoized
+/// result, including the one of [`AstDatabase::expand_proc_macro`]. This is used to measure how
+/// expensive an expansion is.
+pub fn macro_expand_uncached(
+    db: &dyn AstDatabase,
+    id: MacroCallId,
+) -> ExpandResult<Option<Arc<tt::Subtree>>> {
+    let loc: MacroCallLoc = db.lookup_intern_macro_call(id);
+    match loc.def.kind {
+        MacroDefKind::ProcMacro(..) if loc.eager.is_none() => {
+            expand_proc_macro(db, id).map(|tt| Some(Arc::new(tt)))
+        }
+        _ => macro_expand(db, id),
+    }
+}
+
 fn macro_expand_error(db: &dyn AstDatabase, macro_call: MacroCallId) -> Option<ExpandError> {
     db.macro_expand(macro_call).err
 }
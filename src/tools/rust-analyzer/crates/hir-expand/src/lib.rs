@@ -869,8 +869,17 @@ pub fn original_syntax_node(self, db: &dyn db::AstDatabase) -> Option<InFile<Syn
 
 impl InFile<SyntaxToken> {
     pub fn upmap(self, db: &dyn db::AstDatabase) -> Option<InFile<SyntaxToken>> {
+        self.upmap_with_origin(db).map(|(it, _)| it)
+    }
+
+    /// Like [`InFile::upmap`], but also returns whether the token was part of the macro input or
+    /// of the macro definition.
+    pub fn upmap_with_origin(
+        self,
+        db: &dyn db::AstDatabase,
+    ) -> Option<(InFile<SyntaxToken>, Origin)> {
         let expansion = self.file_id.expansion_info(db)?;
-        expansion.map_token_up(db, self.as_ref()).map(|(it, _)| it)
+        expansion.map_token_up(db, self.as_ref())
     }
 
     /// Falls back to the macro call range if the node cannot be mapped up fully.
@@ -9,5 +9,5 @@
     expr::{
         record_literal_missing_fields, record_pattern_missing_fields, BodyValidationDiagnostic,
     },
-    unsafe_check::{missing_unsafe, unsafe_expressions, UnsafeExpr},
+    unsafe_check::{is_unsafe_expr, missing_unsafe, unsafe_expressions, UnsafeExpr},
 };
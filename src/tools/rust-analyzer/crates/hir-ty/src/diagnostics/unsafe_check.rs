@@ -1,6 +1,7 @@
 //! Provides validations for unsafe code. Currently checks if unsafe functions are missing
 //! unsafe blocks.
 
+use chalk_ir::Safety;
 use hir_def::{
     body::Body,
     expr::{Expr, ExprId, UnaryOp},
@@ -53,51 +54,54 @@ pub fn unsafe_expressions(
     walk_unsafe(db, infer, def, body, current, false, unsafe_expr_cb)
 }
 
-fn walk_unsafe(
+/// Returns `true` if evaluating the expression itself, not counting its subexpressions, requires an
+/// `unsafe` context.
+pub fn is_unsafe_expr(
     db: &dyn HirDatabase,
     infer: &InferenceResult,
     def: DefWithBodyId,
     body: &Body,
     current: ExprId,
-    inside_unsafe_block: bool,
-    unsafe_expr_cb: &mut dyn FnMut(UnsafeExpr),
-) {
-    let expr = &body.exprs[current];
-    match expr {
-        &Expr::Call { callee, .. } => {
-            if let Some(func) = infer[callee].as_fn_def(db) {
-                if is_fn_unsafe_to_call(db, func) {
-                    unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
-                }
-            }
-        }
+) -> bool {
+    match &body.exprs[current] {
+        &Expr::Call { callee, .. } => match infer[callee].kind(Interner) {
+            TyKind::Function(fn_ptr) => matches!(fn_ptr.sig.safety, Safety::Unsafe),
+            _ => infer[callee].as_fn_def(db).map_or(false, |func| is_fn_unsafe_to_call(db, func)),
+        },
         Expr::Path(path) => {
             let resolver = resolver_for_expr(db.upcast(), def, current);
             let value_or_partial = resolver.resolve_path_in_value_ns(db.upcast(), path.mod_path());
-            if let Some(ResolveValueResult::ValueNs(ValueNs::StaticId(id))) = value_or_partial {
-                if db.static_data(id).mutable {
-                    unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
-                }
-            }
-        }
-        Expr::MethodCall { .. } => {
-            if infer
-                .method_resolution(current)
-                .map(|(func, _)| is_fn_unsafe_to_call(db, func))
-                .unwrap_or(false)
-            {
-                unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
-            }
+            matches!(
+                value_or_partial,
+                Some(ResolveValueResult::ValueNs(ValueNs::StaticId(id)))
+                    if db.static_data(id).mutable
+            )
         }
+        Expr::MethodCall { .. } => infer
+            .method_resolution(current)
+            .map_or(false, |(func, _)| is_fn_unsafe_to_call(db, func)),
         Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
-            if let TyKind::Raw(..) = &infer[*expr].kind(Interner) {
-                unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
-            }
-        }
-        Expr::Unsafe { body: child } => {
-            return walk_unsafe(db, infer, def, body, *child, true, unsafe_expr_cb);
+            matches!(infer[*expr].kind(Interner), TyKind::Raw(..))
         }
-        _ => {}
+        _ => false,
+    }
+}
+
+fn walk_unsafe(
+    db: &dyn HirDatabase,
+    infer: &InferenceResult,
+    def: DefWithBodyId,
+    body: &Body,
+    current: ExprId,
+    inside_unsafe_block: bool,
+    unsafe_expr_cb: &mut dyn FnMut(UnsafeExpr),
+) {
+    let expr = &body.exprs[current];
+    if let Expr::Unsafe { body: child } = expr {
+        return walk_unsafe(db, infer, def, body, *child, true, unsafe_expr_cb);
+    }
+    if is_unsafe_expr(db, infer, def, body, current) {
+        unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
     }
 
     expr.walk_child_exprs(|child| {
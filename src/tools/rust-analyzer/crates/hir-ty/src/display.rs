@@ -581,6 +581,7 @@ fn hir_fmt(
                             ItemInNs::Types((*def_id).into()),
                             module_id,
                             false,
+                            false,
                         ) {
                             write!(f, "{path}")?;
                         } else {
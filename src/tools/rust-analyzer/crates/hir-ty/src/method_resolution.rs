@@ -230,6 +230,11 @@ pub fn for_self_ty_without_blanket_impls(
             .flat_map(|it| it.iter().copied())
     }
 
+    /// Queries all blanket impls, that is impls whose self type is a type parameter.
+    pub fn blanket_impls(&self) -> impl Iterator<Item = ImplId> + '_ {
+        self.map.values().flat_map(|map| map.get(&None).into_iter()).flat_map(|v| v.iter().copied())
+    }
+
     /// Queries all impls of the given trait.
     pub fn for_trait(&self, trait_: TraitId) -> impl Iterator<Item = ImplId> + '_ {
         self.map
@@ -766,6 +771,29 @@ fn find_matching_impl(
     }
 }
 
+/// Checks whether the given impl applies to `self_ty`, that is whether the impl's self type unifies
+/// with it and the impl's where clauses hold.
+pub fn impl_applies_to_ty(
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    impl_: ImplId,
+    self_ty: &Ty,
+) -> bool {
+    let mut table = InferenceTable::new(db, env);
+    let impl_substs =
+        TyBuilder::subst_for_def(db, impl_, None).fill_with_inference_vars(&mut table).build();
+    let impl_self_ty = db.impl_self_ty(impl_).substitute(Interner, &impl_substs);
+    if !table.unify(&impl_self_ty, self_ty) {
+        return false;
+    }
+
+    let wcs = crate::chalk_db::convert_where_clauses(db, impl_.into(), &impl_substs)
+        .into_iter()
+        .map(|b| b.cast(Interner));
+    let goal = crate::Goal::all(Interner, wcs);
+    table.try_obligation(goal).is_some()
+}
+
 pub fn iterate_path_candidates(
     ty: &Canonical<Ty>,
     db: &dyn HirDatabase,
@@ -31,6 +31,7 @@ impl Default for TestDB {
     fn default() -> Self {
         let mut this = Self { storage: Default::default(), events: Default::default() };
         this.set_enable_proc_attr_macros(true);
+        this.set_expansion_limits(Default::default());
         this
     }
 }
@@ -103,7 +103,9 @@ fn next(&mut self) -> Option<Self::Item> {
     }
 }
 
-fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId, cb: impl FnMut(TraitId)) {
+/// Calls `cb` with the super traits the trait directly declares, as bounds on `Self` in its
+/// header or where clauses.
+pub fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId, cb: impl FnMut(TraitId)) {
     let resolver = trait_.resolver(db);
     let generic_params = db.generic_params(trait_.into());
     let trait_self = generic_params.find_trait_self_param();
@@ -61,6 +61,7 @@
     all_super_traits, autoderef,
     consteval::{unknown_const_as_generic, ComputedExpr, ConstEvalError, ConstExt},
     diagnostics::BodyValidationDiagnostic,
+    direct_super_traits,
     layout::layout_of_ty,
     method_resolution::{self, TyFingerprint},
     primitive::UintTy,
@@ -626,8 +627,15 @@ pub fn find_use_path(
         db: &dyn DefDatabase,
         item: impl Into<ItemInNs>,
         prefer_no_std: bool,
+        prefer_defining_crate: bool,
     ) -> Option<ModPath> {
-        hir_def::find_path::find_path(db, item.into().into(), self.into(), prefer_no_std)
+        hir_def::find_path::find_path(
+            db,
+            item.into().into(),
+            self.into(),
+            prefer_no_std,
+            prefer_defining_crate,
+        )
     }
 
     /// Finds a path that can be used to refer to the given item from within
@@ -638,6 +646,7 @@ pub fn find_use_path_prefixed(
         item: impl Into<ItemInNs>,
         prefix_kind: PrefixKind,
         prefer_no_std: bool,
+        prefer_defining_crate: bool,
     ) -> Option<ModPath> {
         hir_def::find_path::find_path_prefixed(
             db,
@@ -645,6 +654,7 @@ pub fn find_use_path_prefixed(
             self.into(),
             prefix_kind,
             prefer_no_std,
+            prefer_defining_crate,
         )
     }
 }
@@ -1858,6 +1868,13 @@ pub fn items_with_supertraits(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
         traits.iter().flat_map(|tr| Trait::from(*tr).items(db)).collect()
     }
 
+    /// The traits this trait directly declares as super traits.
+    pub fn direct_supertraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        let mut traits = Vec::new();
+        direct_super_traits(db.upcast(), self.id, |it| traits.push(it.into()));
+        traits
+    }
+
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_auto
     }
@@ -2806,6 +2823,37 @@ pub fn all_for_type(db: &dyn HirDatabase, Type { ty, env }: Type) -> Vec<Impl> {
         all
     }
 
+    /// Returns the blanket impls applying to the given type, from the type's crate and those of
+    /// its dependencies and dependents accepted by `crate_filter`.
+    ///
+    /// The standard library is skipped, as its blanket impls apply to nearly every type.
+    pub fn blanket_impls_for_type(
+        db: &dyn HirDatabase,
+        Type { ty, env }: Type,
+        crate_filter: impl Fn(Crate) -> bool,
+    ) -> Vec<Impl> {
+        let crate_graph = db.crate_graph();
+        let mut crates: Vec<_> = crate_graph
+            .transitive_deps(env.krate)
+            .chain(crate_graph.transitive_rev_deps(env.krate))
+            .map(|id| Crate { id })
+            .filter(|&krate| {
+                krate.id == env.krate || (!krate.is_builtin(db) && crate_filter(krate))
+            })
+            .collect();
+        crates.sort_by_key(|krate| krate.id);
+        crates.dedup();
+
+        crates
+            .into_iter()
+            .flat_map(|Crate { id }| {
+                db.trait_impls_in_crate(id).blanket_impls().collect::<Vec<_>>()
+            })
+            .filter(|&id| method_resolution::impl_applies_to_ty(db, env.clone(), id, &ty))
+            .map(Self::from)
+            .collect()
+    }
+
     pub fn all_for_trait(db: &dyn HirDatabase, trait_: Trait) -> Vec<Impl> {
         let krate = trait_.module(db).krate();
         let mut all = Vec::new();
@@ -2852,6 +2900,17 @@ pub fn is_builtin_derive(self, db: &dyn HirDatabase) -> Option<InFile<ast::Attr>
         let src = self.source(db)?;
         src.file_id.is_builtin_derive(db.upcast())
     }
+
+    /// Returns the `derive` attribute this impl was generated by, for both builtin and custom
+    /// derives.
+    pub fn derive_attr(self, db: &dyn HirDatabase) -> Option<InFile<ast::Attr>> {
+        let file_id = self.source(db)?.file_id;
+        if !file_id.is_custom_derive(db.upcast()) {
+            return file_id.is_builtin_derive(db.upcast());
+        }
+        let attr = file_id.call_node(db.upcast())?;
+        Some(attr.with_value(ast::Attr::cast(attr.value.clone())?))
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
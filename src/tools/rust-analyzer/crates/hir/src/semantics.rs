@@ -2,7 +2,11 @@
 
 mod source_to_def;
 
-use std::{cell::RefCell, fmt, iter, mem, ops};
+use std::{
+    cell::RefCell,
+    fmt, iter, mem, ops,
+    time::{Duration, Instant},
+};
 
 use base_db::{FileId, FileRange};
 use either::Either;
@@ -152,6 +156,28 @@ pub fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         self.imp.expand_attr_macro(item)
     }
 
+    /// Expands `macro_call` again, ignoring any cached result, and returns how long the expansion
+    /// took together with the number of tokens it produced.
+    pub fn measure_macro_expansion(
+        &self,
+        macro_call: &ast::MacroCall,
+    ) -> Option<(Duration, usize)> {
+        self.imp.measure_macro_expansion(macro_call)
+    }
+
+    /// Like [`Semantics::measure_macro_expansion`], for the attribute macro call on `item`.
+    pub fn measure_attr_macro_expansion(&self, item: &ast::Item) -> Option<(Duration, usize)> {
+        self.imp.measure_attr_macro_expansion(item)
+    }
+
+    /// Like [`Semantics::measure_macro_expansion`], for each derive of the `derive` attribute.
+    pub fn measure_derive_macro_expansions(
+        &self,
+        derive: &ast::Attr,
+    ) -> Option<Vec<Option<(Duration, usize)>>> {
+        self.imp.measure_derive_macro_expansions(derive)
+    }
+
     pub fn expand_derive_as_pseudo_attr_macro(&self, attr: &ast::Attr) -> Option<SyntaxNode> {
         self.imp.expand_derive_as_pseudo_attr_macro(attr)
     }
@@ -476,6 +502,10 @@ pub fn is_unsafe_method_call(&self, method_call_expr: &ast::MethodCallExpr) -> b
         self.imp.is_unsafe_method_call(method_call_expr)
     }
 
+    pub fn is_unsafe_call(&self, call_expr: &ast::CallExpr) -> bool {
+        self.imp.is_unsafe_call(call_expr)
+    }
+
     pub fn is_unsafe_ref_expr(&self, ref_expr: &ast::RefExpr) -> bool {
         self.imp.is_unsafe_ref_expr(ref_expr)
     }
@@ -520,6 +550,41 @@ fn expand(&self, macro_call: &ast::MacroCall) -> Option<SyntaxNode> {
         Some(node)
     }
 
+    fn measure_macro_expansion(&self, macro_call: &ast::MacroCall) -> Option<(Duration, usize)> {
+        let sa = self.analyze_no_infer(macro_call.syntax())?;
+        // Eager macros are expanded when their call id is created, the call ids of other macros
+        // are just interned, so each macro call is expanded exactly once here.
+        let start = Instant::now();
+        let file_id = sa.expand(self.db, InFile::new(sa.file_id, macro_call))?;
+        let macro_call_id = file_id.macro_file()?.macro_call_id;
+        Some(self.measure_expansion(start, macro_call_id))
+    }
+
+    fn measure_attr_macro_expansion(&self, item: &ast::Item) -> Option<(Duration, usize)> {
+        let src = self.wrap_node_infile(item.clone());
+        let macro_call_id = self.with_ctx(|ctx| ctx.item_to_macro_call(src))?;
+        Some(self.measure_expansion(Instant::now(), macro_call_id))
+    }
+
+    fn measure_derive_macro_expansions(
+        &self,
+        attr: &ast::Attr,
+    ) -> Option<Vec<Option<(Duration, usize)>>> {
+        let calls = self.derive_macro_calls(attr)?;
+        Some(
+            calls
+                .into_iter()
+                .map(|call| Some(self.measure_expansion(Instant::now(), call?)))
+                .collect(),
+        )
+    }
+
+    fn measure_expansion(&self, start: Instant, macro_call_id: MacroCallId) -> (Duration, usize) {
+        let expansion = hir_expand::db::macro_expand_uncached(self.db.upcast(), macro_call_id);
+        let elapsed = start.elapsed();
+        (elapsed, expansion.value.map_or(0, |tt| tt.count()))
+    }
+
     fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         let src = self.wrap_node_infile(item.clone());
         let macro_call_id = self.with_ctx(|ctx| ctx.item_to_macro_call(src))?;
@@ -1406,6 +1471,11 @@ fn is_unsafe_method_call(&self, method_call_expr: &ast::MethodCallExpr) -> bool
             .unwrap_or(false)
     }
 
+    fn is_unsafe_call(&self, call_expr: &ast::CallExpr) -> bool {
+        self.analyze(call_expr.syntax())
+            .map_or(false, |sa| sa.is_unsafe_expr(self.db, &call_expr.clone().into()))
+    }
+
     fn is_unsafe_ref_expr(&self, ref_expr: &ast::RefExpr) -> bool {
         ref_expr
             .expr()
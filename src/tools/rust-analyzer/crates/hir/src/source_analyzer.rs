@@ -35,8 +35,8 @@
 };
 use hir_ty::{
     diagnostics::{
-        record_literal_missing_fields, record_pattern_missing_fields, unsafe_expressions,
-        UnsafeExpr,
+        is_unsafe_expr, record_literal_missing_fields, record_pattern_missing_fields,
+        unsafe_expressions, UnsafeExpr,
     },
     method_resolution::{self, lang_items_for_bin_op},
     Adjustment, InferenceResult, Interner, Substitution, Ty, TyExt, TyKind, TyLoweringContext,
@@ -776,6 +776,12 @@ pub(crate) fn is_unsafe_macro_call(
         false
     }
 
+    pub(crate) fn is_unsafe_expr(&self, db: &dyn HirDatabase, expr: &ast::Expr) -> bool {
+        let (Some((def, body, _)), Some(infer)) = (&self.def, &self.infer) else { return false };
+        self.expr_id(db, expr)
+            .map_or(false, |expr_id| is_unsafe_expr(db, infer, *def, body, expr_id))
+    }
+
     fn resolve_impl_method_or_trait_def(
         &self,
         db: &dyn HirDatabase,
@@ -93,6 +93,8 @@ struct SymbolCollectorWork {
 
 pub struct SymbolCollector<'a> {
     db: &'a dyn HirDatabase,
+    /// Whether to lower bodies to collect the items declared in their blocks.
+    collect_bodies: bool,
     symbols: Vec<FileSymbol>,
     work: Vec<SymbolCollectorWork>,
     current_container_name: Option<SmolStr>,
@@ -102,8 +104,23 @@ pub struct SymbolCollector<'a> {
 /// all symbols that should be indexed for the given module.
 impl<'a> SymbolCollector<'a> {
     pub fn collect(db: &dyn HirDatabase, module: Module) -> Vec<FileSymbol> {
+        SymbolCollector::collect_inner(db, module, true)
+    }
+
+    /// Like [`SymbolCollector::collect`], but skips the items declared inside of bodies, which
+    /// avoids lowering them.
+    pub fn collect_without_bodies(db: &dyn HirDatabase, module: Module) -> Vec<FileSymbol> {
+        SymbolCollector::collect_inner(db, module, false)
+    }
+
+    fn collect_inner(
+        db: &dyn HirDatabase,
+        module: Module,
+        collect_bodies: bool,
+    ) -> Vec<FileSymbol> {
         let mut symbol_collector = SymbolCollector {
             db,
+            collect_bodies,
             symbols: Default::default(),
             current_container_name: None,
             // The initial work is the root module we're collecting, additional work will
@@ -189,6 +206,9 @@ fn collect_from_module(&mut self, module_id: ModuleId) {
     }
 
     fn collect_from_body(&mut self, body_id: impl Into<DefWithBodyId>) {
+        if !self.collect_bodies {
+            return;
+        }
         let body_id = body_id.into();
         let body = self.db.body(body_id);
 
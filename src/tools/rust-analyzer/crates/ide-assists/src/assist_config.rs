@@ -14,5 +14,17 @@ pub struct AssistConfig {
     pub allowed: Option<Vec<AssistKind>>,
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
+    pub prefer_defining_crate: bool,
     pub assist_emit_must_use: bool,
+    pub async_runtime: AsyncRuntime,
+    /// Additional mappings from paths of blocking std functions, like `std::fs::read`, to the
+    /// paths of their async equivalents. These take precedence over the built-in ones.
+    pub async_io_mappings: Vec<(String, String)>,
+}
+
+/// The async runtime whose IO functions replace blocking std ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncRuntime {
+    Tokio,
+    AsyncStd,
 }
@@ -0,0 +1,281 @@
+use hir::{AssocItem, HasSource};
+use ide_db::{defs::Definition, rename::source_edit_from_references, FxHashSet};
+use syntax::{
+    ast::{self, HasName},
+    AstNode,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: align_param_names_with_trait
+//
+// Renames the parameters of a trait method implementation to the names the trait declaration
+// gives them at the same positions, when the implementation uses the same names in another order.
+//
+// ```
+// trait Shape {
+//     fn resize(&mut self, width: u32, height: u32);
+// }
+//
+// struct Rect { w: u32, h: u32 }
+//
+// impl Shape for Rect {
+//     fn $0resize(&mut self, height: u32, width: u32) {
+//         self.w = width;
+//         self.h = height;
+//     }
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn resize(&mut self, width: u32, height: u32);
+// }
+//
+// struct Rect { w: u32, h: u32 }
+//
+// impl Shape for Rect {
+//     fn resize(&mut self, width: u32, height: u32) {
+//         self.w = height;
+//         self.h = width;
+//     }
+// }
+// ```
+pub(crate) fn align_param_names_with_trait(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let fn_ast = ctx.find_node_at_offset::<ast::Fn>()?;
+    let param_list = fn_ast.param_list()?;
+    let head_range = fn_ast.name()?.syntax().text_range().cover(param_list.syntax().text_range());
+    if !head_range.contains_range(ctx.selection_trimmed()) {
+        return None;
+    }
+
+    let func = ctx.sema.to_def(&fn_ast)?;
+    let trait_ = func.as_assoc_item(ctx.db())?.containing_trait_impl(ctx.db())?;
+    let trait_fn = trait_.items(ctx.db()).into_iter().find_map(|item| match item {
+        AssocItem::Function(it) if it.name(ctx.db()) == func.name(ctx.db()) => Some(it),
+        _ => None,
+    })?;
+    let trait_fn_ast = trait_fn.source(ctx.db())?.value;
+
+    let params = param_idents(&param_list)?;
+    let trait_names = param_idents(&trait_fn_ast.param_list()?)?
+        .iter()
+        .map(|it| it.name().map(|it| it.text().to_string()))
+        .collect::<Option<Vec<_>>>()?;
+    let names = params
+        .iter()
+        .map(|it| it.name().map(|it| it.text().to_string()))
+        .collect::<Option<Vec<_>>>()?;
+
+    if names == trait_names {
+        cov_mark::hit!(param_names_already_aligned);
+        return None;
+    }
+    // Only a permutation of the declared names is surely a mistake, other names may just be
+    // better suited to the implementation.
+    let unique_names: FxHashSet<_> = names.iter().collect();
+    let trait_names_set: FxHashSet<_> = trait_names.iter().collect();
+    if unique_names.len() != names.len() || unique_names != trait_names_set {
+        cov_mark::hit!(param_names_not_a_permutation);
+        return None;
+    }
+
+    acc.add(
+        AssistId("align_param_names_with_trait", AssistKind::RefactorRewrite),
+        "Align parameter names with the trait declaration",
+        param_list.syntax().text_range(),
+        |builder| {
+            for ((param, name), new_name) in params.iter().zip(&names).zip(&trait_names) {
+                if name == new_name {
+                    continue;
+                }
+                let Some(local) = ctx.sema.to_def(param) else { continue };
+                let def = Definition::Local(local);
+                if let Some(name) = param.name() {
+                    builder.replace(name.syntax().text_range(), new_name);
+                }
+                for (file_id, references) in def.usages(&ctx.sema).all() {
+                    builder.edit_file(file_id);
+                    for indel in source_edit_from_references(&references, def, new_name) {
+                        builder.replace(indel.delete, indel.insert);
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// The binding patterns of the parameters other than `self`, if they are all plain bindings.
+fn param_idents(param_list: &ast::ParamList) -> Option<Vec<ast::IdentPat>> {
+    param_list
+        .params()
+        .map(|param| match param.pat()? {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => Some(it),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn swaps_param_names() {
+        check_assist(
+            align_param_names_with_trait,
+            r#"
+trait Trait {
+    fn f(a: u32, b: bool);
+}
+struct S;
+impl Trait for S {
+    fn f(b$0: u32, a: bool) {
+        let _ = (a, b);
+    }
+}
+"#,
+            r#"
+trait Trait {
+    fn f(a: u32, b: bool);
+}
+struct S;
+impl Trait for S {
+    fn f(a: u32, b: bool) {
+        let _ = (b, a);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rotates_param_names_keeping_binding_modes() {
+        check_assist(
+            align_param_names_with_trait,
+            r#"
+trait Trait {
+    fn f(&self, x: i32, y: i32, z: i32) -> i32;
+}
+struct S;
+impl Trait for S {
+    fn $0f(&self, mut z: i32, x: i32, y: i32) -> i32 {
+        z += 1;
+        x * y * z
+    }
+}
+"#,
+            r#"
+trait Trait {
+    fn f(&self, x: i32, y: i32, z: i32) -> i32;
+}
+struct S;
+impl Trait for S {
+    fn f(&self, mut x: i32, y: i32, z: i32) -> i32 {
+        x += 1;
+        y * z * x
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_record_field_shorthands_valid() {
+        check_assist(
+            align_param_names_with_trait,
+            r#"
+struct P { a: u8, b: u8 }
+trait Trait {
+    fn f(a: u8, b: u8) -> P;
+}
+impl Trait for P {
+    fn f$0(b: u8, a: u8) -> P {
+        P { a, b }
+    }
+}
+"#,
+            r#"
+struct P { a: u8, b: u8 }
+trait Trait {
+    fn f(a: u8, b: u8) -> P;
+}
+impl Trait for P {
+    fn f(a: u8, b: u8) -> P {
+        P { a: b, b: a }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_aligned() {
+        cov_mark::check!(param_names_already_aligned);
+        check_assist_not_applicable(
+            align_param_names_with_trait,
+            r#"
+trait Trait {
+    fn f(a: u32, b: u32);
+}
+struct S;
+impl Trait for S {
+    fn f$0(a: u32, b: u32) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_other_names() {
+        cov_mark::check!(param_names_not_a_permutation);
+        check_assist_not_applicable(
+            align_param_names_with_trait,
+            r#"
+trait Trait {
+    fn f(a: u32, b: u32);
+}
+struct S;
+impl Trait for S {
+    fn f$0(b: u32, other: u32) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_trait_impls() {
+        check_assist_not_applicable(
+            align_param_names_with_trait,
+            r#"
+struct S;
+impl S {
+    fn f$0(b: u32, a: u32) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            align_param_names_with_trait,
+            r#"
+trait Trait {
+    fn f(a: u32, b: u32);
+}
+struct S;
+impl Trait for S {
+    fn f(b: u32, a: u32) {
+        $0let _ = a;
+    }
+}
+"#,
+        );
+    }
+}
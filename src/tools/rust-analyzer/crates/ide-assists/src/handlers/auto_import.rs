@@ -66,6 +66,15 @@
 //
 // In `VS Code` the configuration for this is `rust-analyzer.imports.prefix`.
 //
+// .Re-exports
+//
+// When an item of another crate is reachable through several crates, the shortest path is
+// inserted, which usually goes through a re-export. Enabling the `imports.prefer.definingCrate`
+// setting makes the path through the crate defining the item win instead. This applies to
+// completions, assists and diagnostic fixes alike.
+//
+// In `VS Code` the configuration for this is `rust-analyzer.imports.prefer.definingCrate`.
+//
 // image::https://user-images.githubusercontent.com/48062697/113020673-b85be580-917a-11eb-9022-59585f35d4f8.gif[]
 
 // Assist: auto_import
@@ -93,6 +102,7 @@ pub(crate) fn auto_import(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<
         &ctx.sema,
         ctx.config.insert_use.prefix_kind,
         ctx.config.prefer_no_std,
+        ctx.config.prefer_defining_crate,
     );
     if proposed_imports.is_empty() {
         return None;
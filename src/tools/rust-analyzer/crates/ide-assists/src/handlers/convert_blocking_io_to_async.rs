@@ -0,0 +1,412 @@
+use hir::{AsAssocItem, AssocItemContainer, HirDisplay, ModuleDef, PathResolution};
+use ide_db::{famous_defs::FamousDefs, RootDatabase};
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    SyntaxKind, SyntaxNode,
+};
+
+use crate::{AssistConfig, AssistContext, AssistId, AssistKind, Assists, AsyncRuntime};
+
+/// Blocking std functions with their `tokio` and `async-std` equivalents.
+const ASYNC_IO_MAPPINGS: &[(&str, &str, &str)] = &[
+    ("std::fs::copy", "tokio::fs::copy", "async_std::fs::copy"),
+    ("std::fs::create_dir", "tokio::fs::create_dir", "async_std::fs::create_dir"),
+    ("std::fs::create_dir_all", "tokio::fs::create_dir_all", "async_std::fs::create_dir_all"),
+    ("std::fs::metadata", "tokio::fs::metadata", "async_std::fs::metadata"),
+    ("std::fs::read", "tokio::fs::read", "async_std::fs::read"),
+    ("std::fs::read_to_string", "tokio::fs::read_to_string", "async_std::fs::read_to_string"),
+    ("std::fs::remove_dir", "tokio::fs::remove_dir", "async_std::fs::remove_dir"),
+    ("std::fs::remove_dir_all", "tokio::fs::remove_dir_all", "async_std::fs::remove_dir_all"),
+    ("std::fs::remove_file", "tokio::fs::remove_file", "async_std::fs::remove_file"),
+    ("std::fs::rename", "tokio::fs::rename", "async_std::fs::rename"),
+    ("std::fs::write", "tokio::fs::write", "async_std::fs::write"),
+    ("std::fs::File::create", "tokio::fs::File::create", "async_std::fs::File::create"),
+    ("std::fs::File::open", "tokio::fs::File::open", "async_std::fs::File::open"),
+    (
+        "std::net::TcpListener::bind",
+        "tokio::net::TcpListener::bind",
+        "async_std::net::TcpListener::bind",
+    ),
+    (
+        "std::net::TcpStream::connect",
+        "tokio::net::TcpStream::connect",
+        "async_std::net::TcpStream::connect",
+    ),
+    ("std::net::UdpSocket::bind", "tokio::net::UdpSocket::bind", "async_std::net::UdpSocket::bind"),
+];
+
+// Assist: convert_blocking_io_to_async
+//
+// Makes a function async and replaces the blocking `std::fs` and `std::net` functions it calls
+// with their async equivalents from the configured runtime. Calls without a known equivalent are
+// marked with a `FIXME` comment.
+//
+// ```
+// //- /main.rs crate:main deps:std
+// fn load$0(path: &str) -> Vec<u8> {
+//     std::fs::read(path)
+// }
+// //- /std.rs crate:std
+// pub mod fs {
+//     pub fn read(path: &str) -> Vec<u8> { loop {} }
+// }
+// ```
+// ->
+// ```
+// async fn load(path: &str) -> Vec<u8> {
+//     tokio::fs::read(path).await
+// }
+// ```
+pub(crate) fn convert_blocking_io_to_async(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let function = ctx.find_node_at_offset::<ast::Fn>()?;
+    let body = function.body()?;
+    if function.async_token().is_some() || body.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let krate = ctx.sema.scope(function.syntax())?.krate();
+    let std = FamousDefs(&ctx.sema, krate).std()?;
+
+    // Calls we know an async equivalent for, and the remaining blocking calls
+    let mut converted = Vec::new();
+    let mut blocking = Vec::new();
+    for expr in body.syntax().descendants().filter_map(ast::Expr::cast) {
+        if is_nested_sync_context(body.syntax(), expr.syntax()) {
+            continue;
+        }
+        let (func, path) = match &expr {
+            ast::Expr::CallExpr(call) => {
+                let Some(ast::Expr::PathExpr(callee)) = call.expr() else { continue };
+                let Some(path) = callee.path() else { continue };
+                match ctx.sema.resolve_path(&path) {
+                    Some(PathResolution::Def(ModuleDef::Function(func))) => (func, Some(path)),
+                    _ => continue,
+                }
+            }
+            ast::Expr::MethodCallExpr(call) => match ctx.sema.resolve_method_call(call) {
+                Some(func) => (func, None),
+                None => continue,
+            },
+            _ => continue,
+        };
+        if func.module(ctx.db()).krate() != std {
+            continue;
+        }
+        let Some(std_path) = blocking_std_path(ctx.db(), func) else { continue };
+        match (path, async_equivalent(ctx.config, &std_path)) {
+            (Some(path), Some(async_path)) => converted.push((expr, path, async_path)),
+            _ => blocking.push((expr, func.name(ctx.db()))),
+        }
+    }
+    if converted.is_empty() {
+        return None;
+    }
+
+    let label = match blocking.len() {
+        0 => "Convert to async IO".to_owned(),
+        1 => "Convert to async IO (1 call left blocking)".to_owned(),
+        n => format!("Convert to async IO ({n} calls left blocking)"),
+    };
+    let target = function.syntax().text_range();
+    acc.add(
+        AssistId("convert_blocking_io_to_async", AssistKind::RefactorRewrite),
+        label,
+        target,
+        |builder| {
+            let async_offset = match (function.unsafe_token(), function.abi()) {
+                (Some(unsafe_token), _) => unsafe_token.text_range().start(),
+                (None, Some(abi)) => abi.syntax().text_range().start(),
+                (None, None) => match function.fn_token() {
+                    Some(fn_token) => fn_token.text_range().start(),
+                    None => return,
+                },
+            };
+            builder.insert(async_offset, "async ");
+
+            for (call, path, async_path) in converted {
+                builder.replace(path.syntax().text_range(), async_path);
+                builder.insert(call.syntax().text_range().end(), ".await");
+            }
+
+            for (call, name) in blocking {
+                let Some(stmt) = call
+                    .syntax()
+                    .ancestors()
+                    .find(|it| it.parent().map_or(false, |it| it.kind() == SyntaxKind::STMT_LIST))
+                else {
+                    continue;
+                };
+                let indent = IndentLevel::from_node(&stmt);
+                builder.insert(
+                    stmt.text_range().start(),
+                    format!(
+                        "// FIXME: `{name}` has no async equivalent and still blocks\n{indent}"
+                    ),
+                );
+            }
+        },
+    )
+}
+
+/// Whether `node` is inside a closure or an item nested in `body`, where `.await` can't be used.
+fn is_nested_sync_context(body: &SyntaxNode, node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .take_while(|it| it != body)
+        .any(|it| it.kind() == SyntaxKind::CLOSURE_EXPR || ast::Item::can_cast(it.kind()))
+}
+
+/// The path of a function of the `std::fs`, `std::io` or `std::net` modules, with the private
+/// submodules left out, e.g. `std::net::TcpStream::connect`.
+fn blocking_std_path(db: &RootDatabase, func: hir::Function) -> Option<String> {
+    let modules = func.module(db).path_to_root(db);
+    let top_module = modules.iter().rev().nth(1)?.name(db)?.to_string();
+    if !matches!(top_module.as_str(), "fs" | "io" | "net") {
+        return None;
+    }
+    let owner = match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(trait_)) => format!("{}::", trait_.name(db)),
+        Some(AssocItemContainer::Impl(impl_)) => format!("{}::", impl_.self_ty(db).display(db)),
+        None => String::new(),
+    };
+    Some(format!("std::{top_module}::{owner}{}", func.name(db)))
+}
+
+fn async_equivalent(config: &AssistConfig, std_path: &str) -> Option<String> {
+    if let Some((_, async_path)) = config.async_io_mappings.iter().find(|(it, _)| it == std_path) {
+        return Some(async_path.clone());
+    }
+    let &(_, tokio, async_std) = ASYNC_IO_MAPPINGS.iter().find(|(it, ..)| *it == std_path)?;
+    let async_path = match config.async_runtime {
+        AsyncRuntime::Tokio => tokio,
+        AsyncRuntime::AsyncStd => async_std,
+    };
+    Some(async_path.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable, check_assist_with_config,
+        TEST_CONFIG,
+    };
+
+    use super::*;
+
+    const STD: &str = r#"
+//- /std.rs crate:std
+pub mod fs {
+    pub struct File;
+    impl File {
+        pub fn open(path: &str) -> File { File }
+    }
+    impl crate::io::Read for File {
+        fn read_to_string(&mut self, buf: &mut String) -> usize { 0 }
+    }
+    pub fn read(path: &str) -> Vec<u8> { loop {} }
+    pub fn write(path: &str, contents: &[u8]) {}
+}
+pub mod io {
+    pub trait Read {
+        fn read_to_string(&mut self, buf: &mut String) -> usize;
+    }
+}
+pub mod net {
+    mod tcp {
+        pub struct TcpStream;
+        impl TcpStream {
+            pub fn connect(addr: &str) -> TcpStream { TcpStream }
+        }
+    }
+    pub use self::tcp::TcpStream;
+}
+"#;
+
+    #[test]
+    fn converts_fs_calls() {
+        check_assist(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+use std::fs;
+
+pub fn copy$0(from: &str, to: &str) {{
+    let data = fs::read(from);
+    fs::write(to, &data);
+}}
+{STD}"#
+            ),
+            r#"
+use std::fs;
+
+pub async fn copy(from: &str, to: &str) {
+    let data = tokio::fs::read(from).await;
+    tokio::fs::write(to, &data).await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_nested_and_associated_calls() {
+        check_assist(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+use std::{{fs, net::TcpStream}};
+
+unsafe fn $0sync(path: &str) {{
+    let stream = TcpStream::connect("localhost:80");
+    fs::write(path, &fs::read(path));
+}}
+{STD}"#
+            ),
+            r#"
+use std::{fs, net::TcpStream};
+
+async unsafe fn sync(path: &str) {
+    let stream = tokio::net::TcpStream::connect("localhost:80").await;
+    tokio::fs::write(path, &tokio::fs::read(path).await).await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn marks_blocking_calls() {
+        check_assist_by_label(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+use std::{{fs::File, io::Read}};
+
+fn load$0(path: &str) -> String {{
+    let mut file = File::open(path);
+    let mut buf = String::new();
+    if !path.is_empty() {{
+        file.read_to_string(&mut buf);
+    }}
+    buf
+}}
+{STD}"#
+            ),
+            r#"
+use std::{fs::File, io::Read};
+
+async fn load(path: &str) -> String {
+    let mut file = tokio::fs::File::open(path).await;
+    let mut buf = String::new();
+    if !path.is_empty() {
+        // FIXME: `read_to_string` has no async equivalent and still blocks
+        file.read_to_string(&mut buf);
+    }
+    buf
+}
+"#,
+            "Convert to async IO (1 call left blocking)",
+        );
+    }
+
+    #[test]
+    fn uses_configured_runtime_and_mappings() {
+        let config = AssistConfig {
+            async_runtime: AsyncRuntime::AsyncStd,
+            async_io_mappings: vec![("std::fs::write".to_owned(), "smol::fs::write".to_owned())],
+            ..TEST_CONFIG
+        };
+        check_assist_with_config(
+            config,
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+fn copy$0(from: &str, to: &str) {{
+    std::fs::write(to, &std::fs::read(from));
+}}
+{STD}"#
+            ),
+            r#"
+async fn copy(from: &str, to: &str) {
+    smol::fs::write(to, &async_std::fs::read(from).await).await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_closures_and_nested_items() {
+        check_assist(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+fn load$0(path: &str) {{
+    let read = |path: &str| std::fs::read(path);
+    fn nested(path: &str) -> Vec<u8> {{
+        std::fs::read(path)
+    }}
+    std::fs::read(path);
+}}
+{STD}"#
+            ),
+            r#"
+async fn load(path: &str) {
+    let read = |path: &str| std::fs::read(path);
+    fn nested(path: &str) -> Vec<u8> {
+        std::fs::read(path)
+    }
+    tokio::fs::read(path).await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_async_fn() {
+        check_assist_not_applicable(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+async fn load$0(path: &str) {{
+    std::fs::read(path);
+}}
+{STD}"#
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_known_calls() {
+        check_assist_not_applicable(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+fn load$0(file: &mut std::fs::File, buf: &mut String) {{
+    std::io::Read::read_to_string(file, buf);
+}}
+{STD}"#
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            convert_blocking_io_to_async,
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+fn load(path: &str) {{
+    std::fs::read(path$0);
+}}
+{STD}"#
+            ),
+        );
+    }
+}
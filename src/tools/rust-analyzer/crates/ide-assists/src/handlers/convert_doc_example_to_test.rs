@@ -0,0 +1,869 @@
+use hir::{
+    AsAssocItem, AssocItemContainer, HasSource, HasVisibility, InFile, ModuleDef, ModuleSource,
+    Visibility,
+};
+use ide_db::{
+    assists::{AssistId, AssistKind},
+    source_change::SourceChangeBuilder,
+    RootDatabase,
+};
+use itertools::Itertools;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    algo::skip_whitespace_token,
+    ast::{
+        self, edit::IndentLevel, CommentKind, CommentPlacement, CommentShape, HasAttrs,
+        HasDocComments, HasModuleItem, HasName,
+    },
+    match_ast, AstNode, AstToken, Direction, SyntaxNode, TextRange, TextSize,
+};
+
+use crate::assist_context::{AssistContext, Assists};
+
+// Assist: convert_doc_example_to_test
+//
+// Moves a code block from an item's documentation into a test in the `tests` module.
+//
+// ```
+// /// Adds two numbers.
+// ///
+// /// # Examples
+// ///
+// /// ```$0
+// /// # use test::add;
+// /// assert_eq!(add(1, 2), 3);
+// /// ```
+// pub fn add(a: i32, b: i32) -> i32 { a + b }
+//
+// mod tests {
+//     use super::*;
+// }
+// ```
+// ->
+// ```
+// /// Adds two numbers.
+// pub fn add(a: i32, b: i32) -> i32 { a + b }
+//
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn add_doc_example() {
+//         use crate::add;
+//         assert_eq!(add(1, 2), 3);
+//     }
+// }
+// ```
+pub(crate) fn convert_doc_example_to_test(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let comment = ctx.find_token_at_offset::<ast::Comment>()?;
+    if !is_outer_line_doc(&comment) {
+        return None;
+    }
+    let item = comment.syntax().parent().filter(|it| ast::Item::can_cast(it.kind()))?;
+    let name = ast::AnyHasName::cast(item.clone())?.name()?;
+    let doc_comments: Vec<_> = ast::AnyHasDocComments::cast(item.clone())?
+        .doc_comments()
+        .filter(is_outer_line_doc)
+        .collect();
+    let lines: Vec<_> = doc_comments.iter().map(doc_line).collect();
+    let cursor = doc_comments.iter().position(|it| it == &comment)?;
+    let block =
+        code_blocks(&lines).into_iter().find(|it| (it.open..=it.close).contains(&cursor))?;
+    let attrs = test_attrs_for_info(block.info)?;
+
+    let db = ctx.db();
+    let module = ctx.sema.scope(&item)?.module();
+    let krate = module.krate().display_name(db)?.to_string();
+    let code: Vec<_> = lines[block.open + 1..block.close]
+        .iter()
+        .map(|line| replace_crate_name(&unhide_line(line), &krate, "crate"))
+        .collect();
+    if code.iter().any(|line| line.contains("fn main(")) {
+        // Rustdoc doesn't wrap examples that declare their own `main` in a function.
+        return None;
+    }
+
+    let tests_module = module
+        .children(db)
+        .find(|it| it.name(db).map_or(false, |name| name.to_smol_str() == "tests"));
+    let test_name = {
+        let base = format!("{}_doc_example", to_lower_snake_case(&name.text()));
+        let existing: Vec<_> = tests_module
+            .into_iter()
+            .flat_map(|it| it.declarations(db))
+            .filter_map(|it| it.name(db))
+            .map(|it| it.to_smol_str())
+            .collect();
+        (1..)
+            .map(|i| if i == 1 { base.clone() } else { format!("{base}_{i}") })
+            .find(|it| !existing.iter().any(|name| name.as_str() == it.as_str()))?
+    };
+
+    let mut test_fn = String::new();
+    for attr in attrs {
+        format_to!(test_fn, "#[{attr}]\n");
+    }
+    format_to!(test_fn, "fn {test_name}() {{\n");
+    for line in &code {
+        if !line.trim().is_empty() {
+            format_to!(test_fn, "    {line}");
+        }
+        test_fn.push('\n');
+    }
+    test_fn.push('}');
+
+    let (target_source, test_text) = match tests_module {
+        Some(tests_module) => (tests_module.definition_source(db), test_fn),
+        // The `tests` module may exist but be disabled by `cfg`, don't add a second one.
+        None if has_tests_module_item(&module.definition_source(db).value) => return None,
+        None => (
+            module.definition_source(db),
+            format!("#[cfg(test)]\nmod tests {{\n    use super::*;\n\n{}\n}}", indent(&test_fn, 1)),
+        ),
+    };
+    let target_file = target_source.file_id.file_id()?;
+
+    // Remove the `# Examples` header along with the example if nothing else follows it.
+    let mut start = block.open;
+    if block.close + 1 == lines.len()
+        && start >= 2
+        && lines[start - 1].trim().is_empty()
+        && lines[start - 2].trim() == "# Examples"
+    {
+        start -= 2;
+        if start > 0 && lines[start - 1].trim().is_empty() {
+            start -= 1;
+        }
+    }
+    // Don't leave two consecutive blank lines behind.
+    let mut end = block.close;
+    if start > 0
+        && lines[start - 1].trim().is_empty()
+        && lines.get(end + 1).map_or(false, |it| it.trim().is_empty())
+    {
+        end += 1;
+    }
+    let delete_range = TextRange::new(
+        doc_comments[start].syntax().text_range().start(),
+        line_end(doc_comments[end].syntax()),
+    );
+
+    acc.add(
+        AssistId("convert_doc_example_to_test", AssistKind::RefactorRewrite),
+        "Convert doc example to test",
+        TextRange::new(
+            doc_comments[block.open].syntax().text_range().start(),
+            doc_comments[block.close].syntax().text_range().end(),
+        ),
+        |builder| {
+            builder.delete(delete_range);
+            builder.edit_file(target_file);
+            insert_into_module(builder, target_source, &test_text);
+        },
+    )
+}
+
+// Assist: convert_test_to_doc_example
+//
+// Moves a test into the documentation of the function it calls as an example.
+//
+// ```
+// pub fn add(a: i32, b: i32) -> i32 { a + b }
+//
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn $0test_add() {
+//         let three = add(1, 2);
+//     }
+// }
+// ```
+// ->
+// ```
+// /// # Examples
+// ///
+// /// ```
+// /// use test::add;
+// ///
+// /// let three = add(1, 2);
+// /// ```
+// pub fn add(a: i32, b: i32) -> i32 { a + b }
+//
+// mod tests {
+//     use super::*;
+// }
+// ```
+pub(crate) fn convert_test_to_doc_example(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let test_fn = name.syntax().parent().and_then(ast::Fn::cast)?;
+    let info = info_for_test_attrs(&test_fn)?;
+    let body = test_fn.body()?;
+
+    let db = ctx.db();
+    let test_module = ctx.sema.scope(test_fn.syntax())?.module();
+    let krate = test_module.krate().display_name(db)?.to_string();
+    let target = find_called_function(ctx, body.syntax(), &|function| {
+        let module = function.module(db);
+        module.krate() == test_module.krate() && module != test_module
+    })?;
+    // Doc examples are compiled as a separate crate, so they can only use the public API.
+    let item = match target.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Impl(imp)) => {
+            if imp.trait_(db).is_none() && target.visibility(db) != Visibility::Public {
+                return None;
+            }
+            ModuleDef::Adt(imp.self_ty(db).as_adt()?)
+        }
+        Some(AssocItemContainer::Trait(trait_)) => ModuleDef::Trait(trait_),
+        None => ModuleDef::Function(target),
+    };
+    if !is_public_api(db, item) {
+        return None;
+    }
+    let target_source = target.source(db)?;
+    let target_file = target_source.file_id.file_id()?;
+    let target_fn = target_source.value;
+
+    let body_text = body.stmt_list()?.syntax().text().to_string();
+    let body_text = body_text.strip_prefix('{')?.strip_suffix('}')?;
+    let mut code: Vec<_> = dedent(body_text)
+        .into_iter()
+        .filter(|line| line.trim() != "use super::*;")
+        .map(|line| escape_line(&replace_crate_name(&line, "crate", &krate)))
+        .collect();
+    if !code.iter().any(|line| line.starts_with("use ")) {
+        code.insert(0, format!("use {krate}::{};", item.canonical_path(db)?));
+        code.insert(1, String::new());
+    }
+    // A code block can only be closed by a fence at least as long as the opening one.
+    let longest_backtick_run =
+        code.iter().flat_map(|line| line.split(|c| c != '`').map(str::len)).max().unwrap_or(0);
+    let fence = "`".repeat(longest_backtick_run.max(2) + 1);
+
+    let existing_docs: Vec<_> = target_fn.doc_comments().filter(is_outer_line_doc).collect();
+    let mut doc_lines = Vec::new();
+    if !existing_docs.is_empty() {
+        doc_lines.push(String::new());
+    }
+    if !existing_docs.iter().any(|it| doc_line(it).trim() == "# Examples") {
+        doc_lines.extend(["# Examples".to_owned(), String::new()]);
+    }
+    doc_lines.push(format!("{fence}{info}"));
+    doc_lines.extend(code);
+    doc_lines.push(fence);
+    let indent_level = IndentLevel::from_node(target_fn.syntax());
+    let insert_offset = match existing_docs.last() {
+        Some(last) => skip_whitespace_token(last.syntax().next_token()?, Direction::Next)?
+            .text_range()
+            .start(),
+        None => target_fn.syntax().text_range().start(),
+    };
+
+    let delete_start = match test_fn.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == syntax::SyntaxKind::WHITESPACE => ws.text_range().start(),
+        _ => test_fn.syntax().text_range().start(),
+    };
+
+    acc.add(
+        AssistId("convert_test_to_doc_example", AssistKind::RefactorRewrite),
+        format!("Convert test to doc example of `{}`", target.name(db)),
+        test_fn.syntax().text_range(),
+        |builder| {
+            builder.delete(TextRange::new(delete_start, test_fn.syntax().text_range().end()));
+            builder.edit_file(target_file);
+            let mut docs = String::new();
+            for line in doc_lines {
+                docs.push_str("///");
+                if !line.is_empty() {
+                    format_to!(docs, " {line}");
+                }
+                format_to!(docs, "\n{indent_level}");
+            }
+            builder.insert(insert_offset, docs);
+        },
+    )
+}
+
+/// Finds the first function called within `node` for which `filter` returns true, looking into
+/// macro calls.
+fn find_called_function(
+    ctx: &AssistContext<'_>,
+    node: &SyntaxNode,
+    filter: &dyn Fn(hir::Function) -> bool,
+) -> Option<hir::Function> {
+    node.descendants().find_map(|node| {
+        let function = match_ast! {
+            match node {
+                ast::CallExpr(it) => match it.expr()? {
+                    ast::Expr::PathExpr(callee) => match ctx.sema.resolve_path(&callee.path()?)? {
+                        hir::PathResolution::Def(ModuleDef::Function(it)) => it,
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                ast::MethodCallExpr(it) => ctx.sema.resolve_method_call(&it)?,
+                ast::MacroCall(it) => {
+                    let expansion = ctx.sema.expand(&it)?;
+                    return find_called_function(ctx, &expansion, filter);
+                },
+                _ => return None,
+            }
+        };
+        filter(function).then_some(function)
+    })
+}
+
+/// Whether `def` and all modules containing it are public, so that it can be named from other
+/// crates.
+fn is_public_api(db: &RootDatabase, def: ModuleDef) -> bool {
+    def.visibility(db) == Visibility::Public
+        && def.module(db).map_or(false, |module| {
+            module.path_to_root(db).into_iter().all(|it| it.visibility(db) == Visibility::Public)
+        })
+}
+
+/// Replaces the first segment of every path in `line` starting with `from` by `to`.
+fn replace_crate_name(line: &str, from: &str, to: &str) -> String {
+    let pattern = format!("{from}::");
+    let mut result = String::new();
+    let mut copied = 0;
+    for (idx, _) in line.match_indices(&pattern) {
+        let is_path_start =
+            !line[..idx].ends_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '$' | ':'));
+        if is_path_start {
+            format_to!(result, "{}{to}::", &line[copied..idx]);
+            copied = idx + pattern.len();
+        }
+    }
+    result.push_str(&line[copied..]);
+    result
+}
+
+struct CodeBlock<'a> {
+    /// The index of the line containing the opening fence.
+    open: usize,
+    /// The index of the line containing the closing fence.
+    close: usize,
+    info: &'a str,
+}
+
+fn code_blocks(lines: &[String]) -> Vec<CodeBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut open = None;
+    for (idx, line) in lines.iter().enumerate() {
+        let line = line.trim_start();
+        let fence_len = line.chars().take_while(|&c| c == '`').count();
+        if fence_len < 3 {
+            continue;
+        }
+        match open {
+            None => open = Some((idx, fence_len, line[fence_len..].trim())),
+            Some((start, open_len, info))
+                if fence_len >= open_len && line.trim() == &line[..fence_len] =>
+            {
+                blocks.push(CodeBlock { open: start, close: idx, info });
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    blocks
+}
+
+/// Returns the test attributes equivalent to a code block's info string, or `None` if the code
+/// block isn't a Rust example that runs.
+fn test_attrs_for_info(info: &str) -> Option<Vec<&'static str>> {
+    let mut attrs = vec!["test"];
+    for token in info.split(|c: char| c == ',' || c.is_whitespace()).filter(|it| !it.is_empty()) {
+        match token {
+            "rust" | "edition2015" | "edition2018" | "edition2021" => {}
+            "should_panic" => attrs.push("should_panic"),
+            "no_run" => attrs.push("ignore"),
+            _ => return None,
+        }
+    }
+    Some(attrs)
+}
+
+/// Returns the code block info string equivalent to a test's attributes, or `None` if the test
+/// has attributes that have no equivalent.
+fn info_for_test_attrs(test_fn: &ast::Fn) -> Option<String> {
+    let mut is_test = false;
+    let mut info = Vec::new();
+    for attr in test_fn.attrs() {
+        match attr.as_simple_atom()?.as_str() {
+            "test" => is_test = true,
+            "should_panic" => info.push("should_panic"),
+            "ignore" => info.push("no_run"),
+            _ => return None,
+        }
+    }
+    is_test.then(|| info.join(","))
+}
+
+fn is_outer_line_doc(comment: &ast::Comment) -> bool {
+    comment.kind() == CommentKind { shape: CommentShape::Line, doc: Some(CommentPlacement::Outer) }
+}
+
+fn doc_line(comment: &ast::Comment) -> String {
+    let text = comment.doc_comment().unwrap_or_default();
+    text.strip_prefix(' ').unwrap_or(text).to_owned()
+}
+
+/// Turns a line of a doc example into code, revealing lines hidden by rustdoc.
+fn unhide_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    if trimmed == "#" {
+        String::new()
+    } else if let Some(rest) = trimmed.strip_prefix("# ") {
+        format!("{indent}{rest}")
+    } else if let Some(rest) = trimmed.strip_prefix("##") {
+        format!("{indent}#{rest}")
+    } else {
+        line.to_owned()
+    }
+}
+
+/// Turns a line of code into a line of a doc example, so that rustdoc doesn't hide it.
+fn escape_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if trimmed == "#" || trimmed.starts_with("# ") || trimmed.starts_with("##") {
+        let indent = &line[..line.len() - trimmed.len()];
+        format!("{indent}#{trimmed}")
+    } else {
+        line.to_owned()
+    }
+}
+
+/// Splits `text` into lines with surrounding blank lines and the common indentation removed.
+fn dedent(text: &str) -> Vec<String> {
+    let lines: Vec<_> = text
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let common = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines.iter().map(|line| line.get(common..).unwrap_or("").trim_end().to_owned()).collect()
+}
+
+fn indent(text: &str, level: u8) -> String {
+    let indent = IndentLevel(level).to_string();
+    text.lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{indent}{line}") })
+        .join("\n")
+}
+
+/// Returns the offset after the newline following `token`, or its end if there is none.
+fn line_end(token: &syntax::SyntaxToken) -> TextSize {
+    match token.next_token() {
+        Some(ws) if ws.kind() == syntax::SyntaxKind::WHITESPACE => ws.text_range().end(),
+        _ => token.text_range().end(),
+    }
+}
+
+fn has_tests_module_item(module: &ModuleSource) -> bool {
+    let is_tests = |item: ast::Item| match item {
+        ast::Item::Module(it) => it.name().map_or(false, |name| name.text() == "tests"),
+        _ => false,
+    };
+    match module {
+        ModuleSource::Module(it) => it.item_list().map_or(false, |it| it.items().any(is_tests)),
+        ModuleSource::SourceFile(it) => it.items().any(is_tests),
+        ModuleSource::BlockExpr(_) => false,
+    }
+}
+
+fn insert_into_module(builder: &mut SourceChangeBuilder, module: InFile<ModuleSource>, text: &str) {
+    match module.value {
+        ModuleSource::Module(module) => {
+            let Some(item_list) = module.item_list() else { return };
+            let indent_level = IndentLevel::from_node(module.syntax());
+            let text = indent(text, indent_level.0 + 1);
+            match item_list.items().last() {
+                Some(last) => {
+                    builder.insert(last.syntax().text_range().end(), format!("\n\n{text}"))
+                }
+                None => builder.replace(
+                    item_list.syntax().text_range(),
+                    format!("{{\n{text}\n{indent_level}}}"),
+                ),
+            }
+        }
+        ModuleSource::SourceFile(file) => match file.items().last() {
+            Some(last) => builder.insert(last.syntax().text_range().end(), format!("\n\n{text}")),
+            None => builder.insert(TextSize::from(0), format!("{text}\n")),
+        },
+        ModuleSource::BlockExpr(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn doc_example_to_new_tests_module() {
+        check_assist(
+            convert_doc_example_to_test,
+            r#"
+/// Frobs the widget.
+///
+/// ```should_panic
+/// # fn setup() {}
+/// # setup();
+/// ## [allow(unused)]
+/// frob$0();
+/// ```
+///
+/// More docs.
+pub fn frob() {}
+"#,
+            r#"
+/// Frobs the widget.
+///
+/// More docs.
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn frob_doc_example() {
+        fn setup() {}
+        setup();
+        # [allow(unused)]
+        frob();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn doc_example_to_test_in_other_file() {
+        check_assist(
+            convert_doc_example_to_test,
+            r#"
+//- /lib.rs crate:foo cfg:test
+/// ```
+/// use foo::frob;
+/// frob$0();
+/// ```
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests;
+//- /tests.rs
+use super::*;
+
+#[test]
+fn frob_doc_example() {}
+"#,
+            r#"
+//- /lib.rs
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests;
+//- /tests.rs
+use super::*;
+
+#[test]
+fn frob_doc_example() {}
+
+#[test]
+fn frob_doc_example_2() {
+    use crate::frob;
+    frob();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn doc_example_not_applicable() {
+        check_assist_not_applicable(
+            convert_doc_example_to_test,
+            r#"
+/// ```text
+/// frob$0();
+/// ```
+pub fn frob() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_doc_example_to_test,
+            r#"
+/// ```
+/// fn main() { frob$0(); }
+/// ```
+pub fn frob() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_doc_example_to_test,
+            r#"
+/// Frobs$0 the widget.
+///
+/// ```
+/// frob();
+/// ```
+pub fn frob() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_to_doc_example_of_method() {
+        check_assist(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+pub mod widget {
+    pub struct Widget;
+    impl Widget {
+        /// Frobs the widget.
+        pub fn frob(&self) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn frob$0_widget() {
+        let w = widget::Widget;
+        w.frob();
+        # [allow(unused)]
+        let s = "```";
+    }
+}
+"#,
+            r#"
+pub mod widget {
+    pub struct Widget;
+    impl Widget {
+        /// Frobs the widget.
+        ///
+        /// # Examples
+        ///
+        /// ````no_run
+        /// use foo::widget::Widget;
+        ///
+        /// let w = widget::Widget;
+        /// w.frob();
+        /// ## [allow(unused)]
+        /// let s = "```";
+        /// ````
+        pub fn frob(&self) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_to_doc_example_through_macro() {
+        check_assist(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+macro_rules! check {
+    ($e:expr) => { $e };
+}
+
+pub fn frob() -> bool { true }
+
+#[cfg(test)]
+mod tests {
+    use crate::frob;
+
+    #[test]
+    fn frob$0s() {
+        check!(frob());
+    }
+}
+"#,
+            r#"
+macro_rules! check {
+    ($e:expr) => { $e };
+}
+
+/// # Examples
+///
+/// ```
+/// use foo::frob;
+///
+/// check!(frob());
+/// ```
+pub fn frob() -> bool { true }
+
+#[cfg(test)]
+mod tests {
+    use crate::frob;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn crate_paths_are_replaced() {
+        check_assist(
+            convert_doc_example_to_test,
+            r#"
+//- /lib.rs crate:foo cfg:test
+/// ```
+/// use foo::{frob, Widget}; use foo::Gadget;
+/// let w: foo::Widget = frob$0(); let my_foo::x = 1;
+/// ```
+pub fn frob() {}
+"#,
+            r#"
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frob_doc_example() {
+        use crate::{frob, Widget}; use crate::Gadget;
+        let w: crate::Widget = frob(); let my_foo::x = 1;
+    }
+}
+"#,
+        );
+        check_assist(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests {
+    use crate::frob;
+
+    #[test]
+    fn frob$0s() {
+        use crate::Widget; use crate::Gadget;
+        let w: crate::Widget = frob(); $crate::x();
+    }
+}
+"#,
+            r#"
+/// # Examples
+///
+/// ```
+/// use foo::Widget; use foo::Gadget;
+/// let w: foo::Widget = frob(); $crate::x();
+/// ```
+pub fn frob() {}
+
+#[cfg(test)]
+mod tests {
+    use crate::frob;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_to_doc_example_not_applicable() {
+        check_assist_not_applicable(
+            convert_test_to_doc_example,
+            r#"
+fn helper() {}
+fn frob$0() { helper(); }
+"#,
+        );
+        check_assist_not_applicable(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+#[cfg(test)]
+mod tests {
+    fn helper() {}
+    #[test]
+    fn frob$0() { helper(); }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+pub(crate) fn frob() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn frob$0s() { crate::frob(); }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+mod widget {
+    pub struct Widget;
+    impl Widget {
+        pub fn frob(&self) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn frob$0s() { crate::widget::Widget.frob(); }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_test_to_doc_example,
+            r#"
+//- /lib.rs crate:foo cfg:test
+pub struct Widget;
+impl Widget {
+    fn frob(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn frob$0s() { crate::Widget.frob(); }
+}
+"#,
+        );
+    }
+}
@@ -50,7 +50,12 @@ pub(crate) fn convert_into_to_from(acc: &mut Assists, ctx: &AssistContext<'_>) -
             _ => return None,
         };
 
-        mod_path_to_ast(&module.find_use_path(ctx.db(), src_type_def, ctx.config.prefer_no_std)?)
+        mod_path_to_ast(&module.find_use_path(
+            ctx.db(),
+            src_type_def,
+            ctx.config.prefer_no_std,
+            ctx.config.prefer_defining_crate,
+        )?)
     };
 
     let dest_type = match &ast_trait {
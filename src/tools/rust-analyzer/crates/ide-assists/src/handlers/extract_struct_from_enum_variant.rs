@@ -384,6 +384,7 @@ fn process_references(
                     *enum_module_def,
                     ctx.config.insert_use.prefix_kind,
                     ctx.config.prefer_no_std,
+                    ctx.config.prefer_defining_crate,
                 );
                 if let Some(mut mod_path) = mod_path {
                     mod_path.pop_segment();
@@ -0,0 +1,712 @@
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, HasAttrs, HasGenericParams, HasModuleItem, HasName},
+    SyntaxNode, TextSize,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_mock_trait_impl
+//
+// Generates a mock implementation of a trait in the test module, recording the
+// arguments of every call and returning queued values.
+//
+// ```
+// trait Store$0 {
+//     fn get(&self, key: &str) -> Option<u32>;
+// }
+// ```
+// ->
+// ```
+// trait Store {
+//     fn get(&self, key: &str) -> Option<u32>;
+// }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[derive(Default)]
+//     $0struct MockStore {
+//         get_calls: std::cell::RefCell<Vec<String>>,
+//         get_returns: std::cell::RefCell<std::collections::VecDeque<Option<u32>>>,
+//     }
+//
+//     impl Store for MockStore {
+//         fn get(&self, key: &str) -> Option<u32> {
+//             self.get_calls.borrow_mut().push(key.to_owned());
+//             self.get_returns.borrow_mut().pop_front().expect("no return value queued for `MockStore::get`")
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_mock_trait_impl(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let trait_ = ast::Trait::cast(name.syntax().parent()?)?;
+    let assoc_items = trait_.assoc_item_list()?;
+    let container = trait_.syntax().parent()?;
+    let mock_name = format!("Mock{name}");
+
+    let test_module = find_test_module(&container);
+    if let Some(module) = &test_module {
+        let already_exists = module
+            .item_list()?
+            .items()
+            .filter_map(|item| match item {
+                ast::Item::Struct(strukt) => strukt.name(),
+                _ => None,
+            })
+            .any(|it| it.text() == mock_name);
+        if already_exists {
+            return None;
+        }
+    }
+
+    let target = trait_.syntax().text_range();
+    acc.add(
+        AssistId("generate_mock_trait_impl", AssistKind::Generate),
+        format!("Generate `{mock_name}` for tests"),
+        target,
+        |builder| {
+            let cursor = if ctx.config.snippet_cap.is_some() { "$0" } else { "" };
+            let mock = render_mock(&trait_, &assoc_items, &mock_name, cursor);
+
+            match test_module.as_ref().and_then(|it| it.item_list()) {
+                Some(item_list) => {
+                    let module_indent = IndentLevel::from_node(item_list.syntax());
+                    let mock = reindent(&mock, module_indent + 1);
+                    let (offset, text) = match item_list.items().last() {
+                        Some(last) => (last.syntax().text_range().end(), format!("\n\n{mock}")),
+                        None => {
+                            let r_curly = item_list.r_curly_token();
+                            let offset = r_curly.map_or_else(
+                                || item_list.syntax().text_range().end(),
+                                |it| it.text_range().start(),
+                            );
+                            (offset, format!("\n{mock}\n{module_indent}"))
+                        }
+                    };
+                    insert(builder, ctx, offset, text);
+                }
+                None => {
+                    let indent = IndentLevel::from_node(trait_.syntax());
+                    let mut module =
+                        String::from("#[cfg(test)]\nmod tests {\n    use super::*;\n\n");
+                    module.push_str(&reindent(&mock, IndentLevel(1)));
+                    module.push_str("\n}");
+                    let offset =
+                        container.children().filter_map(ast::Item::cast).last().map_or_else(
+                            || trait_.syntax().text_range().end(),
+                            |it| it.syntax().text_range().end(),
+                        );
+                    insert(builder, ctx, offset, format!("\n\n{}", reindent(&module, indent)));
+                }
+            }
+        },
+    )
+}
+
+fn insert(
+    builder: &mut ide_db::source_change::SourceChangeBuilder,
+    ctx: &AssistContext<'_>,
+    offset: TextSize,
+    text: String,
+) {
+    match ctx.config.snippet_cap {
+        Some(cap) => builder.insert_snippet(cap, offset, text),
+        None => builder.insert(offset, text),
+    }
+}
+
+/// Finds a `#[cfg(test)]` module among the items of `container`.
+fn find_test_module(container: &SyntaxNode) -> Option<ast::Module> {
+    container.children().filter_map(ast::Module::cast).find(|module| {
+        module.item_list().is_some()
+            && module.attrs().any(|attr| {
+                attr.as_simple_call()
+                    .map_or(false, |(name, tt)| name == "cfg" && tt.syntax().text() == "(test)")
+            })
+    })
+}
+
+fn reindent(text: &str, indent: IndentLevel) -> String {
+    text.lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{indent}{line}") })
+        .join("\n")
+}
+
+fn render_mock(
+    trait_: &ast::Trait,
+    assoc_items: &ast::AssocItemList,
+    mock_name: &str,
+    cursor: &str,
+) -> String {
+    let trait_name = trait_.name().map(|it| it.to_string()).unwrap_or_default();
+    let trait_generics = trait_.generic_param_list();
+    let trait_args = trait_generics.as_ref().map(|it| it.to_generic_args().to_string());
+    let trait_args = trait_args.unwrap_or_default();
+    let where_clause = trait_.where_clause().map(|it| format!(" {it}")).unwrap_or_default();
+
+    // The mock has the generic parameters of the trait, followed by one type
+    // parameter for every associated type with bounds `()` wouldn't satisfy.
+    let mut params: Vec<String> = Vec::new();
+    let mut args: Vec<String> = Vec::new();
+    // Types mentioning every lifetime and type parameter, so none of them is unused.
+    let mut marker: Vec<String> = Vec::new();
+    for param in trait_generics.iter().flat_map(|it| it.generic_params()) {
+        params.push(param.to_string());
+        match &param {
+            ast::GenericParam::LifetimeParam(it) => {
+                if let Some(lifetime) = it.lifetime() {
+                    args.push(lifetime.to_string());
+                    marker.push(format!("&{lifetime} ()"));
+                }
+            }
+            ast::GenericParam::TypeParam(it) => {
+                if let Some(name) = it.name() {
+                    args.push(name.to_string());
+                    marker.push(name.to_string());
+                }
+            }
+            ast::GenericParam::ConstParam(it) => args.extend(it.name().map(|it| it.to_string())),
+        }
+    }
+
+    let mut fields = Vec::new();
+    let mut items = Vec::new();
+    for item in assoc_items.assoc_items() {
+        match item {
+            ast::AssocItem::Fn(fn_) if fn_.body().is_none() => {
+                let mock_fn = MockFn::new(&fn_, mock_name);
+                fields.extend(mock_fn.fields());
+                items.push(mock_fn.render());
+            }
+            ast::AssocItem::TypeAlias(alias) if alias.ty().is_none() => {
+                let Some(name) = alias.name() else { continue };
+                let alias_generics = alias.generic_param_list();
+                match alias.type_bound_list() {
+                    // The test picks a type satisfying the bounds.
+                    Some(bounds) if alias_generics.is_none() => {
+                        params.push(format!("{name}: {bounds}"));
+                        args.push(name.to_string());
+                        marker.push(name.to_string());
+                        items.push(format!("type {name} = {name};"));
+                    }
+                    _ => {
+                        let alias_generics =
+                            alias_generics.map(|it| it.to_string()).unwrap_or_default();
+                        items.push(format!("type {name}{alias_generics} = ();"));
+                    }
+                }
+            }
+            ast::AssocItem::Const(const_) if const_.body().is_none() => {
+                if let (Some(name), Some(ty)) = (const_.name(), const_.ty()) {
+                    let value = default_const_value(&ty).unwrap_or("todo!()");
+                    items.push(format!("const {name}: {ty} = {value};"));
+                }
+            }
+            _ => (),
+        }
+    }
+    if !marker.is_empty() {
+        let marker = match marker.as_slice() {
+            [ty] => ty.clone(),
+            tys => format!("({})", tys.join(", ")),
+        };
+        fields.push(format!("_marker: std::marker::PhantomData<{marker}>"));
+    }
+
+    let (generic_params, generic_args) = if params.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (format!("<{}>", params.join(", ")), format!("<{}>", args.join(", ")))
+    };
+
+    let mut buf = String::new();
+    format_to!(buf, "#[derive(Default)]\n{cursor}struct {mock_name}{generic_params}{where_clause}");
+    if fields.is_empty() {
+        buf.push(';');
+    } else {
+        buf.push_str(" {\n");
+        for field in fields {
+            format_to!(buf, "    {field},\n");
+        }
+        buf.push('}');
+    }
+    format_to!(
+        buf,
+        "\n\nimpl{generic_params} {trait_name}{trait_args} for {mock_name}{generic_args}{where_clause} {{"
+    );
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            buf.push('\n');
+        }
+        buf.push('\n');
+        buf.push_str(&reindent(item, IndentLevel(1)));
+    }
+    buf.push_str("\n}");
+    buf
+}
+
+struct MockFn {
+    mock_name: String,
+    name: String,
+    signature: String,
+    has_self: bool,
+    /// `(recorded type, expression producing the recorded value)` for every
+    /// parameter whose value can be stored in the mock.
+    recorded: Vec<(String, String)>,
+    /// The return type, if a value of it can be queued in the mock.
+    canned_return: Option<String>,
+    returns_value: bool,
+}
+
+impl MockFn {
+    fn new(fn_: &ast::Fn, mock_name: &str) -> MockFn {
+        let name = fn_.name().map(|it| it.to_string()).unwrap_or_default();
+        let fn_generics: Vec<String> = fn_
+            .generic_param_list()
+            .map(|list| {
+                list.type_or_const_params()
+                    .filter_map(|param| match param {
+                        ast::TypeOrConstParam::Type(it) => it.name(),
+                        ast::TypeOrConstParam::Const(it) => it.name(),
+                    })
+                    .map(|it| it.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let param_list = fn_.param_list();
+        let self_param = param_list.as_ref().and_then(|it| it.self_param());
+        let mut params = Vec::new();
+        let mut recorded = Vec::new();
+        for (idx, param) in param_list.iter().flat_map(|it| it.params()).enumerate() {
+            let param_name = match param.pat() {
+                Some(ast::Pat::IdentPat(pat)) if pat.ref_token().is_none() => {
+                    pat.name().map_or_else(|| format!("arg{idx}"), |it| it.to_string())
+                }
+                _ => format!("arg{idx}"),
+            };
+            let ty = match param.ty() {
+                Some(ty) => ty,
+                None => continue,
+            };
+            params.push(format!("{param_name}: {ty}"));
+            recorded.extend(recorded_param(&ty, &param_name, &fn_generics));
+        }
+
+        let ret_ty = fn_.ret_type().and_then(|it| it.ty());
+        let returns_value = ret_ty.as_ref().map_or(false, |ty| match ty {
+            ast::Type::TupleType(tuple) => tuple.fields().next().is_some(),
+            _ => true,
+        });
+        let canned_return = ret_ty
+            .as_ref()
+            .filter(|_| returns_value && self_param.is_some())
+            .filter(|ty| is_storable(ty, &fn_generics))
+            .map(|ty| ty.to_string());
+
+        let mut signature = String::new();
+        if fn_.unsafe_token().is_some() {
+            signature.push_str("unsafe ");
+        }
+        format_to!(signature, "fn {name}");
+        if let Some(generic_params) = fn_.generic_param_list() {
+            format_to!(signature, "{generic_params}");
+        }
+        let params = self_param.iter().map(|it| it.to_string()).chain(params).join(", ");
+        format_to!(signature, "({params})");
+        if let Some(ret_ty) = fn_.ret_type() {
+            format_to!(signature, " {ret_ty}");
+        }
+        if let Some(where_clause) = fn_.where_clause() {
+            format_to!(signature, " {where_clause}");
+        }
+
+        MockFn {
+            mock_name: mock_name.to_owned(),
+            name,
+            signature,
+            has_self: self_param.is_some(),
+            recorded,
+            canned_return,
+            returns_value,
+        }
+    }
+
+    fn fields(&self) -> Vec<String> {
+        if !self.has_self {
+            return Vec::new();
+        }
+        let recorded_ty = match self.recorded.as_slice() {
+            [(ty, _)] => ty.clone(),
+            recorded => format!("({})", recorded.iter().map(|(ty, _)| ty).join(", ")),
+        };
+        let mut fields =
+            vec![format!("{}_calls: std::cell::RefCell<Vec<{recorded_ty}>>", self.name)];
+        if let Some(ret_ty) = &self.canned_return {
+            fields.push(format!(
+                "{}_returns: std::cell::RefCell<std::collections::VecDeque<{ret_ty}>>",
+                self.name
+            ));
+        }
+        fields
+    }
+
+    fn render(&self) -> String {
+        let mut body = Vec::new();
+        if self.has_self {
+            let value = match self.recorded.as_slice() {
+                [(_, value)] => value.clone(),
+                recorded => format!("({})", recorded.iter().map(|(_, value)| value).join(", ")),
+            };
+            body.push(format!("self.{}_calls.borrow_mut().push({value});", self.name));
+        }
+        if self.canned_return.is_some() {
+            body.push(format!(
+                "self.{name}_returns.borrow_mut().pop_front().expect(\"no return value queued for `{mock}::{name}`\")",
+                name = self.name,
+                mock = self.mock_name,
+            ));
+        } else if self.returns_value || !self.has_self {
+            body.push("todo!()".to_owned());
+        }
+        let body = body.iter().map(|line| format!("    {line}")).join("\n");
+        format!("{} {{\n{body}\n}}", self.signature)
+    }
+}
+
+/// Returns the type under which a parameter of type `ty` is recorded together
+/// with the expression converting the parameter to it.
+fn recorded_param(ty: &ast::Type, name: &str, fn_generics: &[String]) -> Option<(String, String)> {
+    match ty {
+        ast::Type::RefType(ref_ty) => {
+            let inner = ref_ty.ty()?;
+            if !is_storable(&inner, fn_generics) {
+                return None;
+            }
+            let owned = match &inner {
+                ast::Type::PathType(path) if path.syntax().text() == "str" => "String".to_owned(),
+                ast::Type::SliceType(slice) => format!("Vec<{}>", slice.ty()?),
+                _ => format!("<{inner} as ToOwned>::Owned"),
+            };
+            Some((owned, format!("{name}.to_owned()")))
+        }
+        _ if is_storable(ty, fn_generics) => Some((ty.to_string(), name.to_owned())),
+        _ => None,
+    }
+}
+
+/// A literal usable as the value of an associated constant of type `ty`.
+fn default_const_value(ty: &ast::Type) -> Option<&'static str> {
+    let value = match ty.syntax().text().to_string().as_str() {
+        "bool" => "false",
+        "char" => "'\\0'",
+        "f32" | "f64" => "0.0",
+        "&str" | "&'static str" => "\"\"",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => "0",
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Whether a value of type `ty` can be stored in a field of the mock, that is
+/// it doesn't borrow, and doesn't mention `Self` or generics of the method.
+fn is_storable(ty: &ast::Type, fn_generics: &[String]) -> bool {
+    !ty.syntax().descendants().any(|node| {
+        if let Some(ty) = ast::Type::cast(node.clone()) {
+            return matches!(
+                ty,
+                ast::Type::RefType(_)
+                    | ast::Type::ImplTraitType(_)
+                    | ast::Type::DynTraitType(_)
+                    | ast::Type::InferType(_)
+            );
+        }
+        if ast::Lifetime::can_cast(node.kind()) {
+            return true;
+        }
+        match ast::NameRef::cast(node) {
+            Some(name_ref) => {
+                name_ref.Self_token().is_some()
+                    || fn_generics.iter().any(|it| name_ref.text() == it.as_str())
+            }
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_no_snippet_cap, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn creates_test_module() {
+        check_assist(
+            generate_mock_trait_impl,
+            r#"
+trait Store$0 {
+    fn get(&self, key: &str) -> Option<u32>;
+    fn set(&mut self, key: String, value: u32);
+}
+"#,
+            r#"
+trait Store {
+    fn get(&self, key: &str) -> Option<u32>;
+    fn set(&mut self, key: String, value: u32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    $0struct MockStore {
+        get_calls: std::cell::RefCell<Vec<String>>,
+        get_returns: std::cell::RefCell<std::collections::VecDeque<Option<u32>>>,
+        set_calls: std::cell::RefCell<Vec<(String, u32)>>,
+    }
+
+    impl Store for MockStore {
+        fn get(&self, key: &str) -> Option<u32> {
+            self.get_calls.borrow_mut().push(key.to_owned());
+            self.get_returns.borrow_mut().pop_front().expect("no return value queued for `MockStore::get`")
+        }
+
+        fn set(&mut self, key: String, value: u32) {
+            self.set_calls.borrow_mut().push((key, value));
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn appends_to_existing_test_module() {
+        check_assist(
+            generate_mock_trait_impl,
+            r#"
+trait Clock$0 {
+    fn now(&self) -> u64;
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+}
+"#,
+            r#"
+trait Clock {
+    fn now(&self) -> u64;
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+
+    #[derive(Default)]
+    $0struct MockClock {
+        now_calls: std::cell::RefCell<Vec<()>>,
+        now_returns: std::cell::RefCell<std::collections::VecDeque<u64>>,
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> u64 {
+            self.now_calls.borrow_mut().push(());
+            self.now_returns.borrow_mut().pop_front().expect("no return value queued for `MockClock::now`")
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generics_and_lifetimes() {
+        check_assist(
+            generate_mock_trait_impl,
+            r#"
+trait Codec$0<T: Clone> {
+    type Error;
+    fn encode(&self, value: &T, buf: &mut [u8]) -> usize;
+    fn decode<'a>(&self, bytes: &'a [u8]) -> &'a T;
+    fn convert<U>(&self, value: U, scale: f32) -> U;
+    fn name() -> String;
+    fn reset(&self) {}
+}
+"#,
+            r#"
+trait Codec<T: Clone> {
+    type Error;
+    fn encode(&self, value: &T, buf: &mut [u8]) -> usize;
+    fn decode<'a>(&self, bytes: &'a [u8]) -> &'a T;
+    fn convert<U>(&self, value: U, scale: f32) -> U;
+    fn name() -> String;
+    fn reset(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    $0struct MockCodec<T: Clone> {
+        encode_calls: std::cell::RefCell<Vec<(<T as ToOwned>::Owned, Vec<u8>)>>,
+        encode_returns: std::cell::RefCell<std::collections::VecDeque<usize>>,
+        decode_calls: std::cell::RefCell<Vec<Vec<u8>>>,
+        convert_calls: std::cell::RefCell<Vec<f32>>,
+        _marker: std::marker::PhantomData<T>,
+    }
+
+    impl<T: Clone> Codec<T> for MockCodec<T> {
+        type Error = ();
+
+        fn encode(&self, value: &T, buf: &mut [u8]) -> usize {
+            self.encode_calls.borrow_mut().push((value.to_owned(), buf.to_owned()));
+            self.encode_returns.borrow_mut().pop_front().expect("no return value queued for `MockCodec::encode`")
+        }
+
+        fn decode<'a>(&self, bytes: &'a [u8]) -> &'a T {
+            self.decode_calls.borrow_mut().push(bytes.to_owned());
+            todo!()
+        }
+
+        fn convert<U>(&self, value: U, scale: f32) -> U {
+            self.convert_calls.borrow_mut().push(scale);
+            todo!()
+        }
+
+        fn name() -> String {
+            todo!()
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_snippet_cap() {
+        check_assist_no_snippet_cap(
+            generate_mock_trait_impl,
+            r#"
+trait Notify$0 {
+    fn send(&self, _: u32);
+}
+"#,
+            r#"
+trait Notify {
+    fn send(&self, _: u32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockNotify {
+        send_calls: std::cell::RefCell<Vec<u32>>,
+    }
+
+    impl Notify for MockNotify {
+        fn send(&self, arg0: u32) {
+            self.send_calls.borrow_mut().push(arg0);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_mock_exists() {
+        check_assist_not_applicable(
+            generate_mock_trait_impl,
+            r#"
+trait Clock$0 {
+    fn now(&self) -> u64;
+}
+
+#[cfg(test)]
+mod tests {
+    struct MockClock;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_trait_name() {
+        check_assist_not_applicable(
+            generate_mock_trait_impl,
+            r#"
+trait Clock {
+    fn now(&self) -> u64;$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn lifetimes_bounded_assoc_types_and_consts() {
+        check_assist(
+            generate_mock_trait_impl,
+            r#"
+trait Source$0<'a> {
+    type Item: std::fmt::Display;
+    type Error;
+    const LIMIT: usize;
+    fn next(&mut self, input: &'a str) -> Option<Self::Item>;
+}
+"#,
+            r#"
+trait Source<'a> {
+    type Item: std::fmt::Display;
+    type Error;
+    const LIMIT: usize;
+    fn next(&mut self, input: &'a str) -> Option<Self::Item>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    $0struct MockSource<'a, Item: std::fmt::Display> {
+        next_calls: std::cell::RefCell<Vec<String>>,
+        _marker: std::marker::PhantomData<(&'a (), Item)>,
+    }
+
+    impl<'a, Item: std::fmt::Display> Source<'a> for MockSource<'a, Item> {
+        type Item = Item;
+
+        type Error = ();
+
+        const LIMIT: usize = 0;
+
+        fn next(&mut self, input: &'a str) -> Option<Self::Item> {
+            self.next_calls.borrow_mut().push(input.to_owned());
+            todo!()
+        }
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,566 @@
+use std::{cmp::Ordering, iter, ops::Range};
+
+use hir::{HasVisibility, Module, ModuleDef, Name, PathResolution};
+use ide_db::{
+    base_db::{AnchoredPathBuf, FileId},
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    FxHashMap,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        HasAttrs, HasName, HasVisibility as _, VisibilityKind,
+    },
+    AstNode, Direction,
+    SyntaxKind::WHITESPACE,
+    SyntaxNode, TextRange, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+use super::fix_visibility::target_data_for_def;
+
+// Assist: move_module_to_parent
+//
+// Moves a module out of its parent module, into the module containing the parent. The files of
+// the module are moved along, and the paths to the module and the visibility of the items of its
+// old parent it uses are updated.
+//
+// ```
+// mod foo {
+//     mod $0bar {
+//         pub fn f() { super::g() }
+//     }
+//     fn g() {}
+//     pub fn h() { bar::f() }
+// }
+// ```
+// ->
+// ```
+// mod foo {
+//     pub(super) fn g() {}
+//     pub fn h() { crate::bar::f() }
+// }
+//
+// mod bar {
+//     pub fn f() { super::foo::g() }
+// }
+// ```
+pub(crate) fn move_module_to_parent(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    let head_end = match module_ast.item_list() {
+        Some(item_list) => item_list.syntax().text_range().start(),
+        None => module_ast.syntax().text_range().end(),
+    };
+    if head_end <= ctx.offset() {
+        return None;
+    }
+    let target = TextRange::new(module_ast.syntax().text_range().start(), head_end);
+
+    let db = ctx.db();
+    let module = ctx.sema.to_def(&module_ast)?;
+    let name = module.name(db)?;
+    let parent = module.parent(db)?;
+    let grandparent = parent.parent(db)?;
+    let parent_decl = parent.declaration_source(db)?;
+    if parent_decl.file_id.is_macro() {
+        return None;
+    }
+    let parent_decl_file = parent_decl.file_id.original_file(db);
+    let parent_decl = parent_decl.value;
+    let parent_name = parent.name(db)?;
+
+    // The files of modules with a `#[path]` attribute are not where we expect them.
+    if has_path_attr(&module_ast) || has_path_attr(&parent_decl) {
+        return None;
+    }
+    if grandparent.scope(db, None).into_iter().any(|(it, _)| it == name) {
+        return None;
+    }
+    let descendants = descendants(ctx, module);
+    // The files of the submodules of an inline module would need to be moved as well.
+    if module.is_inline(db) && descendants.iter().any(|it| !it.is_inline(db)) {
+        return None;
+    }
+
+    acc.add(
+        AssistId("move_module_to_parent", AssistKind::Refactor),
+        format!("Move module to `{}`", grandparent_label(ctx, grandparent)),
+        target,
+        |builder| {
+            let decl_file = ctx.file_id();
+            let decl_range = module_ast.syntax().text_range();
+            let mut edits: Vec<(FileId, TextRange, String)> = Vec::new();
+
+            // `pub(super)` in the new parent is what private was in the old one.
+            if let Some(vis) = module_ast.visibility() {
+                if let VisibilityKind::PubSuper = vis.kind() {
+                    edits.push((decl_file, with_trailing_whitespace(vis.syntax()), String::new()));
+                }
+            }
+
+            edits.extend(rewrite_usages(ctx, module, &name, grandparent));
+
+            let roots: Vec<(FileId, SyntaxNode)> = if module.is_inline(db) {
+                vec![(decl_file, module_ast.syntax().clone())]
+            } else {
+                descendants
+                    .iter()
+                    .filter(|it| !it.is_inline(db))
+                    .map(|it| {
+                        let file_id = it.definition_source(db).file_id.original_file(db);
+                        (file_id, ctx.sema.parse(file_id).syntax().clone())
+                    })
+                    .collect()
+            };
+            let mut used_defs = Vec::new();
+            for (file_id, root) in roots {
+                for path in root.descendants().filter_map(ast::Path::cast) {
+                    if let Some(PathResolution::Def(def)) = ctx.sema.resolve_path(&path) {
+                        if !used_defs.contains(&def) {
+                            used_defs.push(def);
+                        }
+                    }
+                    let Some((range, new_path)) =
+                        rewrite_super_path(ctx, module, &parent_name, &path)
+                    else {
+                        continue;
+                    };
+                    // Paths to the module itself have been rewritten as a whole.
+                    if !edits.iter().any(|(f, r, _)| *f == file_id && r.contains_range(range)) {
+                        edits.push((file_id, range, new_path));
+                    }
+                }
+            }
+
+            for def in used_defs {
+                edits.extend(widen_visibility(ctx, module, parent, grandparent, def));
+            }
+
+            let (moved, edits): (Vec<_>, Vec<_>) =
+                edits.into_iter().partition(|(file_id, range, _)| {
+                    *file_id == decl_file && decl_range.contains_range(*range)
+                });
+            let mut decl = module_ast.syntax().to_string();
+            for (_, range, new_text) in
+                moved.into_iter().sorted_by_key(|(_, range, _)| range.start()).rev()
+            {
+                let range: Range<usize> = (range - decl_range.start()).into();
+                decl.replace_range(range, &new_text);
+            }
+            let indent = IndentLevel::from_node(parent_decl.syntax());
+            let decl = ast::SourceFile::parse(&decl)
+                .tree()
+                .syntax()
+                .descendants()
+                .find_map(ast::Module::cast)
+                .map_or(decl, |it| {
+                    it.dedent(IndentLevel::from_node(module_ast.syntax()))
+                        .indent(indent)
+                        .to_string()
+                });
+
+            let mut edits_by_file: FxHashMap<FileId, Vec<(TextRange, String)>> =
+                FxHashMap::default();
+            for (file_id, range, new_text) in edits {
+                edits_by_file.entry(file_id).or_default().push((range, new_text));
+            }
+            edits_by_file
+                .entry(decl_file)
+                .or_default()
+                .push((with_leading_whitespace(module_ast.syntax()), String::new()));
+            edits_by_file.entry(parent_decl_file).or_default().push((
+                TextRange::empty(parent_decl.syntax().text_range().end()),
+                format!("\n\n{indent}{decl}"),
+            ));
+            for (file_id, edits) in edits_by_file {
+                builder.edit_file(file_id);
+                for (range, new_text) in edits {
+                    builder.replace(range, new_text);
+                }
+            }
+
+            if !module.is_inline(db) {
+                let anchor = module.definition_source(db).file_id.original_file(db);
+                let name = name.unescaped().to_string();
+                let path = |path: String| AnchoredPathBuf { anchor, path };
+                if module.is_mod_rs(db) {
+                    builder.move_dir(
+                        path(format!("../{name}")),
+                        anchor,
+                        path(format!("../../{name}")),
+                    );
+                } else {
+                    builder.move_file(anchor, path(format!("../{name}.rs")));
+                    if module.children(db).any(|it| !it.is_inline(db)) {
+                        builder.move_dir(path(name.clone()), anchor, path(format!("../{name}")));
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn has_path_attr(module: &ast::Module) -> bool {
+    module.attrs().any(|attr| attr.simple_name().as_deref() == Some("path"))
+}
+
+/// The module and all the modules nested in it.
+fn descendants(ctx: &AssistContext<'_>, module: Module) -> Vec<Module> {
+    let mut res = vec![module];
+    let mut idx = 0;
+    while let Some(&module) = res.get(idx) {
+        res.extend(module.children(ctx.db()));
+        idx += 1;
+    }
+    res
+}
+
+fn grandparent_label(ctx: &AssistContext<'_>, grandparent: Module) -> String {
+    match grandparent.name(ctx.db()) {
+        Some(name) => name.to_string(),
+        None => "crate".to_owned(),
+    }
+}
+
+/// The path to the module in its new place, as `crate::path::to::name`.
+fn absolute_path(ctx: &AssistContext<'_>, grandparent: Module, name: &Name) -> String {
+    let mut path = grandparent.path_to_root(ctx.db());
+    path.reverse();
+    iter::once("crate".to_owned())
+        .chain(path.into_iter().filter_map(|it| it.name(ctx.db())).map(|it| it.to_string()))
+        .chain(iter::once(name.to_string()))
+        .join("::")
+}
+
+/// Rewrites the paths ending with the moved module, like `foo::bar` to `crate::bar`.
+fn rewrite_usages(
+    ctx: &AssistContext<'_>,
+    module: Module,
+    name: &Name,
+    grandparent: Module,
+) -> Vec<(FileId, TextRange, String)> {
+    let db = ctx.db();
+    let absolute_path = absolute_path(ctx, grandparent, name);
+    let mut edits = Vec::new();
+    let usages = Definition::Module(module).usages(&ctx.sema).all();
+    for (&file_id, references) in usages.iter() {
+        for reference in references {
+            let ast::NameLike::NameRef(name_ref) = &reference.name else { continue };
+            // `self` and `super` keep resolving to the module.
+            if matches!(name_ref.token_kind(), T![self] | T![super] | T![crate]) {
+                continue;
+            }
+            if ctx.sema.hir_file_for(name_ref.syntax()).is_macro() {
+                continue;
+            }
+            let Some(segment) = name_ref.syntax().parent().and_then(ast::PathSegment::cast) else {
+                continue;
+            };
+            let path = segment.parent_path();
+            let Some(scope) = ctx.sema.scope(path.syntax()) else { continue };
+            let from = scope.module();
+
+            let new_path = if from == grandparent {
+                name.to_string()
+            } else if from.path_to_root(db).contains(&module) {
+                absolute_path.clone()
+            } else {
+                match from.find_use_path(
+                    db,
+                    ModuleDef::Module(grandparent),
+                    ctx.config.prefer_no_std,
+                    ctx.config.prefer_defining_crate,
+                ) {
+                    Some(path) => format!("{}::{name}", mod_path_to_ast(&path)),
+                    None => absolute_path.clone(),
+                }
+            };
+
+            let use_tree = path.top_path().syntax().parent().and_then(ast::UseTree::cast);
+            let nested_use_tree = use_tree.filter(|it| {
+                it.syntax().parent().map_or(false, |it| ast::UseTreeList::can_cast(it.kind()))
+            });
+            match nested_use_tree {
+                // The new path can't be nested in the old one, so import it in a new `use` item.
+                Some(use_tree) => {
+                    let Some(use_item) = use_tree.syntax().ancestors().find_map(ast::Use::cast)
+                    else {
+                        continue;
+                    };
+                    let tree_range = use_tree.syntax().text_range();
+                    let rest = &use_tree.syntax().text().to_string()
+                        [usize::from(path.syntax().text_range().end() - tree_range.start())..];
+                    let mut end = tree_range.end();
+                    let mut siblings =
+                        use_tree.syntax().siblings_with_tokens(Direction::Next).skip(1);
+                    if let Some(comma) = siblings.next().filter(|it| it.kind() == T![,]) {
+                        end = comma.text_range().end();
+                        if let Some(ws) = siblings.next().filter(|it| it.kind() == WHITESPACE) {
+                            end = ws.text_range().end();
+                        }
+                    }
+                    edits.push((file_id, TextRange::new(tree_range.start(), end), String::new()));
+
+                    let indent = IndentLevel::from_node(use_item.syntax());
+                    let vis = use_item.visibility().map_or(String::new(), |it| format!("{it} "));
+                    edits.push((
+                        file_id,
+                        TextRange::empty(use_item.syntax().text_range().end()),
+                        format!("\n{indent}{vis}use {new_path}{rest};"),
+                    ));
+                }
+                None => edits.push((file_id, path.syntax().text_range(), new_path)),
+            }
+        }
+    }
+    edits
+}
+
+/// Fixes a `super` path inside of the moved module, as its ancestors change.
+fn rewrite_super_path(
+    ctx: &AssistContext<'_>,
+    module: Module,
+    parent_name: &Name,
+    path: &ast::Path,
+) -> Option<(TextRange, String)> {
+    if path.qualifier().is_some() || path.segment()?.super_token().is_none() {
+        return None;
+    }
+    // `pub(super)` still includes the old parent.
+    if let Some(vis) = path.syntax().parent().and_then(ast::Visibility::cast) {
+        if vis.in_token().is_none() {
+            return None;
+        }
+    }
+    let mut supers = path.clone();
+    let mut count = 1;
+    while let Some(parent_path) =
+        supers.parent_path().filter(|it| it.segment().and_then(|it| it.super_token()).is_some())
+    {
+        supers = parent_path;
+        count += 1;
+    }
+
+    let depth = ctx
+        .sema
+        .scope(path.syntax())?
+        .module()
+        .path_to_root(ctx.db())
+        .iter()
+        .position(|&it| it == module)?;
+    let range = supers.syntax().text_range();
+    match count.cmp(&(depth + 1)) {
+        Ordering::Less => None,
+        // `super` of the moved module is now the module its old parent is declared in.
+        Ordering::Equal => Some((range, format!("{supers}::{parent_name}"))),
+        Ordering::Greater => Some((range, iter::repeat("super").take(count - 1).join("::"))),
+    }
+}
+
+/// Makes an item of the old parent visible to the moved module.
+fn widen_visibility(
+    ctx: &AssistContext<'_>,
+    module: Module,
+    parent: Module,
+    grandparent: Module,
+    def: ModuleDef,
+) -> Option<(FileId, TextRange, String)> {
+    let db = ctx.db();
+    let def_module = def.module(db)?;
+    let def_ancestors = def_module.path_to_root(db);
+    if !def_ancestors.contains(&parent) || def_ancestors.contains(&module) {
+        return None;
+    }
+    if def.visibility(db).is_visible_from(db, grandparent.into()) {
+        return None;
+    }
+
+    let (offset, current_visibility, _, file_id, _) = target_data_for_def(db, def)?;
+    match current_visibility {
+        None if def_module == parent => {
+            Some((file_id, TextRange::empty(offset), "pub(super) ".to_owned()))
+        }
+        None => Some((file_id, TextRange::empty(offset), "pub(crate) ".to_owned())),
+        Some(vis) => Some((file_id, vis.syntax().text_range(), "pub(crate)".to_owned())),
+    }
+}
+
+fn with_leading_whitespace(node: &SyntaxNode) -> TextRange {
+    let range = node.text_range();
+    match node.prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE) {
+        Some(ws) => TextRange::new(ws.text_range().start(), range.end()),
+        None => with_trailing_whitespace(node),
+    }
+}
+
+fn with_trailing_whitespace(node: &SyntaxNode) -> TextRange {
+    let range = node.text_range();
+    match node.next_sibling_or_token().filter(|it| it.kind() == WHITESPACE) {
+        Some(ws) => TextRange::new(range.start(), ws.text_range().end()),
+        None => range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn move_inline_module() {
+        check_assist(
+            move_module_to_parent,
+            r#"
+mod foo {
+    pub mod $0bar {
+        pub struct S;
+    }
+    pub struct Other;
+}
+mod baz {
+    use crate::foo::bar::S;
+    use crate::foo::{bar::S as T, Other};
+}
+"#,
+            r#"
+mod foo {
+    pub struct Other;
+}
+
+pub mod bar {
+    pub struct S;
+}
+mod baz {
+    use crate::bar::S;
+    use crate::foo::{Other};
+    use crate::bar::S as T;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_super_paths_and_visibility() {
+        check_assist(
+            move_module_to_parent,
+            r#"
+mod foo {
+    pub(super) mod $0bar {
+        pub(super) fn f() { super::inner::g(); super::super::h(); }
+    }
+    mod inner {
+        pub(super) fn g() {}
+    }
+}
+fn h() {}
+"#,
+            r#"
+mod foo {
+    pub(super) mod inner {
+        pub(crate) fn g() {}
+    }
+}
+
+mod bar {
+    pub(super) fn f() { super::foo::inner::g(); super::h(); }
+}
+fn h() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_module_file() {
+        check_assist(
+            move_module_to_parent,
+            r#"
+//- /main.rs
+mod foo;
+//- /foo.rs
+mod $0bar;
+fn g() {}
+//- /foo/bar.rs
+fn f() { super::g(); }
+"#,
+            r#"
+//- /main.rs
+mod foo;
+
+mod bar;
+//- /foo.rs
+pub(super) fn g() {}
+//- /foo/bar.rs
+fn f() { super::foo::g(); }
+//- /bar.rs
+fn f() { super::g(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn move_module_dir() {
+        check_assist(
+            move_module_to_parent,
+            r#"
+//- /main.rs
+mod foo;
+//- /foo/mod.rs
+mod $0bar;
+//- /foo/bar/mod.rs
+mod baz;
+//- /foo/bar/baz.rs
+pub fn f() {}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+
+mod bar;
+//- /foo/mod.rs
+//- /bar
+// moved from /foo/bar
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_children_of_crate_root() {
+        check_assist_not_applicable(move_module_to_parent, r#"mod $0foo {}"#);
+    }
+
+    #[test]
+    fn not_applicable_in_module_items() {
+        check_assist_not_applicable(
+            move_module_to_parent,
+            r#"mod foo { mod bar { $0fn f() {} } }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_name_conflict() {
+        check_assist_not_applicable(
+            move_module_to_parent,
+            r#"mod foo { mod $0bar {} } mod bar {}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_path_attr() {
+        check_assist_not_applicable(
+            move_module_to_parent,
+            r#"
+//- /main.rs
+mod foo;
+//- /foo.rs
+#[path = "baz.rs"]
+mod $0bar;
+//- /foo/baz.rs
+"#,
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use hir::Adt;
+use ide_db::{
+    base_db::FileId, defs::Definition, search::FileReference,
+    syntax_helpers::node_ext::parse_tt_as_comma_sep_paths, FxHashMap, RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, HasArgList, HasName},
+    AstNode, SourceFile, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: reorder_fields_to_match_constructor
+//
+// Reorders the fields of a struct to match the order its literals most commonly use. For tuple
+// structs, the order is the one of the parameters of the functions constructing the struct from
+// them, and all constructor calls, patterns and field accesses are updated to the new positions.
+//
+// ```
+// struct Point$0(f32, f32, &'static str);
+//
+// impl Point {
+//     fn new(x: f32, y: f32, name: &'static str) -> Self {
+//         Self(y, x, name)
+//     }
+//
+//     fn x(&self) -> f32 {
+//         self.1
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32, &'static str);
+//
+// impl Point {
+//     fn new(x: f32, y: f32, name: &'static str) -> Self {
+//         Self(x, y, name)
+//     }
+//
+//     fn x(&self) -> f32 {
+//         self.0
+//     }
+// }
+// ```
+pub(crate) fn reorder_fields_to_match_constructor(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let strukt = ast::Struct::cast(name.syntax().parent()?)?;
+    let field_list = strukt.field_list()?;
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    if field_order_is_observable(ctx.db(), &strukt, strukt_def) {
+        cov_mark::hit!(field_order_is_observable);
+        return None;
+    }
+    let fields = strukt_def.fields(ctx.db());
+    if fields.len() < 2 {
+        return None;
+    }
+
+    let def = Definition::Adt(Adt::Struct(strukt_def));
+    let usages = def.usages(&ctx.sema).include_self_refs().all();
+    let field_names = fields.iter().map(|it| it.name(ctx.db()).to_string()).collect::<Vec<_>>();
+    let mut orders = Vec::new();
+    for (&file_id, references) in usages.iter() {
+        let source_file = ctx.sema.parse(file_id);
+        for reference in references {
+            let order = match &field_list {
+                ast::FieldList::RecordFieldList(_) => {
+                    record_literal_order(&source_file, reference, &field_names)
+                }
+                ast::FieldList::TupleFieldList(_) => {
+                    constructor_order(&source_file, reference, fields.len())
+                }
+            };
+            orders.extend(order);
+        }
+    }
+    let order = most_common(orders)?;
+    if order.iter().copied().eq(0..fields.len()) {
+        cov_mark::hit!(fields_already_in_constructor_order);
+        return None;
+    }
+
+    let field_ranges = match &field_list {
+        ast::FieldList::RecordFieldList(it) => {
+            it.fields().map(|it| it.syntax().text_range()).collect::<Vec<_>>()
+        }
+        ast::FieldList::TupleFieldList(it) => {
+            it.fields().map(|it| it.syntax().text_range()).collect::<Vec<_>>()
+        }
+    };
+    if field_ranges.len() != fields.len() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("reorder_fields_to_match_constructor", AssistKind::RefactorRewrite),
+        "Reorder fields to match the constructor",
+        strukt.syntax().text_range(),
+        |builder| {
+            let mut edits: FxHashMap<FileId, Vec<Edit>> = FxHashMap::default();
+            let field_ranges = field_ranges.into_iter().map(Some).collect();
+            edits.entry(ctx.file_id()).or_default().push(Edit::reorder(field_ranges));
+
+            // Field names of record structs carry the meaning of the values, tuple structs are
+            // positional, and everything using positions has to follow.
+            if let ast::FieldList::TupleFieldList(_) = &field_list {
+                let mut new_positions = vec![0; order.len()];
+                for (new, &old) in order.iter().enumerate() {
+                    new_positions[old] = new;
+                }
+                for (file_id, references) in usages {
+                    let source_file = ctx.sema.parse(file_id);
+                    let file_edits = edits.entry(file_id).or_default();
+                    file_edits.extend(references.iter().filter_map(|reference| {
+                        positional_usage_edit(&source_file, reference, fields.len())
+                    }));
+                }
+                for (old, field) in fields.iter().enumerate() {
+                    if new_positions[old] == old {
+                        continue;
+                    }
+                    let new_name = new_positions[old].to_string();
+                    for (file_id, references) in Definition::Field(*field).usages(&ctx.sema).all() {
+                        edits.entry(file_id).or_default().extend(
+                            references.into_iter().map(|it| Edit::replace(it.range, &new_name)),
+                        );
+                    }
+                }
+            }
+
+            for (file_id, file_edits) in edits {
+                let text = ctx.sema.parse(file_id).syntax().text().to_string();
+                builder.edit_file(file_id);
+                for (range, new_text) in render_edits(&text, file_edits, &order) {
+                    builder.replace(range, new_text);
+                }
+            }
+        },
+    )
+}
+
+/// Whether the order of the fields matters beyond the source: for the memory layout, or for the
+/// derived traits comparing, hashing or printing the fields in declaration order.
+fn field_order_is_observable(db: &RootDatabase, strukt: &ast::Struct, def: hir::Struct) -> bool {
+    if def.repr(db).map_or(false, |repr| repr.c() || repr.packed()) {
+        return true;
+    }
+    strukt
+        .attrs()
+        .filter_map(|attr| attr.as_simple_call())
+        .filter(|(name, _)| name == "derive")
+        .filter_map(|(_, tt)| parse_tt_as_comma_sep_paths(tt))
+        .flatten()
+        .filter_map(|path| path.segment()?.name_ref())
+        .any(|name| matches!(name.text().as_str(), "PartialOrd" | "Ord" | "Hash" | "Debug"))
+}
+
+/// The order of the fields in a record literal listing all of them, as indices of the fields.
+fn record_literal_order(
+    source_file: &SourceFile,
+    reference: &FileReference,
+    field_names: &[String],
+) -> Option<Vec<usize>> {
+    let record = struct_path_parent::<ast::RecordExpr>(source_file, reference)?;
+    let field_list = record.record_expr_field_list()?;
+    if field_list.spread().is_some() {
+        return None;
+    }
+    let order = field_list
+        .fields()
+        .map(|field| {
+            let name = field.field_name()?;
+            field_names.iter().position(|it| *it == name.text().as_str())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (order.len() == field_names.len() && order.iter().all_unique()).then_some(order)
+}
+
+/// The order in which a function passes its parameters to the tuple struct it constructs, like
+/// `[1, 0]` for `fn new(a: A, b: B) -> Self { Self(b, a) }`.
+fn constructor_order(
+    source_file: &SourceFile,
+    reference: &FileReference,
+    field_count: usize,
+) -> Option<Vec<usize>> {
+    let call = struct_path_parent::<ast::PathExpr>(source_file, reference)?
+        .syntax()
+        .parent()
+        .and_then(ast::CallExpr::cast)?;
+    let param_list = call.syntax().ancestors().find_map(ast::Fn::cast)?.param_list()?;
+    let param_names = param_list
+        .params()
+        .map(|param| match param.pat()? {
+            ast::Pat::IdentPat(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let param_indices = call
+        .arg_list()?
+        .args()
+        .map(|arg| {
+            let ast::Expr::PathExpr(path_expr) = arg else { return None };
+            let name = path_expr.path()?.as_single_name_ref()?;
+            param_names.iter().position(|it| it.as_deref() == Some(name.text().as_str()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if param_indices.len() != field_count || !param_indices.iter().all_unique() {
+        return None;
+    }
+    Some((0..field_count).sorted_by_key(|&field| param_indices[field]).collect())
+}
+
+/// Returns the most common order, preferring the first one seen on ties.
+fn most_common(orders: Vec<Vec<usize>>) -> Option<Vec<usize>> {
+    let mut counts: FxHashMap<&[usize], usize> = FxHashMap::default();
+    for order in &orders {
+        *counts.entry(order.as_slice()).or_default() += 1;
+    }
+    let max = counts.values().copied().max()?;
+    orders.iter().find(|it| counts[it.as_slice()] == max).cloned()
+}
+
+/// Finds the node of type `N` whose path is the reference to the struct.
+fn struct_path_parent<N: AstNode>(
+    source_file: &SourceFile,
+    reference: &FileReference,
+) -> Option<N> {
+    let name_ref: ast::NameRef = find_node_at_range(source_file.syntax(), reference.range)?;
+    let mut path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    while let Some(parent) = path.parent_path() {
+        path = parent;
+    }
+    N::cast(path.syntax().parent()?)
+}
+
+fn positional_usage_edit(
+    source_file: &SourceFile,
+    reference: &FileReference,
+    field_count: usize,
+) -> Option<Edit> {
+    if let Some(pat) = struct_path_parent::<ast::TupleStructPat>(source_file, reference) {
+        let fields = pat.fields().map(|it| it.syntax().text_range()).collect::<Vec<_>>();
+        if fields.len() == 1 && pat.fields().all(|it| matches!(it, ast::Pat::RestPat(_))) {
+            cov_mark::hit!(keeps_wildcard_rest_pattern);
+            return None;
+        }
+        let Some(rest) = pat.fields().position(|it| matches!(it, ast::Pat::RestPat(_))) else {
+            if fields.len() != field_count {
+                return None;
+            }
+            return Some(Edit::reorder(fields.into_iter().map(Some).collect()));
+        };
+        // `S(a, .., b)` binds the fields at both ends, spell out the ones in between.
+        let after_rest = fields.len() - rest - 1;
+        let mut items = vec![None; field_count];
+        for (idx, &field) in fields[..rest].iter().enumerate() {
+            *items.get_mut(idx)? = Some(field);
+        }
+        for (idx, &field) in fields[rest + 1..].iter().enumerate() {
+            *items.get_mut((field_count + idx).checked_sub(after_rest)?)? = Some(field);
+        }
+        let range = fields.first()?.cover(*fields.last()?);
+        return Some(Edit::Reorder { range, items, spelled_out: true });
+    }
+
+    let path_expr = struct_path_parent::<ast::PathExpr>(source_file, reference)?;
+    match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+        Some(call) => {
+            let args =
+                call.arg_list()?.args().map(|it| Some(it.syntax().text_range())).collect_vec();
+            if args.len() != field_count {
+                return None;
+            }
+            Some(Edit::reorder(args))
+        }
+        // The constructor used as a function, pass the arguments on in their new order.
+        None => {
+            let params = (0..field_count).map(|it| format!("f{it}")).join(", ");
+            Some(Edit::Constructor {
+                range: path_expr.syntax().text_range(),
+                params,
+                needs_parens: !path_expr.syntax().parent().map_or(false, |it| {
+                    ast::ArgList::can_cast(it.kind())
+                        || ast::LetStmt::can_cast(it.kind())
+                        || ast::ParenExpr::can_cast(it.kind())
+                }),
+            })
+        }
+    }
+}
+
+/// An edit of the text of a file, which may contain other edits in the text it moves around.
+enum Edit {
+    /// Puts the items listed in the struct's field order into the new order.
+    Reorder {
+        range: TextRange,
+        items: Vec<Option<TextRange>>,
+        /// Whether the items replace a `..` pattern, in which case missing items are spelled `_`
+        /// and the items are separated by `, ` instead of the separators of the source.
+        spelled_out: bool,
+    },
+    /// Wraps the constructor used as a function in a closure passing its arguments on in the new
+    /// order.
+    Constructor {
+        range: TextRange,
+        params: String,
+        needs_parens: bool,
+    },
+    Replace {
+        range: TextRange,
+        text: String,
+    },
+}
+
+impl Edit {
+    /// Reorders items which are all present, keeping the separators of the source.
+    fn reorder(items: Vec<Option<TextRange>>) -> Edit {
+        let range = items.iter().flatten().copied().reduce(TextRange::cover).unwrap_or_default();
+        Edit::Reorder { range, items, spelled_out: false }
+    }
+
+    fn replace(range: TextRange, text: &str) -> Edit {
+        Edit::Replace { range, text: text.to_owned() }
+    }
+
+    fn range(&self) -> TextRange {
+        match self {
+            Edit::Reorder { range, .. }
+            | Edit::Constructor { range, .. }
+            | Edit::Replace { range, .. } => *range,
+        }
+    }
+}
+
+/// Turns the edits into text replacements which don't overlap, by rendering edits nested in the
+/// text moved by other edits into the replacement of the outer edits.
+fn render_edits(text: &str, mut edits: Vec<Edit>, order: &[usize]) -> Vec<(TextRange, String)> {
+    edits.sort_by_key(|it| (it.range().start(), std::cmp::Reverse(it.range().end())));
+    edits.dedup_by_key(|it| it.range());
+    let renderer = Renderer { text, edits: &edits, order };
+    renderer.outermost(None).map(|idx| (edits[idx].range(), renderer.render_edit(idx))).collect()
+}
+
+struct Renderer<'a> {
+    text: &'a str,
+    edits: &'a [Edit],
+    order: &'a [usize],
+}
+
+impl Renderer<'_> {
+    /// The indices of the edits within `range`, or in the whole file, which are not nested in
+    /// another edit.
+    fn outermost(&self, range: Option<TextRange>) -> impl Iterator<Item = usize> + '_ {
+        let mut end = None;
+        (0..self.edits.len()).filter(move |&idx| {
+            let edit_range = self.edits[idx].range();
+            if range.map_or(false, |it| !it.contains_range(edit_range)) {
+                return false;
+            }
+            if end.map_or(false, |end| edit_range.end() <= end) {
+                return false;
+            }
+            end = Some(edit_range.end());
+            true
+        })
+    }
+
+    fn render_range(&self, range: TextRange) -> String {
+        let mut res = String::new();
+        let mut offset = range.start();
+        for idx in self.outermost(Some(range)).collect::<Vec<_>>() {
+            let edit_range = self.edits[idx].range();
+            res.push_str(&self.text[TextRange::new(offset, edit_range.start())]);
+            res.push_str(&self.render_edit(idx));
+            offset = edit_range.end();
+        }
+        res.push_str(&self.text[TextRange::new(offset, range.end())]);
+        res
+    }
+
+    fn render_edit(&self, idx: usize) -> String {
+        match &self.edits[idx] {
+            Edit::Reorder { items, spelled_out, .. } => {
+                let mut res = String::new();
+                for (new, &old) in self.order.iter().enumerate() {
+                    if new > 0 {
+                        match (*spelled_out, items[new - 1], items[new]) {
+                            (false, Some(prev), Some(next)) => {
+                                res.push_str(&self.text[TextRange::new(prev.end(), next.start())])
+                            }
+                            _ => res.push_str(", "),
+                        }
+                    }
+                    match items[old] {
+                        Some(item) => res.push_str(&self.render_range(item)),
+                        None => res.push('_'),
+                    }
+                }
+                res
+            }
+            Edit::Constructor { range, params, needs_parens } => {
+                let args = self.order.iter().map(|it| format!("f{it}")).join(", ");
+                let closure = format!("|{params}| {}({args})", &self.text[*range]);
+                if *needs_parens {
+                    format!("({closure})")
+                } else {
+                    closure
+                }
+            }
+            Edit::Replace { text, .. } => text.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn reorders_record_fields_like_the_literals() {
+        check_assist(
+            reorder_fields_to_match_constructor,
+            r#"
+struct Rect$0 {
+    height: u32,
+    width: u32,
+}
+fn square(side: u32) -> Rect {
+    Rect { width: side, height: side }
+}
+fn wide() -> Rect {
+    Rect { width: 10, height: 1 }
+}
+fn tall() -> Rect {
+    Rect { height: 10, width: 1 }
+}
+"#,
+            r#"
+struct Rect {
+    width: u32,
+    height: u32,
+}
+fn square(side: u32) -> Rect {
+    Rect { width: side, height: side }
+}
+fn wide() -> Rect {
+    Rect { width: 10, height: 1 }
+}
+fn tall() -> Rect {
+    Rect { height: 10, width: 1 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_incomplete_record_literals() {
+        cov_mark::check!(fields_already_in_constructor_order);
+        check_assist_not_applicable(
+            reorder_fields_to_match_constructor,
+            r#"
+struct Rect$0 { height: u32, width: u32 }
+fn f(r: Rect) -> Rect {
+    Rect { width: 1, ..r }
+}
+fn g() -> Rect {
+    Rect { height: 1, width: 2 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrites_tuple_struct_usages() {
+        cov_mark::check!(keeps_wildcard_rest_pattern);
+        check_assist(
+            reorder_fields_to_match_constructor,
+            r#"
+struct Pair$0(u8, bool);
+impl Pair {
+    fn new(flag: bool, value: u8) -> Self {
+        Self(value, flag)
+    }
+    fn flag(&self) -> bool {
+        self.1
+    }
+}
+fn f(p: Pair) -> u8 {
+    let Pair(value, _) = Pair(1, true);
+    match p {
+        Pair(v, true) => v + value,
+        Pair(..) => 0,
+    }
+}
+"#,
+            r#"
+struct Pair(bool, u8);
+impl Pair {
+    fn new(flag: bool, value: u8) -> Self {
+        Self(flag, value)
+    }
+    fn flag(&self) -> bool {
+        self.0
+    }
+}
+fn f(p: Pair) -> u8 {
+    let Pair(_, value) = Pair(true, 1);
+    match p {
+        Pair(true, v) => v + value,
+        Pair(..) => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn spells_out_rest_patterns() {
+        check_assist(
+            reorder_fields_to_match_constructor,
+            r#"
+struct T$0(u8, u16, u32);
+fn new(c: u32, a: u8, b: u16) -> T {
+    T(a, b, c)
+}
+fn first(T(a, ..): T) -> u8 {
+    a
+}
+"#,
+            r#"
+struct T(u32, u8, u16);
+fn new(c: u32, a: u8, b: u16) -> T {
+    T(c, a, b)
+}
+fn first(T(_, a, _): T) -> u8 {
+    a
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn handles_nested_constructors_and_function_values() {
+        check_assist(
+            reorder_fields_to_match_constructor,
+            r#"
+struct P$0(u8, u16);
+fn new(b: u16, a: u8) -> P {
+    P(a, b)
+}
+fn nested() -> P {
+    P(P(1, 2).0, 3)
+}
+fn values() -> [Option<P>; 2] {
+    let f = P;
+    [Some(f(1, 2)), Some(1).zip(Some(2)).map(|(a, b)| (P)(a, b))]
+}
+"#,
+            r#"
+struct P(u16, u8);
+fn new(b: u16, a: u8) -> P {
+    P(b, a)
+}
+fn nested() -> P {
+    P(3, P(2, 1).1)
+}
+fn values() -> [Option<P>; 2] {
+    let f = |f0, f1| P(f1, f0);
+    [Some(f(1, 2)), Some(1).zip(Some(2)).map(|(a, b)| (|f0, f1| P(f1, f0))(a, b))]
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_observable_field_order() {
+        let usages = r#"
+fn square(side: u32) -> Rect {
+    Rect { width: side, height: side }
+}
+"#;
+        for attrs in [
+            "#[repr(C)]",
+            "#[repr(packed)]",
+            "#[repr(C, align(8))]",
+            "#[derive(Clone, PartialOrd)]",
+            "#[derive(Ord)]",
+            "#[derive(core::hash::Hash)]",
+            "#[derive(Debug)]",
+        ] {
+            cov_mark::check!(field_order_is_observable);
+            check_assist_not_applicable(
+                reorder_fields_to_match_constructor,
+                &format!("{attrs}\nstruct Rect$0 {{ height: u32, width: u32 }}\n{usages}"),
+            );
+        }
+    }
+
+    #[test]
+    fn applicable_with_other_derives_and_reprs() {
+        check_assist(
+            reorder_fields_to_match_constructor,
+            r#"
+#[derive(Clone, PartialEq, Eq)]
+#[repr(align(8))]
+struct Rect$0 { height: u32, width: u32 }
+fn square(side: u32) -> Rect {
+    Rect { width: side, height: side }
+}
+"#,
+            r#"
+#[derive(Clone, PartialEq, Eq)]
+#[repr(align(8))]
+struct Rect { width: u32, height: u32 }
+fn square(side: u32) -> Rect {
+    Rect { width: side, height: side }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_constructor() {
+        check_assist_not_applicable(
+            reorder_fields_to_match_constructor,
+            r#"
+struct P$0(u8, u16);
+fn make(x: u8) -> P {
+    P(x, 2)
+}
+"#,
+        );
+    }
+}
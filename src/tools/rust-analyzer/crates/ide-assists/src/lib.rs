@@ -77,7 +77,7 @@ macro_rules! eprintln {
 
 pub(crate) use crate::assist_context::{AssistContext, Assists};
 
-pub use assist_config::AssistConfig;
+pub use assist_config::{AssistConfig, AsyncRuntime};
 pub use ide_db::assists::{
     Assist, AssistId, AssistKind, AssistResolveStrategy, GroupLabel, SingleResolve,
 };
@@ -112,11 +112,14 @@ mod handlers {
     mod add_lifetime_to_type;
     mod add_missing_impl_members;
     mod add_turbo_fish;
+    mod align_param_names_with_trait;
     mod apply_demorgan;
     mod auto_import;
     mod change_visibility;
+    mod convert_blocking_io_to_async;
     mod convert_bool_then;
     mod convert_comment_block;
+    mod convert_doc_example_to_test;
     mod convert_integer_literal;
     mod convert_into_to_from;
     mod convert_iter_for_each_to_for;
@@ -155,6 +158,7 @@ mod handlers {
     mod generate_getter;
     mod generate_impl;
     mod generate_is_empty_from_len;
+    mod generate_mock_trait_impl;
     mod generate_new;
     mod generate_setter;
     mod generate_delegate_methods;
@@ -171,6 +175,7 @@ mod handlers {
     mod move_const_to_impl;
     mod move_guard;
     mod move_module_to_file;
+    mod move_module_to_parent;
     mod move_to_mod_rs;
     mod move_from_mod_rs;
     mod number_representation;
@@ -184,6 +189,7 @@ mod handlers {
     mod remove_unused_param;
     mod remove_parentheses;
     mod reorder_fields;
+    mod reorder_fields_to_match_constructor;
     mod reorder_impl_items;
     mod replace_try_expr_with_match;
     mod replace_derive_with_manual_impl;
@@ -217,12 +223,16 @@ pub(crate) fn all() -> &'static [Handler] {
             add_lifetime_to_type::add_lifetime_to_type,
             add_return_type::add_return_type,
             add_turbo_fish::add_turbo_fish,
+            align_param_names_with_trait::align_param_names_with_trait,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_visibility::change_visibility,
+            convert_blocking_io_to_async::convert_blocking_io_to_async,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
             convert_comment_block::convert_comment_block,
+            convert_doc_example_to_test::convert_doc_example_to_test,
+            convert_doc_example_to_test::convert_test_to_doc_example,
             convert_integer_literal::convert_integer_literal,
             convert_into_to_from::convert_into_to_from,
             convert_iter_for_each_to_for::convert_iter_for_each_to_for,
@@ -259,6 +269,7 @@ pub(crate) fn all() -> &'static [Handler] {
             generate_impl::generate_impl,
             generate_impl::generate_trait_impl,
             generate_is_empty_from_len::generate_is_empty_from_len,
+            generate_mock_trait_impl::generate_mock_trait_impl,
             generate_new::generate_new,
             inline_call::inline_call,
             inline_call::inline_into_callers,
@@ -276,6 +287,7 @@ pub(crate) fn all() -> &'static [Handler] {
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
             move_module_to_file::move_module_to_file,
+            move_module_to_parent::move_module_to_parent,
             move_to_mod_rs::move_to_mod_rs,
             move_from_mod_rs::move_from_mod_rs,
             number_representation::reformat_number_literal,
@@ -291,6 +303,7 @@ pub(crate) fn all() -> &'static [Handler] {
             remove_unused_param::remove_unused_param,
             remove_parentheses::remove_parentheses,
             reorder_fields::reorder_fields,
+            reorder_fields_to_match_constructor::reorder_fields_to_match_constructor,
             reorder_impl_items::reorder_impl_items,
             replace_try_expr_with_match::replace_try_expr_with_match,
             replace_derive_with_manual_impl::replace_derive_with_manual_impl,
@@ -5,7 +5,7 @@
 use expect_test::expect;
 use hir::{db::DefDatabase, Semantics};
 use ide_db::{
-    base_db::{fixture::WithFixture, FileId, FileRange, SourceDatabaseExt},
+    base_db::{fixture::WithFixture, AnchoredPathBuf, FileId, FileRange, SourceDatabaseExt},
     imports::insert_use::{ImportGranularity, InsertUseConfig},
     source_change::FileSystemEdit,
     RootDatabase, SnippetCap,
@@ -16,7 +16,7 @@
 
 use crate::{
     assists, handlers::Handler, Assist, AssistConfig, AssistContext, AssistKind,
-    AssistResolveStrategy, Assists, SingleResolve,
+    AssistResolveStrategy, Assists, AsyncRuntime, SingleResolve,
 };
 
 pub(crate) const TEST_CONFIG: AssistConfig = AssistConfig {
@@ -30,7 +30,10 @@
         skip_glob_imports: true,
     },
     prefer_no_std: false,
+    prefer_defining_crate: false,
     assist_emit_must_use: false,
+    async_runtime: AsyncRuntime::Tokio,
+    async_io_mappings: Vec::new(),
 };
 
 pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
@@ -44,7 +47,10 @@
         skip_glob_imports: true,
     },
     prefer_no_std: false,
+    prefer_defining_crate: false,
     assist_emit_must_use: false,
+    async_runtime: AsyncRuntime::Tokio,
+    async_io_mappings: Vec::new(),
 };
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
@@ -73,6 +79,23 @@ pub(crate) fn check_assist_no_snippet_cap(
     );
 }
 
+#[track_caller]
+pub(crate) fn check_assist_with_config(
+    config: AssistConfig,
+    assist: Handler,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+) {
+    let ra_fixture_after = trim_indent(ra_fixture_after);
+    check_with_config(
+        config,
+        assist,
+        ra_fixture_before,
+        ExpectedResult::After(&ra_fixture_after),
+        None,
+    );
+}
+
 // There is no way to choose what assist within a group you want to test against,
 // so this is here to allow you choose.
 pub(crate) fn check_assist_by_label(
@@ -203,23 +226,25 @@ fn check_with_config(
                 buf.push_str(&text);
             }
 
+            let anchored_path = |path: &AnchoredPathBuf| {
+                let sr = db.file_source_root(path.anchor);
+                let sr = db.source_root(sr);
+                let mut base = sr.path_for_file(&path.anchor).unwrap().clone();
+                base.pop();
+                base.join(&path.path).unwrap()
+            };
             for file_system_edit in source_change.file_system_edits {
                 let (dst, contents) = match file_system_edit {
                     FileSystemEdit::CreateFile { dst, initial_contents } => (dst, initial_contents),
                     FileSystemEdit::MoveFile { src, dst } => {
                         (dst, db.file_text(src).as_ref().to_owned())
                     }
-                    FileSystemEdit::MoveDir { src, src_id, dst } => {
-                        // temporary placeholder for MoveDir since we are not using MoveDir in ide assists yet.
-                        (dst, format!("{src_id:?}\n{src:?}"))
+                    FileSystemEdit::MoveDir { src, src_id: _, dst } => {
+                        // Directories have no text, show where they are moved from instead.
+                        (dst, format!("// moved from {}\n", anchored_path(&src)))
                     }
                 };
-                let sr = db.file_source_root(dst.anchor);
-                let sr = db.source_root(sr);
-                let mut base = sr.path_for_file(&dst.anchor).unwrap().clone();
-                base.pop();
-                let created_file_path = base.join(&dst.path).unwrap();
-                format_to!(buf, "//- {}\n", created_file_path);
+                format_to!(buf, "//- {}\n", anchored_path(&dst));
                 buf.push_str(&contents);
             }
 
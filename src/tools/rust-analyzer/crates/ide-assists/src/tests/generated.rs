@@ -227,6 +227,41 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_align_param_names_with_trait() {
+    check_doc_test(
+        "align_param_names_with_trait",
+        r#####"
+trait Shape {
+    fn resize(&mut self, width: u32, height: u32);
+}
+
+struct Rect { w: u32, h: u32 }
+
+impl Shape for Rect {
+    fn $0resize(&mut self, height: u32, width: u32) {
+        self.w = width;
+        self.h = height;
+    }
+}
+"#####,
+        r#####"
+trait Shape {
+    fn resize(&mut self, width: u32, height: u32);
+}
+
+struct Rect { w: u32, h: u32 }
+
+impl Shape for Rect {
+    fn resize(&mut self, width: u32, height: u32) {
+        self.w = height;
+        self.h = width;
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_apply_demorgan() {
     check_doc_test(
@@ -278,6 +313,28 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_blocking_io_to_async() {
+    check_doc_test(
+        "convert_blocking_io_to_async",
+        r#####"
+//- /main.rs crate:main deps:std
+fn load$0(path: &str) -> Vec<u8> {
+    std::fs::read(path)
+}
+//- /std.rs crate:std
+pub mod fs {
+    pub fn read(path: &str) -> Vec<u8> { loop {} }
+}
+"#####,
+        r#####"
+async fn load(path: &str) -> Vec<u8> {
+    tokio::fs::read(path).await
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_bool_then_to_if() {
     check_doc_test(
@@ -300,6 +357,42 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_doc_example_to_test() {
+    check_doc_test(
+        "convert_doc_example_to_test",
+        r#####"
+/// Adds two numbers.
+///
+/// # Examples
+///
+/// ```$0
+/// # use test::add;
+/// assert_eq!(add(1, 2), 3);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+mod tests {
+    use super::*;
+}
+"#####,
+        r#####"
+/// Adds two numbers.
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_doc_example() {
+        use crate::add;
+        assert_eq!(add(1, 2), 3);
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_with_for_each() {
     check_doc_test(
@@ -494,6 +587,39 @@ pub fn y(&self) -> f32 {
     )
 }
 
+#[test]
+fn doctest_convert_test_to_doc_example() {
+    check_doc_test(
+        "convert_test_to_doc_example",
+        r#####"
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn $0test_add() {
+        let three = add(1, 2);
+    }
+}
+"#####,
+        r#####"
+/// # Examples
+///
+/// ```
+/// use test::add;
+///
+/// let three = add(1, 2);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+mod tests {
+    use super::*;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -1368,6 +1494,41 @@ pub fn is_empty(&self) -> bool {
     )
 }
 
+#[test]
+fn doctest_generate_mock_trait_impl() {
+    check_doc_test(
+        "generate_mock_trait_impl",
+        r#####"
+trait Store$0 {
+    fn get(&self, key: &str) -> Option<u32>;
+}
+"#####,
+        r#####"
+trait Store {
+    fn get(&self, key: &str) -> Option<u32>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    $0struct MockStore {
+        get_calls: std::cell::RefCell<Vec<String>>,
+        get_returns: std::cell::RefCell<std::collections::VecDeque<Option<u32>>>,
+    }
+
+    impl Store for MockStore {
+        fn get(&self, key: &str) -> Option<u32> {
+            self.get_calls.borrow_mut().push(key.to_owned());
+            self.get_returns.borrow_mut().pop_front().expect("no return value queued for `MockStore::get`")
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_new() {
     check_doc_test(
@@ -1867,6 +2028,32 @@ fn t() {}
     )
 }
 
+#[test]
+fn doctest_move_module_to_parent() {
+    check_doc_test(
+        "move_module_to_parent",
+        r#####"
+mod foo {
+    mod $0bar {
+        pub fn f() { super::g() }
+    }
+    fn g() {}
+    pub fn h() { bar::f() }
+}
+"#####,
+        r#####"
+mod foo {
+    pub(super) fn g() {}
+    pub fn h() { crate::bar::f() }
+}
+
+mod bar {
+    pub fn f() { super::foo::g() }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_to_mod_rs() {
     check_doc_test(
@@ -2104,6 +2291,39 @@ fn doctest_reorder_fields() {
     )
 }
 
+#[test]
+fn doctest_reorder_fields_to_match_constructor() {
+    check_doc_test(
+        "reorder_fields_to_match_constructor",
+        r#####"
+struct Point$0(f32, f32, &'static str);
+
+impl Point {
+    fn new(x: f32, y: f32, name: &'static str) -> Self {
+        Self(y, x, name)
+    }
+
+    fn x(&self) -> f32 {
+        self.1
+    }
+}
+"#####,
+        r#####"
+struct Point(f32, f32, &'static str);
+
+impl Point {
+    fn new(x: f32, y: f32, name: &'static str) -> Self {
+        Self(x, y, name)
+    }
+
+    fn x(&self) -> f32 {
+        self.0
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_reorder_impl_items() {
     check_doc_test(
@@ -169,6 +169,7 @@ pub(crate) fn complete_expr_path(
                                 ctx.db,
                                 hir::ModuleDef::from(strukt),
                                 ctx.config.prefer_no_std,
+                                ctx.config.prefer_defining_crate,
                             )
                             .filter(|it| it.len() > 1);
 
@@ -191,6 +192,7 @@ pub(crate) fn complete_expr_path(
                                 ctx.db,
                                 hir::ModuleDef::from(un),
                                 ctx.config.prefer_no_std,
+                                ctx.config.prefer_defining_crate,
                             )
                             .filter(|it| it.len() > 1);
 
@@ -266,6 +266,7 @@ fn import_on_the_fly(
                 &ctx.sema,
                 ctx.config.insert_use.prefix_kind,
                 ctx.config.prefer_no_std,
+                ctx.config.prefer_defining_crate,
             )
             .into_iter()
             .filter(ns_filter)
@@ -314,6 +315,7 @@ fn import_on_the_fly_pat_(
                 &ctx.sema,
                 ctx.config.insert_use.prefix_kind,
                 ctx.config.prefer_no_std,
+                ctx.config.prefer_defining_crate,
             )
             .into_iter()
             .filter(ns_filter)
@@ -352,7 +354,12 @@ fn import_on_the_fly_method(
     let user_input_lowercased = potential_import_name.to_lowercase();
 
     import_assets
-        .search_for_imports(&ctx.sema, ctx.config.insert_use.prefix_kind, ctx.config.prefer_no_std)
+        .search_for_imports(
+            &ctx.sema,
+            ctx.config.insert_use.prefix_kind,
+            ctx.config.prefer_no_std,
+            ctx.config.prefer_defining_crate,
+        )
         .into_iter()
         .filter(|import| {
             !ctx.is_item_hidden(&import.item_to_import)
@@ -18,6 +18,7 @@ pub struct CompletionConfig {
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
+    pub prefer_defining_crate: bool,
     pub snippets: Vec<Snippet>,
     pub limit: Option<usize>,
 }
@@ -241,6 +241,7 @@ pub fn resolve_completion_edits(
                     candidate,
                     config.insert_use.prefix_kind,
                     config.prefer_no_std,
+                    config.prefer_defining_crate,
                 )
             })
             .find(|mod_path| mod_path.to_string() == full_import_path);
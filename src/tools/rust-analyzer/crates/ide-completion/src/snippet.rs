@@ -179,6 +179,7 @@ fn import_edits(ctx: &CompletionContext<'_>, requires: &[GreenNode]) -> Option<V
             item,
             ctx.config.insert_use.prefix_kind,
             ctx.config.prefer_no_std,
+            ctx.config.prefer_defining_crate,
         )?;
         Some((path.len() > 1).then(|| LocatedImport::new(path.clone(), item, item, None)))
     };
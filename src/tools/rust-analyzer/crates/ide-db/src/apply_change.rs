@@ -145,6 +145,9 @@ macro_rules! purge_each_query {
             // LineIndexDatabase
             crate::LineIndexQuery
 
+            // SearchDatabase
+            crate::search::FileIdentsQuery
+
             // InternDatabase
             hir::db::InternFunctionQuery
             hir::db::InternStructQuery
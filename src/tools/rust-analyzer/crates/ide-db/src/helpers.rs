@@ -2,7 +2,7 @@
 
 use std::collections::VecDeque;
 
-use base_db::FileId;
+use base_db::{FileId, SourceDatabaseExt};
 use hir::{ItemInNs, ModuleDef, Name, Semantics};
 use syntax::{
     ast::{self, make},
@@ -19,6 +19,12 @@ pub fn item_name(db: &RootDatabase, item: ItemInNs) -> Option<Name> {
     }
 }
 
+/// Whether the analysis policy of the file's source root includes the file. See
+/// [`AnalysisPolicy`](base_db::AnalysisPolicy).
+pub fn is_analyzed(db: &RootDatabase, file_id: FileId) -> bool {
+    db.source_root(db.file_source_root(file_id)).analysis_policy.is_analyzed(file_id)
+}
+
 /// Picks the token with the highest rank returned by the passed in function.
 pub fn pick_best_token(
     tokens: TokenAtOffset<SyntaxToken>,
@@ -15,6 +15,7 @@
 pub mod label;
 pub mod line_index;
 pub mod path_transform;
+pub mod query_stats;
 pub mod rename;
 pub mod rust_doc;
 pub mod search;
@@ -73,6 +74,7 @@ pub mod syntax_helpers {
     hir::db::HirDatabaseStorage,
     hir::db::InternDatabaseStorage,
     LineIndexDatabaseStorage,
+    search::SearchDatabaseStorage,
     symbol_index::SymbolsDatabaseStorage
 )]
 pub struct RootDatabase {
@@ -125,7 +127,13 @@ fn relevant_crates(&self, file_id: FileId) -> Arc<NoHashHashSet<CrateId>> {
     }
 }
 
-impl salsa::Database for RootDatabase {}
+impl salsa::Database for RootDatabase {
+    fn salsa_event(&self, event: salsa::Event) {
+        if let salsa::EventKind::WillExecute { database_key } = event.kind {
+            query_stats::record(|| format!("{:?}", database_key.debug(self)));
+        }
+    }
+}
 
 impl Default for RootDatabase {
     fn default() -> RootDatabase {
@@ -139,16 +147,59 @@ pub fn new(lru_capacity: Option<usize>) -> RootDatabase {
         db.set_crate_graph_with_durability(Default::default(), Durability::HIGH);
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
+        db.set_index_library_bodies_with_durability(true, Durability::HIGH);
         db.set_enable_proc_attr_macros(false);
+        db.set_expansion_limits(Default::default());
         db.update_lru_capacity(lru_capacity);
         db
     }
 
     pub fn update_lru_capacity(&mut self, lru_capacity: Option<usize>) {
+        self.update_lru_capacities(lru_capacity, &FxHashMap::default());
+    }
+
+    /// Sets the LRU capacities of the expensive queries. `lru_capacities` overrides the capacity
+    /// of the queries by name, like `InferQueryQuery`, which is how they are listed by
+    /// [`RootDatabase::per_query_memory_usage`]. A capacity of 0 means no limit.
+    ///
+    /// Returns the names in `lru_capacities` which don't match any of these queries.
+    pub fn update_lru_capacities(
+        &mut self,
+        lru_capacity: Option<usize>,
+        lru_capacities: &FxHashMap<Box<str>, usize>,
+    ) -> Vec<Box<str>> {
         let lru_capacity = lru_capacity.unwrap_or(base_db::DEFAULT_LRU_CAP);
-        base_db::ParseQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
-        hir::db::ParseMacroExpansionQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
-        hir::db::MacroExpandQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
+        let mut unknown: Vec<_> = lru_capacities.keys().cloned().collect();
+        macro_rules! update_lru_capacity_per_query {
+            ($($q:path = $default:expr,)*) => {$(
+                let q: $q = Default::default();
+                let name = format!("{q:?}");
+                unknown.retain(|it| **it != *name);
+                let capacity = lru_capacities.get(&*name).copied().unwrap_or($default);
+                q.in_db_mut(self).set_lru_capacity(capacity);
+            )*}
+        }
+        update_lru_capacity_per_query![
+            base_db::ParseQuery = lru_capacity,
+            hir::db::ParseMacroExpansionQuery = lru_capacity,
+            hir::db::MacroExpandQuery = lru_capacity,
+            hir::db::BodyWithSourceMapQuery = 0,
+            hir::db::BodyQuery = 0,
+            hir::db::InferQueryQuery = 0,
+        ];
+        unknown.sort();
+        unknown
+    }
+
+    /// Drops the memoized values of the expensive queries, which are recomputed on demand.
+    pub fn evict_lru_queries(&mut self) {
+        let _p = profile::span("RootDatabase::evict_lru_queries");
+        base_db::ParseQuery.in_db(self).purge();
+        hir::db::ParseMacroExpansionQuery.in_db(self).purge();
+        hir::db::MacroExpandQuery.in_db(self).purge();
+        hir::db::BodyWithSourceMapQuery.in_db(self).purge();
+        hir::db::BodyQuery.in_db(self).purge();
+        hir::db::InferQueryQuery.in_db(self).purge();
     }
 }
 
@@ -244,5 +295,27 @@ pub const fn new(allow_snippets: bool) -> Option<SnippetCap> {
 
 #[cfg(test)]
 mod tests {
+    use rustc_hash::FxHashMap;
+
+    use crate::RootDatabase;
+
     mod sourcegen_lints;
+
+    #[test]
+    fn lru_capacities_match_memory_usage_names() {
+        let names =
+            ["ParseQuery", "MacroExpandQuery", "BodyQuery", "InferQueryQuery", "InferQuery"];
+        let lru_capacities: FxHashMap<Box<str>, usize> =
+            names.iter().map(|&name| (name.into(), 16)).collect();
+
+        let mut db = RootDatabase::new(None);
+        let unknown = db.update_lru_capacities(None, &lru_capacities);
+        assert_eq!(unknown, [Box::<str>::from("InferQuery")]);
+
+        let listed: Vec<_> =
+            db.per_query_memory_usage().into_iter().map(|(name, _)| name).collect();
+        for name in names.iter().filter(|&&name| name != "InferQuery") {
+            assert!(listed.iter().any(|it| it == name), "`{name}` is not listed in memory usage");
+        }
+    }
 }
@@ -0,0 +1,73 @@
+//! Counts the salsa queries executed on the current thread.
+//!
+//! Recording is opt-in via [`collect`], and costs a thread-local lookup per executed query
+//! otherwise. Queries executed on other threads, like the parallel parts of the symbol index, are
+//! not counted.
+
+use std::cell::RefCell;
+
+use crate::FxHashMap;
+
+thread_local! {
+    static COUNTS: RefCell<Option<FxHashMap<String, u32>>> = RefCell::new(None);
+}
+
+/// The number of executions per query, most executed queries first.
+#[derive(Debug, Default, Clone)]
+pub struct QueryStats {
+    pub counts: Vec<(String, u32)>,
+}
+
+/// Runs `f`, counting the salsa queries it executes (as opposed to fetching memoized results).
+///
+/// Nested calls are not supported, the inner call takes over recording until it returns.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, QueryStats) {
+    let prev = COUNTS.with(|counts| counts.replace(Some(FxHashMap::default())));
+    let res = f();
+    let counts = COUNTS.with(|counts| counts.replace(prev)).unwrap_or_default();
+
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|(name_a, count_a), (name_b, count_b)| {
+        count_b.cmp(count_a).then_with(|| name_a.cmp(name_b))
+    });
+    (res, QueryStats { counts })
+}
+
+/// Records the execution of a query, `debug` renders the query's database key.
+pub(crate) fn record(debug: impl FnOnce() -> String) {
+    if COUNTS.with(|counts| counts.borrow().is_none()) {
+        return;
+    }
+    // Rendering the key may execute queries itself, so don't hold on to the borrow meanwhile.
+    let mut name = debug();
+    // Strip the query key, `crate_def_map_query(CrateId(0))` becomes `crate_def_map_query`.
+    if let Some(idx) = name.find('(') {
+        name.truncate(idx);
+    }
+    COUNTS.with(|counts| {
+        if let Some(counts) = &mut *counts.borrow_mut() {
+            *counts.entry(name).or_default() += 1;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_recorded_queries() {
+        let ((), stats) = collect(|| {
+            record(|| "parse_query(FileId(0))".to_owned());
+            record(|| "infer_query(FunctionId(1))".to_owned());
+            record(|| "parse_query(FileId(1))".to_owned());
+        });
+        assert_eq!(
+            stats.counts,
+            vec![("parse_query".to_owned(), 2), ("infer_query".to_owned(), 1)]
+        );
+
+        // Nothing is recorded outside of `collect`.
+        record(|| unreachable!());
+    }
+}
@@ -27,7 +27,7 @@
 use hir::{FieldSource, HasSource, InFile, ModuleSource, Semantics};
 use stdx::never;
 use syntax::{
-    ast::{self, HasName},
+    ast::{self, HasAttrs, HasName},
     AstNode, SyntaxKind, TextRange, T,
 };
 use text_edit::{TextEdit, TextEditBuilder};
@@ -188,8 +188,12 @@ fn rename_mod(
         return Ok(source_change);
     }
 
+    // The file of a module declared with a `#[path]` attribute does not depend on its name.
+    let has_path_attr = module.declaration_source(sema.db).map_or(false, |src| {
+        src.value.attrs().any(|attr| attr.simple_name().as_deref() == Some("path"))
+    });
     let InFile { file_id, value: def_source } = module.definition_source(sema.db);
-    if let ModuleSource::SourceFile(..) = def_source {
+    if matches!(def_source, ModuleSource::SourceFile(..)) && !has_path_attr {
         let new_name = new_name.trim_start_matches("r#");
         let anchor = file_id.original_file(sema.db);
 
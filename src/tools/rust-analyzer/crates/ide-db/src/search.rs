@@ -6,7 +6,7 @@
 
 use std::{mem, sync::Arc};
 
-use base_db::{FileId, FileRange, SourceDatabase, SourceDatabaseExt};
+use base_db::{salsa, FileId, FileRange, SourceDatabase, SourceDatabaseExt};
 use hir::{
     AsAssocItem, DefWithBody, HasAttrs, HasSource, InFile, ModuleSource, Semantics, Visibility,
 };
@@ -14,14 +14,82 @@
 use once_cell::unsync::Lazy;
 use parser::SyntaxKind;
 use stdx::hash::NoHashHashMap;
-use syntax::{ast, match_ast, AstNode, TextRange, TextSize};
+use syntax::{
+    ast::{self, HasVisibility},
+    match_ast, AstNode, SmolStr, TextRange, TextSize,
+};
 
 use crate::{
     defs::{Definition, NameClass, NameRefClass},
     traits::{as_trait_assoc_def, convert_to_def_in_trait},
-    RootDatabase,
+    FxHashSet, RootDatabase,
 };
 
+#[salsa::query_group(SearchDatabaseStorage)]
+pub trait SearchDatabase: SourceDatabase {
+    /// The identifiers mentioned in a given file. Unlike a raw text search this is cached, which
+    /// makes it cheap to rule out files when the same name is looked up repeatedly.
+    fn file_idents(&self, file_id: FileId) -> Arc<FileIdents>;
+}
+
+fn file_idents(db: &dyn SearchDatabase, file_id: FileId) -> Arc<FileIdents> {
+    let _p = profile::span("file_idents");
+    let source_file = db.parse(file_id).tree();
+    let mut res = FileIdents::default();
+    let idents = |node: &syntax::SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == SyntaxKind::IDENT)
+            .map(|it| SmolStr::new(it.text().trim_start_matches("r#")))
+            .collect::<Vec<_>>()
+    };
+    res.all.extend(idents(source_file.syntax()));
+    for node in source_file.syntax().descendants() {
+        match_ast! {
+            match node {
+                ast::Use(it) => {
+                    let is_pub = it
+                        .visibility()
+                        .map_or(false, |vis| matches!(vis.kind(), ast::VisibilityKind::Pub));
+                    if is_pub {
+                        res.pub_use.extend(idents(it.syntax()));
+                    }
+                },
+                ast::MacroRules(it) => res.macro_def.extend(idents(it.syntax())),
+                ast::MacroDef(it) => res.macro_def.extend(idents(it.syntax())),
+                _ => (),
+            }
+        }
+    }
+    Arc::new(res)
+}
+
+/// The identifiers of a file, as computed by [`SearchDatabase::file_idents`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileIdents {
+    all: FxHashSet<SmolStr>,
+    pub_use: FxHashSet<SmolStr>,
+    macro_def: FxHashSet<SmolStr>,
+}
+
+impl FileIdents {
+    /// Whether `name` occurs anywhere in the file, including inside macro calls.
+    pub fn contains(&self, name: &str) -> bool {
+        self.all.contains(name)
+    }
+
+    /// Whether `name` occurs in a `pub use` item of the file.
+    pub fn contains_in_pub_use(&self, name: &str) -> bool {
+        self.pub_use.contains(name)
+    }
+
+    /// Whether `name` occurs in a macro definition of the file. Such occurrences can't be
+    /// resolved without knowing where the macro is expanded.
+    pub fn contains_in_macro_def(&self, name: &str) -> bool {
+        self.macro_def.contains(name)
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct UsageSearchResult {
     pub references: NoHashHashMap<FileId, Vec<FileReference>>,
@@ -813,17 +881,28 @@ fn new(def: &Definition, r: &ast::NameRef) -> Option<ReferenceCategory> {
             return is_name_ref_in_import(r).then_some(ReferenceCategory::Import);
         }
 
+        let ends_at_ref =
+            |expr: ast::Expr| expr.syntax().text_range().end() == r.syntax().text_range().end();
         let mode = r.syntax().ancestors().find_map(|node| {
         match_ast! {
             match node {
+                // Initializing a field in a record literal: `S { field: value }`. Shorthand fields
+                // also read a local of the same name, so they stay reads.
+                ast::RecordExprField(field) => {
+                    let is_init = matches!(def, Definition::Field(_)) && field.name_ref().as_ref() == Some(r);
+                    is_init.then_some(ReferenceCategory::Write)
+                },
+                // Taking a mutable reference: `&mut local`, `&mut s.field`
+                ast::RefExpr(expr) => {
+                    let is_mut_borrow = expr.mut_token().is_some() && expr.expr().map_or(false, ends_at_ref);
+                    is_mut_borrow.then_some(ReferenceCategory::Write)
+                },
                 ast::BinExpr(expr) => {
                     if matches!(expr.op_kind()?, ast::BinaryOp::Assignment { .. }) {
                         // If the variable or field ends on the LHS's end then it's a Write (covers fields and locals).
                         // FIXME: This is not terribly accurate.
-                        if let Some(lhs) = expr.lhs() {
-                            if lhs.syntax().text_range().end() == r.syntax().text_range().end() {
-                                return Some(ReferenceCategory::Write);
-                            }
+                        if expr.lhs().map_or(false, ends_at_ref) {
+                            return Some(ReferenceCategory::Write);
                         }
                     }
                     Some(ReferenceCategory::Read)
@@ -202,6 +202,10 @@ pub fn move_file(&mut self, src: FileId, dst: AnchoredPathBuf) {
         let file_system_edit = FileSystemEdit::MoveFile { src, dst };
         self.source_change.push_file_system_edit(file_system_edit);
     }
+    pub fn move_dir(&mut self, src: AnchoredPathBuf, src_id: FileId, dst: AnchoredPathBuf) {
+        let file_system_edit = FileSystemEdit::MoveDir { src, src_id, dst };
+        self.source_change.push_file_system_edit(file_system_edit);
+    }
     pub fn trigger_signature_help(&mut self) {
         self.trigger_signature_help = true;
     }
@@ -43,7 +43,7 @@
 
 use crate::RootDatabase;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Query {
     query: String,
     lowercased: String,
@@ -68,6 +68,28 @@ pub fn new(query: String) -> Query {
         }
     }
 
+    /// Parses a query typed by the user, stripping the search operators from it. `#` searches
+    /// all symbols instead of only types, `*` includes dependencies in the search and a query in
+    /// double quotes only matches symbols with exactly that name.
+    pub fn parse(input: &str) -> (Query, QueryOperators) {
+        let operators =
+            QueryOperators { all_symbols: input.contains('#'), libs: input.contains('*') };
+        let text: String = input.chars().filter(|&c| c != '#' && c != '*').collect();
+        let text = text.trim();
+        let mut query = match text.strip_prefix('"').and_then(|it| it.strip_suffix('"')) {
+            Some(name) => {
+                let mut query = Query::new(name.to_owned());
+                query.exact();
+                query
+            }
+            None => Query::new(text.to_owned()),
+        };
+        if operators.libs {
+            query.libs();
+        }
+        (query, operators)
+    }
+
     pub fn only_types(&mut self) {
         self.only_types = true;
     }
@@ -89,6 +111,15 @@ pub fn limit(&mut self, limit: usize) {
     }
 }
 
+/// The search operators used in a query typed by the user.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryOperators {
+    /// `#` was used to search all symbols instead of only types.
+    pub all_symbols: bool,
+    /// `*` was used to include dependencies in the search.
+    pub libs: bool,
+}
+
 #[salsa::query_group(SymbolsDatabaseStorage)]
 pub trait SymbolsDatabase: HirDatabase + SourceDatabaseExt + Upcast<dyn HirDatabase> {
     /// The symbol index for a given module. These modules should only be in source roots that
@@ -107,11 +138,21 @@ pub trait SymbolsDatabase: HirDatabase + SourceDatabaseExt + Upcast<dyn HirDatab
     /// Files in libraries are assumed to never change.
     #[salsa::input]
     fn library_roots(&self) -> Arc<FxHashSet<SourceRootId>>;
+
+    /// Whether the items declared in the bodies of library crates are indexed. This requires
+    /// lowering all of these bodies, which are otherwise only lowered on demand.
+    #[salsa::input]
+    fn index_library_bodies(&self) -> bool;
 }
 
 fn library_symbols(db: &dyn SymbolsDatabase, source_root_id: SourceRootId) -> Arc<SymbolIndex> {
     let _p = profile::span("library_symbols");
 
+    let collect = if db.index_library_bodies() {
+        SymbolCollector::collect
+    } else {
+        SymbolCollector::collect_without_bodies
+    };
     // todo: this could be parallelized, once I figure out how to do that...
     let symbols = db
         .source_root_crates(source_root_id)
@@ -120,7 +161,7 @@ fn library_symbols(db: &dyn SymbolsDatabase, source_root_id: SourceRootId) -> Ar
         // we specifically avoid calling SymbolsDatabase::module_symbols here, even they do the same thing,
         // as the index for a library is not going to really ever change, and we do not want to store each
         // module's index in salsa.
-        .flat_map(|module| SymbolCollector::collect(db.upcast(), module))
+        .flat_map(|module| collect(db.upcast(), module))
         .collect();
 
     Arc::new(SymbolIndex::new(symbols))
@@ -162,11 +203,12 @@ fn deref(&self) -> &Self::Target {
 //
 // - `Foo` searches for `Foo` type in the current workspace
 // - `foo#` searches for `foo` function in the current workspace
-// - `Foo*` searches for `Foo` type among dependencies, including `stdlib`
-// - `foo#*` searches for `foo` function among dependencies
+// - `Foo*` searches for `Foo` type in the workspace and among dependencies, including `stdlib`
+// - `foo#*` searches for `foo` function in the workspace and among dependencies
+// - `"Foo"` searches for types named exactly `Foo`
 //
-// That is, `#` switches from "types" to all symbols, `*` switches from the current
-// workspace to dependencies.
+// That is, `#` switches from "types" to all symbols, `*` extends the search from the current
+// workspace to dependencies and quotes switch from fuzzy to exact matching.
 //
 // Note that filtering does not currently work in VSCode due to the editor never
 // sending the special symbols to the language server. Instead, you can configure
@@ -181,26 +223,25 @@ fn deref(&self) -> &Self::Target {
 pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
     let _p = profile::span("world_symbols").detail(|| query.query.clone());
 
-    let indices: Vec<_> = if query.libs {
-        db.library_roots()
-            .par_iter()
-            .map_with(Snap::new(db), |snap, &root| snap.library_symbols(root))
-            .collect()
-    } else {
-        let mut modules = Vec::new();
-
-        for &root in db.local_roots().iter() {
-            let crates = db.source_root_crates(root);
-            for &krate in crates.iter() {
-                modules.extend(Crate::from(krate).modules(db));
-            }
+    let mut modules = Vec::new();
+    for &root in db.local_roots().iter() {
+        let crates = db.source_root_crates(root);
+        for &krate in crates.iter() {
+            modules.extend(Crate::from(krate).modules(db));
         }
+    }
+    let mut indices: Vec<_> = modules
+        .par_iter()
+        .map_with(Snap::new(db), |snap, &module| snap.module_symbols(module))
+        .collect();
 
-        modules
-            .par_iter()
-            .map_with(Snap::new(db), |snap, &module| snap.module_symbols(module))
-            .collect()
-    };
+    if query.libs {
+        indices.par_extend(
+            db.library_roots()
+                .par_iter()
+                .map_with(Snap::new(db), |snap, &root| snap.library_symbols(root)),
+        );
+    }
 
     query.search(&indices)
 }
@@ -305,8 +346,13 @@ pub(crate) fn search(self, indices: &[Arc<SymbolIndex>]) -> Vec<FileSymbol> {
         let _p = profile::span("symbol_index::Query::search");
         let mut op = fst::map::OpBuilder::new();
         for file_symbols in indices.iter() {
-            let automaton = fst::automaton::Subsequence::new(&self.lowercased);
-            op = op.add(file_symbols.map.search(automaton))
+            op = if self.exact {
+                let automaton = fst::automaton::Str::new(&self.lowercased);
+                op.add(file_symbols.map.search(automaton))
+            } else {
+                let automaton = fst::automaton::Subsequence::new(&self.lowercased);
+                op.add(file_symbols.map.search(automaton))
+            };
         }
         let mut stream = op.union();
         let mut res = Vec::new();
@@ -349,6 +395,17 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_query_parse() {
+        let (query, operators) = Query::parse("foo#");
+        assert_eq!((query.query.as_str(), query.exact, query.libs), ("foo", false, false));
+        assert_eq!(operators, QueryOperators { all_symbols: true, libs: false });
+
+        let (query, operators) = Query::parse("\"Foo\"*");
+        assert_eq!((query.query.as_str(), query.exact, query.libs), ("Foo", true, true));
+        assert_eq!(operators, QueryOperators { all_symbols: false, libs: true });
+    }
+
     #[test]
     fn test_symbol_index_collection() {
         let (db, _) = RootDatabase::with_many_files(
@@ -426,4 +483,34 @@ mod a_mod {
 
         expect_file!["./test_data/test_symbol_index_collection.txt"].assert_debug_eq(&symbols);
     }
+
+    #[test]
+    fn test_symbol_index_collection_without_bodies() {
+        let (db, _) = RootDatabase::with_many_files(
+            r#"
+//- /main.rs
+struct Struct;
+
+fn main() {
+    struct StructInFn;
+}
+
+const _: () = {
+    struct StructInUnnamedConst;
+};
+"#,
+        );
+
+        let names = |symbols: Vec<FileSymbol>| {
+            let mut names: Vec<_> = symbols.into_iter().map(|it| it.name.to_string()).collect();
+            names.sort();
+            names
+        };
+        let module = Crate::from(db.test_crate()).root_module(&db);
+        assert_eq!(
+            names(SymbolCollector::collect(&db, module)),
+            ["Struct", "StructInFn", "StructInUnnamedConst", "main"]
+        );
+        assert_eq!(names(SymbolCollector::collect_without_bodies(&db, module)), ["Struct", "main"]);
+    }
 }
@@ -132,5 +132,5 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
 }
 
 fn is_text(k: SyntaxKind) -> bool {
-    k.is_keyword() || k.is_literal() || k == IDENT || k == UNDERSCORE
+    k.is_keyword() || k.is_literal() || k == IDENT || k == UNDERSCORE || k == COMMENT
 }
@@ -60,6 +60,20 @@ fn main() {
         );
     }
 
+    #[test]
+    fn missing_unsafe_diagnostic_with_unsafe_fn_ptr_call() {
+        check_diagnostics(
+            r#"
+fn call(f: unsafe fn(u8), g: fn(u8)) {
+    f(0);
+  //^^^^ error: this operation is unsafe and requires an unsafe function or block
+    g(0);
+    unsafe { f(0) };
+}
+"#,
+        );
+    }
+
     #[test]
     fn missing_unsafe_diagnostic_with_static_mut() {
         check_diagnostics(
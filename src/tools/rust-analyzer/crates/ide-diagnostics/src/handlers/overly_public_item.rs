@@ -0,0 +1,437 @@
+//! Diagnostic for `pub` items that are never used outside of the crate defining them.
+
+use hir::{Crate, ModuleDef, Semantics};
+use ide_db::{
+    base_db::{FileId, SourceDatabaseExt},
+    defs::Definition,
+    search::{SearchDatabase, SearchScope},
+    source_change::SourceChange,
+    FxHashSet, RootDatabase,
+};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, HasVisibility},
+    match_ast, AstNode, SyntaxKind, SyntaxNode,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: overly-public-item
+//
+// This diagnostic is triggered for `pub` items of a workspace crate that are never used by any of
+// the workspace crates depending on it, and could thus be `pub(crate)` instead.
+//
+// It is disabled by default, enable it with `rust-analyzer.diagnostics.overlyPublicItems.enable`.
+// Crates without dependents in the workspace are not checked, and modules listed in
+// `rust-analyzer.diagnostics.overlyPublicItems.allowedModules` are considered public API.
+pub(crate) fn overly_public_items(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+) -> Option<()> {
+    let sema = &ctx.sema;
+    let krate = sema.to_module_def(file_id)?.krate();
+    let files = CrateFiles::new(sema, krate)?;
+
+    let source_file = sema.parse(file_id);
+    for item in source_file.syntax().descendants().filter_map(ast::Item::cast) {
+        // Only look at module level items, associated items and fields follow their parent.
+        let parent = item.syntax().parent().map(|it| it.kind());
+        if !matches!(parent, Some(SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST)) {
+            continue;
+        }
+        let Some((def, visibility)) = module_def_and_visibility(sema, &item) else { continue };
+        if !matches!(visibility.kind(), ast::VisibilityKind::Pub)
+            || is_exported_symbol(sema.db, def)
+            || is_in_allowed_module(ctx, def)
+        {
+            continue;
+        }
+        let Some(name) = def.name(sema.db) else { continue };
+        let name = name.unescaped().to_smol_str();
+        if files.is_used_by_dependents(sema, def.into(), &name)
+            || files.is_reexported(sema, def.into(), &name)
+            || files.is_in_public_signature(sema, def)
+        {
+            continue;
+        }
+
+        let range = visibility.syntax().text_range();
+        let edit = TextEdit::replace(range, "pub(crate)".to_string());
+        acc.push(
+            Diagnostic::new(
+                "overly-public-item",
+                format!("`{name}` is never used outside of its crate"),
+                range,
+            )
+            .severity(Severity::WeakWarning)
+            .experimental()
+            .with_fixes(Some(vec![fix(
+                "restrict_visibility_to_crate",
+                "Change visibility to `pub(crate)`",
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )])),
+        );
+    }
+
+    Some(())
+}
+
+/// The files of the crate being checked and of the workspace crates depending on it.
+struct CrateFiles {
+    own: Vec<FileId>,
+    dependents: Vec<FileId>,
+}
+
+impl CrateFiles {
+    /// Returns `None` if the crate is a library dependency or has no dependents in the workspace.
+    fn new(sema: &Semantics<'_, RootDatabase>, krate: Crate) -> Option<CrateFiles> {
+        let db = sema.db;
+        let own_source_root = db.file_source_root(krate.root_file(db));
+        if db.source_root(own_source_root).is_library {
+            return None;
+        }
+
+        let mut res = CrateFiles { own: Vec::new(), dependents: Vec::new() };
+        let mut seen = FxHashSet::default();
+        for rev_dep in krate.transitive_reverse_dependencies(db) {
+            let source_root_id = db.file_source_root(rev_dep.root_file(db));
+            if !seen.insert(source_root_id) {
+                continue;
+            }
+            let source_root = db.source_root(source_root_id);
+            if source_root_id != own_source_root {
+                res.dependents.extend(source_root.iter());
+                continue;
+            }
+            // Binaries, tests and examples usually share the source root of the library they
+            // depend on.
+            for file_id in source_root.iter() {
+                let crates = sema.to_module_defs(file_id).map(|it| it.krate()).collect_vec();
+                if crates.contains(&krate) {
+                    res.own.push(file_id);
+                }
+                if crates.iter().any(|&it| it != krate) {
+                    res.dependents.push(file_id);
+                }
+            }
+        }
+
+        (!res.dependents.is_empty()).then_some(res)
+    }
+
+    fn is_used_by_dependents(
+        &self,
+        sema: &Semantics<'_, RootDatabase>,
+        def: Definition,
+        name: &str,
+    ) -> bool {
+        let files =
+            self.dependents.iter().copied().filter(|&it| sema.db.file_idents(it).contains(name));
+        let files = files.collect_vec();
+        !files.is_empty() && def.usages(sema).in_scope(SearchScope::files(&files)).at_least_one()
+    }
+
+    /// Whether the item is re-exported by a `pub use`, or might be referred to by a macro
+    /// expanded in another crate. Restricting its visibility would break the crate in both cases.
+    fn is_reexported(
+        &self,
+        sema: &Semantics<'_, RootDatabase>,
+        def: Definition,
+        name: &str,
+    ) -> bool {
+        let mut files = Vec::new();
+        for &file_id in &self.own {
+            let idents = sema.db.file_idents(file_id);
+            if idents.contains_in_macro_def(name) {
+                return true;
+            }
+            if idents.contains_in_pub_use(name) {
+                files.push(file_id);
+            }
+        }
+        if files.is_empty() {
+            return false;
+        }
+        def.usages(sema).in_scope(SearchScope::files(&files)).all().iter().any(|(_, refs)| {
+            refs.iter().any(|it| {
+                it.name
+                    .syntax()
+                    .ancestors()
+                    .find_map(ast::Use::cast)
+                    .and_then(|it| it.visibility())
+                    .map_or(false, |vis| matches!(vis.kind(), ast::VisibilityKind::Pub))
+            })
+        })
+    }
+
+    /// Whether the item is named in the signature of a `pub` item of its crate. Restricting its
+    /// visibility would turn it into a private type in a public interface (E0446).
+    fn is_in_public_signature(&self, sema: &Semantics<'_, RootDatabase>, def: ModuleDef) -> bool {
+        if !matches!(def, ModuleDef::Adt(_) | ModuleDef::Trait(_) | ModuleDef::TypeAlias(_)) {
+            return false;
+        }
+        let usages =
+            Definition::from(def).usages(sema).in_scope(SearchScope::files(&self.own)).all();
+        usages.iter().any(|(_, refs)| refs.iter().any(|it| is_public_signature(it.name.syntax())))
+    }
+}
+
+/// Whether `node` is part of the signature of a `pub` item, as opposed to its body.
+fn is_public_signature(node: &SyntaxNode) -> bool {
+    let is_pub = |vis: Option<ast::Visibility>| {
+        vis.map_or(false, |it| matches!(it.kind(), ast::VisibilityKind::Pub))
+    };
+    // Fields of enum variants have the visibility of the enum.
+    let is_variant_field = |field: &SyntaxNode| {
+        field
+            .parent()
+            .and_then(|it| it.parent())
+            .map_or(false, |it| ast::Variant::can_cast(it.kind()))
+    };
+    for node in node.ancestors() {
+        if ast::Expr::can_cast(node.kind()) {
+            return false;
+        }
+        match_ast! {
+            match node {
+                ast::RecordField(it) => {
+                    if !is_pub(it.visibility()) && !is_variant_field(it.syntax()) {
+                        return false;
+                    }
+                },
+                ast::TupleField(it) => {
+                    if !is_pub(it.visibility()) && !is_variant_field(it.syntax()) {
+                        return false;
+                    }
+                },
+                ast::Impl(_) => return false,
+                ast::Use(_) => return false,
+                ast::Item(it) => {
+                    let visibility = ast::AnyHasVisibility::cast(it.syntax().clone())
+                        .and_then(|it| it.visibility());
+                    if is_pub(visibility) {
+                        return true;
+                    }
+                    // Associated items of traits and trait impls have the visibility of the trait.
+                    match it.syntax().parent().and_then(|it| it.parent()) {
+                        Some(parent) if ast::Trait::can_cast(parent.kind()) => {}
+                        Some(parent) => {
+                            return ast::Impl::cast(parent).map_or(false, |it| it.trait_().is_some())
+                        }
+                        None => return false,
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+    false
+}
+
+fn module_def_and_visibility(
+    sema: &Semantics<'_, RootDatabase>,
+    item: &ast::Item,
+) -> Option<(ModuleDef, ast::Visibility)> {
+    let (def, visibility) = match item {
+        ast::Item::Const(it) => (sema.to_def(it)?.into(), it.visibility()),
+        ast::Item::Enum(it) => (hir::Adt::from(sema.to_def(it)?).into(), it.visibility()),
+        ast::Item::Fn(it) => (sema.to_def(it)?.into(), it.visibility()),
+        ast::Item::Static(it) => (sema.to_def(it)?.into(), it.visibility()),
+        ast::Item::Struct(it) => (hir::Adt::from(sema.to_def(it)?).into(), it.visibility()),
+        ast::Item::Trait(it) => (sema.to_def(it)?.into(), it.visibility()),
+        ast::Item::TypeAlias(it) => (sema.to_def(it)?.into(), it.visibility()),
+        ast::Item::Union(it) => (hir::Adt::from(sema.to_def(it)?).into(), it.visibility()),
+        _ => return None,
+    };
+    Some((def, visibility?))
+}
+
+/// Items exported under a fixed symbol name are meant to be used from outside of Rust.
+fn is_exported_symbol(db: &RootDatabase, def: ModuleDef) -> bool {
+    def.attrs(db).map_or(false, |attrs| {
+        attrs.by_key("no_mangle").exists() || attrs.by_key("export_name").exists()
+    })
+}
+
+fn is_in_allowed_module(ctx: &DiagnosticsContext<'_>, def: ModuleDef) -> bool {
+    let allowed_modules = &ctx.config.overly_public_items.allowed_modules;
+    if allowed_modules.is_empty() {
+        return false;
+    }
+    let db = ctx.sema.db;
+    let Some(module) = def.module(db) else { return false };
+    let Some(crate_name) = module.krate().display_name(db) else { return false };
+    let mut path = crate_name.to_string();
+    for name in module.path_to_root(db).into_iter().rev().filter_map(|it| it.name(db)) {
+        format_to!(path, "::{name}");
+    }
+    allowed_modules.iter().any(|allowed| {
+        let rest = path.strip_prefix(allowed.as_str());
+        rest.map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::check_diagnostics_with_config, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str) {
+        check_with_allowed_modules(&[], ra_fixture)
+    }
+
+    fn check_with_allowed_modules(allowed_modules: &[&str], ra_fixture: &str) {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.overly_public_items.enable = true;
+        config.overly_public_items.allowed_modules =
+            allowed_modules.iter().map(|it| it.to_string()).collect();
+        check_diagnostics_with_config(config, ra_fixture)
+    }
+
+    #[test]
+    fn unused_by_dependents() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+pub struct Used;
+pub mod module {
+    pub struct Unused;
+  //^^^ 💡 weak: `Unused` is never used outside of its crate
+    pub(crate) fn crate_visible() {}
+    fn private() {}
+    pub fn only_used_internally() {}
+  //^^^ 💡 weak: `only_used_internally` is never used outside of its crate
+    pub trait Trait {}
+  //^^^ 💡 weak: `Trait` is never used outside of its crate
+    impl Unused {
+        pub fn method(&self) {
+            only_used_internally();
+        }
+    }
+}
+//- /main.rs crate:main deps:lib
+fn main() {
+    let _ = lib::Used;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn used_through_reexport_or_macro() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+mod inner {
+    pub struct Reexported;
+    pub fn called_by_macro() {}
+}
+pub use inner::{called_by_macro, Reexported};
+pub fn used_via_glob() {}
+#[macro_export]
+macro_rules! m {
+    () => { $crate::called_by_macro() };
+}
+//- /main.rs crate:main deps:lib
+use lib::*;
+fn main() {
+    used_via_glob();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn used_in_public_signature() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+pub mod m {
+    pub struct Returned;
+    pub struct FieldType;
+    pub struct VariantType;
+    pub trait Bound {}
+    pub struct InPrivateField;
+  //^^^ 💡 weak: `InPrivateField` is never used outside of its crate
+    pub struct InBody;
+  //^^^ 💡 weak: `InBody` is never used outside of its crate
+    pub struct InPrivateFn;
+  //^^^ 💡 weak: `InPrivateFn` is never used outside of its crate
+    pub fn make() -> Returned {
+        let _: InBody = InBody;
+        Returned
+    }
+    pub struct Wrapper {
+        pub field: FieldType,
+        private: InPrivateField,
+    }
+    pub enum Enum {
+        Variant(VariantType),
+    }
+    pub trait Trait {
+        fn bounded<T: Bound>(&self, t: T);
+    }
+    fn private(_: InPrivateFn) {}
+}
+//- /main.rs crate:main deps:lib
+use lib::m;
+fn main() {
+    let _ = m::make();
+    let _: m::Wrapper;
+    let _: m::Enum;
+    let _: &dyn m::Trait;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_dependents() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+pub struct Unused;
+"#,
+        );
+    }
+
+    #[test]
+    fn exported_symbols() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+#[no_mangle]
+pub extern "C" fn exported() {}
+#[export_name = "renamed"]
+pub static STATIC: u32 = 0;
+//- /main.rs crate:main deps:lib
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed_modules() {
+        check_with_allowed_modules(
+            &["lib::api"],
+            r#"
+//- /lib.rs crate:lib
+pub mod api {
+    pub fn entry_point() {}
+    pub mod nested {
+        pub fn also_allowed() {}
+    }
+}
+pub mod api_like {
+    pub fn not_allowed() {}
+  //^^^ 💡 weak: `not_allowed` is never used outside of its crate
+}
+//- /main.rs crate:main deps:lib
+fn main() {}
+"#,
+        );
+    }
+}
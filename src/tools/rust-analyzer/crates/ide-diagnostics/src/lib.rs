@@ -52,6 +52,7 @@ mod handlers {
     pub(crate) mod field_shorthand;
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
+    pub(crate) mod overly_public_item;
     pub(crate) mod json_is_not_rust;
 }
 
@@ -143,6 +144,13 @@ fn default() -> Self {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct OverlyPublicItemsConfig {
+    pub enable: bool,
+    /// Paths of modules, such as `my_crate::api`, whose items are intentionally public.
+    pub allowed_modules: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DiagnosticsConfig {
     pub proc_macros_enabled: bool,
@@ -153,6 +161,8 @@ pub struct DiagnosticsConfig {
     // FIXME: We may want to include a whole `AssistConfig` here
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
+    pub prefer_defining_crate: bool,
+    pub overly_public_items: OverlyPublicItemsConfig,
 }
 
 impl DiagnosticsConfig {
@@ -174,6 +184,8 @@ pub fn test_sample() -> Self {
                 skip_glob_imports: false,
             },
             prefer_no_std: false,
+            prefer_defining_crate: false,
+            overly_public_items: Default::default(),
         }
     }
 }
@@ -238,6 +250,9 @@ pub fn diagnostics(
     if module.is_none() {
         handlers::unlinked_file::unlinked_file(&ctx, &mut res, file_id);
     }
+    if config.overly_public_items.enable {
+        handlers::overly_public_item::overly_public_items(&ctx, &mut res, file_id);
+    }
 
     let mut diags = Vec::new();
     if let Some(m) = module {
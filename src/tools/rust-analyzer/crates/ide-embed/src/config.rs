@@ -0,0 +1,92 @@
+//! The configuration of the analysis, which follows the defaults of the rust-analyzer server
+//! without snippets.
+
+use ide::{
+    AdjustmentHints, AdjustmentHintsMode, AssistConfig, AsyncRuntime, CallableSnippets,
+    ClosureReturnTypeHints, CompletionConfig, DiagnosticsConfig, DiscriminantHints,
+    ExprFillDefaultMode, HoverConfig, HoverDocFormat, InlayHintsConfig, LifetimeElisionHints,
+    OverlyPublicItemsConfig,
+};
+use ide_db::imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind};
+
+const INSERT_USE: InsertUseConfig = InsertUseConfig {
+    granularity: ImportGranularity::Crate,
+    enforce_granularity: false,
+    prefix_kind: PrefixKind::Plain,
+    group: true,
+    skip_glob_imports: true,
+};
+
+pub(crate) fn hover() -> HoverConfig {
+    HoverConfig {
+        links_in_hover: true,
+        documentation: true,
+        keywords: true,
+        format: HoverDocFormat::Markdown,
+    }
+}
+
+pub(crate) fn completion() -> CompletionConfig {
+    CompletionConfig {
+        enable_postfix_completions: true,
+        // Imports are added when resolving a completion, which the API does not have.
+        enable_imports_on_the_fly: false,
+        enable_self_on_the_fly: true,
+        enable_private_editable: false,
+        callable: Some(CallableSnippets::AddParentheses),
+        snippet_cap: None,
+        insert_use: INSERT_USE,
+        prefer_no_std: false,
+        prefer_defining_crate: false,
+        snippets: Vec::new(),
+        limit: None,
+    }
+}
+
+pub(crate) fn inlay_hints() -> InlayHintsConfig {
+    InlayHintsConfig {
+        render_colons: true,
+        type_hints: true,
+        discriminant_hints: DiscriminantHints::Never,
+        parameter_hints: true,
+        chaining_hints: true,
+        adjustment_hints: AdjustmentHints::Never,
+        adjustment_hints_mode: AdjustmentHintsMode::Prefix,
+        adjustment_hints_hide_outside_unsafe: false,
+        closure_return_type_hints: ClosureReturnTypeHints::Never,
+        binding_mode_hints: false,
+        lifetime_elision_hints: LifetimeElisionHints::Never,
+        param_names_for_lifetime_elision_hints: false,
+        hide_named_constructor_hints: false,
+        hide_closure_initialization_hints: false,
+        max_length: Some(25),
+        closing_brace_hints_min_lines: Some(25),
+    }
+}
+
+pub(crate) fn diagnostics() -> DiagnosticsConfig {
+    DiagnosticsConfig {
+        proc_macros_enabled: false,
+        proc_attr_macros_enabled: false,
+        disable_experimental: true,
+        disabled: Default::default(),
+        expr_fill_default: ExprFillDefaultMode::Todo,
+        insert_use: INSERT_USE,
+        prefer_no_std: false,
+        prefer_defining_crate: false,
+        overly_public_items: OverlyPublicItemsConfig::default(),
+    }
+}
+
+pub(crate) fn assist() -> AssistConfig {
+    AssistConfig {
+        snippet_cap: None,
+        allowed: None,
+        insert_use: INSERT_USE,
+        prefer_no_std: false,
+        prefer_defining_crate: false,
+        assist_emit_must_use: false,
+        async_runtime: AsyncRuntime::Tokio,
+        async_io_mappings: Vec::new(),
+    }
+}
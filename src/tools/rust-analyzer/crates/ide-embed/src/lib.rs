@@ -0,0 +1,313 @@
+//! A stable API to embed rust-analyzer's analysis into other tools, like code review bots or
+//! custom editors.
+//!
+//! The `ide` crate changes with every release of rust-analyzer, so this crate only exposes a small
+//! curated surface on top of it: files are addressed by their path, positions are UTF-8 byte
+//! offsets into the file text and all results are plain data which can be serialized with
+//! `serde`. Breaking changes to the items of this crate follow semver.
+//!
+//! ```ignore
+//! let mut host = AnalysisHost::new();
+//! let mut change = Change::new();
+//! change.set_file("/lib.rs", "fn main() { let x = 92; }");
+//! change.set_crates(vec![Crate::new("lib", "/lib.rs")]);
+//! host.change(change)?;
+//!
+//! let analysis = host.analyze();
+//! let hints = analysis.inlay_hints("/lib.rs")?;
+//! ```
+
+mod config;
+mod to_embed;
+mod types;
+#[cfg(test)]
+mod tests;
+
+use std::{fmt, sync::Arc};
+
+use cfg::CfgOptions;
+use ide::{
+    AssistResolveStrategy, FileId, FilePosition, FileRange, SourceRoot, TextRange, TextSize,
+};
+use ide_db::{
+    base_db::{
+        CrateDisplayName, CrateGraph, CrateName, CrateOrigin, Dependency, Env, FileSet, VfsPath,
+    },
+    FxHashMap,
+};
+
+pub use crate::types::{
+    Assist, Completion, CompletionKind, Crate, Diagnostic, FileEdit, Hover, InlayHint,
+    InlayHintKind, Range, Severity, TextEdit,
+};
+
+/// The files and crates to analyze, which [`AnalysisHost::change`] updates.
+#[derive(Debug, Default)]
+pub struct AnalysisHost {
+    host: ide::AnalysisHost,
+    files: Arc<Files>,
+}
+
+impl AnalysisHost {
+    pub fn new() -> AnalysisHost {
+        AnalysisHost::default()
+    }
+
+    /// Applies `change`, cancelling the queries running on [`Analysis`] snapshots in other threads.
+    /// Blocks until all snapshots are dropped.
+    ///
+    /// Nothing is changed if an error is returned.
+    pub fn change(&mut self, change: Change) -> Result<(), Error> {
+        if let Some((path, _)) = change.files.iter().find(|(path, _)| !path.starts_with('/')) {
+            return Err(Error::InvalidPath(path.clone()));
+        }
+
+        let mut files = Files::clone(&self.files);
+        let mut new_files = false;
+        let mut ide_change = ide::Change::new();
+        for (path, text) in change.files {
+            let file_id = files.id(&path).unwrap_or_else(|| {
+                new_files = true;
+                files.insert(path)
+            });
+            ide_change.change_file(file_id, text.map(Arc::new));
+        }
+        if let Some(crates) = &change.crates {
+            ide_change.set_crate_graph(crate_graph(crates, &files)?);
+        }
+        if new_files {
+            let mut file_set = FileSet::default();
+            for (idx, path) in files.paths.iter().enumerate() {
+                file_set.insert(FileId(idx as u32), VfsPath::new_virtual_path(path.clone()));
+            }
+            ide_change.set_roots(vec![SourceRoot::new_local(file_set)]);
+        }
+
+        self.files = Arc::new(files);
+        self.host.apply_change(ide_change);
+        Ok(())
+    }
+
+    /// Returns a snapshot of the current state to query.
+    pub fn analyze(&self) -> Analysis {
+        Analysis { analysis: self.host.analysis(), files: self.files.clone() }
+    }
+}
+
+/// Changes to the files and crates of an [`AnalysisHost`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Change {
+    /// The new text of files by their absolute path, `None` removes the file.
+    pub files: Vec<(String, Option<String>)>,
+    /// The crates to analyze, replacing all previous ones if set.
+    pub crates: Option<Vec<Crate>>,
+}
+
+impl Change {
+    pub fn new() -> Change {
+        Change::default()
+    }
+
+    pub fn set_file(&mut self, path: impl Into<String>, text: impl Into<String>) {
+        self.files.push((path.into(), Some(text.into())));
+    }
+
+    pub fn remove_file(&mut self, path: impl Into<String>) {
+        self.files.push((path.into(), None));
+    }
+
+    pub fn set_crates(&mut self, crates: Vec<Crate>) {
+        self.crates = Some(crates);
+    }
+}
+
+/// A snapshot of an [`AnalysisHost`] to query, which is cancelled by changes to the host.
+///
+/// All offsets and ranges are UTF-8 byte offsets into the text of the file.
+#[derive(Debug)]
+pub struct Analysis {
+    analysis: ide::Analysis,
+    files: Arc<Files>,
+}
+
+impl Analysis {
+    /// The documentation and signature of the item at `offset`.
+    pub fn hover(&self, path: &str, offset: u32) -> Result<Option<Hover>, Error> {
+        let position = self.position(path, offset)?;
+        let range =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        let hover = self.analysis.hover(&config::hover(), range)?;
+        Ok(hover.map(|it| Hover { range: it.range.into(), markdown: it.info.markup.to_string() }))
+    }
+
+    /// The completions at `offset`, most relevant first.
+    pub fn completions(&self, path: &str, offset: u32) -> Result<Vec<Completion>, Error> {
+        let position = self.position(path, offset)?;
+        let mut items =
+            self.analysis.completions(&config::completion(), position, None)?.unwrap_or_default();
+        items.sort_by_key(|it| std::cmp::Reverse(it.relevance.score()));
+        Ok(items.into_iter().map(to_embed::completion).collect())
+    }
+
+    pub fn inlay_hints(&self, path: &str) -> Result<Vec<InlayHint>, Error> {
+        let file_id = self.file_id(path)?;
+        let hints = self.analysis.inlay_hints(&config::inlay_hints(), file_id, None)?;
+        Ok(hints.into_iter().map(to_embed::inlay_hint).collect())
+    }
+
+    /// The diagnostics of the file along with their fixes, without the ones of `cargo check`.
+    pub fn diagnostics(&self, path: &str) -> Result<Vec<Diagnostic>, Error> {
+        let file_id = self.file_id(path)?;
+        let diagnostics = self.analysis.diagnostics(
+            &config::diagnostics(),
+            AssistResolveStrategy::All,
+            file_id,
+        )?;
+        Ok(diagnostics.into_iter().map(|it| to_embed::diagnostic(&self.files, it)).collect())
+    }
+
+    /// The assists and diagnostic fixes applicable to `range`.
+    ///
+    /// Assists which create or move files are left out.
+    pub fn assists(&self, path: &str, range: Range) -> Result<Vec<Assist>, Error> {
+        let start = self.position(path, range.start)?;
+        let end = self.position(path, range.end)?;
+        if start.offset > end.offset {
+            return Err(Error::InvalidOffset { path: path.to_owned(), offset: range.end });
+        }
+        let range =
+            FileRange { file_id: start.file_id, range: TextRange::new(start.offset, end.offset) };
+        let assists = self.analysis.assists_with_fixes(
+            &config::assist(),
+            &config::diagnostics(),
+            AssistResolveStrategy::All,
+            range,
+        )?;
+        Ok(assists.into_iter().filter_map(|it| to_embed::assist(&self.files, it)).collect())
+    }
+
+    fn file_id(&self, path: &str) -> Result<FileId, Error> {
+        self.files.id(path).ok_or_else(|| Error::UnknownFile(path.to_owned()))
+    }
+
+    fn position(&self, path: &str, offset: u32) -> Result<FilePosition, Error> {
+        let file_id = self.file_id(path)?;
+        let text = self.analysis.file_text(file_id)?;
+        if !text.is_char_boundary(offset as usize) {
+            return Err(Error::InvalidOffset { path: path.to_owned(), offset });
+        }
+        Ok(FilePosition { file_id, offset: TextSize::from(offset) })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The [`Analysis`] was cancelled by a change to its host.
+    Cancelled,
+    /// The path is not absolute.
+    InvalidPath(String),
+    /// No file with the path was added.
+    UnknownFile(String),
+    /// The offset is not inside the file or not on a char boundary.
+    InvalidOffset { path: String, offset: u32 },
+    /// The crates could not be added, for example because they depend on each other cyclically.
+    InvalidCrate(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Cancelled => f.write_str("the analysis was cancelled by a change"),
+            Error::InvalidPath(path) => write!(f, "`{path}` is not an absolute path"),
+            Error::UnknownFile(path) => write!(f, "there is no file at `{path}`"),
+            Error::InvalidOffset { path, offset } => {
+                write!(f, "{offset} is not a valid offset in `{path}`")
+            }
+            Error::InvalidCrate(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ide::Cancelled> for Error {
+    fn from(_: ide::Cancelled) -> Error {
+        Error::Cancelled
+    }
+}
+
+/// The paths of the files added to the host, a file's id being the index of its path.
+#[derive(Debug, Default, Clone)]
+struct Files {
+    paths: Vec<String>,
+    ids: FxHashMap<String, FileId>,
+}
+
+impl Files {
+    fn id(&self, path: &str) -> Option<FileId> {
+        self.ids.get(path).copied()
+    }
+
+    fn path(&self, file_id: FileId) -> &str {
+        &self.paths[file_id.0 as usize]
+    }
+
+    fn insert(&mut self, path: String) -> FileId {
+        let file_id = FileId(self.paths.len() as u32);
+        self.paths.push(path.clone());
+        self.ids.insert(path, file_id);
+        file_id
+    }
+}
+
+fn crate_graph(crates: &[Crate], files: &Files) -> Result<CrateGraph, Error> {
+    let mut crate_graph = CrateGraph::default();
+    let mut crate_ids = FxHashMap::default();
+    for krate in crates {
+        let root = files.id(&krate.root).ok_or_else(|| Error::UnknownFile(krate.root.clone()))?;
+        let edition = krate.edition.parse().map_err(|_| {
+            Error::InvalidCrate(format!(
+                "`{}` has the unknown edition {}",
+                krate.name, krate.edition
+            ))
+        })?;
+        let mut cfg_options = CfgOptions::default();
+        for atom in &krate.cfg {
+            cfg_options.insert_atom(atom.as_str().into());
+        }
+        let crate_id = crate_graph.add_crate_root(
+            root,
+            edition,
+            Some(CrateDisplayName::from_canonical_name(krate.name.clone())),
+            None,
+            cfg_options.clone(),
+            cfg_options,
+            Env::default(),
+            Ok(Vec::new()),
+            false,
+            CrateOrigin::CratesIo { repo: None, name: None },
+            Err("embedded analysis has no target layout".into()),
+        );
+        if crate_ids.insert(krate.name.as_str(), crate_id).is_some() {
+            return Err(Error::InvalidCrate(format!(
+                "there are two crates named `{}`",
+                krate.name
+            )));
+        }
+    }
+    for krate in crates {
+        for dep in &krate.deps {
+            let Some(&dep_id) = crate_ids.get(dep.as_str()) else {
+                return Err(Error::InvalidCrate(format!(
+                    "`{}` depends on the unknown crate `{dep}`",
+                    krate.name
+                )));
+            };
+            let dep = Dependency::new(CrateName::normalize_dashes(dep), dep_id);
+            crate_graph.add_dep(crate_ids[krate.name.as_str()], dep).map_err(|_| {
+                Error::InvalidCrate(format!("`{}` depends on itself cyclically", krate.name))
+            })?;
+        }
+    }
+    Ok(crate_graph)
+}
@@ -0,0 +1,135 @@
+use crate::{
+    AnalysisHost, Change, CompletionKind, Crate, Error, InlayHint, InlayHintKind, Range, Severity,
+    TextEdit,
+};
+
+fn host(files: &[(&str, &str)], crates: Vec<Crate>) -> AnalysisHost {
+    let mut host = AnalysisHost::new();
+    let mut change = Change::new();
+    for &(path, text) in files {
+        change.set_file(path, text);
+    }
+    change.set_crates(crates);
+    host.change(change).unwrap();
+    host
+}
+
+fn offset(text: &str, needle: &str) -> u32 {
+    text.find(needle).unwrap() as u32
+}
+
+#[test]
+fn hover_across_crates() {
+    let main = "fn main() { dep::answer(); }";
+    let dep = "/// The answer.\npub fn answer() -> u32 { 42 }";
+    let mut main_crate = Crate::new("main", "/main.rs");
+    main_crate.deps.push("dep".to_owned());
+    let host = host(
+        &[("/main.rs", main), ("/dep.rs", dep)],
+        vec![main_crate, Crate::new("dep", "/dep.rs")],
+    );
+
+    let hover = host.analyze().hover("/main.rs", offset(main, "answer")).unwrap().unwrap();
+    assert!(hover.markdown.contains("pub fn answer() -> u32"), "{}", hover.markdown);
+    assert!(hover.markdown.contains("The answer."), "{}", hover.markdown);
+}
+
+#[test]
+fn completions_and_diagnostics() {
+    let text = "struct S { field: u32 }\nfn f(s: S) { let x = s.f; }\nmod missing;";
+    let host = host(&[("/lib.rs", text)], vec![Crate::new("lib", "/lib.rs")]);
+    let analysis = host.analyze();
+
+    let completions = analysis.completions("/lib.rs", offset(text, "f; }") + 1).unwrap();
+    let field = completions.iter().find(|it| it.label == "field").unwrap();
+    assert_eq!(field.kind, CompletionKind::Field);
+    let start = offset(text, "f; }");
+    assert_eq!(
+        field.edits,
+        vec![TextEdit { range: Range { start, end: start + 1 }, new_text: "field".to_owned() }]
+    );
+
+    let diagnostics = analysis.diagnostics("/lib.rs").unwrap();
+    let missing = diagnostics.iter().find(|it| it.code == "unresolved-module").unwrap();
+    assert_eq!(missing.severity, Severity::Error);
+    // The fix creates the file of the module, which is not supported.
+    assert!(missing.fixes.is_empty());
+}
+
+#[test]
+fn assists_edit_files() {
+    let text = "fn f() { let x = 1 + 2; let _ = x; }";
+    let host = host(&[("/lib.rs", text)], vec![Crate::new("lib", "/lib.rs")]);
+    let start = offset(text, "x =");
+
+    let assists = host.analyze().assists("/lib.rs", Range { start, end: start + 1 }).unwrap();
+    let inline = assists.iter().find(|it| it.id == "inline_local_variable").unwrap();
+    assert_eq!(inline.edits.len(), 1);
+    assert_eq!(inline.edits[0].path, "/lib.rs");
+}
+
+#[test]
+fn changes_update_the_analysis() {
+    let mut host = host(&[("/lib.rs", "fn f() {}")], vec![Crate::new("lib", "/lib.rs")]);
+    assert_eq!(host.analyze().inlay_hints("/lib.rs").unwrap(), Vec::new());
+
+    let text = "fn f() { let x = 92; }";
+    let mut change = Change::new();
+    change.set_file("/lib.rs", text);
+    host.change(change).unwrap();
+
+    let hints = host.analyze().inlay_hints("/lib.rs").unwrap();
+    let x = offset(text, "x");
+    assert_eq!(
+        hints,
+        vec![InlayHint {
+            range: Range { start: x, end: x + 1 },
+            kind: InlayHintKind::Type,
+            label: "i32".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn invalid_input() {
+    let mut host = host(&[("/lib.rs", "fn é() {}")], vec![Crate::new("lib", "/lib.rs")]);
+    let analysis = host.analyze();
+    assert_eq!(analysis.hover("/main.rs", 0), Err(Error::UnknownFile("/main.rs".to_owned())));
+    assert_eq!(
+        analysis.hover("/lib.rs", 4),
+        Err(Error::InvalidOffset { path: "/lib.rs".to_owned(), offset: 4 })
+    );
+    drop(analysis);
+
+    let mut change = Change::new();
+    change.set_file("lib.rs", "");
+    assert_eq!(host.change(change), Err(Error::InvalidPath("lib.rs".to_owned())));
+
+    let mut krate = Crate::new("lib", "/lib.rs");
+    krate.deps.push("lib".to_owned());
+    let mut change = Change::new();
+    change.set_crates(vec![krate]);
+    assert!(matches!(host.change(change), Err(Error::InvalidCrate(_))));
+}
+
+#[test]
+fn serialize_change() {
+    let json = r#"{
+        "files": [["/lib.rs", "fn f() {}"], ["/old.rs", null]],
+        "crates": [{ "name": "lib", "root": "/lib.rs", "edition": "2018" }]
+    }"#;
+    let change: Change = serde_json::from_str(json).unwrap();
+    let mut expected = Change::new();
+    expected.set_file("/lib.rs", "fn f() {}");
+    expected.remove_file("/old.rs");
+    let mut krate = Crate::new("lib", "/lib.rs");
+    krate.edition = "2018".to_owned();
+    expected.set_crates(vec![krate]);
+    assert_eq!(change, expected);
+
+    let edit = TextEdit { range: Range { start: 1, end: 2 }, new_text: "x".to_owned() };
+    assert_eq!(
+        serde_json::to_string(&edit).unwrap(),
+        r#"{"range":{"start":1,"end":2},"new_text":"x"}"#
+    );
+}
@@ -0,0 +1,114 @@
+//! Conversion of the results of `ide` to the types of this crate.
+
+use ide::{CompletionItem, CompletionItemKind, InlayKind, SourceChange, SymbolKind};
+
+use crate::{
+    types::{
+        Assist, Completion, CompletionKind, Diagnostic, FileEdit, InlayHint, InlayHintKind,
+        Severity, TextEdit,
+    },
+    Files,
+};
+
+pub(crate) fn completion(item: CompletionItem) -> Completion {
+    Completion {
+        label: item.label.to_string(),
+        kind: completion_kind(item.kind),
+        detail: item.detail,
+        documentation: item.documentation.map(|it| it.as_str().to_owned()),
+        edits: text_edits(item.text_edit),
+        is_snippet: item.is_snippet,
+        deprecated: item.deprecated,
+    }
+}
+
+fn completion_kind(kind: CompletionItemKind) -> CompletionKind {
+    match kind {
+        CompletionItemKind::Keyword => CompletionKind::Keyword,
+        CompletionItemKind::Snippet => CompletionKind::Snippet,
+        CompletionItemKind::Method => CompletionKind::Method,
+        CompletionItemKind::Binding => CompletionKind::Variable,
+        CompletionItemKind::BuiltinType | CompletionItemKind::InferredType => CompletionKind::Type,
+        CompletionItemKind::UnresolvedReference => CompletionKind::Other,
+        CompletionItemKind::SymbolKind(symbol) => match symbol {
+            SymbolKind::Module | SymbolKind::ToolModule => CompletionKind::Module,
+            SymbolKind::Enum
+            | SymbolKind::SelfType
+            | SymbolKind::Struct
+            | SymbolKind::Trait
+            | SymbolKind::TypeAlias
+            | SymbolKind::TypeParam
+            | SymbolKind::Union => CompletionKind::Type,
+            SymbolKind::Function => CompletionKind::Function,
+            SymbolKind::Field => CompletionKind::Field,
+            SymbolKind::Variant => CompletionKind::Variant,
+            SymbolKind::Const | SymbolKind::ConstParam | SymbolKind::Static => {
+                CompletionKind::Constant
+            }
+            SymbolKind::Local | SymbolKind::SelfParam | SymbolKind::ValueParam => {
+                CompletionKind::Variable
+            }
+            SymbolKind::Attribute
+            | SymbolKind::BuiltinAttr
+            | SymbolKind::Derive
+            | SymbolKind::DeriveHelper
+            | SymbolKind::Macro => CompletionKind::Macro,
+            SymbolKind::Impl | SymbolKind::Label | SymbolKind::LifetimeParam => {
+                CompletionKind::Other
+            }
+        },
+    }
+}
+
+pub(crate) fn inlay_hint(hint: ide::InlayHint) -> InlayHint {
+    let kind = match hint.kind {
+        InlayKind::Type => InlayHintKind::Type,
+        InlayKind::Parameter => InlayHintKind::Parameter,
+        InlayKind::Chaining => InlayHintKind::Chaining,
+        _ => InlayHintKind::Other,
+    };
+    InlayHint { range: hint.range.into(), kind, label: hint.label.to_string() }
+}
+
+pub(crate) fn diagnostic(files: &Files, diagnostic: ide::Diagnostic) -> Diagnostic {
+    Diagnostic {
+        code: diagnostic.code.as_str().to_owned(),
+        message: diagnostic.message,
+        range: diagnostic.range.into(),
+        severity: match diagnostic.severity {
+            ide::Severity::Error => Severity::Error,
+            ide::Severity::WeakWarning => Severity::Warning,
+        },
+        unused: diagnostic.unused,
+        fixes: diagnostic.fixes.into_iter().flatten().filter_map(|it| assist(files, it)).collect(),
+    }
+}
+
+/// Converts a resolved assist, if it only edits files.
+pub(crate) fn assist(files: &Files, assist: ide::Assist) -> Option<Assist> {
+    let SourceChange { source_file_edits, file_system_edits, is_snippet } = assist.source_change?;
+    if !file_system_edits.is_empty() {
+        return None;
+    }
+    let mut edits: Vec<_> = source_file_edits
+        .into_iter()
+        .map(|(file_id, edit)| FileEdit {
+            path: files.path(file_id).to_owned(),
+            edits: text_edits(edit),
+        })
+        .collect();
+    edits.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(Assist {
+        id: assist.id.0.to_owned(),
+        label: assist.label.to_string(),
+        target: assist.target.into(),
+        edits,
+        is_snippet,
+    })
+}
+
+fn text_edits(edit: ide::TextEdit) -> Vec<TextEdit> {
+    edit.into_iter()
+        .map(|indel| TextEdit { range: indel.delete.into(), new_text: indel.insert })
+        .collect()
+}
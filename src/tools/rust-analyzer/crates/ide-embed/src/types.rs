@@ -0,0 +1,153 @@
+//! The results of the analysis, which are plain data to serialize.
+
+use ide::TextRange;
+use serde::{Deserialize, Serialize};
+
+/// A crate, whose files are all the files reachable through `mod` items from its root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Crate {
+    /// The name other crates refer to this crate with.
+    pub name: String,
+    /// The path of the root file of the crate, like `/src/lib.rs`.
+    pub root: String,
+    /// The edition of the crate, like `2021`.
+    pub edition: String,
+    /// The names of the crates this crate depends on.
+    #[serde(default)]
+    pub deps: Vec<String>,
+    /// The enabled `cfg` atoms, like `test`.
+    #[serde(default)]
+    pub cfg: Vec<String>,
+}
+
+impl Crate {
+    /// A 2021 edition crate without dependencies.
+    pub fn new(name: impl Into<String>, root: impl Into<String>) -> Crate {
+        Crate {
+            name: name.into(),
+            root: root.into(),
+            edition: "2021".to_owned(),
+            deps: Vec::new(),
+            cfg: Vec::new(),
+        }
+    }
+}
+
+/// A range of UTF-8 byte offsets into the text of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Range {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl From<TextRange> for Range {
+    fn from(range: TextRange) -> Range {
+        Range { start: range.start().into(), end: range.end().into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// The range to replace, in the text before any edit is applied.
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// The edits to apply to one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEdit {
+    pub path: String,
+    /// The edits, which do not overlap, in the order of their ranges.
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hover {
+    /// The range of the hovered item.
+    pub range: Range,
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionKind {
+    Keyword,
+    Snippet,
+    Module,
+    Type,
+    Function,
+    Method,
+    Field,
+    Variant,
+    Constant,
+    Variable,
+    Macro,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// Additional information, like the signature of a function.
+    pub detail: Option<String>,
+    pub documentation: Option<String>,
+    /// The edits to the completed file, replacing the text typed so far.
+    pub edits: Vec<TextEdit>,
+    /// Whether the new text of the edits is a snippet, with `$0` marking the cursor position.
+    pub is_snippet: bool,
+    pub deprecated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InlayHintKind {
+    /// The type of a binding, shown after it.
+    Type,
+    /// The name of a parameter, shown before the argument.
+    Parameter,
+    /// The type of an expression in a method call chain, shown after it.
+    Chaining,
+    /// Other hints, like the name of the item a closing brace ends.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InlayHint {
+    /// The range of the code the hint is about.
+    pub range: Range,
+    pub kind: InlayHintKind,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The code of the diagnostic, like `unresolved-module`.
+    pub code: String,
+    pub message: String,
+    pub range: Range,
+    pub severity: Severity,
+    /// Whether the diagnostic is about unused code.
+    pub unused: bool,
+    pub fixes: Vec<Assist>,
+}
+
+/// A code transformation, like inlining a variable, or the fix of a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assist {
+    /// The id of the assist, like `inline_local_variable`.
+    pub id: String,
+    pub label: String,
+    /// The range the assist applies to.
+    pub target: Range,
+    pub edits: Vec<FileEdit>,
+    /// Whether the new text of the edits is a snippet, with `$0` marking the cursor position.
+    pub is_snippet: bool,
+}
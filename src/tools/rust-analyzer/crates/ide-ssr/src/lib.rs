@@ -39,9 +39,14 @@
 // | Constraint    | Restricts placeholder
 //
 // | kind(literal) | Is a literal (e.g. `42` or `"forty two"`)
+// | type(T)       | Has a type matching `T`, where `_` matches any type (e.g. `Result<_, _>`)
+// | path(p)       | Is a path resolving to the same item as `p` (e.g. `std::mem::drop`)
 // | not(a)        | Negates the constraint `a`
 // |===
 //
+// For example, `${x:type(Result<_, _>)}.unwrap() ==>> $x.expect("failed")` only rewrites calls
+// to `unwrap` on values of type `Result`.
+//
 // Available via the command `rust-analyzer.ssr`.
 //
 // ```rust
@@ -11,7 +11,7 @@
 use std::{cell::Cell, iter::Peekable};
 use syntax::{
     ast::{self, AstNode, AstToken},
-    SmolStr, SyntaxElement, SyntaxElementChildren, SyntaxKind, SyntaxNode, SyntaxToken,
+    match_ast, SmolStr, SyntaxElement, SyntaxElementChildren, SyntaxKind, SyntaxNode, SyntaxToken,
 };
 
 // Creates a match error. If we're currently attempting to match some code that we thought we were
@@ -319,6 +319,38 @@ fn check_constraint(
             Constraint::Kind(kind) => {
                 kind.matches(code)?;
             }
+            Constraint::Type(ty) => {
+                let code_ty = if let Some(expr) = ast::Expr::cast(code.clone()) {
+                    self.sema.type_of_expr(&expr).map(|it| it.original)
+                } else if let Some(pat) = ast::Pat::cast(code.clone()) {
+                    self.sema.type_of_pat(&pat).map(|it| it.original)
+                } else if let Some(ty) = ast::Type::cast(code.clone()) {
+                    self.sema.resolve_type(&ty)
+                } else {
+                    None
+                };
+                let code_ty = code_ty.ok_or_else(|| {
+                    match_error!("Failed to determine the type of '{}'", code.text())
+                })?;
+                if !self.type_matches(ty, &code_ty) {
+                    fail_match!("Type of '{}' doesn't match `{}`", code.text(), ty);
+                }
+            }
+            Constraint::Path(path) => {
+                let code_path = match_ast! {
+                    match code {
+                        ast::Path(it) => Some(it),
+                        ast::PathExpr(it) => it.path(),
+                        ast::PathType(it) => it.path(),
+                        ast::PathPat(it) => it.path(),
+                        _ => None,
+                    }
+                };
+                let resolution = code_path.and_then(|it| self.sema.resolve_path(&it));
+                if resolution.as_ref() != self.rule.pattern.constraint_paths.get(path.syntax()) {
+                    fail_match!("Code '{}' doesn't resolve to `{}`", code.text(), path);
+                }
+            }
             Constraint::Not(sub) => {
                 if self.check_constraint(&*sub, code).is_ok() {
                     fail_match!("Constraint {:?} failed for '{}'", constraint, code.text());
@@ -328,6 +360,58 @@ fn check_constraint(
         Ok(())
     }
 
+    /// Checks whether `ty` matches the type from a type constraint. `_` matches any type and
+    /// omitted trailing type arguments match any type arguments.
+    fn type_matches(&self, pattern: &ast::Type, ty: &hir::Type) -> bool {
+        match pattern {
+            ast::Type::InferType(_) => true,
+            ast::Type::ParenType(it) => it.ty().map_or(false, |it| self.type_matches(&it, ty)),
+            ast::Type::RefType(it) => match ty.as_reference() {
+                Some((inner, mutability)) => {
+                    it.mut_token().is_some() == (mutability == hir::Mutability::Mut)
+                        && it.ty().map_or(false, |it| self.type_matches(&it, &inner))
+                }
+                None => false,
+            },
+            ast::Type::TupleType(it) => {
+                let pattern_fields: Vec<_> = it.fields().collect();
+                if pattern_fields.is_empty() {
+                    return ty.is_unit();
+                }
+                let fields = ty.tuple_fields(self.sema.db);
+                pattern_fields.len() == fields.len()
+                    && pattern_fields.iter().zip(&fields).all(|(p, f)| self.type_matches(p, f))
+            }
+            ast::Type::PathType(it) => {
+                let Some(path) = it.path() else { return false };
+                let same_type = match self.rule.pattern.constraint_paths.get(path.syntax()) {
+                    Some(hir::PathResolution::Def(hir::ModuleDef::Adt(adt))) => {
+                        ty.as_adt() == Some(*adt)
+                    }
+                    Some(hir::PathResolution::Def(hir::ModuleDef::BuiltinType(builtin))) => {
+                        ty.as_builtin() == Some(*builtin)
+                    }
+                    _ => false,
+                };
+                let Some(generic_args) = path.segment().and_then(|it| it.generic_arg_list()) else {
+                    return same_type;
+                };
+                let pattern_args: Vec<_> = generic_args
+                    .generic_args()
+                    .filter_map(|arg| match arg {
+                        ast::GenericArg::TypeArg(it) => it.ty(),
+                        _ => None,
+                    })
+                    .collect();
+                let args: Vec<_> = ty.type_arguments().collect();
+                same_type
+                    && pattern_args.len() <= args.len()
+                    && pattern_args.iter().zip(&args).all(|(p, a)| self.type_matches(p, a))
+            }
+            _ => false,
+        }
+    }
+
     /// Paths are matched based on whether they refer to the same thing, even if they're written
     /// differently.
     fn attempt_match_path(
@@ -649,7 +733,7 @@ fn render_template_paths(
         for (path, resolved_path) in &template.resolved_paths {
             if let hir::PathResolution::Def(module_def) = resolved_path.resolution {
                 let mod_path =
-                    module.find_use_path(sema.db, module_def, false).ok_or_else(|| {
+                    module.find_use_path(sema.db, module_def, false, false).ok_or_else(|| {
                         match_error!("Failed to render template path `{}` at match location")
                     })?;
                 self.rendered_template_paths.insert(path.clone(), mod_path);
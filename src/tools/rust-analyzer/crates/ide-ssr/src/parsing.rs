@@ -6,7 +6,7 @@
 //! e.g. expressions, type references etc.
 use ide_db::{FxHashMap, FxHashSet};
 use std::{fmt::Display, str::FromStr};
-use syntax::{SmolStr, SyntaxKind, SyntaxNode, T};
+use syntax::{ast, AstNode, SmolStr, SyntaxKind, SyntaxNode, T};
 
 use crate::errors::bail;
 use crate::{fragments, SsrError, SsrPattern, SsrRule};
@@ -46,6 +46,10 @@ pub(crate) struct Placeholder {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum Constraint {
     Kind(NodeKind),
+    /// The code must have a type matching this type. `_` matches any type.
+    Type(ast::Type),
+    /// The code must be a path that resolves to the same item as this path.
+    Path(ast::Path),
     Not(Box<Constraint>),
 }
 
@@ -326,10 +330,46 @@ fn parse_constraint(tokens: &mut std::vec::IntoIter<Token>) -> Result<Constraint
             expect_token(tokens, ")")?;
             Ok(Constraint::Not(Box::new(sub)))
         }
+        "type" => {
+            let text = parenthesized_text(tokens)?;
+            let ty = fragments::ty(&text)
+                .ok()
+                .and_then(ast::Type::cast)
+                .ok_or_else(|| SsrError(format!("Invalid type constraint `{text}`")))?;
+            Ok(Constraint::Type(ty))
+        }
+        "path" => {
+            let text = parenthesized_text(tokens)?;
+            let path = fragments::ty(&text)
+                .ok()
+                .and_then(ast::PathType::cast)
+                .and_then(|it| it.path())
+                .ok_or_else(|| SsrError(format!("Invalid path constraint `{text}`")))?;
+            Ok(Constraint::Path(path))
+        }
         x => bail!("Unsupported constraint type '{}'", x),
     }
 }
 
+/// Consumes a parenthesized group of tokens and returns the text between the parentheses.
+fn parenthesized_text(tokens: &mut std::vec::IntoIter<Token>) -> Result<String, SsrError> {
+    expect_token(tokens, "(")?;
+    let mut text = String::new();
+    let mut depth = 0;
+    loop {
+        let t = tokens
+            .next()
+            .ok_or_else(|| SsrError::new("Unexpected end of constraint while looking for ')'"))?;
+        match t.kind {
+            T!['('] => depth += 1,
+            T![')'] if depth == 0 => return Ok(text),
+            T![')'] => depth -= 1,
+            _ => {}
+        }
+        text.push_str(&t.text);
+    }
+}
+
 fn expect_token(tokens: &mut std::vec::IntoIter<Token>, expected: &str) -> Result<(), SsrError> {
     if let Some(t) = tokens.next() {
         if t.text == expected {
@@ -5,7 +5,11 @@
 use parsing::Placeholder;
 use syntax::{ast, SmolStr, SyntaxKind, SyntaxNode, SyntaxToken};
 
-use crate::{errors::error, parsing, SsrError};
+use crate::{
+    errors::{bail, error},
+    parsing::{self, Constraint},
+    SsrError,
+};
 
 pub(crate) struct ResolutionScope<'db> {
     scope: hir::SemanticsScope<'db>,
@@ -25,6 +29,8 @@ pub(crate) struct ResolvedPattern {
     pub(crate) resolved_paths: FxHashMap<SyntaxNode, ResolvedPath>,
     pub(crate) ufcs_function_calls: FxHashMap<SyntaxNode, UfcsCallInfo>,
     pub(crate) contains_self: bool,
+    // Paths within the type and path constraints of placeholders that we've resolved.
+    pub(crate) constraint_paths: FxHashMap<SyntaxNode, hir::PathResolution>,
 }
 
 pub(crate) struct ResolvedPath {
@@ -104,15 +110,71 @@ fn resolve_pattern_tree(&self, pattern: SyntaxNode) -> Result<ResolvedPattern, S
                 SyntaxElement::Token(t) => t.kind() == T![self],
                 _ => false,
             });
+        let mut constraint_paths = FxHashMap::default();
+        for placeholder in self.placeholders_by_stand_in.values() {
+            for constraint in &placeholder.constraints {
+                self.resolve_constraint(constraint, &mut constraint_paths)?;
+            }
+        }
         Ok(ResolvedPattern {
             node: pattern,
             resolved_paths,
             placeholders_by_stand_in: self.placeholders_by_stand_in.clone(),
             ufcs_function_calls,
             contains_self,
+            constraint_paths,
         })
     }
 
+    fn resolve_constraint(
+        &self,
+        constraint: &Constraint,
+        constraint_paths: &mut FxHashMap<SyntaxNode, hir::PathResolution>,
+    ) -> Result<(), SsrError> {
+        use syntax::ast::AstNode;
+        match constraint {
+            Constraint::Kind(_) => {}
+            Constraint::Not(sub) => self.resolve_constraint(sub, constraint_paths)?,
+            Constraint::Path(path) => {
+                let resolution = self
+                    .resolution_scope
+                    .resolve_path(path)
+                    .ok_or_else(|| error!("Failed to resolve path `{}`", path))?;
+                constraint_paths.insert(path.syntax().clone(), resolution);
+            }
+            Constraint::Type(ty) => {
+                for ty in ty.syntax().descendants().filter_map(ast::Type::cast) {
+                    let path = match &ty {
+                        ast::Type::InferType(_)
+                        | ast::Type::ParenType(_)
+                        | ast::Type::RefType(_)
+                        | ast::Type::TupleType(_) => continue,
+                        ast::Type::PathType(it) => it.path(),
+                        _ => bail!("Unsupported type `{}` in type constraint", ty),
+                    };
+                    let Some(path) = path else { continue };
+                    let resolution = self
+                        .resolution_scope
+                        .resolve_path(&path)
+                        .ok_or_else(|| error!("Failed to resolve path `{}`", path))?;
+                    if !matches!(
+                        resolution,
+                        hir::PathResolution::Def(
+                            hir::ModuleDef::Adt(_) | hir::ModuleDef::BuiltinType(_)
+                        )
+                    ) {
+                        bail!(
+                            "Path `{}` in type constraint must resolve to an ADT or builtin type",
+                            path
+                        );
+                    }
+                    constraint_paths.insert(path.syntax().clone(), resolution);
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn resolve(
         &self,
         node: SyntaxNode,
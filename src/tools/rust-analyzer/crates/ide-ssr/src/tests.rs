@@ -536,6 +536,83 @@ fn f1() {
     assert_matches("Some(${a:not(kind(literal))})", code, &["Some(x1)", "Some(40 + 2)"]);
 }
 
+#[test]
+fn type_constraint() {
+    let code = r#"
+        enum Option<T> { Some(T), None }
+        enum Result<T, E> { Ok(T), Err(E) }
+        impl<T> Option<T> { fn unwrap(self) -> T { loop {} } }
+        impl<T, E> Result<T, E> { fn unwrap(self) -> T { loop {} } }
+        fn f1(a: Option<i32>, b: Result<i32, ()>, c: &Result<u8, ()>) {
+            a.unwrap();
+            b.unwrap();
+            c.unwrap();
+        }
+        "#;
+    assert_matches("${x:type(Result<_, _>)}.unwrap()", code, &["b.unwrap()"]);
+    assert_matches("${x:type(Result<i32>)}.unwrap()", code, &["b.unwrap()"]);
+    assert_matches("${x:type(&Result<u8, ()>)}.unwrap()", code, &["c.unwrap()"]);
+    assert_matches("${x:not(type(Result<_, _>))}.unwrap()", code, &["a.unwrap()", "c.unwrap()"]);
+}
+
+#[test]
+fn path_constraint() {
+    let code = r#"
+        mod foo {
+            pub fn bar() {}
+            pub fn baz() {}
+        }
+        use foo::bar;
+        fn call(f: fn()) {}
+        fn f1() {
+            call(foo::bar);
+            call(foo::baz);
+            call(bar);
+        }
+        "#;
+    assert_matches("call(${f:path(foo::bar)})", code, &["call(foo::bar)", "call(bar)"]);
+    assert_matches("call(${f:not(path(foo::bar))})", code, &["call(foo::baz)"]);
+}
+
+#[test]
+fn replace_with_type_constraint() {
+    assert_ssr_transform(
+        r#"${x:type(Result<_, _>)}.unwrap() ==>> $x.expect("failed")"#,
+        r#"
+        enum Result<T, E> { Ok(T), Err(E) }
+        impl<T, E> Result<T, E> { fn unwrap(self) -> T { loop {} } }
+        struct Foo;
+        impl Foo { fn unwrap(self) {} }
+        fn f1(a: Result<i32, ()>, b: Foo) {
+            a.unwrap();
+            b.unwrap();
+        }
+        "#,
+        expect![[r#"
+            enum Result<T, E> { Ok(T), Err(E) }
+            impl<T, E> Result<T, E> { fn unwrap(self) -> T { loop {} } }
+            struct Foo;
+            impl Foo { fn unwrap(self) {} }
+            fn f1(a: Result<i32, ()>, b: Foo) {
+                a.expect("failed");
+                b.unwrap();
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn constraint_errors() {
+    assert_eq!(parse_error_text("${a:type(+)} ==>> 1"), "Parse error: Invalid type constraint `+`");
+    let (db, position, selections) = single_file("fn f1() {}");
+    let mut match_finder = MatchFinder::in_context(&db, position, selections).unwrap();
+    let rule: SsrRule = "f1(${a:path(foo::bar)}) ==>> f1()".parse().unwrap();
+    assert_eq!(
+        match_finder.add_rule(rule).unwrap_err().to_string(),
+        "Parse error: Failed to resolve path `foo::bar`"
+    );
+}
+
 #[test]
 fn match_reordered_struct_instantiation() {
     assert_matches(
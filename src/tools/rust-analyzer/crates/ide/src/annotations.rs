@@ -9,7 +9,7 @@
 
 use crate::{
     annotations::fn_references::find_all_methods,
-    goto_implementation::goto_implementation,
+    goto_implementation::goto_trait_implementation,
     references::find_all_refs,
     runnables::{runnables, Runnable},
     NavigationTarget, RunnableKind,
@@ -22,6 +22,9 @@
 // Provides user with annotations above items for looking up references or impl blocks
 // and running/debugging binaries.
 //
+// Traits are annotated with the number of their implementations and types with the number of
+// trait impls applying to them. Inherent impls are not counted.
+//
 // image::https://user-images.githubusercontent.com/48062697/113020672-b7c34f00-917a-11eb-8f6e-858735660a0e.png[]
 #[derive(Debug)]
 pub struct Annotation {
@@ -39,7 +42,8 @@ pub enum AnnotationKind {
 pub struct AnnotationConfig {
     pub binary_target: bool,
     pub annotate_runnables: bool,
-    pub annotate_impls: bool,
+    pub annotate_trait_impls: bool,
+    pub annotate_adt_impls: bool,
     pub annotate_references: bool,
     pub annotate_method_references: bool,
     pub annotate_enum_variant_references: bool,
@@ -85,7 +89,9 @@ pub(crate) fn annotations(
             Definition::Const(konst) if config.annotate_references => {
                 konst.source(db).and_then(|node| name_range(db, node, file_id))
             }
-            Definition::Trait(trait_) if config.annotate_references || config.annotate_impls => {
+            Definition::Trait(trait_)
+                if config.annotate_references || config.annotate_trait_impls =>
+            {
                 trait_.source(db).and_then(|node| name_range(db, node, file_id))
             }
             Definition::Adt(adt) => match adt {
@@ -109,14 +115,14 @@ pub(crate) fn annotations(
                                 })
                             })
                     }
-                    if config.annotate_references || config.annotate_impls {
+                    if config.annotate_references || config.annotate_adt_impls {
                         enum_.source(db).and_then(|node| name_range(db, node, file_id))
                     } else {
                         None
                     }
                 }
                 _ => {
-                    if config.annotate_references || config.annotate_impls {
+                    if config.annotate_references || config.annotate_adt_impls {
                         adt.source(db).and_then(|node| name_range(db, node, file_id))
                     } else {
                         None
@@ -131,7 +137,12 @@ pub(crate) fn annotations(
             None => return,
         };
         let (annotation_range, target_pos) = mk_ranges(range);
-        if config.annotate_impls && !matches!(def, Definition::Const(_)) {
+        let annotate_impls = match def {
+            Definition::Trait(_) => config.annotate_trait_impls,
+            Definition::Adt(_) => config.annotate_adt_impls,
+            _ => false,
+        };
+        if annotate_impls {
             annotations.push(Annotation {
                 range: annotation_range,
                 kind: AnnotationKind::HasImpls { pos: target_pos, data: None },
@@ -178,7 +189,7 @@ fn name_range<T: HasName>(
 pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation) -> Annotation {
     match annotation.kind {
         AnnotationKind::HasImpls { pos, ref mut data } => {
-            *data = goto_implementation(db, pos).map(|range| range.info);
+            *data = goto_trait_implementation(db, pos).map(|range| range.info);
         }
         AnnotationKind::HasReferences { pos, ref mut data } => {
             *data = find_all_refs(&Semantics::new(db), pos, None).map(|result| {
@@ -215,7 +226,8 @@ mod tests {
     const DEFAULT_CONFIG: AnnotationConfig = AnnotationConfig {
         binary_target: true,
         annotate_runnables: true,
-        annotate_impls: true,
+        annotate_trait_impls: true,
+        annotate_adt_impls: true,
         annotate_references: true,
         annotate_method_references: true,
         annotate_enum_variant_references: true,
@@ -652,17 +664,7 @@ fn main() {
                                 offset: 7,
                             },
                             data: Some(
-                                [
-                                    NavigationTarget {
-                                        file_id: FileId(
-                                            0,
-                                        ),
-                                        full_range: 14..56,
-                                        focus_range: 19..23,
-                                        name: "impl",
-                                        kind: Impl,
-                                    },
-                                ],
+                                [],
                             ),
                         },
                     },
@@ -909,4 +911,48 @@ fn test_annotations_appear_above_whole_item_when_configured_to_do_so() {
             &AnnotationConfig { location: AnnotationLocation::AboveWholeItem, ..DEFAULT_CONFIG },
         );
     }
+
+    #[test]
+    fn impl_annotations_can_be_disabled_per_kind() {
+        check_with_config(
+            r#"
+trait Trait {}
+struct Foo;
+impl Trait for Foo {}
+"#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 6..11,
+                        kind: HasImpls {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 6,
+                            },
+                            data: Some(
+                                [
+                                    NavigationTarget {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        full_range: 27..48,
+                                        focus_range: 42..45,
+                                        name: "impl",
+                                        kind: Impl,
+                                    },
+                                ],
+                            ),
+                        },
+                    },
+                ]
+            "#]],
+            &AnnotationConfig {
+                annotate_adt_impls: false,
+                annotate_references: false,
+                ..DEFAULT_CONFIG
+            },
+        );
+    }
 }
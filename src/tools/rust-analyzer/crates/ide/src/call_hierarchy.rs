@@ -1,7 +1,8 @@
 //! Entry point for call-hierarchy
 
-use hir::Semantics;
+use hir::{AsAssocItem, HirDisplay, Semantics};
 use ide_db::{
+    base_db::SourceDatabaseExt,
     defs::{Definition, NameClass, NameRefClass},
     helpers::pick_best_token,
     search::FileReference,
@@ -41,24 +42,50 @@ pub(crate) fn incoming_calls(
     let file = file.syntax();
     let mut calls = CallLocations::default();
 
-    let references = sema
+    let funcs: Vec<_> = sema
         .find_nodes_at_offset_with_descend(file, offset)
         .filter_map(move |node| match node {
             ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, &name_ref)? {
-                NameRefClass::Definition(def @ Definition::Function(_)) => Some(def),
+                NameRefClass::Definition(Definition::Function(it)) => Some(it),
                 _ => None,
             },
             ast::NameLike::Name(name) => match NameClass::classify(sema, &name)? {
-                NameClass::Definition(def @ Definition::Function(_)) => Some(def),
+                NameClass::Definition(Definition::Function(it)) => Some(it),
                 _ => None,
             },
             ast::NameLike::Lifetime(_) => None,
         })
-        .flat_map(|func| func.usages(sema).all());
+        .collect();
+
+    for func in funcs {
+        add_incoming_calls(sema, &mut calls, func, |_| true);
+        // Calls through the trait, on `dyn Trait` or on generic receivers, may dispatch to a
+        // method of a trait impl. References to the trait method include the calls resolved
+        // to any impl though, so only keep the ones which weren't.
+        if let Some(trait_func) = implemented_trait_method(db, func) {
+            add_incoming_calls(sema, &mut calls, trait_func, |name_ref| {
+                matches!(
+                    NameRefClass::classify(sema, name_ref),
+                    Some(NameRefClass::Definition(Definition::Function(it))) if it == trait_func
+                )
+            });
+        }
+    }
 
-    for (_, references) in references {
-        let references =
-            references.iter().filter_map(|FileReference { name, .. }| name.as_name_ref());
+    Some(calls.into_items())
+}
+
+fn add_incoming_calls(
+    sema: &Semantics<'_, RootDatabase>,
+    calls: &mut CallLocations,
+    func: hir::Function,
+    filter: impl Fn(&ast::NameRef) -> bool,
+) {
+    for (_, references) in Definition::Function(func).usages(sema).all() {
+        let references = references
+            .iter()
+            .filter_map(|FileReference { name, .. }| name.as_name_ref())
+            .filter(|name| filter(name));
         for name in references {
             // This target is the containing function
             let nav = sema.ancestors_with_macros(name.syntax().clone()).find_map(|node| {
@@ -70,8 +97,6 @@ pub(crate) fn incoming_calls(
             }
         }
     }
-
-    Some(calls.into_items())
 }
 
 pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
@@ -96,32 +121,94 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
         })
         .flatten()
         .filter_map(ast::CallableExpr::cast)
-        .filter_map(|call_node| {
-            let (nav_target, range) = match call_node {
-                ast::CallableExpr::Call(call) => {
-                    let expr = call.expr()?;
-                    let callable = sema.type_of_expr(&expr)?.original.as_callable(db)?;
-                    match callable.kind() {
-                        hir::CallableKind::Function(it) => {
-                            let range = expr.syntax().text_range();
-                            it.try_to_nav(db).zip(Some(range))
-                        }
-                        _ => None,
-                    }
-                }
-                ast::CallableExpr::MethodCall(expr) => {
-                    let range = expr.name_ref()?.syntax().text_range();
-                    let function = sema.resolve_method_call(&expr)?;
-                    function.try_to_nav(db).zip(Some(range))
+        .filter_map(|call_node| match call_node {
+            ast::CallableExpr::Call(call) => {
+                let expr = call.expr()?;
+                let callable = sema.type_of_expr(&expr)?.original.as_callable(db)?;
+                match callable.kind() {
+                    hir::CallableKind::Function(it) => Some((it, expr.syntax().text_range(), None)),
+                    _ => None,
                 }
-            }?;
-            Some((nav_target, range))
+            }
+            ast::CallableExpr::MethodCall(expr) => {
+                let range = expr.name_ref()?.syntax().text_range();
+                let function = sema.resolve_method_call(&expr)?;
+                let receiver = expr
+                    .receiver()
+                    .and_then(|it| sema.type_of_expr(&it))
+                    .and_then(|it| it.original.strip_references().as_adt());
+                Some((function, range, receiver))
+            }
         })
-        .for_each(|(nav, range)| calls.add(nav, range));
+        .for_each(|(func, range, receiver)| {
+            if let Some(nav) = func.try_to_nav(db) {
+                calls.add(nav, range);
+            }
+            // A call to a trait method which couldn't be resolved to an impl, e.g. on a generic
+            // receiver, may dispatch to any of its implementations.
+            for (impl_, impl_func) in implementing_methods(db, func, receiver) {
+                if let Some(mut nav) = impl_func.try_to_nav(db) {
+                    nav.container_name = Some(impl_label(db, impl_).into());
+                    calls.add(nav, range);
+                }
+            }
+        });
 
     Some(calls.into_items())
 }
 
+/// The trait method implemented by `func`, if it is a method of a trait impl.
+fn implemented_trait_method(db: &RootDatabase, func: hir::Function) -> Option<hir::Function> {
+    let trait_ = func.as_assoc_item(db)?.containing_trait_impl(db)?;
+    let name = func.name(db);
+    trait_.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::Function(it) if it.name(db) == name => Some(it),
+        _ => None,
+    })
+}
+
+/// The methods implementing `func` if it is a trait method, along with their impls.
+///
+/// Only impls in workspace crates are considered, as listing every implementation of traits
+/// like `Clone` or `Iterator` in the dependencies is expensive and not helpful. If the receiver
+/// is known to be an ADT, only its impls are returned.
+fn implementing_methods(
+    db: &RootDatabase,
+    func: hir::Function,
+    receiver: Option<hir::Adt>,
+) -> Vec<(hir::Impl, hir::Function)> {
+    let Some(trait_) = func.as_assoc_item(db).and_then(|it| it.containing_trait(db)) else {
+        return Vec::new();
+    };
+    let name = func.name(db);
+    hir::Impl::all_for_trait(db, trait_)
+        .into_iter()
+        .filter(|impl_| match receiver {
+            Some(adt) => impl_.self_ty(db).as_adt() == Some(adt),
+            None => true,
+        })
+        .filter(|impl_| {
+            let source_root = db.file_source_root(impl_.module(db).krate().root_file(db));
+            !db.source_root(source_root).is_library
+        })
+        .filter_map(|impl_| {
+            let impl_func = impl_.items(db).into_iter().find_map(|item| match item {
+                hir::AssocItem::Function(it) if it.name(db) == name => Some(it),
+                _ => None,
+            })?;
+            Some((impl_, impl_func))
+        })
+        .collect()
+}
+
+fn impl_label(db: &RootDatabase, impl_: hir::Impl) -> String {
+    let self_ty = impl_.self_ty(db).display(db).to_string();
+    match impl_.trait_(db) {
+        Some(trait_) => format!("impl {} for {self_ty}", trait_.name(db)),
+        None => format!("impl {self_ty}"),
+    }
+}
+
 #[derive(Default)]
 struct CallLocations {
     funcs: FxIndexMap<NavigationTarget, Vec<TextRange>>,
@@ -482,4 +569,148 @@ fn caller() {
             expect![[]],
         );
     }
+
+    #[test]
+    fn test_trait_impl_method_call_hierarchy_dispatched_calls() {
+        check_hierarchy(
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn ar$0ea(&self) -> u32 { 0 }
+}
+
+struct Circle;
+
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+
+fn direct(s: Square) -> u32 {
+    s.area()
+}
+
+fn dynamic(s: &dyn Shape) -> u32 {
+    s.area()
+}
+
+fn generic<T: Shape>(s: T) -> u32 {
+    s.area()
+}
+
+fn other(c: Circle) -> u32 {
+    c.area()
+}
+"#,
+            expect![["area Function FileId(0) 88..115 91..95"]],
+            expect![[r#"
+                direct Function FileId(0) 194..238 197..203 : [230..234]
+                dynamic Function FileId(0) 240..289 243..250 : [281..285]
+                generic Function FileId(0) 291..341 294..301 : [333..337]"#]],
+            expect![[]],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_generic_receiver() {
+        check_hierarchy(
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+
+struct Circle;
+
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+
+fn tot$0al<T: Shape>(shape: T) -> u32 {
+    shape.area()
+}
+"#,
+            expect![["total Function FileId(0) 194..250 197..202"]],
+            expect![[]],
+            expect![[r#"
+                area Function FileId(0) 18..40 21..25 : [242..246]
+                area Function FileId(0) 88..115 91..95 impl Shape for Square : [242..246]
+                area Function FileId(0) 163..190 166..170 impl Shape for Circle : [242..246]"#]],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_concrete_receiver() {
+        check_hierarchy(
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+
+struct Circle;
+
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+
+fn tot$0al(shape: &Square) -> u32 {
+    shape.area()
+}
+"#,
+            expect![["total Function FileId(0) 194..246 197..202"]],
+            expect![[]],
+            expect![[r#"
+                area Function FileId(0) 88..115 91..95 : [238..242]"#]],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_skips_library_impls() {
+        check_hierarchy(
+            r#"
+//- /lib.rs crate:lib new_source_root:library
+pub trait Shape {
+    fn area(&self) -> u32;
+}
+
+pub struct Triangle;
+
+impl Shape for Triangle {
+    fn area(&self) -> u32 { 2 }
+}
+//- /main.rs crate:main deps:lib new_source_root:local
+use lib::Shape;
+
+struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+
+fn tot$0al<T: Shape>(shape: T) -> u32 {
+    shape.area()
+}
+"#,
+            expect![["total Function FileId(1) 92..148 95..100"]],
+            expect![[]],
+            expect![[r#"
+                area Function FileId(0) 22..44 25..29 : [140..144]
+                area Function FileId(1) 61..88 64..68 impl Shape for Square : [140..144]"#]],
+        );
+    }
 }
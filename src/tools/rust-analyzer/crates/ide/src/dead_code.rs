@@ -0,0 +1,235 @@
+use hir::{
+    AsAssocItem, AssocItem, Crate, HasSource, HasVisibility, HirDisplay, Module, ModuleDef,
+    Semantics, Visibility,
+};
+use ide_assists::utils::test_related_attribute;
+use ide_db::{base_db::SourceDatabaseExt, defs::Definition, search::FileReference, RootDatabase};
+use syntax::{ast, AstNode};
+
+use crate::{navigation_target::TryToNav, NavigationTarget};
+
+/// The items of a workspace crate that aren't referred to anywhere in the workspace.
+#[derive(Debug)]
+pub struct DeadCodeCrate {
+    pub name: String,
+    pub items: Vec<DeadCodeItem>,
+}
+
+#[derive(Debug)]
+pub struct DeadCodeItem {
+    /// The path of the item within its crate, like `module::Type::method`.
+    pub path: String,
+    /// Public items might still be used by crates outside of the workspace.
+    pub is_public: bool,
+    pub nav: NavigationTarget,
+}
+
+// Feature: Dead Code Report
+//
+// Lists the items of the workspace crates that are not referred to anywhere in the workspace,
+// grouped by crate. References from tests and from macro expansions count as uses, items
+// disabled by `#[cfg]` are not analyzed. Test functions, `main`, items with an exported symbol
+// name, trait items and trait implementations are never reported.
+//
+// Public items are reported too, as they are often only public by accident, but are marked as
+// such because crates outside of the workspace might use them.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: Show Dead Code Report**
+// |===
+pub(crate) fn dead_code(db: &RootDatabase) -> Vec<DeadCodeCrate> {
+    let sema = Semantics::new(db);
+    let mut res = Vec::new();
+    for krate in Crate::all(db) {
+        let source_root = db.file_source_root(krate.root_file(db));
+        if db.source_root(source_root).is_library {
+            continue;
+        }
+        let mut items = Vec::new();
+        let mut modules = vec![krate.root_module(db)];
+        while let Some(module) = modules.pop() {
+            modules.extend(module.children(db));
+            collect_dead_items(&sema, module, &mut items);
+        }
+        if items.is_empty() {
+            continue;
+        }
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        let name = krate.display_name(db).map_or_else(|| "unknown".to_owned(), |it| it.to_string());
+        res.push(DeadCodeCrate { name, items });
+    }
+    res.sort_by(|a, b| a.name.cmp(&b.name));
+    res
+}
+
+fn collect_dead_items(
+    sema: &Semantics<'_, RootDatabase>,
+    module: Module,
+    acc: &mut Vec<DeadCodeItem>,
+) {
+    let db = sema.db;
+    let module_path = module
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .filter_map(|it| it.name(db))
+        .map(|name| format!("{name}::"))
+        .collect::<String>();
+
+    for def in module.declarations(db) {
+        if matches!(def, ModuleDef::Module(_) | ModuleDef::BuiltinType(_))
+            || is_entry_point(db, def)
+        {
+            continue;
+        }
+        let Some(name) = def.name(db) else { continue };
+        let path = format!("{module_path}{name}");
+        push_if_dead(sema, Definition::from(def), def.visibility(db), path, acc);
+    }
+
+    // `macro_rules!` macros without `#[macro_export]` are scoped textually instead of being
+    // declared in the module, and are visible in the child modules too.
+    let local_macros = module
+        .legacy_macros(db)
+        .into_iter()
+        .filter(|it| it.module(db) == module && !it.is_macro_export(db));
+    for mac in local_macros {
+        let path = format!("{module_path}{}", mac.name(db));
+        push_if_dead(sema, Definition::Macro(mac), mac.visibility(db), path, acc);
+    }
+
+    // Items of trait implementations are used through the trait.
+    for impl_ in module.impl_defs(db).into_iter().filter(|it| it.trait_(db).is_none()) {
+        let self_ty = impl_.self_ty(db).display(db).to_string();
+        for item in impl_.items(db) {
+            let (def, visibility) = match item {
+                AssocItem::Function(it) if is_entry_point(db, it.into()) => continue,
+                AssocItem::Function(it) => (Definition::from(it), it.visibility(db)),
+                AssocItem::Const(it) => (Definition::from(it), it.visibility(db)),
+                AssocItem::TypeAlias(it) => (Definition::from(it), it.visibility(db)),
+            };
+            let Some(name) = item.name(db) else { continue };
+            let path = format!("{module_path}{self_ty}::{name}");
+            push_if_dead(sema, def, visibility, path, acc);
+        }
+    }
+}
+
+fn push_if_dead(
+    sema: &Semantics<'_, RootDatabase>,
+    def: Definition,
+    visibility: Visibility,
+    path: String,
+    acc: &mut Vec<DeadCodeItem>,
+) {
+    let usages = def.usages(sema).all();
+    let is_used = usages.iter().any(|(_, refs)| refs.iter().any(|it| !is_impl_self_ty(it)));
+    if is_used {
+        return;
+    }
+    let Some(nav) = def.try_to_nav(sema.db) else { return };
+    let is_public = matches!(visibility, Visibility::Public)
+        || matches!(def, Definition::Macro(it) if it.is_macro_export(sema.db));
+    acc.push(DeadCodeItem { path, is_public, nav });
+}
+
+/// Naming a type in the header of its own `impl` block doesn't use it.
+fn is_impl_self_ty(reference: &FileReference) -> bool {
+    let name = reference.name.syntax();
+    name.ancestors()
+        .find_map(ast::Impl::cast)
+        .and_then(|it| it.self_ty())
+        .map_or(false, |it| it.syntax().text_range().contains_range(name.text_range()))
+}
+
+/// Items that are used by the compiler, the test harness or from outside of Rust.
+fn is_entry_point(db: &RootDatabase, def: ModuleDef) -> bool {
+    if let ModuleDef::Function(func) = def {
+        if func.as_assoc_item(db).is_none()
+            && func.module(db).is_crate_root(db)
+            && func.name(db).to_smol_str() == "main"
+        {
+            return true;
+        }
+        if func.source(db).map_or(false, |src| test_related_attribute(&src.value).is_some()) {
+            return true;
+        }
+    }
+    def.attrs(db).map_or(false, |attrs| {
+        attrs.by_key("no_mangle").exists() || attrs.by_key("export_name").exists()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        let mut actual = String::new();
+        for krate in analysis.dead_code().unwrap() {
+            actual.push_str(&format!("{}\n", krate.name));
+            for item in krate.items {
+                let visibility = if item.is_public { "pub " } else { "" };
+                actual.push_str(&format!("  {visibility}{}\n", item.path));
+            }
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn reports_unreferenced_items() {
+        check(
+            r#"
+//- /lib.rs crate:lib cfg:test
+pub struct Used;
+pub struct Unused;
+struct OnlyImplemented;
+impl OnlyImplemented {
+    fn method(&self) {}
+}
+macro_rules! m {
+    () => { module::used_by_macro() };
+}
+pub trait Trait {
+    fn f(&self);
+}
+impl Trait for Used {
+    fn f(&self) {
+        m!();
+    }
+}
+mod module {
+    pub(crate) fn used_by_test() {}
+    pub(crate) fn used_by_macro() {}
+    fn unused() {}
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn test() {
+            super::used_by_test();
+        }
+    }
+}
+//- /main.rs crate:main deps:lib
+fn main() {
+    let _ = lib::Used;
+}
+#[no_mangle]
+fn exported() {}
+"#,
+            expect![[r#"
+                lib
+                  OnlyImplemented
+                  OnlyImplemented::method
+                  pub Unused
+                  module::unused
+            "#]],
+        );
+    }
+}
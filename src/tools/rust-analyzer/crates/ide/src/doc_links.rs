@@ -5,12 +5,17 @@
 
 mod intra_doc_links;
 
+use std::{ffi::OsStr, path::Path};
+
 use pulldown_cmark::{BrokenLink, CowStr, Event, InlineStr, LinkType, Options, Parser, Tag};
 use pulldown_cmark_to_cmark::{cmark_resume_with_options, Options as CMarkOptions};
 use stdx::format_to;
 use url::Url;
 
-use hir::{db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, HasAttrs};
+use hir::{
+    db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, HasAttrs, HasVisibility,
+    ItemInNs, Module, Name, PathKind, Visibility,
+};
 use ide_db::{
     base_db::{CrateOrigin, LangCrateOrigin, SourceDatabase},
     defs::{Definition, NameClass, NameRefClass},
@@ -29,8 +34,16 @@
     FilePosition, Semantics,
 };
 
-/// Weblink to an item's documentation.
-pub(crate) type DocumentationLink = String;
+/// Web and local links to an item's documentation.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DocumentationLinks {
+    /// The URL to the documentation on docs.rs or the `html_root_url` of the crate.
+    /// May not lead anywhere.
+    pub web_url: Option<String>,
+    /// The URL to the documentation generated by `cargo doc` in the local file system.
+    /// May not lead anywhere.
+    pub local_url: Option<String>,
+}
 
 const MARKDOWN_OPTIONS: Options =
     Options::ENABLE_FOOTNOTES.union(Options::ENABLE_TABLES).union(Options::ENABLE_TASKLISTS);
@@ -107,11 +120,16 @@ pub(crate) fn remove_links(markdown: &str) -> String {
     out
 }
 
-/// Retrieve a link to documentation for the given symbol.
+/// Retrieve links to the documentation of the given symbol.
+///
+/// The local link is only computed if the target directory or, for the standard library, the
+/// sysroot is known.
 pub(crate) fn external_docs(
     db: &RootDatabase,
     position: &FilePosition,
-) -> Option<DocumentationLink> {
+    target_dir: Option<&OsStr>,
+    sysroot: Option<&OsStr>,
+) -> Option<DocumentationLinks> {
     let sema = &Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
     let token = pick_best_token(file.token_at_offset(position.offset), |kind| match kind {
@@ -139,7 +157,7 @@ pub(crate) fn external_docs(
         }
     };
 
-    get_doc_link(db, definition)
+    Some(get_doc_links(db, definition, target_dir, sysroot))
 }
 
 /// Extracts all links from a given markdown text returning the definition text range, link-text
@@ -296,19 +314,31 @@ fn broken_link_clone_cb(link: BrokenLink<'_>) -> Option<(CowStr<'_>, CowStr<'_>)
 //
 // This should cease to be a problem if RFC2988 (Stable Rustdoc URLs) is implemented
 // https://github.com/rust-lang/rfcs/pull/2988
-fn get_doc_link(db: &RootDatabase, def: Definition) -> Option<String> {
-    let (target, file, frag) = filename_and_frag_for_def(db, def)?;
+fn get_doc_links(
+    db: &RootDatabase,
+    def: Definition,
+    target_dir: Option<&OsStr>,
+    sysroot: Option<&OsStr>,
+) -> DocumentationLinks {
+    let Some((target, file, frag)) = filename_and_frag_for_def(db, def) else {
+        return DocumentationLinks::default();
+    };
+    let path = mod_path_of_def(db, target);
 
-    let mut url = get_doc_base_url(db, target)?;
+    let finish = |base: Option<Url>| -> Option<String> {
+        let mut url = base?;
+        if let Some(path) = &path {
+            url = url.join(path).ok()?;
+        }
+        url = url.join(&file).ok()?;
+        url.set_fragment(frag.as_deref());
+        Some(url.into())
+    };
 
-    if let Some(path) = mod_path_of_def(db, target) {
-        url = url.join(&path).ok()?;
+    DocumentationLinks {
+        web_url: finish(get_doc_base_url(db, target)),
+        local_url: finish(get_local_doc_base_url(db, target, target_dir, sysroot)),
     }
-
-    url = url.join(&file).ok()?;
-    url.set_fragment(frag.as_deref());
-
-    Some(url.into())
 }
 
 fn rewrite_intra_doc_link(
@@ -351,12 +381,36 @@ fn rewrite_url_link(db: &RootDatabase, def: Definition, target: &str) -> Option<
     url.join(target).ok().map(Into::into)
 }
 
+/// Get the module path under which rustdoc documents a definition.
+///
+/// Items defined in a private module are documented where they are publicly re-exported, so the
+/// path of the re-export is used for them.
 fn mod_path_of_def(db: &RootDatabase, def: Definition) -> Option<String> {
-    def.canonical_module_path(db).map(|it| {
-        let mut path = String::new();
-        it.flat_map(|it| it.name(db)).for_each(|name| format_to!(path, "{}/", name));
-        path
-    })
+    let modules: Vec<Module> = def.canonical_module_path(db)?.collect();
+    let is_public = modules.iter().all(|it| it.visibility(db) == Visibility::Public);
+    let names = if is_public { None } else { reexport_mod_path(db, def) }
+        .unwrap_or_else(|| modules.iter().flat_map(|it| it.name(db)).collect());
+
+    let mut path = String::new();
+    names.iter().for_each(|name| format_to!(path, "{}/", name));
+    Some(path)
+}
+
+/// Finds the module path of the shortest public re-export of a definition in its own crate.
+fn reexport_mod_path(db: &RootDatabase, def: Definition) -> Option<Vec<Name>> {
+    // Variants are documented on the page of their enum.
+    let def = match def {
+        Definition::Variant(variant) => Definition::Adt(variant.parent_enum(db).into()),
+        def => def,
+    };
+    let root = def.krate(db)?.root_module(db);
+    let item: ItemInNs = Option::from(def)?;
+    let path = root.find_use_path(db, item, false, false)?;
+    if !matches!(path.kind, PathKind::Plain | PathKind::Crate) {
+        return None;
+    }
+    let (_, modules) = path.segments().split_last()?;
+    Some(modules.to_vec())
 }
 
 /// Rewrites a markdown document, applying 'callback' to each link.
@@ -456,6 +510,42 @@ fn get_doc_base_url(db: &RootDatabase, def: Definition) -> Option<Url> {
     Url::parse(&base).ok()?.join(&format!("{display_name}/")).ok()
 }
 
+/// Get the root URL for the documentation of a definition generated in the local file system,
+/// either by `cargo doc` in the target directory or by `rustup` in the sysroot.
+///
+/// ```ignore
+/// file:///project/root/target/doc/std/iter/trait.Iterator.html#tymethod.next
+/// ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+/// ```
+fn get_local_doc_base_url(
+    db: &RootDatabase,
+    def: Definition,
+    target_dir: Option<&OsStr>,
+    sysroot: Option<&OsStr>,
+) -> Option<Url> {
+    let sysroot_docs = || Some(Path::new(sysroot?).join("share/doc/rust/html"));
+
+    let (dir, display_name) = match def {
+        Definition::BuiltinType(..) => (sysroot_docs()?, String::from("core")),
+        _ => {
+            let krate = def.krate(db)?;
+            let display_name = krate.display_name(db)?.to_string();
+            let dir = match db.crate_graph()[krate.into()].origin {
+                CrateOrigin::Lang(
+                    LangCrateOrigin::Alloc
+                    | LangCrateOrigin::Core
+                    | LangCrateOrigin::ProcMacro
+                    | LangCrateOrigin::Std
+                    | LangCrateOrigin::Test,
+                ) => sysroot_docs()?,
+                _ => Path::new(target_dir?).join("doc"),
+            };
+            (dir, display_name)
+        }
+    };
+    Url::from_directory_path(dir).ok()?.join(&format!("{display_name}/")).ok()
+}
+
 /// Get the filename and extension generated for a symbol by rustdoc.
 ///
 /// ```ignore
@@ -1,3 +1,5 @@
+use std::ffi::OsStr;
+
 use expect_test::{expect, Expect};
 use hir::{HasAttrs, Semantics};
 use ide_db::{
@@ -15,7 +17,23 @@
 
 fn check_external_docs(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
-    let url = analysis.external_docs(position).unwrap().expect("could not find url for symbol");
+    let links =
+        analysis.external_docs(position, None, None).unwrap().expect("could not find symbol");
+    assert_eq!(links.local_url, None);
+    let url = links.web_url.expect("could not find url for symbol");
+
+    expect.assert_eq(&url)
+}
+
+fn check_local_docs(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let target_dir = OsStr::new("/home/user/project/target");
+    let sysroot = OsStr::new("/home/user/.rustup/toolchains/stable");
+    let links = analysis
+        .external_docs(position, Some(target_dir), Some(sysroot))
+        .unwrap()
+        .expect("could not find symbol");
+    let url = links.local_url.expect("could not find local url for symbol");
 
     expect.assert_eq(&url)
 }
@@ -277,6 +295,63 @@ pub mod ba$0r {}
     )
 }
 
+#[test]
+fn external_docs_private_module_reexport() {
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub mod wrapper {
+    mod private {
+        pub struct Item;
+        impl Item {
+            pub fn method(&self) {}
+        }
+    }
+    pub use private::Item;
+}
+
+fn foo(it: wrapper::Item) {
+    it.meth$0od();
+}
+"#,
+        expect![[r##"https://docs.rs/foo/*/foo/wrapper/struct.Item.html#method.method"##]],
+    )
+}
+
+#[test]
+#[cfg(not(windows))]
+fn local_docs_in_target_dir() {
+    check_local_docs(
+        r#"
+//- /main.rs crate:foo
+pub mod bar {
+    pub trait Trait {
+        fn method$0();
+    }
+}
+"#,
+        expect![[
+            r##"file:///home/user/project/target/doc/foo/bar/trait.Trait.html#tymethod.method"##
+        ]],
+    )
+}
+
+#[test]
+#[cfg(not(windows))]
+fn local_docs_in_sysroot() {
+    check_local_docs(
+        r#"
+//- /main.rs crate:foo deps:std
+fn foo(_: std::Vec$0) {}
+//- /std.rs crate:std
+pub struct Vec;
+"#,
+        expect![[
+            r#"file:///home/user/.rustup/toolchains/stable/share/doc/rust/html/std/struct.Vec.html"#
+        ]],
+    )
+}
+
 #[test]
 fn external_docs_reexport_order() {
     check_external_docs(
@@ -0,0 +1,268 @@
+use hir::{ModuleDef, PathResolution, Semantics};
+use ide_db::{
+    base_db::{Edition, FileRange},
+    defs::{Definition, NameClass},
+    FxHashSet, RootDatabase,
+};
+use stdx::format_to;
+use syntax::{
+    ast::{self, HasName},
+    match_ast, AstNode,
+};
+
+/// How to get the value of an expression.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExpressionEvaluation {
+    /// The value of a constant, computed by const evaluation.
+    Value(String),
+    /// The source of a program printing the value of the expression with `{:?}`, to be compiled
+    /// as a crate of the given edition.
+    Program { source: String, edition: Edition },
+}
+
+// Feature: Evaluate Expression
+//
+// Evaluates the selected expression and shows its value, a quick calculator for pure snippets.
+// Constants are const-evaluated, any other expression is wrapped in a `fn main` printing its
+// value, together with the imports in scope from the standard library and the items of the file
+// it refers to, and compiled and run in a sandbox. Running programs has to be enabled with
+// `rust-analyzer.evaluateExpression.runPrograms.enable`.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: Evaluate Selected Expression**
+// |===
+pub(crate) fn evaluate_expression(
+    db: &RootDatabase,
+    frange: FileRange,
+) -> Option<ExpressionEvaluation> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(frange.file_id);
+    let expr = match source_file.syntax().covering_element(frange.range) {
+        syntax::NodeOrToken::Node(node) => node.ancestors().find_map(ast::Expr::cast),
+        syntax::NodeOrToken::Token(token) => token.parent_ancestors().find_map(ast::Expr::cast),
+    }?;
+
+    if let ast::Expr::PathExpr(path_expr) = &expr {
+        let resolution = path_expr.path().and_then(|path| sema.resolve_path(&path));
+        if let Some(PathResolution::Def(ModuleDef::Const(konst))) = resolution {
+            if let Ok(value) = konst.eval(db) {
+                return Some(ExpressionEvaluation::Value(value.to_string()));
+            }
+        }
+    }
+
+    let local_items = referenced_local_items(&sema, &source_file, &expr);
+    let mut source = String::from("#![allow(unused)]\n");
+    for use_ in uses_in_scope(&expr) {
+        let trees = use_.syntax().descendants().filter_map(ast::UseTree::cast);
+        let mut leaves = trees.filter(|tree| tree.use_tree_list().is_none()).peekable();
+        if leaves.peek().is_some() && leaves.all(|tree| imports_from_sysroot(&sema, &tree)) {
+            format_to!(source, "{}\n", use_.syntax().text());
+        }
+    }
+    for item in local_items {
+        format_to!(source, "\n{}\n", item.syntax().text());
+    }
+    format_to!(
+        source,
+        "\nfn main() {{\n    let value = {{ {} }};\n    println!(\"{{value:?}}\");\n}}\n",
+        expr
+    );
+
+    let edition = sema
+        .to_module_def(frange.file_id)
+        .map_or(Edition::CURRENT, |module| module.krate().edition(db));
+    Some(ExpressionEvaluation::Program { source, edition })
+}
+
+/// The `use` items of the file which are in scope at `expr`, outermost first.
+fn uses_in_scope(expr: &ast::Expr) -> Vec<ast::Use> {
+    let mut scopes: Vec<Vec<ast::Item>> = expr
+        .syntax()
+        .ancestors()
+        .filter_map(|node| {
+            match_ast! {
+                match node {
+                    ast::SourceFile(it) => Some(it.items().collect()),
+                    ast::ItemList(it) => Some(it.items().collect()),
+                    ast::StmtList(it) => Some(
+                        it.statements()
+                            .filter_map(|stmt| match stmt {
+                                ast::Stmt::Item(item) => Some(item),
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                    _ => None,
+                }
+            }
+        })
+        .collect();
+    scopes.reverse();
+    scopes
+        .into_iter()
+        .flatten()
+        .filter_map(|item| match item {
+            ast::Item::Use(use_) => Some(use_),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the path imported by the leaf `tree` of a `use` item resolves into the standard
+/// library, the only crates available to the program.
+fn imports_from_sysroot(sema: &Semantics<'_, RootDatabase>, tree: &ast::UseTree) -> bool {
+    let module = match tree.path().and_then(|path| sema.resolve_path(&path)) {
+        Some(PathResolution::Def(ModuleDef::Module(module))) => module,
+        Some(PathResolution::Def(def)) => match def.module(sema.db) {
+            Some(module) => module,
+            // Builtin types are always available.
+            None => return true,
+        },
+        _ => return false,
+    };
+    module.krate().is_builtin(sema.db)
+}
+
+/// The top-level items of the file the expression refers to, together with the `impl` blocks of
+/// the referenced types, which are copied into the program.
+fn referenced_local_items(
+    sema: &Semantics<'_, RootDatabase>,
+    source_file: &ast::SourceFile,
+    expr: &ast::Expr,
+) -> Vec<ast::Item> {
+    let referenced: FxHashSet<Definition> = expr
+        .syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter_map(|path| match sema.resolve_path(&path)? {
+            PathResolution::Def(def) => Some(Definition::from(def)),
+            _ => None,
+        })
+        .collect();
+    source_file
+        .items()
+        .filter(|item| {
+            let def = match item {
+                ast::Item::Impl(impl_) => sema
+                    .to_def(impl_)
+                    .and_then(|impl_| impl_.self_ty(sema.db).as_adt())
+                    .map(Definition::Adt),
+                ast::Item::Use(_) | ast::Item::Module(_) | ast::Item::ExternCrate(_) => None,
+                _ => ast::AnyHasName::cast(item.syntax().clone())
+                    .and_then(|it| it.name())
+                    .and_then(|name| NameClass::classify(sema, &name))
+                    .and_then(NameClass::defined),
+            };
+            def.map_or(false, |def| referenced.contains(&def))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::ExpressionEvaluation;
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, frange) = fixture::range(ra_fixture);
+        let actual = match analysis.evaluate_expression(frange).unwrap().unwrap() {
+            ExpressionEvaluation::Value(value) => format!("value: {value}"),
+            ExpressionEvaluation::Program { source, edition } => {
+                format!("edition {edition}\n{source}")
+            }
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn evaluates_constants() {
+        check(
+            r#"
+const C: u32 = 1 + 2;
+fn f() {
+    let _ = $0C$0;
+}
+"#,
+            expect![[r#"value: 3"#]],
+        );
+    }
+
+    #[test]
+    fn wraps_expressions_with_std_imports() {
+        check(
+            r#"
+//- /main.rs crate:main deps:std
+use std::collections::HashMap;
+use crate::foo::Bar;
+fn f() {
+    let _ = $0(1..=10).map(|it| it * it).sum::<u32>()$0;
+}
+//- /std/lib.rs crate:std
+pub mod collections { pub struct HashMap; }
+"#,
+            expect![[r#"
+                edition 2021
+                #![allow(unused)]
+                use std::collections::HashMap;
+
+                fn main() {
+                    let value = { (1..=10).map(|it| it * it).sum::<u32>() };
+                    println!("{value:?}");
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn carries_over_uses_in_scope_and_referenced_items() {
+        check(
+            r#"
+//- /main.rs crate:main deps:std,dep
+use std::{collections::{self, HashMap}, fmt::*};
+use std::{io, fmt::Debug as _, vec::Vec as V};
+use dep::Thing;
+struct Point { x: u32 }
+impl Point { fn double(&self) -> u32 { self.x * 2 } }
+fn helper() -> u32 { 1 }
+fn unrelated() {}
+mod m {
+    use std::io::Read;
+}
+fn f() {
+    use std::vec::Vec;
+    let _ = $0helper() + Point { x: 1 }.double()$0;
+}
+//- /std/lib.rs crate:std
+pub mod collections { pub struct HashMap; }
+pub mod fmt { pub trait Debug {} pub trait Display {} }
+pub mod io { pub trait Read {} }
+pub mod vec { pub struct Vec; }
+//- /dep/lib.rs crate:dep
+pub struct Thing;
+"#,
+            expect![[r#"
+                edition 2021
+                #![allow(unused)]
+                use std::{collections::{self, HashMap}, fmt::*};
+                use std::{io, fmt::Debug as _, vec::Vec as V};
+                use std::vec::Vec;
+
+                struct Point { x: u32 }
+
+                impl Point { fn double(&self) -> u32 { self.x * 2 } }
+
+                fn helper() -> u32 { 1 }
+
+                fn main() {
+                    let value = { helper() + Point { x: 1 }.double() };
+                    println!("{value:?}");
+                }
+            "#]],
+        );
+    }
+}
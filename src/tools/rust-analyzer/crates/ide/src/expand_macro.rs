@@ -1,12 +1,26 @@
-use hir::Semantics;
+use hir::{InFile, Origin, Semantics};
 use ide_db::{
     base_db::FileId, helpers::pick_best_token,
     syntax_helpers::insert_whitespace_into_node::insert_ws_into, RootDatabase,
 };
-use syntax::{ast, ted, AstNode, NodeOrToken, SyntaxKind, SyntaxNode, T};
+use syntax::{
+    ast::{self, HasName},
+    ted::{self, Position},
+    AstNode, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, T,
+};
 
 use crate::FilePosition;
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpandMacroConfig {
+    /// How many levels of macro calls to expand. `Some(1)` only expands the macro call at the
+    /// cursor, `None` expands all macro calls in its expansion as well.
+    pub depth: Option<usize>,
+    /// Whether to annotate identifiers with a comment telling whether they were written at the
+    /// call site, in the body of a `macro_rules!`, or generated by a macro.
+    pub annotate_hygiene: bool,
+}
+
 pub struct ExpandedMacro {
     pub name: String,
     pub expansion: String,
@@ -16,6 +30,9 @@ pub struct ExpandedMacro {
 //
 // Shows the full macro expansion of the macro at current cursor.
 //
+// The expansion can also be limited to a number of levels of nested macro calls to step through
+// it, and identifiers can be annotated with their hygiene context to debug macros.
+//
 // |===
 // | Editor  | Action Name
 //
@@ -23,7 +40,11 @@ pub struct ExpandedMacro {
 // |===
 //
 // image::https://user-images.githubusercontent.com/48062697/113020648-b3973180-917a-11eb-84a9-ecb921293dc5.gif[]
-pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<ExpandedMacro> {
+pub(crate) fn expand_macro(
+    db: &RootDatabase,
+    config: &ExpandMacroConfig,
+    position: FilePosition,
+) -> Option<ExpandedMacro> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id);
 
@@ -58,8 +79,11 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
             .take_while(|it| it != &token)
             .filter(|it| it.kind() == T![,])
             .count();
-        let expansion =
-            format(db, SyntaxKind::MACRO_ITEMS, position.file_id, expansions.get(idx).cloned()?);
+        let expansion = expansions.get(idx)?.clone_for_update();
+        if config.annotate_hygiene {
+            annotate_hygiene(hygiene_annotations(&sema, &expansion));
+        }
+        let expansion = format(db, SyntaxKind::MACRO_ITEMS, position.file_id, expansion);
         Some(ExpandedMacro { name, expansion })
     });
 
@@ -77,7 +101,7 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
             if let Some(def) = sema.resolve_attr_macro_call(&item) {
                 break (
                     def.name(db).to_string(),
-                    expand_attr_macro_recur(&sema, &item)?,
+                    expand_attr_macro_recur(&sema, config, 1, &item)?,
                     SyntaxKind::MACRO_ITEMS,
                 );
             }
@@ -85,7 +109,7 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
         if let Some(mac) = ast::MacroCall::cast(node) {
             break (
                 mac.path()?.segment()?.name_ref()?.to_string(),
-                expand_macro_recur(&sema, &mac)?,
+                expand_macro_recur(&sema, config, 1, &mac)?,
                 mac.syntax().parent().map(|it| it.kind()).unwrap_or(SyntaxKind::MACRO_ITEMS),
             );
         }
@@ -101,43 +125,103 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
 
 fn expand_macro_recur(
     sema: &Semantics<'_, RootDatabase>,
+    config: &ExpandMacroConfig,
+    depth: usize,
     macro_call: &ast::MacroCall,
 ) -> Option<SyntaxNode> {
     let expanded = sema.expand(macro_call)?.clone_for_update();
-    expand(sema, expanded, ast::MacroCall::cast, expand_macro_recur)
+    expand(sema, config, depth, expanded, ast::MacroCall::cast, expand_macro_recur)
 }
 
 fn expand_attr_macro_recur(
     sema: &Semantics<'_, RootDatabase>,
+    config: &ExpandMacroConfig,
+    depth: usize,
     item: &ast::Item,
 ) -> Option<SyntaxNode> {
     let expanded = sema.expand_attr_macro(item)?.clone_for_update();
-    expand(sema, expanded, ast::Item::cast, expand_attr_macro_recur)
+    expand(sema, config, depth, expanded, ast::Item::cast, expand_attr_macro_recur)
 }
 
 fn expand<T: AstNode>(
     sema: &Semantics<'_, RootDatabase>,
+    config: &ExpandMacroConfig,
+    depth: usize,
     expanded: SyntaxNode,
     f: impl FnMut(SyntaxNode) -> Option<T>,
-    exp: impl Fn(&Semantics<'_, RootDatabase>, &T) -> Option<SyntaxNode>,
+    exp: impl Fn(&Semantics<'_, RootDatabase>, &ExpandMacroConfig, usize, &T) -> Option<SyntaxNode>,
 ) -> Option<SyntaxNode> {
-    let children = expanded.descendants().filter_map(f);
+    // The annotations have to be computed before the tree is modified, as the tokens are mapped
+    // back up by their ranges.
+    let annotations =
+        if config.annotate_hygiene { hygiene_annotations(sema, &expanded) } else { Vec::new() };
     let mut replacements = Vec::new();
 
-    for child in children {
-        if let Some(new_node) = exp(sema, &child) {
-            // check if the whole original syntax is replaced
-            if expanded == *child.syntax() {
-                return Some(new_node);
+    if config.depth.map_or(true, |max_depth| depth < max_depth) {
+        for child in expanded.descendants().filter_map(f) {
+            if let Some(new_node) = exp(sema, config, depth + 1, &child) {
+                // check if the whole original syntax is replaced
+                if expanded == *child.syntax() {
+                    return Some(new_node);
+                }
+                replacements.push((child, new_node));
             }
-            replacements.push((child, new_node));
         }
     }
 
+    annotate_hygiene(annotations);
     replacements.into_iter().rev().for_each(|(old, new)| ted::replace(old.syntax(), new));
     Some(expanded)
 }
 
+/// Computes a comment for every identifier in `expanded` describing its hygiene context.
+fn hygiene_annotations(
+    sema: &Semantics<'_, RootDatabase>,
+    expanded: &SyntaxNode,
+) -> Vec<(SyntaxToken, SyntaxToken)> {
+    expanded
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| matches!(token.kind(), SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT))
+        .map(|token| {
+            let context = hygiene_context(sema, &token);
+            let comment = ast::SourceFile::parse(&format!("/* {context} */")).tree();
+            let comment = comment.syntax().clone_for_update().first_token().unwrap();
+            comment.detach();
+            (token, comment)
+        })
+        .collect()
+}
+
+fn annotate_hygiene(annotations: Vec<(SyntaxToken, SyntaxToken)>) {
+    for (token, comment) in annotations {
+        ted::insert_raw(Position::after(token), comment);
+    }
+}
+
+/// Maps `token` up through the macro calls it was expanded from, until it either ends up at the
+/// call site or in the body of a `macro_rules!`. Tokens created by a macro can't be mapped up.
+fn hygiene_context(sema: &Semantics<'_, RootDatabase>, token: &SyntaxToken) -> String {
+    let mut token = match token.parent() {
+        Some(parent) => InFile::new(sema.hir_file_for(&parent), token.clone()),
+        None => return "generated".to_owned(),
+    };
+    while token.file_id.is_macro() {
+        match token.upmap_with_origin(sema.db) {
+            Some((up, Origin::Call)) => token = up,
+            Some((up, Origin::Def)) => {
+                let mac = up.value.parent_ancestors().find_map(ast::Macro::cast);
+                return match mac.and_then(|mac| mac.name()) {
+                    Some(name) => format!("def site of `{name}`"),
+                    None => "def site".to_owned(),
+                };
+            }
+            None => return "generated".to_owned(),
+        }
+    }
+    "call site".to_owned()
+}
+
 fn format(db: &RootDatabase, kind: SyntaxKind, file_id: FileId, expanded: SyntaxNode) -> String {
     let expansion = insert_ws_into(expanded).to_string();
 
@@ -213,12 +297,17 @@ fn _format(
 mod tests {
     use expect_test::{expect, Expect};
 
-    use crate::fixture;
+    use crate::{fixture, ExpandMacroConfig};
 
     #[track_caller]
     fn check(ra_fixture: &str, expect: Expect) {
+        check_with_config(ExpandMacroConfig::default(), ra_fixture, expect);
+    }
+
+    #[track_caller]
+    fn check_with_config(config: ExpandMacroConfig, ra_fixture: &str, expect: Expect) {
         let (analysis, pos) = fixture::position(ra_fixture);
-        let expansion = analysis.expand_macro(pos).unwrap().unwrap();
+        let expansion = analysis.expand_macro(&config, pos).unwrap().unwrap();
         let actual = format!("{}\n{}", expansion.name, expansion.expansion);
         expect.assert_eq(&actual);
     }
@@ -279,6 +368,46 @@ fn b(){}
         );
     }
 
+    #[test]
+    fn macro_expand_single_level() {
+        check_with_config(
+            ExpandMacroConfig { depth: Some(1), ..ExpandMacroConfig::default() },
+            r#"
+macro_rules! bar {
+    () => { fn  b() {} }
+}
+macro_rules! foo {
+    () => { bar!(); }
+}
+f$0oo!();
+"#,
+            expect![[r#"
+                foo
+                bar!();"#]],
+        );
+    }
+
+    #[test]
+    fn macro_expand_hygiene_annotations() {
+        check_with_config(
+            ExpandMacroConfig { annotate_hygiene: true, ..ExpandMacroConfig::default() },
+            r#"
+macro_rules! bar {
+    ($e:expr) => { $e * x };
+}
+macro_rules! foo {
+    ($e:ident) => { bar!(y + $e) };
+}
+fn main() {
+    let _ = f$0oo!(z);
+}
+"#,
+            expect![[r#"
+                foo
+                y /* def site of `foo` */+z /* call site */*x /* def site of `bar` */"#]],
+        );
+    }
+
     #[test]
     fn macro_expand_multiple_lines() {
         check(
@@ -4,7 +4,7 @@
 use ide_db::RootDatabase;
 use syntax::{
     algo::{self, skip_trivia_token},
-    ast::{self, AstNode, AstToken},
+    ast::{self, AstNode, AstToken, IsString},
     Direction, NodeOrToken,
     SyntaxKind::{self, *},
     SyntaxNode, SyntaxToken, TextRange, TextSize, TokenAtOffset, T,
@@ -17,6 +17,10 @@
 // Extends or shrinks the current selection to the encompassing syntactic construct
 // (expression, statement, item, module, etc). It works with multiple cursors.
 //
+// Inside string literals the contents between the quotes are selected before the whole literal.
+// Comma-separated elements of macro arguments are selected first on their own and then together
+// with their separating comma, like the elements of other lists.
+//
 // This is a standard LSP feature and not a protocol extension.
 //
 // |===
@@ -83,6 +87,9 @@ fn try_extend_selection(
     let node = match root.covering_element(range) {
         NodeOrToken::Token(token) => {
             if token.text_range() != range {
+                if let Some(range) = extend_to_string_contents(&token, range) {
+                    return Some(range);
+                }
                 return Some(token.text_range());
             }
             if let Some(comment) = ast::Comment::cast(token.clone()) {
@@ -97,10 +104,20 @@ fn try_extend_selection(
 
     // if we are in single token_tree, we maybe live in macro or attr
     if node.kind() == TOKEN_TREE {
-        if let Some(macro_call) = node.ancestors().find_map(ast::MacroCall::cast) {
-            if let Some(range) = extend_tokens_from_range(sema, macro_call, range) {
-                return Some(range);
-            }
+        let in_macro = node
+            .ancestors()
+            .find_map(ast::MacroCall::cast)
+            .and_then(|macro_call| extend_tokens_from_range(sema, macro_call, range));
+        // A selected nested token tree is itself an element of the enclosing one.
+        let token_tree = if node.text_range() == range {
+            node.parent().filter(|it| it.kind() == TOKEN_TREE)
+        } else {
+            Some(node.clone())
+        };
+        let in_list = token_tree.and_then(|it| extend_token_tree_element(&it, range));
+        // Take the smaller of both steps so that successive expansions don't skip any.
+        if let Some(range) = in_macro.into_iter().chain(in_list).min_by_key(|it| it.len()) {
+            return Some(range);
         }
     }
 
@@ -218,6 +235,14 @@ fn non_word_char(c: char) -> bool {
     }
 }
 
+fn extend_to_string_contents(token: &SyntaxToken, range: TextRange) -> Option<TextRange> {
+    let contents = match ast::String::cast(token.clone()) {
+        Some(string) => string.text_range_between_quotes(),
+        None => ast::ByteString::cast(token.clone())?.text_range_between_quotes(),
+    }?;
+    (contents.contains_range(range) && contents != range).then_some(contents)
+}
+
 fn extend_ws(root: &SyntaxNode, ws: SyntaxToken, offset: TextSize) -> TextRange {
     let ws_text = ws.text();
     let suffix = TextRange::new(offset, ws.text_range().end()) - ws.text_range().start();
@@ -252,12 +277,12 @@ fn priority(n: &SyntaxToken) -> usize {
     }
 }
 
+fn is_single_line_ws(token: &SyntaxToken) -> bool {
+    token.kind() == WHITESPACE && !token.text().contains('\n')
+}
+
 /// Extend list item selection to include nearby delimiter and whitespace.
 fn extend_list_item(node: &SyntaxNode) -> Option<TextRange> {
-    fn is_single_line_ws(node: &SyntaxToken) -> bool {
-        node.kind() == WHITESPACE && !node.text().contains('\n')
-    }
-
     fn nearby_delimiter(
         delimiter_kind: SyntaxKind,
         node: &SyntaxNode,
@@ -295,6 +320,46 @@ fn nearby_delimiter(
     None
 }
 
+/// Extend a selection inside a comma-separated token tree to the whole element, and an element
+/// to include its delimiter and whitespace.
+fn extend_token_tree_element(token_tree: &SyntaxNode, range: TextRange) -> Option<TextRange> {
+    let mut elements: Vec<(TextRange, Option<SyntaxToken>)> = Vec::new();
+    let mut element: Option<TextRange> = None;
+    for child in token_tree.children_with_tokens() {
+        match child.kind() {
+            T!['('] | T![')'] | T!['['] | T![']'] | T!['{'] | T!['}'] => (),
+            kind if kind.is_trivia() => (),
+            T![,] => elements.push((element.take()?, child.into_token())),
+            _ => {
+                let child_range = child.text_range();
+                element = Some(element.map_or(child_range, |it| it.cover(child_range)));
+            }
+        }
+    }
+    elements.extend(element.map(|it| (it, None)));
+    if elements.len() < 2 {
+        return None;
+    }
+
+    let idx = elements.iter().position(|(element, _)| element.contains_range(range))?;
+    let (element, delimiter) = &elements[idx];
+    if *element != range {
+        return Some(*element);
+    }
+    if let Some(delimiter) = delimiter {
+        // Include any following whitespace when delimiter is after list item.
+        let end = delimiter
+            .next_token()
+            .filter(is_single_line_ws)
+            .unwrap_or_else(|| delimiter.clone())
+            .text_range()
+            .end();
+        return Some(TextRange::new(element.start(), end));
+    }
+    let (_, delimiter) = &elements[idx.checked_sub(1)?];
+    Some(TextRange::new(delimiter.as_ref()?.text_range().start(), element.end()))
+}
+
 fn extend_comments(comment: ast::Comment) -> Option<TextRange> {
     let prev = adj_comments(&comment, Direction::Prev);
     let next = adj_comments(&comment, Direction::Next);
@@ -496,7 +561,7 @@ fn bar(){}
 
 " fn f$0oo() {"
 "#,
-            &["foo", "\" fn foo() {\""],
+            &["foo", " fn foo() {", "\" fn foo() {\""],
         );
     }
 
@@ -659,4 +724,36 @@ fn extend_selection_inside_recur_macros() {
             ],
         );
     }
+
+    #[test]
+    fn extend_selection_string_contents() {
+        do_check(
+            r#"fn main() { let s = "hello $0world"; }"#,
+            &["world", "hello world", "\"hello world\""],
+        );
+        do_check(
+            r#"fn main() { let s = b"hello $0world"; }"#,
+            &["world", "hello world", "b\"hello world\""],
+        );
+        do_check(r##"fn main() { let s = r#"hel$0lo"#; }"##, &["hello", "r#\"hello\"#"]);
+    }
+
+    #[test]
+    fn extend_selection_token_tree_elements() {
+        do_check(r#"foo!($0a + b, c);"#, &["a", "a + b", "a + b, ", "(a + b, c)"]);
+        do_check(r#"foo!(a, $0b + c);"#, &["b", "b + c", ", b + c", "(a, b + c)"]);
+        do_check(r#"foo!(a, $0(b, c), d);"#, &["(", "(b, c)", "(b, c), ", "(a, (b, c), d)"]);
+        do_check(r#"foo!(a, ($0b, c));"#, &["b", "b, ", "(b, c)", ", (b, c)", "(a, (b, c))"]);
+    }
+
+    #[test]
+    fn extend_selection_token_tree_elements_in_macros() {
+        do_check(
+            r#"
+macro_rules! foo { ($($e:expr),*) => { ($($e),*) } }
+fn main() { foo!(1, 2 $0+ 3, 4); }
+"#,
+            &["+", "2 + 3", "2 + 3, ", "(1, 2 + 3, 4)"],
+        );
+    }
 }
@@ -25,6 +25,7 @@ pub enum FoldKind {
     WhereClause,
     ReturnType,
     MatchArm,
+    String,
 }
 
 #[derive(Debug)]
@@ -36,7 +37,7 @@ pub struct Fold {
 // Feature: Folding
 //
 // Defines folding regions for curly braced blocks, runs of consecutive use, mod, const or static
-// items, and `region` / `endregion` comment markers.
+// items, multi-line string literals, and `region` / `endregion` comment markers.
 pub(crate) fn folding_ranges(file: &SourceFile) -> Vec<Fold> {
     let mut res = vec![];
     let mut visited_comments = FxHashSet::default();
@@ -139,6 +140,7 @@ fn fold_kind(kind: SyntaxKind) -> Option<FoldKind> {
         ARG_LIST | PARAM_LIST => Some(FoldKind::ArgList),
         ARRAY_EXPR => Some(FoldKind::Array),
         RET_TYPE => Some(FoldKind::ReturnType),
+        STRING | BYTE_STRING => Some(FoldKind::String),
         ASSOC_ITEM_LIST
         | RECORD_FIELD_LIST
         | RECORD_PAT_FIELD_LIST
@@ -316,6 +318,7 @@ fn check(ra_fixture: &str) {
                 FoldKind::WhereClause => "whereclause",
                 FoldKind::ReturnType => "returntype",
                 FoldKind::MatchArm => "matcharm",
+                FoldKind::String => "string",
             };
             assert_eq!(kind, &attr.unwrap());
         }
@@ -571,6 +574,21 @@ fn f2() {}
         )
     }
 
+    #[test]
+    fn fold_multiline_string() {
+        check(
+            r###"
+const TEXT: &str = <fold string>"first line
+second line"</fold>;
+
+const RAW: &[u8] = <fold string>br#"first line
+second line"#</fold>;
+
+const LINE: &str = "single line";
+"###,
+        )
+    }
+
     #[test]
     fn fold_consecutive_const() {
         check(
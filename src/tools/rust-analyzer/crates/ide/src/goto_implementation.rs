@@ -1,5 +1,6 @@
 use hir::{AsAssocItem, Impl, Semantics};
 use ide_db::{
+    base_db::SourceDatabaseExt,
     defs::{Definition, NameClass, NameRefClass},
     helpers::pick_best_token,
     RootDatabase,
@@ -11,7 +12,8 @@
 
 // Feature: Go to Implementation
 //
-// Navigates to the impl blocks of types.
+// Navigates to the impl blocks of types. This includes impls generated by derives, which are
+// navigated to the deriving attribute, and blanket impls applying to the type.
 //
 // |===
 // | Editor  | Shortcut
@@ -23,6 +25,22 @@
 pub(crate) fn goto_implementation(
     db: &RootDatabase,
     position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    implementations(db, position, true)
+}
+
+/// Like [`goto_implementation`], but leaves out the inherent impls of types.
+pub(crate) fn goto_trait_implementation(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    implementations(db, position, false)
+}
+
+fn implementations(
+    db: &RootDatabase,
+    position: FilePosition,
+    include_inherent: bool,
 ) -> Option<RangeInfo<Vec<NavigationTarget>>> {
     let sema = Semantics::new(db);
     let source_file = sema.parse(position.file_id);
@@ -61,9 +79,13 @@ pub(crate) fn goto_implementation(
         .filter_map(|def| {
             let navs = match def {
                 Definition::Trait(trait_) => impls_for_trait(&sema, trait_),
-                Definition::Adt(adt) => impls_for_ty(&sema, adt.ty(sema.db)),
-                Definition::TypeAlias(alias) => impls_for_ty(&sema, alias.ty(sema.db)),
-                Definition::BuiltinType(builtin) => impls_for_ty(&sema, builtin.ty(sema.db)),
+                Definition::Adt(adt) => impls_for_ty(&sema, adt.ty(sema.db), include_inherent),
+                Definition::TypeAlias(alias) => {
+                    impls_for_ty(&sema, alias.ty(sema.db), include_inherent)
+                }
+                Definition::BuiltinType(builtin) => {
+                    impls_for_ty(&sema, builtin.ty(sema.db), include_inherent)
+                }
                 Definition::Function(f) => {
                     let assoc = f.as_assoc_item(sema.db)?;
                     let name = assoc.name(sema.db)?;
@@ -86,8 +108,23 @@ pub(crate) fn goto_implementation(
     Some(RangeInfo { range, info: navs })
 }
 
-fn impls_for_ty(sema: &Semantics<'_, RootDatabase>, ty: hir::Type) -> Vec<NavigationTarget> {
-    Impl::all_for_type(sema.db, ty).into_iter().filter_map(|imp| imp.try_to_nav(sema.db)).collect()
+fn impls_for_ty(
+    sema: &Semantics<'_, RootDatabase>,
+    ty: hir::Type,
+    include_inherent: bool,
+) -> Vec<NavigationTarget> {
+    let db = sema.db;
+    // Checking whether the blanket impls of every dependency apply is too expensive, only look
+    // at the workspace.
+    let blanket_impls = Impl::blanket_impls_for_type(db, ty.clone(), |krate| {
+        !db.source_root(db.file_source_root(krate.root_file(db))).is_library
+    });
+    Impl::all_for_type(db, ty)
+        .into_iter()
+        .chain(blanket_impls)
+        .filter(|imp| include_inherent || imp.trait_(sema.db).is_some())
+        .filter_map(|imp| imp.try_to_nav(sema.db))
+        .collect()
 }
 
 fn impls_for_trait(
@@ -260,6 +297,21 @@ fn goto_implementation_to_builtin_derive() {
         );
     }
 
+    #[test]
+    fn goto_implementation_to_custom_derive() {
+        check(
+            r#"
+//- proc_macros: derive_marker
+//- minicore: derive
+trait Marker {}
+
+  #[derive(proc_macros::DeriveMarker)]
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+struct Foo$0;
+"#,
+        );
+    }
+
     #[test]
     fn goto_implementation_type_alias() {
         check(
@@ -304,6 +356,59 @@ impl bool {}
         );
     }
 
+    #[test]
+    fn goto_implementation_blanket_impls() {
+        check(
+            r#"
+trait Named {}
+trait Greet {}
+trait Unrelated {}
+
+struct Foo$0;
+impl Named for Foo {}
+             //^^^
+
+impl<T: Named> Greet for T {}
+                       //^
+impl<T: Unrelated> Named for T {}
+impl<T> Unrelated for &T {}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_blanket_impls_from_dependents() {
+        check(
+            r#"
+//- /lib.rs crate:lib
+pub trait Named {}
+pub struct Foo$0;
+impl Named for Foo {}
+             //^^^
+//- /main.rs crate:main deps:lib
+trait Greet {}
+impl<T: lib::Named> Greet for T {}
+                            //^
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_blanket_impls_not_from_libraries() {
+        check(
+            r#"
+//- /dep.rs crate:dep new_source_root:library
+pub trait Named {}
+pub trait Greet {}
+impl<T: Named> Greet for T {}
+//- /main.rs crate:main deps:dep new_source_root:local
+struct Foo$0;
+impl dep::Named for Foo {}
+                  //^^^
+"#,
+        );
+    }
+
     #[test]
     fn goto_implementation_trait_functions() {
         check(
@@ -10,7 +10,7 @@
 use syntax::{
     ast::{self, HasLoopBody},
     match_ast, AstNode,
-    SyntaxKind::{self, IDENT, INT_NUMBER},
+    SyntaxKind::{self, IDENT, INT_NUMBER, LIFETIME_IDENT},
     SyntaxNode, SyntaxToken, TextRange, T,
 };
 
@@ -38,9 +38,10 @@ pub struct HighlightRelatedConfig {
 // Highlights constructs related to the thing under the cursor:
 //
 // . if on an identifier, highlights all references to that identifier in the current file
-// . if on an `async` or `await token, highlights all yield points for that async context
+// . if on an `async` or `await` token, highlights all yield points for that async context
 // . if on a `return` or `fn` keyword, `?` character or `->` return type arrow, highlights all exit points for that context
-// . if on a `break`, `loop`, `while` or `for` token, highlights all break points for that loop or block context
+// . if on a `break`, `continue`, `loop`, `while` or `for` token, highlights all break points for that loop or block context
+// . if on the label of a loop or block, or on the label of a `break` or `continue`, highlights all break points for the labeled context
 //
 // Note: `?` and `->` do not currently trigger this behavior in the VSCode editor.
 pub(crate) fn highlight_related(
@@ -55,7 +56,7 @@ pub(crate) fn highlight_related(
         T![?] => 4, // prefer `?` when the cursor is sandwiched like in `await$0?`
         T![->] => 3,
         kind if kind.is_keyword() => 2,
-        IDENT | INT_NUMBER => 1,
+        IDENT | INT_NUMBER | LIFETIME_IDENT => 1,
         _ => 0,
     })?;
     match token.kind() {
@@ -70,6 +71,9 @@ pub(crate) fn highlight_related(
         T![break] | T![loop] | T![while] | T![continue] if config.break_points => {
             highlight_break_points(token)
         }
+        LIFETIME_IDENT if config.break_points && is_jump_label(&token) => {
+            highlight_break_points(token)
+        }
         _ if config.references => highlight_references(sema, &syntax, token, file_id),
         _ => None,
     }
@@ -216,14 +220,15 @@ fn hl(
         highlights.extend(range.map(|range| HighlightedRange { category: None, range }));
         for_each_break_and_continue_expr(label, body, &mut |expr| {
             let range: Option<TextRange> = match (cursor_token_kind, expr) {
-                (T![for] | T![while] | T![loop] | T![break], ast::Expr::BreakExpr(break_)) => {
-                    cover_range(
-                        break_.break_token().map(|it| it.text_range()),
-                        break_.lifetime().map(|it| it.syntax().text_range()),
-                    )
-                }
                 (
-                    T![for] | T![while] | T![loop] | T![continue],
+                    T![for] | T![while] | T![loop] | T![break] | LIFETIME_IDENT,
+                    ast::Expr::BreakExpr(break_),
+                ) => cover_range(
+                    break_.break_token().map(|it| it.text_range()),
+                    break_.lifetime().map(|it| it.syntax().text_range()),
+                ),
+                (
+                    T![for] | T![while] | T![loop] | T![continue] | LIFETIME_IDENT,
                     ast::Expr::ContinueExpr(continue_),
                 ) => cover_range(
                     continue_.continue_token().map(|it| it.text_range()),
@@ -244,6 +249,7 @@ fn hl(
             ast::ForExpr(f) => f.label().and_then(|it| it.lifetime()),
             ast::WhileExpr(w) => w.label().and_then(|it| it.lifetime()),
             ast::BlockExpr(b) => Some(b.label().and_then(|it| it.lifetime())?),
+            ast::Lifetime(l) => Some(l),
             _ => return None,
         }
     };
@@ -321,6 +327,16 @@ fn hl(
     None
 }
 
+/// Whether `token` is the lifetime of a label or of a `break` or `continue` referring to one.
+fn is_jump_label(token: &SyntaxToken) -> bool {
+    token.parent().and_then(|lifetime| lifetime.parent()).map_or(false, |parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::LABEL | SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR
+        )
+    })
+}
+
 fn cover_range(r0: Option<TextRange>, r1: Option<TextRange>) -> Option<TextRange> {
     match (r0, r1) {
         (Some(r0), Some(r1)) => Some(r0.cover(r1)),
@@ -1062,6 +1078,67 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn test_hl_break_loop_label() {
+        check(
+            r#"
+fn foo() {
+    'outer$0: loop {
+ // ^^^^^^^^^^^^
+        'inner: loop {
+            break 'outer;
+         // ^^^^^^^^^^^^
+            continue 'outer;
+         // ^^^^^^^^^^^^^^^
+            break 'inner;
+            continue;
+        }
+        break;
+     // ^^^^^
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_hl_continue_label() {
+        check(
+            r#"
+fn foo() {
+    'outer: for _ in () {
+ // ^^^^^^^^^^^
+        'inner: while true {
+            break 'outer;
+         // ^^^^^^^^^^^^
+            continue 'outer$0;
+         // ^^^^^^^^^^^^^^^
+            continue 'inner;
+        }
+        continue;
+     // ^^^^^^^^
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_hl_lifetime_is_not_jump_label() {
+        check(
+            r#"
+fn foo<'a>(x: &'a$0 ()) -> &'a () {
+    // ^^
+            // ^^
+                       // ^^
+    'a: loop {
+        break 'a x;
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_hl_field_shorthand() {
         check(
@@ -4,7 +4,7 @@
 use syntax::{
     ast::{self, AstNode, AstToken, IsString},
     NodeOrToken, SourceFile, SyntaxElement,
-    SyntaxKind::{self, USE_TREE, WHITESPACE},
+    SyntaxKind::{self, COMMENT, USE_TREE, WHITESPACE},
     SyntaxToken, TextRange, TextSize, T,
 };
 
@@ -15,6 +15,8 @@ pub struct JoinLinesConfig {
     pub remove_trailing_comma: bool,
     pub unwrap_trivial_blocks: bool,
     pub join_assignments: bool,
+    pub join_method_chains: bool,
+    pub merge_if_let_patterns: bool,
 }
 
 // Feature: Join Lines
@@ -148,6 +150,10 @@ fn remove_newline(
         }
     }
 
+    if config.merge_if_let_patterns && merge_if_lets(edit, &prev, &next).is_some() {
+        return;
+    }
+
     if config.join_else_if {
         if let (Some(prev), Some(_next)) = (as_if_expr(&prev), as_if_expr(&next)) {
             match prev.else_token() {
@@ -202,6 +208,11 @@ fn remove_newline(
         return;
     }
 
+    if config.join_method_chains && next.kind() == T![.] && join_method_chain(edit, token).is_some()
+    {
+        return;
+    }
+
     // Remove newline but add a computed amount of whitespace characters
     edit.replace(token.text_range(), compute_ws(prev.kind(), next.kind()).to_string());
 }
@@ -270,6 +281,144 @@ fn join_assignments(
     Some(())
 }
 
+/// Joins all the lines of the method chain whose `.` follows `token`:
+///
+/// ```
+/// foo()
+///     .bar()
+///     .baz()
+/// ```
+///
+/// becomes `foo().bar().baz()`.
+fn join_method_chain(edit: &mut TextEditBuilder, token: &SyntaxToken) -> Option<()> {
+    let mut expr = ast::Expr::cast(token.parent()?)?;
+    chain_receiver(&expr)?;
+    while let Some(parent) = expr.syntax().parent().and_then(ast::Expr::cast) {
+        if chain_receiver(&parent).as_ref() != Some(&expr) {
+            break;
+        }
+        expr = parent;
+    }
+
+    let mut line_breaks = Vec::new();
+    let mut next = Some(expr);
+    while let Some(expr) = next {
+        let dot = expr.syntax().children_with_tokens().find(|it| it.kind() == T![.]);
+        if let Some(ws) = dot.and_then(|it| it.prev_sibling_or_token()) {
+            // The line break after a comment has to stay.
+            let after_comment = ws.prev_sibling_or_token().map(|it| it.kind()) == Some(COMMENT);
+            if ws.kind() == WHITESPACE && ws.to_string().contains('\n') && !after_comment {
+                line_breaks.push(ws.text_range());
+            }
+        }
+        next = chain_receiver(&expr);
+    }
+
+    cov_mark::hit!(join_method_chain);
+    for range in line_breaks {
+        if !edit.invalidates_offset(range.start()) {
+            edit.delete(range);
+        }
+    }
+    Some(())
+}
+
+/// The receiver of a link of a method chain, which is followed by a `.`.
+fn chain_receiver(expr: &ast::Expr) -> Option<ast::Expr> {
+    match expr {
+        ast::Expr::MethodCallExpr(it) => it.receiver(),
+        ast::Expr::FieldExpr(it) => it.expr(),
+        ast::Expr::AwaitExpr(it) => it.expr(),
+        _ => None,
+    }
+}
+
+/// Merges consecutive `if let`s with the same scrutinee and body into one with an or-pattern.
+///
+/// This is only done if the bodies would run once at most without merging: the scrutinee is a
+/// path which the bodies don't use, and the patterns don't overlap. The patterns can't bind
+/// anything either, which would have to be bound by every alternative.
+fn merge_if_lets(
+    edit: &mut TextEditBuilder,
+    prev: &SyntaxElement,
+    next: &SyntaxElement,
+) -> Option<()> {
+    let (prev_if, next_if) = (as_if_expr(prev)?, as_if_expr(next)?);
+    if prev_if.else_branch().is_some() || next_if.else_branch().is_some() {
+        return None;
+    }
+    let (ast::Expr::LetExpr(prev_let), ast::Expr::LetExpr(next_let)) =
+        (prev_if.condition()?, next_if.condition()?)
+    else {
+        return None;
+    };
+    let ast::Expr::PathExpr(scrutinee) = prev_let.expr()? else { return None };
+    let body = prev_if.then_branch()?;
+    if scrutinee.syntax().to_string() != next_let.expr()?.syntax().to_string()
+        || body.syntax().to_string() != next_if.then_branch()?.syntax().to_string()
+    {
+        return None;
+    }
+    let scrutinee_name = scrutinee.path()?.first_segment()?.name_ref()?;
+    if body
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .any(|it| it.text() == scrutinee_name.text())
+    {
+        return None;
+    }
+    let (prev_variants, next_variants) =
+        (pattern_variants(&prev_let.pat()?)?, pattern_variants(&next_let.pat()?)?);
+    if prev_variants.iter().any(|it| next_variants.contains(it)) {
+        return None;
+    }
+
+    cov_mark::hit!(merge_if_lets);
+    edit.insert(prev_let.pat()?.syntax().text_range().end(), format!(" | {}", next_let.pat()?));
+    edit.delete(TextRange::new(prev.text_range().end(), next.text_range().end()));
+    Some(())
+}
+
+/// The names of the variants or constants matched by the alternatives of `pat`, which must not
+/// bind anything.
+///
+/// Alternatives with different names can't overlap, as they match the same scrutinee.
+fn pattern_variants(pat: &ast::Pat) -> Option<Vec<String>> {
+    let alternatives = match pat {
+        ast::Pat::OrPat(it) => it.pats().collect(),
+        _ => vec![pat.clone()],
+    };
+    let binds = |pat: &ast::Pat| {
+        pat.syntax().descendants().filter_map(ast::IdentPat::cast).any(|it| !is_constant_like(&it))
+    };
+    alternatives
+        .iter()
+        .map(|alternative| {
+            if binds(alternative) {
+                return None;
+            }
+            let path = match alternative {
+                ast::Pat::PathPat(it) => it.path(),
+                ast::Pat::TupleStructPat(it) => it.path(),
+                ast::Pat::RecordPat(it) => it.path(),
+                ast::Pat::IdentPat(it) => return Some(it.name()?.to_string()),
+                _ => None,
+            };
+            Some(path?.segment()?.name_ref()?.to_string())
+        })
+        .collect()
+}
+
+/// Whether `pat` is a unit variant or a constant rather than a binding, which can only be told
+/// apart by the naming convention without name resolution.
+fn is_constant_like(pat: &ast::IdentPat) -> bool {
+    pat.ref_token().is_none()
+        && pat.mut_token().is_none()
+        && pat.pat().is_none()
+        && pat.name().map_or(false, |it| it.text().starts_with(char::is_uppercase))
+}
+
 fn as_if_expr(element: &SyntaxElement) -> Option<ast::IfExpr> {
     let mut node = element.as_node()?.clone();
     if let Some(stmt) = ast::ExprStmt::cast(node.clone()) {
@@ -308,19 +457,29 @@ mod tests {
 
     use super::*;
 
+    const TEST_CONFIG: JoinLinesConfig = JoinLinesConfig {
+        join_else_if: true,
+        remove_trailing_comma: true,
+        unwrap_trivial_blocks: true,
+        join_assignments: true,
+        join_method_chains: true,
+        merge_if_let_patterns: true,
+    };
+
     fn check_join_lines(ra_fixture_before: &str, ra_fixture_after: &str) {
-        let config = JoinLinesConfig {
-            join_else_if: true,
-            remove_trailing_comma: true,
-            unwrap_trivial_blocks: true,
-            join_assignments: true,
-        };
+        check_join_lines_with_config(&TEST_CONFIG, ra_fixture_before, ra_fixture_after)
+    }
 
+    fn check_join_lines_with_config(
+        config: &JoinLinesConfig,
+        ra_fixture_before: &str,
+        ra_fixture_after: &str,
+    ) {
         let (before_cursor_pos, before) = extract_offset(ra_fixture_before);
         let file = SourceFile::parse(&before).ok().unwrap();
 
         let range = TextRange::empty(before_cursor_pos);
-        let result = join_lines(&config, &file, range);
+        let result = join_lines(config, &file, range);
 
         let actual = {
             let mut actual = before;
@@ -335,16 +494,9 @@ fn check_join_lines(ra_fixture_before: &str, ra_fixture_after: &str) {
     }
 
     fn check_join_lines_sel(ra_fixture_before: &str, ra_fixture_after: &str) {
-        let config = JoinLinesConfig {
-            join_else_if: true,
-            remove_trailing_comma: true,
-            unwrap_trivial_blocks: true,
-            join_assignments: true,
-        };
-
         let (sel, before) = extract_range(ra_fixture_before);
         let parse = SourceFile::parse(&before);
-        let result = join_lines(&config, &parse.tree(), sel);
+        let result = join_lines(&TEST_CONFIG, &parse.tree(), sel);
         let actual = {
             let mut actual = before;
             result.apply(&mut actual);
@@ -1004,6 +1156,168 @@ fn main() {
         );
     }
 
+    #[test]
+    fn merge_if_lets_with_same_body() {
+        cov_mark::check!(merge_if_lets);
+        check_join_lines(
+            r#"
+fn main() {
+    if let E::A = e {
+        foo();
+    }$0
+    if let E::B | E::C = e {
+        foo();
+    }
+}
+"#,
+            r#"
+fn main() {
+    if let E::A | E::B | E::C = e {
+        foo();
+    }$0
+}
+"#,
+        );
+        check_join_lines(
+            r#"
+fn main() {
+    if let E::A = e {
+        foo();
+    }$0
+    if let E::B = e {
+        bar();
+    }
+}
+"#,
+            r#"
+fn main() {
+    if let E::A = e {
+        foo();
+    }$0 else if let E::B = e {
+        bar();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn dont_merge_if_lets_which_could_run_twice() {
+        let else_if = |before: &str| {
+            let after = before.replacen("}$0\n    if", "}$0 else if", 1);
+            check_join_lines(before, &after);
+        };
+        // The patterns bind `x`.
+        else_if(
+            r#"
+fn main() {
+    if let Some(x) = e {
+        foo();
+    }$0
+    if let Ok(x) = e {
+        foo();
+    }
+}
+"#,
+        );
+        // The patterns overlap.
+        else_if(
+            r#"
+fn main() {
+    if let Some(_) = e {
+        foo();
+    }$0
+    if let Option::Some(1) = e {
+        foo();
+    }
+}
+"#,
+        );
+        // The scrutinee isn't a path.
+        else_if(
+            r#"
+fn main() {
+    if let E::A = next() {
+        foo();
+    }$0
+    if let E::B = next() {
+        foo();
+    }
+}
+"#,
+        );
+        // The body can change the scrutinee.
+        else_if(
+            r#"
+fn main() {
+    if let E::A = e {
+        e = E::B;
+    }$0
+    if let E::B = e {
+        e = E::B;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_method_chains() {
+        cov_mark::check!(join_method_chain);
+        check_join_lines(
+            r#"
+fn main() {
+    foo()$0
+        .bar()
+        // baz
+        .baz
+        .await;
+}
+"#,
+            r#"
+fn main() {
+    foo()$0.bar()
+        // baz
+        .baz.await;
+}
+"#,
+        );
+        check_join_lines(
+            r#"
+fn main() {
+    foo(x
+        .y)$0
+        .bar(|| a
+            .b())
+        .baz();
+}
+"#,
+            r#"
+fn main() {
+    foo(x
+        .y)$0.bar(|| a
+            .b()).baz();
+}
+"#,
+        );
+        check_join_lines_with_config(
+            &JoinLinesConfig { join_method_chains: false, ..TEST_CONFIG },
+            r#"
+fn main() {
+    foo()$0
+        .bar()
+        .baz();
+}
+"#,
+            r#"
+fn main() {
+    foo()$0.bar()
+        .baz();
+}
+"#,
+        );
+    }
+
     #[test]
     fn join_two_ifs_with_existing_else() {
         cov_mark::check!(join_two_ifs_with_existing_else);
@@ -26,7 +26,9 @@ macro_rules! eprintln {
 mod annotations;
 mod call_hierarchy;
 mod signature_help;
+mod dead_code;
 mod doc_links;
+mod evaluate_expression;
 mod highlight_related;
 mod expand_macro;
 mod extend_selection;
@@ -39,11 +41,13 @@ macro_rules! eprintln {
 mod hover;
 mod inlay_hints;
 mod join_lines;
+mod macro_expansion_costs;
 mod markdown_remove;
 mod matching_brace;
 mod moniker;
 mod move_item;
 mod parent_module;
+mod peek_related;
 mod references;
 mod rename;
 mod runnables;
@@ -52,21 +56,25 @@ macro_rules! eprintln {
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod test_explorer;
+mod type_hierarchy;
 mod typing;
 mod view_crate_graph;
 mod view_hir;
 mod view_item_tree;
+mod view_module_tree;
 mod shuffle_crate_graph;
 
-use std::sync::Arc;
+use std::{ffi::OsStr, sync::Arc};
 
 use cfg::CfgOptions;
 use ide_db::{
     base_db::{
-        salsa::{self, ParallelDatabase},
+        salsa::{self, Database, ParallelDatabase},
         CrateOrigin, Env, FileLoader, FileSet, SourceDatabase, VfsPath,
     },
-    symbol_index, LineIndexDatabase,
+    helpers::is_analyzed,
+    symbol_index, FxHashMap, LineIndexDatabase,
 };
 use syntax::SourceFile;
 
@@ -75,7 +83,10 @@ macro_rules! eprintln {
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind, AnnotationLocation},
     call_hierarchy::CallItem,
-    expand_macro::ExpandedMacro,
+    dead_code::{DeadCodeCrate, DeadCodeItem},
+    doc_links::DocumentationLinks,
+    evaluate_expression::ExpressionEvaluation,
+    expand_macro::{ExpandMacroConfig, ExpandedMacro},
     file_structure::{StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
     highlight_related::{HighlightRelatedConfig, HighlightedRange},
@@ -90,6 +101,7 @@ macro_rules! eprintln {
     moniker::{MonikerDescriptorKind, MonikerKind, MonikerResult, PackageInformation},
     move_item::Direction,
     navigation_target::NavigationTarget,
+    peek_related::{PeekRelated, PeekRelatedConfig},
     prime_caches::ParallelPrimeCachesProgress,
     references::ReferenceSearchResult,
     rename::RenameError,
@@ -98,12 +110,15 @@ macro_rules! eprintln {
     static_index::{StaticIndex, StaticIndexedFile, TokenId, TokenStaticData},
     syntax_highlighting::{
         tags::{Highlight, HlMod, HlMods, HlOperator, HlPunct, HlTag},
-        HighlightConfig, HlRange,
+        HighlightConfig, HighlightExportConfig, HighlightExportFormat, HighlightTheme, HlRange,
+        InjectedLanguage, StringInjection,
     },
+    test_explorer::{FileTests, TestItem, TestItemKind},
+    view_crate_graph::ViewCrateGraphConfig,
 };
 pub use hir::{Documentation, Semantics};
 pub use ide_assists::{
-    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
+    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, AsyncRuntime, SingleResolve,
 };
 pub use ide_completion::{
     CallableSnippets, CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance,
@@ -118,10 +133,12 @@ macro_rules! eprintln {
     line_index::{LineCol, LineIndex},
     search::{ReferenceCategory, SearchScope},
     source_change::{FileSystemEdit, SourceChange},
-    symbol_index::Query,
+    symbol_index::{Query, QueryOperators},
     RootDatabase, SymbolKind,
 };
-pub use ide_diagnostics::{Diagnostic, DiagnosticsConfig, ExprFillDefaultMode, Severity};
+pub use ide_diagnostics::{
+    Diagnostic, DiagnosticsConfig, ExprFillDefaultMode, OverlyPublicItemsConfig, Severity,
+};
 pub use ide_ssr::SsrError;
 pub use syntax::{TextRange, TextSize};
 pub use text_edit::{Indel, TextEdit};
@@ -156,6 +173,19 @@ pub fn update_lru_capacity(&mut self, lru_capacity: Option<usize>) {
         self.db.update_lru_capacity(lru_capacity);
     }
 
+    pub fn update_lru_capacities(
+        &mut self,
+        lru_capacity: Option<usize>,
+        lru_capacities: &FxHashMap<Box<str>, usize>,
+    ) -> Vec<Box<str>> {
+        self.db.update_lru_capacities(lru_capacity, lru_capacities)
+    }
+
+    /// Frees the memory of the expensive caches, which are recomputed on demand.
+    pub fn evict_lru_queries(&mut self) {
+        self.db.evict_lru_queries();
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -245,6 +275,12 @@ pub fn from_single_file(text: String) -> (Analysis, FileId) {
         (host.analysis(), file_id)
     }
 
+    /// Returns `Err(Cancelled)` once a change is pending, so that long-running work which doesn't
+    /// query the database can stop and release this snapshot.
+    pub fn check_cancelled(&self) -> Cancellable<()> {
+        self.with_db(|db| db.unwind_if_cancelled())
+    }
+
     /// Debug info about the current state of the analysis.
     pub fn status(&self, file_id: Option<FileId>) -> Cancellable<String> {
         self.with_db(|db| status::status(&*db, file_id))
@@ -312,13 +348,43 @@ pub fn view_item_tree(&self, file_id: FileId) -> Cancellable<String> {
         self.with_db(|db| view_item_tree::view_item_tree(db, file_id))
     }
 
+    /// Lists the items of the workspace crates that aren't referred to in the workspace.
+    pub fn dead_code(&self) -> Cancellable<Vec<DeadCodeCrate>> {
+        self.with_db(dead_code::dead_code)
+    }
+
+    /// Computes how to evaluate the expression covering the range.
+    pub fn evaluate_expression(
+        &self,
+        frange: FileRange,
+    ) -> Cancellable<Option<ExpressionEvaluation>> {
+        self.with_db(|db| evaluate_expression::evaluate_expression(db, frange))
+    }
+
+    /// Lists the macro calls of the file by how expensive their expansion is.
+    pub fn macro_expansion_costs(&self, file_id: FileId) -> Cancellable<String> {
+        self.with_db(|db| macro_expansion_costs::macro_expansion_costs(db, file_id))
+    }
+
     /// Renders the crate graph to GraphViz "dot" syntax.
-    pub fn view_crate_graph(&self, full: bool) -> Cancellable<Result<String, String>> {
-        self.with_db(|db| view_crate_graph::view_crate_graph(db, full))
+    pub fn view_crate_graph(
+        &self,
+        config: ViewCrateGraphConfig,
+    ) -> Cancellable<Result<String, String>> {
+        self.with_db(|db| view_crate_graph::view_crate_graph(db, config))
     }
 
-    pub fn expand_macro(&self, position: FilePosition) -> Cancellable<Option<ExpandedMacro>> {
-        self.with_db(|db| expand_macro::expand_macro(db, position))
+    /// Renders the module tree of the crate containing the file to GraphViz "dot" syntax.
+    pub fn view_module_tree(&self, file_id: FileId) -> Cancellable<Result<String, String>> {
+        self.with_db(|db| view_module_tree::view_module_tree(db, file_id))
+    }
+
+    pub fn expand_macro(
+        &self,
+        config: &ExpandMacroConfig,
+        position: FilePosition,
+    ) -> Cancellable<Option<ExpandedMacro>> {
+        self.with_db(|db| expand_macro::expand_macro(db, config, position))
     }
 
     /// Returns an edit to remove all newlines in the range, cleaning up minor
@@ -371,7 +437,12 @@ pub fn inlay_hints(
         file_id: FileId,
         range: Option<TextRange>,
     ) -> Cancellable<Vec<InlayHint>> {
-        self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config))
+        self.with_db(|db| {
+            if !is_analyzed(db, file_id) {
+                return Vec::new();
+            }
+            inlay_hints::inlay_hints(db, file_id, range, config)
+        })
     }
 
     /// Returns the set of folding ranges.
@@ -427,7 +498,13 @@ pub fn find_all_refs(
         position: FilePosition,
         search_scope: Option<SearchScope>,
     ) -> Cancellable<Option<Vec<ReferenceSearchResult>>> {
-        self.with_db(|db| references::find_all_refs(&Semantics::new(db), position, search_scope))
+        self.with_db(|db| {
+            let mut res = references::find_all_refs(&Semantics::new(db), position, search_scope)?;
+            for it in &mut res {
+                it.references.retain(|&file_id, _| is_analyzed(db, file_id));
+            }
+            Some(res)
+        })
     }
 
     /// Returns a short text describing element at position.
@@ -448,11 +525,16 @@ pub fn moniker(
     }
 
     /// Return URL(s) for the documentation of the symbol under the cursor.
+    ///
+    /// The local URL points into the `doc` folder of `target_dir`, or into the docs of `sysroot`
+    /// for the standard library.
     pub fn external_docs(
         &self,
         position: FilePosition,
-    ) -> Cancellable<Option<doc_links::DocumentationLink>> {
-        self.with_db(|db| doc_links::external_docs(db, &position))
+        target_dir: Option<&OsStr>,
+        sysroot: Option<&OsStr>,
+    ) -> Cancellable<Option<DocumentationLinks>> {
+        self.with_db(|db| doc_links::external_docs(db, &position, target_dir, sysroot))
     }
 
     /// Computes parameter information at the given position.
@@ -478,6 +560,34 @@ pub fn outgoing_calls(&self, position: FilePosition) -> Cancellable<Option<Vec<C
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes type hierarchy candidates for the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
+    /// Computes the direct supertypes of the type at the given file position.
+    pub fn supertypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, position))
+    }
+
+    /// Computes the direct subtypes of the type at the given file position.
+    pub fn subtypes(&self, position: FilePosition) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, position))
+    }
+
+    /// Returns the definition, trait declaration, implementations and references of the symbol
+    /// at `position` at once.
+    pub fn peek_related(
+        &self,
+        config: &PeekRelatedConfig,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<PeekRelated>>> {
+        self.with_db(|db| peek_related::peek_related(db, config, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancellable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -522,13 +632,36 @@ pub fn related_tests(
         self.with_db(|db| runnables::related_tests(db, position, search_scope))
     }
 
+    /// Returns the crates of the workspace, as the roots of the test tree.
+    pub fn discover_test_roots(&self) -> Cancellable<Vec<TestItem>> {
+        self.with_db(test_explorer::discover_test_roots)
+    }
+
+    /// Returns the tests of the workspace crate with the given test id.
+    pub fn discover_tests_in_crate_by_test_id(
+        &self,
+        crate_test_id: &str,
+    ) -> Cancellable<Vec<TestItem>> {
+        self.with_db(|db| test_explorer::discover_tests_in_crate_by_test_id(db, crate_test_id))
+    }
+
+    /// Returns the tests of the given workspace crate.
+    pub fn discover_tests_in_crate(&self, crate_id: CrateId) -> Cancellable<Vec<TestItem>> {
+        self.with_db(|db| test_explorer::discover_tests_in_crate(db, crate_id))
+    }
+
+    /// Returns the tests declared in the given file, along with their parents.
+    pub fn discover_tests_in_file(&self, file_id: FileId) -> Cancellable<FileTests> {
+        self.with_db(|db| test_explorer::discover_tests_in_file(db, file_id))
+    }
+
     /// Computes syntax highlighting for the given file
     pub fn highlight(
         &self,
         highlight_config: HighlightConfig,
         file_id: FileId,
     ) -> Cancellable<Vec<HlRange>> {
-        self.with_db(|db| syntax_highlighting::highlight(db, highlight_config, file_id, None))
+        self.with_db(|db| syntax_highlighting::highlight(db, &highlight_config, file_id, None))
     }
 
     /// Computes all ranges to highlight for a given item in a file.
@@ -538,6 +671,9 @@ pub fn highlight_related(
         position: FilePosition,
     ) -> Cancellable<Option<Vec<HighlightedRange>>> {
         self.with_db(|db| {
+            if !is_analyzed(db, position.file_id) {
+                return None;
+            }
             highlight_related::highlight_related(&Semantics::new(db), config, position)
         })
     }
@@ -549,7 +685,12 @@ pub fn highlight_range(
         frange: FileRange,
     ) -> Cancellable<Vec<HlRange>> {
         self.with_db(|db| {
-            syntax_highlighting::highlight(db, highlight_config, frange.file_id, Some(frange.range))
+            syntax_highlighting::highlight(
+                db,
+                &highlight_config,
+                frange.file_id,
+                Some(frange.range),
+            )
         })
     }
 
@@ -558,6 +699,17 @@ pub fn highlight_as_html(&self, file_id: FileId, rainbow: bool) -> Cancellable<S
         self.with_db(|db| syntax_highlighting::highlight_as_html(db, file_id, rainbow))
     }
 
+    /// Renders the given file, or a range of it, as HTML or Markdown with the
+    /// highlighting inlined as styles.
+    pub fn export_highlighted(
+        &self,
+        file_id: FileId,
+        range: Option<TextRange>,
+        config: &HighlightExportConfig,
+    ) -> Cancellable<String> {
+        self.with_db(|db| syntax_highlighting::export_highlighted(db, file_id, range, config))
+    }
+
     /// Computes completions at the given position.
     pub fn completions(
         &self,
@@ -589,7 +741,12 @@ pub fn diagnostics(
         resolve: AssistResolveStrategy,
         file_id: FileId,
     ) -> Cancellable<Vec<Diagnostic>> {
-        self.with_db(|db| ide_diagnostics::diagnostics(db, config, &resolve, file_id))
+        self.with_db(|db| {
+            if !is_analyzed(db, file_id) {
+                return Vec::new();
+            }
+            ide_diagnostics::diagnostics(db, config, &resolve, file_id)
+        })
     }
 
     /// Convenience function to return assists + quick fixes for diagnostics
@@ -606,7 +763,7 @@ pub fn assists_with_fixes(
         };
 
         self.with_db(|db| {
-            let diagnostic_assists = if include_fixes {
+            let diagnostic_assists = if include_fixes && is_analyzed(db, frange.file_id) {
                 ide_diagnostics::diagnostics(db, diagnostics_config, &resolve, frange.file_id)
                     .into_iter()
                     .flat_map(|it| it.fixes.unwrap_or_default())
@@ -633,7 +790,7 @@ pub fn rename(
         position: FilePosition,
         new_name: &str,
     ) -> Cancellable<Result<SourceChange, RenameError>> {
-        self.with_db(|db| rename::rename(db, position, new_name))
+        self.with_db(|db| rename::rename(db, position, new_name).map(|it| retain_analyzed(db, it)))
     }
 
     pub fn prepare_rename(
@@ -648,7 +805,9 @@ pub fn will_rename_file(
         file_id: FileId,
         new_name_stem: &str,
     ) -> Cancellable<Option<SourceChange>> {
-        self.with_db(|db| rename::will_rename_file(db, file_id, new_name_stem))
+        self.with_db(|db| {
+            rename::will_rename_file(db, file_id, new_name_stem).map(|it| retain_analyzed(db, it))
+        })
     }
 
     pub fn structural_search_replace(
@@ -664,7 +823,7 @@ pub fn structural_search_replace(
                 ide_ssr::MatchFinder::in_context(db, resolve_context, selections)?;
             match_finder.add_rule(rule)?;
             let edits = if parse_only { Default::default() } else { match_finder.edits() };
-            Ok(SourceChange::from(edits))
+            Ok(retain_analyzed(db, SourceChange::from(edits)))
         })
     }
 
@@ -673,7 +832,12 @@ pub fn annotations(
         config: &AnnotationConfig,
         file_id: FileId,
     ) -> Cancellable<Vec<Annotation>> {
-        self.with_db(|db| annotations::annotations(db, config, file_id))
+        self.with_db(|db| {
+            if !is_analyzed(db, file_id) {
+                return Vec::new();
+            }
+            annotations::annotations(db, config, file_id)
+        })
     }
 
     pub fn resolve_annotation(&self, annotation: Annotation) -> Cancellable<Annotation> {
@@ -709,8 +873,41 @@ fn with_db<F, T>(&self, f: F) -> Cancellable<T>
     }
 }
 
+/// Drops the edits of files excluded from analysis by the [`AnalysisPolicy`] of their source root.
+///
+/// [`AnalysisPolicy`]: ide_db::base_db::AnalysisPolicy
+fn retain_analyzed(db: &RootDatabase, mut change: SourceChange) -> SourceChange {
+    change.source_file_edits.retain(|&file_id, _| is_analyzed(db, file_id));
+    change
+}
+
 #[test]
 fn analysis_is_send() {
     fn is_send<T: Send>() {}
     is_send::<Analysis>();
 }
+
+#[test]
+fn excluded_files_are_not_analyzed() {
+    use ide_db::base_db::{
+        fixture::ChangeFixture, salsa::Durability, AnalysisPolicy, SourceDatabaseExt,
+    };
+
+    let mut host = AnalysisHost::default();
+    let change_fixture = ChangeFixture::parse("mod foo;");
+    host.raw_database_mut().apply_change(change_fixture.change);
+    let file_id = change_fixture.files[0];
+    let config = DiagnosticsConfig::test_sample();
+    let diagnostics = |analysis: Analysis| {
+        analysis.diagnostics(&config, AssistResolveStrategy::All, file_id).unwrap()
+    };
+    assert!(!diagnostics(host.analysis()).is_empty());
+
+    let db = host.raw_database_mut();
+    let source_root_id = db.file_source_root(file_id);
+    let source_root = (*db.source_root(source_root_id))
+        .clone()
+        .with_analysis_policy(AnalysisPolicy::excluding([file_id]));
+    db.set_source_root_with_durability(source_root_id, Arc::new(source_root), Durability::LOW);
+    assert!(diagnostics(host.analysis()).is_empty());
+}
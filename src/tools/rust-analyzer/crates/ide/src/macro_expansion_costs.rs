@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use hir::Semantics;
+use ide_db::{
+    base_db::{FileId, SourceDatabase},
+    RootDatabase,
+};
+use stdx::format_to;
+use syntax::{ast, match_ast, AstNode};
+
+// Feature: Macro Expansion Costs
+//
+// Expands every macro call of the currently open file again, bypassing any cached expansion, and
+// lists them by the time their expansion took, together with the number of tokens they produced.
+// This includes attribute macros and derives. It helps to find the macro calls that make analysis
+// slow or hit the expansion limits.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: Show Macro Expansion Costs**
+// |===
+pub(crate) fn macro_expansion_costs(db: &RootDatabase, file_id: FileId) -> String {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(file_id);
+    let line_index = db.line_index(file_id);
+
+    let mut costs = Vec::new();
+    for node in source_file.syntax().descendants() {
+        let line = line_index.line_col(node.text_range().start()).line + 1;
+        match_ast! {
+            match node {
+                ast::MacroCall(it) => costs.extend(fn_like_cost(&sema, &it, line)),
+                ast::Item(it) => costs.extend(attr_cost(&sema, &it, line)),
+                ast::Attr(it) => costs.extend(derive_costs(&sema, &it, line)),
+                _ => (),
+            }
+        }
+    }
+    if costs.is_empty() {
+        return "No macro calls in this file".to_owned();
+    }
+    costs.sort_by(|(lhs, ..), (rhs, ..)| rhs.cmp(lhs));
+
+    let mut buf = String::new();
+    format_to!(buf, "{:>12} {:>10}  {}\n", "time", "tokens", "macro call");
+    for (time, tokens, line, name) in costs {
+        format_to!(buf, "{:>12} {:>10}  {} (line {})\n", format!("{time:.2?}"), tokens, name, line);
+    }
+    buf
+}
+
+/// The time an expansion took, the number of tokens it produced, the line of the call and the
+/// label of the macro.
+type Cost = (Duration, usize, u32, String);
+
+fn fn_like_cost(
+    sema: &Semantics<'_, RootDatabase>,
+    call: &ast::MacroCall,
+    line: u32,
+) -> Option<Cost> {
+    let (time, tokens) = sema.measure_macro_expansion(call)?;
+    let name = call.path().map_or_else(|| "?".to_owned(), |path| path.to_string());
+    Some((time, tokens, line, format!("{name}!")))
+}
+
+fn attr_cost(sema: &Semantics<'_, RootDatabase>, item: &ast::Item, line: u32) -> Option<Cost> {
+    let (time, tokens) = sema.measure_attr_macro_expansion(item)?;
+    let name = macro_name(sema, sema.resolve_attr_macro_call(item));
+    Some((time, tokens, line, format!("#[{name}]")))
+}
+
+fn derive_costs(sema: &Semantics<'_, RootDatabase>, attr: &ast::Attr, line: u32) -> Vec<Cost> {
+    let Some(derives) = sema.measure_derive_macro_expansions(attr) else { return Vec::new() };
+    let macros = sema.resolve_derive_macro(attr).unwrap_or_default();
+    derives
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, cost)| {
+            let (time, tokens) = cost?;
+            let name = macro_name(sema, macros.get(idx).copied().flatten());
+            Some((time, tokens, line, format!("#[derive({name})]")))
+        })
+        .collect()
+}
+
+fn macro_name(sema: &Semantics<'_, RootDatabase>, mac: Option<hir::Macro>) -> String {
+    mac.map_or_else(|| "?".to_owned(), |it| it.name(sema.db).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[test]
+    fn lists_macro_calls_by_cost() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+macro_rules! m {
+    ($($t:tt)*) => { $($t)* };
+}
+m!(struct S;);
+m!(fn f() {});
+"#,
+        );
+        let costs = analysis.macro_expansion_costs(file_id).unwrap();
+        assert_eq!(costs.lines().count(), 3);
+        assert!(costs.contains("m! (line 4)"));
+        assert!(costs.contains("m! (line 5)"));
+    }
+
+    #[test]
+    fn lists_attribute_macros_and_derives() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- proc_macros: identity
+//- minicore: derive, clone
+#[proc_macros::identity]
+fn f() {}
+
+#[derive(Clone)]
+struct S;
+"#,
+        );
+        let costs = analysis.macro_expansion_costs(file_id).unwrap();
+        assert_eq!(costs.lines().count(), 3);
+        assert!(costs.contains("#[identity] (line 1)"));
+        assert!(costs.contains("#[derive(Clone)] (line 4)"));
+    }
+
+    #[test]
+    fn no_macro_calls() {
+        let (analysis, file_id) = fixture::file("fn f() {}");
+        let costs = analysis.macro_expansion_costs(file_id).unwrap();
+        assert_eq!(costs, "No macro calls in this file");
+    }
+}
@@ -290,7 +290,7 @@ fn to_nav(&self, db: &RootDatabase) -> NavigationTarget {
 impl TryToNav for hir::Impl {
     fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
         let InFile { file_id, value } = self.source(db)?;
-        let derive_attr = self.is_builtin_derive(db);
+        let derive_attr = self.derive_attr(db);
 
         let focus_range = if derive_attr.is_some() {
             None
@@ -620,4 +620,19 @@ fn foo() {}
         let navs = analysis.symbol_search(Query::new("foo".to_string())).unwrap();
         assert_eq!(navs.len(), 2)
     }
+
+    #[test]
+    fn test_world_symbols_exact_query() {
+        let (analysis, _) = fixture::file(
+            r#"
+fn foo() {}
+struct Foo;
+struct FooBar;
+"#,
+        );
+
+        let (query, _) = Query::parse("\"Foo\"");
+        let navs = analysis.symbol_search(query).unwrap();
+        assert_eq!(navs.iter().map(|nav| nav.name.as_str()).collect::<Vec<_>>(), ["Foo"]);
+    }
 }
@@ -0,0 +1,194 @@
+//! Entry point for peeking at everything related to a symbol at once.
+
+use hir::Semantics;
+use ide_db::{base_db::FileRange, RootDatabase};
+use itertools::Itertools;
+
+use crate::{
+    goto_declaration, goto_definition, goto_implementation, references, FilePosition,
+    NavigationTarget, RangeInfo,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeekRelatedConfig {
+    /// The maximum number of implementations to return.
+    pub implementations_limit: usize,
+    /// The maximum number of references to return.
+    pub references_limit: usize,
+}
+
+#[derive(Debug)]
+pub struct PeekRelated {
+    pub definitions: Vec<NavigationTarget>,
+    /// The items of the trait declaration, if the definitions are items of a trait impl.
+    pub declarations: Vec<NavigationTarget>,
+    pub implementations: Vec<NavigationTarget>,
+    /// The number of implementations before applying the limit.
+    pub implementations_total: usize,
+    pub references: Vec<FileRange>,
+    /// The number of references before applying the limit.
+    pub references_total: usize,
+}
+
+// Feature: Peek Related
+//
+// Collects the definition, the trait declaration, the implementations and the references of the
+// symbol at the cursor in a single request, so that clients can show all of them in one peek
+// view. The implementations and references are each capped at a configurable limit.
+pub(crate) fn peek_related(
+    db: &RootDatabase,
+    config: &PeekRelatedConfig,
+    position: FilePosition,
+) -> Option<RangeInfo<PeekRelated>> {
+    let RangeInfo { range, info: definitions } = goto_definition::goto_definition(db, position)?;
+    let definitions: Vec<_> = definitions.into_iter().unique().collect();
+    let related = |navs: Option<RangeInfo<Vec<NavigationTarget>>>| -> Vec<_> {
+        navs.into_iter()
+            .flat_map(|it| it.info)
+            .unique()
+            .filter(|nav| !definitions.contains(nav))
+            .collect()
+    };
+
+    // `goto_declaration` falls back to the definitions if there is no declaration in a trait.
+    let declarations = related(goto_declaration::goto_declaration(db, position));
+    let mut implementations = related(goto_implementation::goto_implementation(db, position));
+    let implementations_total = implementations.len();
+    implementations.truncate(config.implementations_limit);
+
+    let sema = Semantics::new(db);
+    let mut references: Vec<_> = references::find_all_refs(&sema, position, None)
+        .into_iter()
+        .flatten()
+        .flat_map(|result| result.references)
+        .flat_map(|(file_id, refs)| {
+            refs.into_iter().map(move |(range, _)| FileRange { file_id, range })
+        })
+        .filter(|frange| {
+            !definitions
+                .iter()
+                .any(|nav| nav.file_id == frange.file_id && nav.focus_range == Some(frange.range))
+        })
+        .sorted_by_key(|frange| (frange.file_id, frange.range.start()))
+        .dedup()
+        .collect();
+    let references_total = references.len();
+    references.truncate(config.references_limit);
+
+    Some(RangeInfo::new(
+        range,
+        PeekRelated {
+            definitions,
+            declarations,
+            implementations,
+            implementations_total,
+            references,
+            references_total,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{fixture, NavigationTarget, PeekRelatedConfig};
+
+    #[track_caller]
+    fn check(ra_fixture: &str, expect: Expect) {
+        check_with_config(
+            PeekRelatedConfig { implementations_limit: 10, references_limit: 10 },
+            ra_fixture,
+            expect,
+        );
+    }
+
+    #[track_caller]
+    fn check_with_config(config: PeekRelatedConfig, ra_fixture: &str, expect: Expect) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+        let related = analysis.peek_related(&config, pos).unwrap().unwrap().info;
+
+        let mut actual = String::new();
+        let mut render = |title: &str, lines: Vec<String>| {
+            actual += &format!("{title}:\n");
+            for line in lines {
+                actual += &format!("    {line}\n");
+            }
+        };
+        let render_navs =
+            |navs: &[NavigationTarget]| navs.iter().map(|it| it.debug_render()).collect();
+        render("definitions", render_navs(&related.definitions));
+        render("declarations", render_navs(&related.declarations));
+        render(
+            &format!("implementations ({} total)", related.implementations_total),
+            render_navs(&related.implementations),
+        );
+        render(
+            &format!("references ({} total)", related.references_total),
+            related
+                .references
+                .iter()
+                .map(|it| format!("{:?} {:?}", it.file_id, it.range))
+                .collect(),
+        );
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn test_peek_related_trait_impl_method() {
+        check(
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+fn f(s: Square, c: Circle) {
+    s.ar$0ea();
+    s.area();
+    c.area();
+}
+"#,
+            expect![[r#"
+                definitions:
+                    area Function FileId(0) 86..113 89..93
+                declarations:
+                    area Function FileId(0) 18..40 21..25
+                implementations (1 total):
+                    area Function FileId(0) 159..186 162..166
+                references (2 total):
+                    FileId(0) 224..228
+                    FileId(0) 238..242
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_peek_related_limits() {
+        check_with_config(
+            PeekRelatedConfig { implementations_limit: 1, references_limit: 1 },
+            r#"
+trait Sha$0pe {}
+struct Square;
+impl Shape for Square {}
+struct Circle;
+impl Shape for Circle {}
+"#,
+            expect![[r#"
+                definitions:
+                    Shape Trait FileId(0) 0..14 6..11
+                declarations:
+                implementations (2 total):
+                    impl Impl FileId(0) 30..54 45..51
+                references (2 total):
+                    FileId(0) 35..40
+            "#]],
+        );
+    }
+}
@@ -12,6 +12,7 @@
         salsa::{Database, ParallelDatabase, Snapshot},
         Cancelled, CrateGraph, CrateId, SourceDatabase, SourceDatabaseExt,
     },
+    symbol_index::SymbolsDatabase,
     FxIndexMap,
 };
 use stdx::hash::NoHashHashSet;
@@ -37,8 +38,9 @@ pub(crate) fn parallel_prime_caches(
     let _p = profile::span("prime_caches");
 
     let graph = db.crate_graph();
+    let (local_crates, dependencies) = compute_crates_to_prime(db, &graph);
     let mut crates_to_prime = {
-        let crate_ids = compute_crates_to_prime(db, &graph);
+        let crate_ids: NoHashHashSet<_> = local_crates.union(&dependencies).copied().collect();
 
         let mut builder = topologic_sort::TopologicalSortIter::builder();
 
@@ -65,13 +67,20 @@ enum ParallelPrimeCacheWorkerProgress {
         let (progress_sender, progress_receiver) = crossbeam_channel::unbounded();
         let (work_sender, work_receiver) = crossbeam_channel::unbounded();
         let prime_caches_worker = move |db: Snapshot<RootDatabase>| {
-            while let Ok((crate_id, crate_name)) = work_receiver.recv() {
+            while let Ok((crate_id, crate_name, is_local)) = work_receiver.recv() {
                 progress_sender
                     .send(ParallelPrimeCacheWorkerProgress::BeginCrate { crate_id, crate_name })?;
 
                 // This also computes the DefMap
                 db.import_map(crate_id);
 
+                // Workspace symbol search only looks at the modules of local crates
+                if is_local {
+                    for module in hir::Crate::from(crate_id).modules(&*db) {
+                        db.module_symbols(module);
+                    }
+                }
+
                 progress_sender.send(ParallelPrimeCacheWorkerProgress::EndCrate { crate_id })?;
             }
 
@@ -103,6 +112,7 @@ enum ParallelPrimeCacheWorkerProgress {
                 .send((
                     crate_id,
                     graph[crate_id].display_name.as_deref().unwrap_or_default().to_string(),
+                    local_crates.contains(&crate_id),
                 ))
                 .ok();
         }
@@ -142,18 +152,26 @@ enum ParallelPrimeCacheWorkerProgress {
     }
 }
 
-fn compute_crates_to_prime(db: &RootDatabase, graph: &CrateGraph) -> NoHashHashSet<CrateId> {
+/// Returns the workspace crates and their direct dependencies.
+fn compute_crates_to_prime(
+    db: &RootDatabase,
+    graph: &CrateGraph,
+) -> (NoHashHashSet<CrateId>, NoHashHashSet<CrateId>) {
     // We're only interested in the workspace crates and the `ImportMap`s of their direct
     // dependencies, though in practice the latter also compute the `DefMap`s.
     // We don't prime transitive dependencies because they're generally not visible in
     // the current workspace.
-    graph
+    let local_crates: NoHashHashSet<_> = graph
         .iter()
         .filter(|&id| {
             let file_id = graph[id].root_file_id;
             let root_id = db.file_source_root(file_id);
             !db.source_root(root_id).is_library
         })
-        .flat_map(|id| graph[id].dependencies.iter().map(|krate| krate.crate_id))
-        .collect()
+        .collect();
+    let dependencies = local_crates
+        .iter()
+        .flat_map(|&id| graph[id].dependencies.iter().map(|krate| krate.crate_id))
+        .collect();
+    (local_crates, dependencies)
 }
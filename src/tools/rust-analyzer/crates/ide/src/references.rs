@@ -291,11 +291,18 @@ fn is_lit_name_ref(name_ref: &ast::NameRef) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use expect_test::{expect, Expect};
-    use ide_db::{base_db::FileId, search::ReferenceCategory};
+    use ide_db::{
+        base_db::{
+            fixture::ChangeFixture, salsa::Durability, AnalysisPolicy, FileId, SourceDatabaseExt,
+        },
+        search::ReferenceCategory,
+    };
     use stdx::format_to;
 
-    use crate::{fixture, SearchScope};
+    use crate::{fixture, AnalysisHost, FilePosition, SearchScope};
 
     #[test]
     fn test_struct_literal_after_space() {
@@ -904,12 +911,64 @@ fn foo() {
             expect![[r#"
                 f Field FileId(0) 15..21 15..16
 
-                FileId(0) 55..56 Read
+                FileId(0) 55..56 Write
                 FileId(0) 68..69 Write
             "#]],
         );
     }
 
+    #[test]
+    fn test_basic_highlight_mut_borrow_write() {
+        check(
+            r#"
+struct S {
+    f: u32,
+}
+
+fn foo() {
+    let mut s$0 = S { f: 0 };
+    let r = &mut s;
+    let f = &mut s.f;
+    let s2 = &s;
+}
+"#,
+            expect![[r#"
+                s Local FileId(0) 45..50 49..50 Write
+
+                FileId(0) 82..83 Write
+                FileId(0) 102..103 Read
+                FileId(0) 121..122 Read
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_basic_highlight_field_init_write() {
+        check(
+            r#"
+struct S {
+    f$0: u32,
+}
+
+fn foo(f: u32) -> u32 {
+    let mut s = S { f };
+    s = S { f: s.f + 1 };
+    let r = &mut s.f;
+    s.f
+}
+"#,
+            expect![[r#"
+                f Field FileId(0) 15..21 15..16
+
+                FileId(0) 70..71 Read
+                FileId(0) 87..88 Write
+                FileId(0) 92..93 Read
+                FileId(0) 120..121 Write
+                FileId(0) 129..130 Read
+            "#]],
+        );
+    }
+
     #[test]
     fn test_basic_highlight_decl_no_write() {
         check(
@@ -1039,7 +1098,7 @@ fn f() -> m::En {
             expect![[r#"
                 field Field FileId(0) 56..65 56..61
 
-                FileId(0) 125..130 Read
+                FileId(0) 125..130 Write
             "#]],
         );
     }
@@ -1090,6 +1149,41 @@ fn foo(self$0) {
         );
     }
 
+    #[test]
+    fn test_find_all_refs_skips_excluded_files() {
+        let mut host = AnalysisHost::default();
+        let change_fixture = ChangeFixture::parse(
+            r#"
+//- /main.rs
+mod generated;
+fn foo$0() {}
+fn bar() { foo(); }
+//- /generated.rs
+fn baz() { super::foo(); }
+"#,
+        );
+        host.raw_database_mut().apply_change(change_fixture.change);
+        let (file_id, offset) = change_fixture.file_position.unwrap();
+        let position = FilePosition { file_id, offset: offset.expect_offset() };
+        let generated = change_fixture.files[1];
+
+        let db = host.raw_database_mut();
+        let source_root_id = db.file_source_root(generated);
+        let source_root = (*db.source_root(source_root_id))
+            .clone()
+            .with_analysis_policy(AnalysisPolicy::excluding([generated]));
+        db.set_source_root_with_durability(source_root_id, Arc::new(source_root), Durability::LOW);
+
+        let analysis = host.analysis();
+        let refs = analysis.find_all_refs(position, None).unwrap().unwrap();
+        assert!(refs.iter().any(|it| it.references.contains_key(&file_id)));
+        assert!(refs.iter().all(|it| !it.references.contains_key(&generated)));
+
+        let edit = analysis.rename(position, "quux").unwrap().unwrap();
+        assert!(edit.source_file_edits.contains_key(&file_id));
+        assert!(!edit.source_file_edits.contains_key(&generated));
+    }
+
     fn check(ra_fixture: &str, expect: Expect) {
         check_with_scope(ra_fixture, None, expect)
     }
@@ -1026,6 +1026,38 @@ fn test_rename_mod_in_dir() {
         );
     }
 
+    #[test]
+    fn test_rename_mod_with_path_attr() {
+        check_expect(
+            "foo2",
+            r#"
+//- /lib.rs
+#[path = "impl.rs"]
+mod fo$0o;
+//- /impl.rs
+// empty
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "foo2",
+                                    delete: 24..27,
+                                },
+                            ],
+                        },
+                    },
+                    file_system_edits: [],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
     #[test]
     fn test_rename_unusually_nested_mod() {
         check_expect(
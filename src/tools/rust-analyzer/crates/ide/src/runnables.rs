@@ -175,6 +175,11 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
                 add_opt(runnable, Some(assoc.into()))
             });
         }
+        if let Definition::Trait(trait_) = def {
+            trait_.items(db).into_iter().for_each(|assoc| {
+                add_opt(module_def_doctest(sema.db, assoc.into()), Some(assoc.into()))
+            });
+        }
     });
 
     sema.to_module_defs(file_id)
@@ -427,27 +432,39 @@ fn module_def_doctest(db: &RootDatabase, def: Definition) -> Option<Runnable> {
     }
     let def_name = def.name(db)?;
     let path = (|| {
+        // `#[macro_export]` macros are documented at the crate root, wherever they are defined.
+        if let Definition::Macro(it) = def {
+            if it.is_macro_export(db) {
+                return Some(def_name.to_string());
+            }
+        }
         let mut path = String::new();
         def.canonical_module_path(db)?
             .flat_map(|it| it.name(db))
             .for_each(|name| format_to!(path, "{}::", name));
         // This probably belongs to canonical_path?
         if let Some(assoc_item) = def.as_assoc_item(db) {
-            if let hir::AssocItemContainer::Impl(imp) = assoc_item.container(db) {
-                let ty = imp.self_ty(db);
-                if let Some(adt) = ty.as_adt() {
-                    let name = adt.name(db);
-                    let mut ty_args = ty.type_arguments().peekable();
-                    format_to!(path, "{}", name);
-                    if ty_args.peek().is_some() {
-                        format_to!(
-                            path,
-                            "<{}>",
-                            ty_args.format_with(",", |ty, cb| cb(&ty.display(db)))
-                        );
+            match assoc_item.container(db) {
+                hir::AssocItemContainer::Impl(imp) => {
+                    let ty = imp.self_ty(db);
+                    if let Some(adt) = ty.as_adt() {
+                        let name = adt.name(db);
+                        let mut ty_args = ty.type_arguments().peekable();
+                        format_to!(path, "{}", name);
+                        if ty_args.peek().is_some() {
+                            format_to!(
+                                path,
+                                "<{}>",
+                                ty_args.format_with(",", |ty, cb| cb(&ty.display(db)))
+                            );
+                        }
+                        format_to!(path, "::{}", def_name);
+                        path.retain(|c| c != ' ');
+                        return Some(path);
                     }
-                    format_to!(path, "::{}", def_name);
-                    path.retain(|c| c != ' ');
+                }
+                hir::AssocItemContainer::Trait(trait_) => {
+                    format_to!(path, "{}::{}", trait_.name(db), def_name);
                     return Some(path);
                 }
             }
@@ -2226,6 +2243,126 @@ macro_rules! foo {
         );
     }
 
+    #[test]
+    fn doc_test_macro_export_mbe_in_submodule() {
+        check(
+            r#"
+//- /lib.rs
+mod foo;
+
+//- /foo.rs
+$0
+/// ```
+/// fn foo() {
+/// }
+/// ```
+#[macro_export]
+macro_rules! foo {
+    () => {
+
+    };
+}
+"#,
+            &[DocTest],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                1,
+                            ),
+                            full_range: 1..94,
+                            name: "foo",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn doc_test_trait_items() {
+        check(
+            r#"
+//- /lib.rs
+$0
+mod foo {
+    /// ```
+    /// ```
+    pub trait Trait {
+        /// ```
+        /// ```
+        fn method();
+
+        /// ```
+        /// ```
+        const CONST: u32;
+    }
+}
+"#,
+            &[DocTest, DocTest, DocTest],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 15..174,
+                            name: "Trait",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Trait",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 65..109,
+                            name: "method",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Trait::method",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 119..168,
+                            name: "CONST",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Trait::CONST",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_paths_with_raw_ident() {
         check(
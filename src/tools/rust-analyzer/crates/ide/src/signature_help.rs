@@ -5,7 +5,8 @@
 
 use either::Either;
 use hir::{
-    AssocItem, GenericParam, HasAttrs, HirDisplay, ModuleDef, PathResolution, Semantics, Trait,
+    AssocItem, GenericParam, HasAttrs, HirDisplay, ModuleDef, PathResolution, Semantics,
+    StructKind, Trait,
 };
 use ide_db::{
     active_parameter::{callable_for_node, generic_def_for_node},
@@ -16,7 +17,7 @@
 use syntax::{
     algo,
     ast::{self, HasArgList},
-    match_ast, AstNode, Direction, SyntaxToken, TextRange, TextSize,
+    match_ast, AstNode, Direction, SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
 
 use crate::RootDatabase;
@@ -102,6 +103,20 @@ pub(crate) fn signature_help(db: &RootDatabase, position: FilePosition) -> Optio
                     }
                     return signature_help_for_record_lit(&sema, record, token);
                 },
+                ast::RecordPat(record) => {
+                    let cursor_outside = record.record_pat_field_list().and_then(|list| list.r_curly_token()).as_ref() == Some(&token);
+                    if cursor_outside {
+                        continue;
+                    }
+                    return signature_help_for_record_pat(&sema, record, token);
+                },
+                ast::TupleStructPat(tuple_pat) => {
+                    let cursor_outside = tuple_pat.r_paren_token().as_ref() == Some(&token);
+                    if cursor_outside {
+                        continue;
+                    }
+                    return signature_help_for_tuple_struct_pat(&sema, tuple_pat, token);
+                },
                 _ => (),
             }
         }
@@ -342,9 +357,36 @@ fn signature_help_for_record_lit(
     record: ast::RecordExpr,
     token: SyntaxToken,
 ) -> Option<SignatureHelp> {
-    let active_parameter = record
-        .record_expr_field_list()?
-        .syntax()
+    let field_list = record.record_expr_field_list()?;
+    let present_fields = field_list
+        .fields()
+        .filter_map(|field| sema.resolve_record_field(&field))
+        .map(|(field, _, ty)| (field, ty));
+    signature_help_for_record_(sema, field_list.syntax(), &record.path()?, present_fields, token)
+}
+
+fn signature_help_for_record_pat(
+    sema: &Semantics<'_, RootDatabase>,
+    record: ast::RecordPat,
+    token: SyntaxToken,
+) -> Option<SignatureHelp> {
+    let field_list = record.record_pat_field_list()?;
+    let present_fields = field_list.fields().filter_map(|field| {
+        let ty = sema.type_of_pat(&field.pat()?)?.original;
+        Some((sema.resolve_record_pat_field(&field)?, ty))
+    });
+    signature_help_for_record_(sema, field_list.syntax(), &record.path()?, present_fields, token)
+}
+
+/// Renders the fields of a record literal or pattern, the ones already written first.
+fn signature_help_for_record_(
+    sema: &Semantics<'_, RootDatabase>,
+    field_list: &SyntaxNode,
+    path: &ast::Path,
+    present_fields: impl Iterator<Item = (hir::Field, hir::Type)>,
+    token: SyntaxToken,
+) -> Option<SignatureHelp> {
+    let active_parameter = field_list
         .children_with_tokens()
         .filter_map(syntax::NodeOrToken::into_token)
         .filter(|t| t.kind() == syntax::T![,])
@@ -361,7 +403,7 @@ fn signature_help_for_record_lit(
     let fields;
 
     let db = sema.db;
-    let path_res = sema.resolve_path(&record.path()?)?;
+    let path_res = sema.resolve_path(path)?;
     if let PathResolution::Def(ModuleDef::Variant(variant)) = path_res {
         fields = variant.fields(db);
         let en = variant.parent_enum(db);
@@ -393,8 +435,7 @@ fn signature_help_for_record_lit(
     let mut fields =
         fields.into_iter().map(|field| (field.name(db), Some(field))).collect::<FxIndexMap<_, _>>();
     let mut buf = String::new();
-    for field in record.record_expr_field_list()?.fields() {
-        let Some((field, _, ty)) = sema.resolve_record_field(&field) else { continue };
+    for (field, ty) in present_fields {
         let name = field.name(db);
         format_to!(buf, "{name}: {}", ty.display_truncated(db, Some(20)));
         res.push_record_field(&buf);
@@ -414,6 +455,79 @@ fn signature_help_for_record_lit(
     Some(res)
 }
 
+fn signature_help_for_tuple_struct_pat(
+    sema: &Semantics<'_, RootDatabase>,
+    pat: ast::TupleStructPat,
+    token: SyntaxToken,
+) -> Option<SignatureHelp> {
+    let mut res = SignatureHelp {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: None,
+    };
+
+    let db = sema.db;
+    let path_res = sema.resolve_path(&pat.path()?)?;
+    let fields = if let PathResolution::Def(ModuleDef::Variant(variant)) = path_res {
+        if variant.kind(db) != StructKind::Tuple {
+            return None;
+        }
+        res.doc = variant.docs(db).map(|it| it.into());
+        format_to!(
+            res.signature,
+            "enum {}::{}",
+            variant.parent_enum(db).name(db),
+            variant.name(db)
+        );
+        variant.fields(db)
+    } else {
+        let strukt = match path_res {
+            PathResolution::SelfType(imp) => match imp.self_ty(db).as_adt()? {
+                hir::Adt::Struct(it) => it,
+                _ => return None,
+            },
+            PathResolution::Def(ModuleDef::Adt(hir::Adt::Struct(it))) => it,
+            _ => return None,
+        };
+        if strukt.kind(db) != StructKind::Tuple {
+            return None;
+        }
+        res.doc = strukt.docs(db).map(|it| it.into());
+        format_to!(res.signature, "struct {}", strukt.name(db));
+        strukt.fields(db)
+    };
+
+    let commas: Vec<_> = pat
+        .syntax()
+        .children_with_tokens()
+        .filter_map(syntax::NodeOrToken::into_token)
+        .filter(|t| t.kind() == syntax::T![,])
+        .collect();
+    let commas_before =
+        commas.iter().take_while(|t| t.text_range().start() <= token.text_range().start()).count();
+    let after_rest_pat = pat.fields().any(|it| {
+        matches!(it, ast::Pat::RestPat(_))
+            && it.syntax().text_range().end() <= token.text_range().start()
+    });
+    // The fields after a `..` are matched from the end.
+    res.active_parameter = if after_rest_pat {
+        fields.len().checked_sub(commas.len() - commas_before + 1)
+    } else {
+        Some(commas_before)
+    };
+
+    res.signature.push('(');
+    let mut buf = String::new();
+    for field in fields {
+        format_to!(buf, "{}", field.ty(db).display_truncated(db, Some(20)));
+        res.push_call_param(&buf);
+        buf.clear();
+    }
+    res.signature.push(')');
+    Some(res)
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
@@ -1546,6 +1660,61 @@ struct S { t: u8 }
         );
     }
 
+    #[test]
+    fn record_pattern() {
+        check(
+            r#"
+struct Point { x: i32, y: i32, z: i32 }
+fn f(p: Point) {
+    let Point { y, $0 } = p;
+}
+"#,
+            expect![[r#"
+                struct Point { y: i32, x: i32, z: i32 }
+                               ------  ^^^^^^  ------
+            "#]],
+        );
+    }
+
+    #[test]
+    fn tuple_struct_pattern() {
+        check(
+            r#"
+enum E {
+    /// A variant.
+    V(u8, u16, bool),
+}
+fn f(e: E) {
+    match e {
+        E::V(a, $0) => {}
+    }
+}
+"#,
+            expect![[r#"
+                A variant.
+                ------
+                enum E::V(u8, u16, bool)
+                          --  ^^^  ----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn tuple_struct_pattern_after_rest_pattern() {
+        check(
+            r#"
+struct S(u8, u16, bool);
+fn f(s: S) {
+    let S(.., $0) = s;
+}
+"#,
+            expect![[r#"
+                struct S(u8, u16, bool)
+                         --  ---  ^^^^
+            "#]],
+        );
+    }
+
     #[test]
     fn test_enum_in_nested_method_in_lambda() {
         check(
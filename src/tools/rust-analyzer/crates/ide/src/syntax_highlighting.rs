@@ -8,7 +8,9 @@
 mod macro_;
 mod inject;
 mod escape;
+mod languages;
 
+mod export;
 mod html;
 #[cfg(test)]
 mod tests;
@@ -29,6 +31,10 @@
 
 pub(crate) use html::highlight_as_html;
 
+pub(crate) use export::export_highlighted;
+pub use export::{HighlightExportConfig, HighlightExportFormat, HighlightTheme};
+pub use languages::{InjectedLanguage, StringInjection};
+
 #[derive(Debug, Clone, Copy)]
 pub struct HlRange {
     pub range: TextRange,
@@ -36,7 +42,7 @@ pub struct HlRange {
     pub binding_hash: Option<u64>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HighlightConfig {
     /// Whether to highlight strings
     pub strings: bool,
@@ -50,10 +56,18 @@ pub struct HighlightConfig {
     pub specialize_operator: bool,
     /// Whether to inject highlights into doc comments
     pub inject_doc_comment: bool,
+    /// Whether to highlight other languages in string literals, like regexes passed to
+    /// `Regex::new`
+    pub inject_strings: bool,
+    /// The functions and macros whose string arguments are highlighted as other languages
+    pub string_injections: Vec<StringInjection>,
     /// Whether to highlight the macro call bang
     pub macro_bang: bool,
     /// Whether to highlight unresolved things be their syntax
     pub syntactic_name_ref_highlighting: bool,
+    /// Whether to highlight everything by its syntax alone, without resolving names, inferring
+    /// types or expanding macros
+    pub syntax_only: bool,
 }
 
 // Feature: Semantic Syntax Highlighting
@@ -160,7 +174,7 @@ pub struct HighlightConfig {
 // declaration:: Emitted for names of definitions, like `foo` in `fn foo() {}`.
 // defaultLibrary:: Emitted for items from built-in crates (std, core, alloc, test and proc_macro).
 // documentation:: Emitted for documentation comments.
-// injected:: Emitted for doc-string injected highlighting like rust source blocks in documentation.
+// injected:: Emitted for doc-string injected highlighting like rust source blocks in documentation, and for other languages injected into string literals like regexes and SQL queries.
 // intraDocLink:: Emitted for intra doc links in doc-strings.
 // library:: Emitted for items that are defined outside of the current crate.
 // mutable:: Emitted for mutable locals and statics as well as functions taking `&mut self`.
@@ -175,7 +189,7 @@ pub struct HighlightConfig {
 // image::https://user-images.githubusercontent.com/48062697/113187625-f7f50100-9250-11eb-825e-91c58f236071.png[]
 pub(crate) fn highlight(
     db: &RootDatabase,
-    config: HighlightConfig,
+    config: &HighlightConfig,
     file_id: FileId,
     range_to_highlight: Option<TextRange>,
 ) -> Vec<HlRange> {
@@ -210,7 +224,7 @@ pub(crate) fn highlight(
 fn traverse(
     hl: &mut Highlights,
     sema: &Semantics<'_, RootDatabase>,
-    config: HighlightConfig,
+    config: &HighlightConfig,
     file_id: FileId,
     root: &SyntaxNode,
     krate: hir::Crate,
@@ -284,7 +298,7 @@ fn item(&self) -> &ast::Item {
                             bindings_shadow_count.clear();
                         }
 
-                        if attr_or_derive_item.is_none() {
+                        if attr_or_derive_item.is_none() && !config.syntax_only {
                             if sema.is_attr_macro_call(&item) {
                                 attr_or_derive_item = Some(AttrOrDerive::Attr(item));
                             } else {
@@ -335,7 +349,7 @@ fn item(&self) -> &ast::Item {
             Enter(it) => it,
             Leave(NodeOrToken::Token(_)) => continue,
             Leave(NodeOrToken::Node(node)) => {
-                if config.inject_doc_comment {
+                if config.inject_doc_comment && !config.syntax_only {
                     // Doc comment highlighting injection, we do this when leaving the node
                     // so that we overwrite the highlighting of the doc comment itself.
                     inject::doc_comment(hl, sema, config, file_id, &node);
@@ -361,12 +375,13 @@ fn item(&self) -> &ast::Item {
 
         // Descending tokens into macros is expensive even if no descending occurs, so make sure
         // that we actually are in a position where descending is possible.
-        let in_macro = tt_level > 0
-            || match attr_or_derive_item {
-                Some(AttrOrDerive::Attr(_)) => true,
-                Some(AttrOrDerive::Derive(_)) => inside_attribute,
-                None => false,
-            };
+        let in_macro = !config.syntax_only
+            && (tt_level > 0
+                || match attr_or_derive_item {
+                    Some(AttrOrDerive::Attr(_)) => true,
+                    Some(AttrOrDerive::Derive(_)) => inside_attribute,
+                    None => false,
+                });
         let descended_element = if in_macro {
             // Attempt to descend tokens into macro-calls.
             match element {
@@ -414,10 +429,21 @@ fn item(&self) -> &ast::Item {
                 let string_to_highlight = ast::String::cast(descended_token.clone());
                 if let Some((string, expanded_string)) = string.zip(string_to_highlight) {
                     if string.is_raw()
+                        && !config.syntax_only
                         && inject::ra_fixture(hl, sema, config, &string, &expanded_string).is_some()
                     {
                         continue;
                     }
+                    if config.inject_strings && !config.syntax_only {
+                        languages::highlight_injected_language(
+                            hl,
+                            sema,
+                            &config.string_injections,
+                            &string,
+                            &expanded_string,
+                            range.start(),
+                        );
+                    }
                     highlight_format_string(hl, &string, &expanded_string, range);
                     highlight_escape_string(hl, &string, range.start());
                 }
@@ -431,6 +457,9 @@ fn item(&self) -> &ast::Item {
         }
 
         let element = match descended_element {
+            NodeOrToken::Node(name_like) if config.syntax_only => {
+                Some((highlight::name_like_by_syntax(name_like), None))
+            }
             NodeOrToken::Node(name_like) => highlight::name_like(
                 sema,
                 krate,
@@ -438,7 +467,10 @@ fn item(&self) -> &ast::Item {
                 config.syntactic_name_ref_highlighting,
                 name_like,
             ),
-            NodeOrToken::Token(token) => highlight::token(sema, token).zip(Some(None)),
+            NodeOrToken::Token(token) => {
+                let sema = if config.syntax_only { None } else { Some(sema) };
+                highlight::token(sema, token).zip(Some(None))
+            }
         };
         if let Some((mut highlight, binding_hash)) = element {
             if is_unlinked && highlight.tag == HlTag::UnresolvedReference {
@@ -0,0 +1,263 @@
+//! Renders a file, or a selection of it, as self-contained HTML with the semantic
+//! highlighting inlined as styles, or as a fenced Markdown code block.
+//!
+//! Unlike `highlight_as_html`, which is meant for debugging and relies on a
+//! stylesheet, the output of this module can be pasted into documents or chat
+//! messages as is.
+
+use hir::{HirDisplay, Semantics};
+use ide_db::{
+    base_db::SourceDatabase,
+    defs::{Definition, IdentClass},
+    FxHashMap, RootDatabase, SymbolKind,
+};
+use stdx::format_to;
+use syntax::{ast, AstNode, SyntaxKind::*, TextRange, TextSize, T};
+
+use crate::{
+    syntax_highlighting::{highlight, HighlightConfig, StringInjection},
+    FileId, HlRange, HlTag,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightExportFormat {
+    /// A `<pre>` element.
+    Html,
+    /// A fenced Markdown code block. Markdown can't carry styles, so coloring is
+    /// left to the renderer of the document.
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightTheme {
+    /// The style of the enclosing `<pre>` element.
+    pub base: String,
+    /// Styles keyed by a tag (`function`), a modifier (`mutable`) or a tag with a
+    /// modifier (`function.unsafe`). When several keys apply to a token, their
+    /// styles are applied in that order.
+    pub styles: FxHashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightExportConfig {
+    pub format: HighlightExportFormat,
+    pub theme: HighlightTheme,
+    /// Whether to add `title` attributes with the type of variables, fields,
+    /// constants and statics, shown when hovering them.
+    pub binding_types: bool,
+}
+
+impl Default for HighlightTheme {
+    /// A Zenburn-like palette, matching the one used by `highlight_as_html`.
+    fn default() -> Self {
+        let styles = [
+            ("lifetime", "color: #DFAF8F; font-style: italic;"),
+            ("label", "color: #DFAF8F; font-style: italic;"),
+            ("comment", "color: #7F9F7F;"),
+            ("documentation", "color: #629755;"),
+            ("intra_doc_link", "font-style: italic;"),
+            ("injected", "opacity: 0.65;"),
+            ("struct", "color: #7CB8BB;"),
+            ("enum", "color: #7CB8BB;"),
+            ("enum_variant", "color: #BDE0F3;"),
+            ("string_literal", "color: #CC9393;"),
+            ("field", "color: #94BFF3;"),
+            ("function", "color: #93E0E3;"),
+            ("function.unsafe", "color: #BC8383;"),
+            ("trait.unsafe", "color: #BC8383;"),
+            ("operator.unsafe", "color: #BC8383;"),
+            ("keyword.unsafe", "color: #BC8383; font-weight: bold;"),
+            ("macro.unsafe", "color: #BC8383;"),
+            ("parameter", "color: #94BFF3;"),
+            ("type", "color: #7CB8BB;"),
+            ("builtin_type", "color: #8CD0D3;"),
+            ("type_param", "color: #DFAF8F;"),
+            ("attribute", "color: #94BFF3;"),
+            ("numeric_literal", "color: #BFEBBF;"),
+            ("bool_literal", "color: #BFE6EB;"),
+            ("macro", "color: #94BFF3;"),
+            ("derive", "color: #94BFF3; font-style: italic;"),
+            ("module", "color: #AFD8AF;"),
+            ("value_param", "color: #DCDCCC;"),
+            ("variable", "color: #DCDCCC;"),
+            ("format_specifier", "color: #CC696B;"),
+            ("mutable", "text-decoration: underline;"),
+            ("escape_sequence", "color: #94BFF3;"),
+            ("keyword", "color: #F0DFAF; font-weight: bold;"),
+            ("control", "font-style: italic;"),
+            ("reference", "font-style: italic; font-weight: bold;"),
+            ("unresolved_reference", "color: #FC5555; text-decoration: wavy underline;"),
+        ];
+        HighlightTheme {
+            base: "color: #DCDCCC; background: #3F3F3F; padding: 0.4em;".to_string(),
+            styles: styles
+                .into_iter()
+                .map(|(key, style)| (key.to_string(), style.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl HighlightTheme {
+    fn style(&self, hl_range: &HlRange) -> String {
+        let tag = hl_range.highlight.tag.to_string();
+        let mut style = String::new();
+        let mut push = |key: &str| {
+            if let Some(it) = self.styles.get(key) {
+                if !style.is_empty() {
+                    style.push(' ');
+                }
+                style.push_str(it);
+            }
+        };
+        push(&tag);
+        for modifier in hl_range.highlight.mods.iter() {
+            push(&modifier.to_string());
+        }
+        for modifier in hl_range.highlight.mods.iter() {
+            push(&format!("{tag}.{modifier}"));
+        }
+        style
+    }
+}
+
+// Feature: Export Highlighted Code
+//
+// Copies the current file, or the selection in it, as HTML with the semantic
+// highlighting inlined as styles, so that it can be pasted into documents while
+// keeping its coloring. It can also be copied as a fenced Markdown code block.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: Copy Highlighted Code as HTML**
+// | VS Code | **rust-analyzer: Copy Code as Markdown**
+// |===
+pub(crate) fn export_highlighted(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: Option<TextRange>,
+    config: &HighlightExportConfig,
+) -> String {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let text = db.file_text(file_id);
+    let range = match range {
+        Some(range) => range.intersect(file.syntax().text_range()).unwrap_or_default(),
+        None => file.syntax().text_range(),
+    };
+    if config.format == HighlightExportFormat::Markdown {
+        return fenced_code_block(&text[range]);
+    }
+
+    let hl_ranges = highlight(
+        db,
+        &HighlightConfig {
+            strings: true,
+            punctuation: true,
+            specialize_punctuation: true,
+            specialize_operator: true,
+            operator: true,
+            inject_doc_comment: true,
+            inject_strings: true,
+            string_injections: StringInjection::defaults(),
+            macro_bang: true,
+            syntactic_name_ref_highlighting: false,
+            syntax_only: false,
+        },
+        file_id,
+        Some(range),
+    );
+
+    let mut code = String::new();
+    for r in &hl_ranges {
+        // The highlighted node may cover more than the selection.
+        let Some(chunk_range) = r.range.intersect(range) else { continue };
+        if chunk_range.is_empty() {
+            continue;
+        }
+        let chunk = html_escape(&text[chunk_range]);
+        let style = if r.highlight.is_empty() { String::new() } else { config.theme.style(r) };
+        let title = if config.binding_types {
+            binding_type(&sema, &file, r.range.start(), r.highlight.tag)
+        } else {
+            None
+        };
+        if style.is_empty() && title.is_none() {
+            code.push_str(&chunk);
+            continue;
+        }
+        code.push_str("<span");
+        if !style.is_empty() {
+            format_to!(code, " style=\"{}\"", html_escape(&style));
+        }
+        if let Some(title) = title {
+            format_to!(code, " title=\"{}\"", html_escape(&title));
+        }
+        format_to!(code, ">{}</span>", chunk);
+    }
+
+    format!(
+        "<pre style=\"{}\"><code>{}</code></pre>",
+        html_escape(&config.theme.base),
+        code.trim_end_matches('\n')
+    )
+}
+
+fn fenced_code_block(code: &str) -> String {
+    // The fence has to be longer than any run of backticks in the code.
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}rust\n{}\n{fence}\n", code.trim_end_matches('\n'))
+}
+
+/// The type of the binding highlighted at `offset`, if any.
+fn binding_type(
+    sema: &Semantics<'_, RootDatabase>,
+    file: &ast::SourceFile,
+    offset: TextSize,
+    tag: HlTag,
+) -> Option<String> {
+    if !matches!(
+        tag,
+        HlTag::Symbol(
+            SymbolKind::Local
+                | SymbolKind::ValueParam
+                | SymbolKind::SelfParam
+                | SymbolKind::Field
+                | SymbolKind::Const
+                | SymbolKind::Static
+        )
+    ) {
+        return None;
+    }
+    let token = file
+        .syntax()
+        .token_at_offset(offset)
+        .right_biased()
+        .filter(|it| matches!(it.kind(), IDENT | INT_NUMBER | T![self]))?;
+    let token = sema.descend_into_macros_single(token);
+    let db = sema.db;
+    let ty = match IdentClass::classify_token(sema, &token)?.definitions().into_iter().next()? {
+        Definition::Local(it) => it.ty(db),
+        Definition::Field(it) => it.ty(db),
+        Definition::Const(it) => it.ty(db),
+        Definition::Static(it) => it.ty(db),
+        _ => return None,
+    };
+    Some(ty.display_truncated(db, None).to_string())
+}
+
+fn html_escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '&' => res.push_str("&amp;"),
+            '"' => res.push_str("&quot;"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
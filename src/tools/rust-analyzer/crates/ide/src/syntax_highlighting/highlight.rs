@@ -16,7 +16,11 @@
     Highlight, HlMod, HlTag,
 };
 
-pub(super) fn token(sema: &Semantics<'_, RootDatabase>, token: SyntaxToken) -> Option<Highlight> {
+/// Highlights a token. Without `sema`, the highlighting is based on the syntax tree alone.
+pub(super) fn token(
+    sema: Option<&Semantics<'_, RootDatabase>>,
+    token: SyntaxToken,
+) -> Option<Highlight> {
     if let Some(comment) = ast::Comment::cast(token.clone()) {
         let h = HlTag::Comment;
         return Some(match comment.kind().doc {
@@ -79,8 +83,29 @@ pub(super) fn name_like(
     Some((highlight, binding_hash))
 }
 
+/// Highlights a name-like node based on the syntax tree alone, without resolving it.
+pub(super) fn name_like_by_syntax(name_like: ast::NameLike) -> Highlight {
+    match name_like {
+        ast::NameLike::NameRef(name_ref) => {
+            match name_ref.syntax().first_token().map(|it| it.kind()) {
+                Some(T![self]) => SymbolKind::SelfParam.into(),
+                Some(T![Self]) => SymbolKind::SelfType.into(),
+                Some(T![crate] | T![super]) => SymbolKind::Module.into(),
+                _ => highlight_name_ref_by_syntax(name_ref, None),
+            }
+        }
+        ast::NameLike::Name(name) => highlight_name_by_syntax(name) | HlMod::Definition,
+        ast::NameLike::Lifetime(lifetime) => match lifetime.syntax().parent() {
+            Some(parent) if ast::Label::can_cast(parent.kind()) => {
+                HlTag::Symbol(SymbolKind::Label) | HlMod::Definition
+            }
+            _ => SymbolKind::LifetimeParam.into(),
+        },
+    }
+}
+
 fn punctuation(
-    sema: &Semantics<'_, RootDatabase>,
+    sema: Option<&Semantics<'_, RootDatabase>>,
     token: SyntaxToken,
     kind: SyntaxKind,
 ) -> Highlight {
@@ -93,7 +118,8 @@ fn punctuation(
             let h = HlTag::Operator(HlOperator::Other).into();
             let is_unsafe = parent
                 .and_then(ast::RefExpr::cast)
-                .map(|ref_expr| sema.is_unsafe_ref_expr(&ref_expr));
+                .zip(sema)
+                .map(|(ref_expr, sema)| sema.is_unsafe_ref_expr(&ref_expr));
             if let Some(true) = is_unsafe {
                 h | HlMod::Unsafe
             } else {
@@ -111,7 +137,7 @@ fn punctuation(
             let is_raw_ptr = (|| {
                 let prefix_expr = parent.and_then(ast::PrefixExpr::cast)?;
                 let expr = prefix_expr.expr()?;
-                sema.type_of_expr(&expr)?.original.is_raw_ptr().then_some(())
+                sema?.type_of_expr(&expr)?.original.is_raw_ptr().then_some(())
             })();
             if let Some(()) = is_raw_ptr {
                 HlTag::Operator(HlOperator::Other) | HlMod::Unsafe
@@ -156,7 +182,7 @@ fn punctuation(
 }
 
 fn keyword(
-    sema: &Semantics<'_, RootDatabase>,
+    sema: Option<&Semantics<'_, RootDatabase>>,
     token: SyntaxToken,
     kind: SyntaxKind,
 ) -> Option<Highlight> {
@@ -187,7 +213,9 @@ fn keyword(
         }
         T![self] if parent_matches::<ast::Name>(&token) => return None,
         T![ref] => match token.parent().and_then(ast::IdentPat::cast) {
-            Some(ident) if sema.is_unsafe_ident_pat(&ident) => h | HlMod::Unsafe,
+            Some(ident) if sema.map_or(false, |sema| sema.is_unsafe_ident_pat(&ident)) => {
+                h | HlMod::Unsafe
+            }
             _ => h,
         },
         _ => h,
@@ -211,7 +239,7 @@ fn highlight_name_ref(
     let name_class = match NameRefClass::classify(sema, &name_ref) {
         Some(name_kind) => name_kind,
         None if syntactic_name_ref_highlighting => {
-            return highlight_name_ref_by_syntax(name_ref, sema, krate)
+            return highlight_name_ref_by_syntax(name_ref, Some((sema, krate)))
         }
         // FIXME: This is required for helper attributes used by proc-macros, as those do not map down
         // to anything when used.
@@ -236,6 +264,11 @@ fn highlight_name_ref(
                 Definition::Local(local) if is_consumed_lvalue(name_ref.syntax(), &local, db) => {
                     h |= HlMod::Consuming;
                 }
+                Definition::Local(_) | Definition::Static(_) | Definition::Const(_)
+                    if is_unsafe_fn_ptr_callee(sema, name_ref.syntax()) =>
+                {
+                    h |= HlMod::Unsafe;
+                }
                 Definition::Trait(trait_) if trait_.is_unsafe(db) => {
                     if ast::Impl::for_trait_name_ref(&name_ref)
                         .map_or(false, |impl_| impl_.unsafe_token().is_some())
@@ -593,10 +626,11 @@ fn highlight_name_by_syntax(name: ast::Name) -> Highlight {
     tag.into()
 }
 
+/// Highlights a name reference by its syntax, using `sema` only to resolve method calls and
+/// fields if given.
 fn highlight_name_ref_by_syntax(
     name: ast::NameRef,
-    sema: &Semantics<'_, RootDatabase>,
-    krate: hir::Crate,
+    sema: Option<(&Semantics<'_, RootDatabase>, hir::Crate)>,
 ) -> Highlight {
     let default = HlTag::UnresolvedReference;
 
@@ -607,15 +641,18 @@ fn highlight_name_ref_by_syntax(
 
     match parent.kind() {
         METHOD_CALL_EXPR => ast::MethodCallExpr::cast(parent)
-            .and_then(|it| highlight_method_call(sema, krate, &it))
+            .zip(sema)
+            .and_then(|(it, (sema, krate))| highlight_method_call(sema, krate, &it))
             .unwrap_or_else(|| SymbolKind::Function.into()),
         FIELD_EXPR => {
             let h = HlTag::Symbol(SymbolKind::Field);
             let is_union = ast::FieldExpr::cast(parent)
-                .and_then(|field_expr| sema.resolve_field(&field_expr))
-                .map_or(false, |field| {
-                    matches!(field.parent_def(sema.db), hir::VariantDef::Union(_))
-                });
+                .zip(sema)
+                .and_then(|(field_expr, (sema, _))| {
+                    let field = sema.resolve_field(&field_expr)?;
+                    Some(matches!(field.parent_def(sema.db), hir::VariantDef::Union(_)))
+                })
+                .unwrap_or(false);
             if is_union {
                 h | HlMod::Unsafe
             } else {
@@ -670,6 +707,16 @@ fn is_consumed_lvalue(node: &SyntaxNode, local: &hir::Local, db: &RootDatabase)
         && !local.ty(db).is_copy(db)
 }
 
+/// Returns true if `node` names an `unsafe fn` pointer that is being called.
+fn is_unsafe_fn_ptr_callee(sema: &Semantics<'_, RootDatabase>, node: &SyntaxNode) -> bool {
+    parents_match(node.clone().into(), &[PATH_SEGMENT, PATH, PATH_EXPR, CALL_EXPR])
+        && node
+            .ancestors()
+            .nth(4)
+            .and_then(ast::CallExpr::cast)
+            .map_or(false, |call| sema.is_unsafe_call(&call))
+}
+
 /// Returns true if the parent nodes of `node` all match the `SyntaxKind`s in `kinds` exactly.
 fn parents_match(mut node: NodeOrToken<SyntaxNode, SyntaxToken>, mut kinds: &[SyntaxKind]) -> bool {
     while let (Some(parent), [kind, rest @ ..]) = (&node.parent(), kinds) {
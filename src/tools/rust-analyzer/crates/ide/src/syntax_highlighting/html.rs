@@ -6,7 +6,7 @@
 use syntax::AstNode;
 
 use crate::{
-    syntax_highlighting::{highlight, HighlightConfig},
+    syntax_highlighting::{highlight, HighlightConfig, StringInjection},
     FileId, RootDatabase,
 };
 
@@ -25,15 +25,18 @@ fn rainbowify(seed: u64) -> String {
 
     let hl_ranges = highlight(
         db,
-        HighlightConfig {
+        &HighlightConfig {
             strings: true,
             punctuation: true,
             specialize_punctuation: true,
             specialize_operator: true,
             operator: true,
             inject_doc_comment: true,
+            inject_strings: true,
+            string_injections: StringInjection::defaults(),
             macro_bang: true,
             syntactic_name_ref_highlighting: false,
+            syntax_only: false,
         },
         file_id,
         None,
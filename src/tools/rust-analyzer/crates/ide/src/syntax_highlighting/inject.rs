@@ -22,7 +22,7 @@
 pub(super) fn ra_fixture(
     hl: &mut Highlights,
     sema: &Semantics<'_, RootDatabase>,
-    config: HighlightConfig,
+    config: &HighlightConfig,
     literal: &ast::String,
     expanded: &ast::String,
 ) -> Option<()> {
@@ -66,7 +66,7 @@ pub(super) fn ra_fixture(
 
     for mut hl_range in analysis
         .highlight(
-            HighlightConfig { syntactic_name_ref_highlighting: false, ..config },
+            HighlightConfig { syntactic_name_ref_highlighting: false, ..config.clone() },
             tmp_file_id,
         )
         .unwrap()
@@ -93,7 +93,7 @@ pub(super) fn ra_fixture(
 pub(super) fn doc_comment(
     hl: &mut Highlights,
     sema: &Semantics<'_, RootDatabase>,
-    config: HighlightConfig,
+    config: &HighlightConfig,
     src_file_id: FileId,
     node: &SyntaxNode,
 ) {
@@ -217,7 +217,7 @@ pub(super) fn doc_comment(
     if let Ok(ranges) = analysis.with_db(|db| {
         super::highlight(
             db,
-            HighlightConfig { syntactic_name_ref_highlighting: true, ..config },
+            &HighlightConfig { syntactic_name_ref_highlighting: true, ..config.clone() },
             tmp_file_id,
             None,
         )
@@ -0,0 +1,269 @@
+//! Syntax highlighting for other languages in string literals, like the regex passed to
+//! `Regex::new` or the query passed to `sqlx::query!`.
+
+use hir::{AsAssocItem, AssocItemContainer, CallableKind, Semantics};
+use ide_db::{active_parameter::callable_for_token, RootDatabase, SymbolKind};
+use syntax::{
+    ast::{self, IsString},
+    AstNode, AstToken, TextRange, TextSize,
+};
+
+use crate::{
+    syntax_highlighting::{highlights::Highlights, tags::Highlight},
+    HlMod, HlOperator, HlPunct, HlRange, HlTag,
+};
+
+/// A language which can be highlighted in string literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectedLanguage {
+    Regex,
+    Sql,
+}
+
+/// A function or macro whose string argument is highlighted as another language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringInjection {
+    /// The path of the function or macro, `krate::name`, or `krate::Type::name` for an
+    /// associated function. The modules are left out, as the items are often re-exported.
+    pub path: String,
+    /// The index of the argument of the function, or of the string literal among the arguments
+    /// of the macro.
+    pub argument: usize,
+    pub language: InjectedLanguage,
+}
+
+impl StringInjection {
+    /// The strings languages are injected into by default.
+    pub fn defaults() -> Vec<StringInjection> {
+        let injection =
+            |path: &str, language| StringInjection { path: path.to_owned(), argument: 0, language };
+        vec![
+            injection("regex::Regex::new", InjectedLanguage::Regex),
+            injection("regex::RegexBuilder::new", InjectedLanguage::Regex),
+            injection("sqlx::query", InjectedLanguage::Sql),
+            injection("sqlx::query_as", InjectedLanguage::Sql),
+            injection("sqlx::query_scalar", InjectedLanguage::Sql),
+        ]
+    }
+
+    /// The crate, the type if any and the name of the function or macro.
+    fn path_segments(&self) -> Option<(&str, Option<&str>, &str)> {
+        let segments: Vec<_> = self.path.split("::").collect();
+        match *segments.as_slice() {
+            [krate, name] => Some((krate, None, name)),
+            [krate, ty, name] => Some((krate, Some(ty), name)),
+            _ => None,
+        }
+    }
+}
+
+#[rustfmt::skip]
+const SQL_KEYWORDS: &[&str] = &[
+    "ALL", "AND", "AS", "ASC", "BETWEEN", "BY", "CASE", "CONFLICT", "CREATE", "CROSS", "DEFAULT",
+    "DELETE", "DESC", "DISTINCT", "DO", "DROP", "ELSE", "END", "EXISTS", "FALSE", "FROM", "FULL",
+    "GROUP", "HAVING", "IN", "INNER", "INSERT", "INTO", "IS", "JOIN", "KEY", "LEFT", "LIKE",
+    "LIMIT", "NOT", "NOTHING", "NULL", "OFFSET", "ON", "OR", "ORDER", "OUTER", "PRIMARY",
+    "RETURNING", "RIGHT", "SELECT", "SET", "TABLE", "THEN", "TRUE", "UNION", "UPDATE", "VALUES",
+    "WHEN", "WHERE", "WITH",
+];
+
+/// Highlights the contents of `string` if it is passed where a language is injected.
+pub(super) fn highlight_injected_language(
+    hl: &mut Highlights,
+    sema: &Semantics<'_, RootDatabase>,
+    injections: &[StringInjection],
+    string: &ast::String,
+    expanded_string: &ast::String,
+    start: TextSize,
+) -> Option<()> {
+    let language = injected_language(sema, injections, string, expanded_string)?;
+    let contents = string.text_range_between_quotes()?;
+    let offset = contents.start() - string.syntax().text_range().start();
+    let text = &string.text()[TextRange::at(offset, contents.len())];
+    // The escapes of non-raw strings are highlighted already and would be misread by the lexers.
+    if !string.is_raw() && text.contains('\\') {
+        return None;
+    }
+
+    let mut add = |range: TextRange, highlight: Highlight| {
+        hl.add(HlRange {
+            range: range + start + offset,
+            highlight: highlight | HlMod::Injected,
+            binding_hash: None,
+        })
+    };
+    match language {
+        InjectedLanguage::Regex => lex_regex(text, &mut add),
+        InjectedLanguage::Sql => lex_sql(text, &mut add),
+    }
+    Some(())
+}
+
+fn injected_language(
+    sema: &Semantics<'_, RootDatabase>,
+    injections: &[StringInjection],
+    string: &ast::String,
+    expanded_string: &ast::String,
+) -> Option<InjectedLanguage> {
+    macro_arg_language(sema, injections, string)
+        .or_else(|| fn_arg_language(sema, injections, expanded_string))
+}
+
+fn macro_arg_language(
+    sema: &Semantics<'_, RootDatabase>,
+    injections: &[StringInjection],
+    string: &ast::String,
+) -> Option<InjectedLanguage> {
+    let db = sema.db;
+    let macro_call = string.syntax().parent_ancestors().find_map(ast::MacroCall::cast)?;
+    let index = macro_call
+        .token_tree()?
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token().and_then(ast::String::cast))
+        .position(|it| it.syntax() == string.syntax())?;
+    let mac = sema.resolve_macro_call(&macro_call)?;
+    let krate = mac.module(db).krate().display_name(db)?;
+    let mac_name = mac.name(db).to_smol_str();
+    injections.iter().find_map(|injection| {
+        let (krate_name, ty, name) = injection.path_segments()?;
+        (krate.canonical_name() == krate_name
+            && ty.is_none()
+            && mac_name == name
+            && injection.argument == index)
+            .then_some(injection.language)
+    })
+}
+
+fn fn_arg_language(
+    sema: &Semantics<'_, RootDatabase>,
+    injections: &[StringInjection],
+    expanded_string: &ast::String,
+) -> Option<InjectedLanguage> {
+    let db = sema.db;
+    let (callable, index) = callable_for_token(sema, expanded_string.syntax().clone())?;
+    let (CallableKind::Function(func), Some(index)) = (callable.kind(), index) else {
+        return None;
+    };
+    let krate = func.module(db).krate().display_name(db)?;
+    let self_ty = match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Impl(imp)) => {
+            Some(imp.self_ty(db).as_adt()?.name(db).to_smol_str())
+        }
+        _ => None,
+    };
+    let func_name = func.name(db).to_smol_str();
+    injections.iter().find_map(|injection| {
+        let (krate_name, ty, name) = injection.path_segments()?;
+        (krate.canonical_name() == krate_name
+            && self_ty.as_deref() == ty
+            && func_name == name
+            && injection.argument == index)
+            .then_some(injection.language)
+    })
+}
+
+fn lex_regex(text: &str, add: &mut dyn FnMut(TextRange, Highlight)) {
+    let mut chars = text.char_indices().peekable();
+    let mut in_class = false;
+    while let Some((start, c)) = chars.next() {
+        let at =
+            |len: usize| TextRange::at(TextSize::from(start as u32), TextSize::from(len as u32));
+        match c {
+            '\\' => {
+                let Some((_, escaped)) = chars.next() else { break };
+                let mut end = start + 1 + escaped.len_utf8();
+                // Unicode classes like `\p{Greek}`.
+                if matches!(escaped, 'p' | 'P') && chars.peek().map(|&(_, c)| c) == Some('{') {
+                    for (idx, c) in chars.by_ref() {
+                        end = idx + c.len_utf8();
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                }
+                add(at(end - start), HlTag::EscapeSequence.into());
+            }
+            '[' if !in_class => {
+                in_class = true;
+                add(at(1), HlPunct::Bracket.into());
+                if let Some(&(idx, '^')) = chars.peek() {
+                    chars.next();
+                    add(TextRange::at((idx as u32).into(), 1.into()), HlOperator::Other.into());
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                add(at(1), HlPunct::Bracket.into());
+            }
+            _ if in_class => (),
+            '(' | ')' => add(at(1), HlPunct::Parenthesis.into()),
+            '{' => {
+                let len = text[start + 1..]
+                    .find(|c: char| !(c.is_ascii_digit() || c == ','))
+                    .filter(|&idx| idx > 0 && text[start + 1 + idx..].starts_with('}'));
+                if let Some(len) = len {
+                    for _ in 0..=len {
+                        chars.next();
+                    }
+                    add(at(len + 2), HlOperator::Other.into());
+                }
+            }
+            '*' | '+' | '?' | '|' | '^' | '$' | '.' => add(at(1), HlOperator::Other.into()),
+            _ => (),
+        }
+    }
+}
+
+fn lex_sql(text: &str, add: &mut dyn FnMut(TextRange, Highlight)) {
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let mut eat_while = |pred: &dyn Fn(char) -> bool| {
+            while let Some(&(idx, c)) = chars.peek() {
+                if !pred(c) {
+                    break;
+                }
+                chars.next();
+                end = idx + c.len_utf8();
+            }
+        };
+        let highlight: Highlight = match c {
+            '-' if text[end..].starts_with('-') => {
+                eat_while(&|c| c != '\n');
+                HlTag::Comment.into()
+            }
+            '\'' => {
+                // A doubled quote is an escaped quote, which lexes as two adjacent strings.
+                eat_while(&|c| c != '\'');
+                chars.next();
+                end = (end + 1).min(text.len());
+                HlTag::StringLiteral.into()
+            }
+            '$' if text[end..].starts_with(|c: char| c.is_ascii_digit()) => {
+                eat_while(&|c| c.is_ascii_digit());
+                SymbolKind::ValueParam.into()
+            }
+            '?' => SymbolKind::ValueParam.into(),
+            c if c.is_ascii_digit() => {
+                eat_while(&|c| c.is_ascii_digit() || c == '.');
+                HlTag::NumericLiteral.into()
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                eat_while(&|c| c.is_alphanumeric() || c == '_');
+                let word = &text[start..end];
+                if !SQL_KEYWORDS.iter().any(|it| it.eq_ignore_ascii_case(word)) {
+                    continue;
+                }
+                HlTag::Keyword.into()
+            }
+            '=' | '<' | '>' | '!' | '+' | '-' | '*' | '/' | '%' | '|' => HlOperator::Other.into(),
+            '(' | ')' => HlPunct::Parenthesis.into(),
+            ',' => HlPunct::Comma.into(),
+            ';' => HlPunct::Semi.into(),
+            '.' => HlPunct::Dot.into(),
+            _ => continue,
+        };
+        add(TextRange::new((start as u32).into(), (end as u32).into()), highlight);
+    }
+}
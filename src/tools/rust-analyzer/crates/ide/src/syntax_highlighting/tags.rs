@@ -66,7 +66,7 @@ pub enum HlMod {
     Definition,
     /// Doc-strings like this one.
     Documentation,
-    /// Highlighting injection like rust code in doc strings or ra_fixture.
+    /// Highlighting injection like rust code in doc strings or ra_fixture, or regexes in strings.
     Injected,
     /// Used for intra doc links in doc injection.
     IntraDocLink,
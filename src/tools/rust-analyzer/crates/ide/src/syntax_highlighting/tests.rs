@@ -4,19 +4,27 @@
 use ide_db::SymbolKind;
 use test_utils::{bench, bench_fixture, skip_slow_tests, AssertLinear};
 
-use crate::{fixture, FileRange, HighlightConfig, HlTag, TextRange};
-
-const HL_CONFIG: HighlightConfig = HighlightConfig {
-    strings: true,
-    punctuation: true,
-    specialize_punctuation: true,
-    specialize_operator: true,
-    operator: true,
-    inject_doc_comment: true,
-    macro_bang: true,
-    syntactic_name_ref_highlighting: false,
+use crate::{
+    fixture, FileRange, HighlightConfig, HighlightExportConfig, HighlightExportFormat,
+    HighlightTheme, HlMod, HlTag, InjectedLanguage, StringInjection, TextRange,
 };
 
+fn hl_config() -> HighlightConfig {
+    HighlightConfig {
+        strings: true,
+        punctuation: true,
+        specialize_punctuation: true,
+        specialize_operator: true,
+        operator: true,
+        inject_doc_comment: true,
+        inject_strings: true,
+        string_injections: StringInjection::defaults(),
+        macro_bang: true,
+        syntactic_name_ref_highlighting: false,
+        syntax_only: false,
+    }
+}
+
 #[test]
 fn attributes() {
     check_highlighting(
@@ -563,6 +571,7 @@ fn calls_autoref(&self) {}
 fn main() {
     let x = &5 as *const _ as *const usize;
     let u = Union { b: 0 };
+    let fn_ptr: unsafe fn() = unsafe_fn;
 
     id! {
         unsafe { unsafe_deref!() }
@@ -581,6 +590,9 @@ fn main() {
         }
         Struct { field: 0 }.unsafe_method();
 
+        // unsafe fn pointer call
+        fn_ptr();
+
         // unsafe deref
         *x;
 
@@ -1008,7 +1020,7 @@ struct Foo {
     // The "x"
     let highlights = &analysis
         .highlight_range(
-            HL_CONFIG,
+            hl_config(),
             FileRange { file_id, range: TextRange::at(45.into(), 1.into()) },
         )
         .unwrap();
@@ -1025,7 +1037,7 @@ macro_rules! test {}
 }"#
         .trim(),
     );
-    let _ = analysis.highlight(HL_CONFIG, file_id).unwrap();
+    let _ = analysis.highlight(hl_config(), file_id).unwrap();
 }
 
 #[test]
@@ -1045,7 +1057,187 @@ trait Trait {}
 fn foo(x: &fn(&dyn Trait)) {}
 "#,
     );
-    let _ = analysis.highlight(HL_CONFIG, file_id).unwrap();
+    let _ = analysis.highlight(hl_config(), file_id).unwrap();
+}
+
+#[test]
+fn export_highlighted_selection() {
+    let (analysis, frange) = fixture::range(
+        r#"
+fn main() {
+    let mut count = 0u8;
+    $0count += 1;
+    let less = count < 2;$0
+}
+"#,
+    );
+    let mut config = HighlightExportConfig {
+        format: HighlightExportFormat::Html,
+        theme: HighlightTheme::default(),
+        binding_types: true,
+    };
+    let html = analysis.export_highlighted(frange.file_id, Some(frange.range), &config).unwrap();
+
+    assert!(html.starts_with(
+        r#"<pre style="color: #DCDCCC; background: #3F3F3F; padding: 0.4em;"><code>"#
+    ));
+    assert!(html.ends_with("</code></pre>"));
+    assert!(!html.contains("main"));
+    assert!(html.contains(
+        r#"<span style="color: #DCDCCC; text-decoration: underline;" title="u8">count</span>"#
+    ));
+    assert!(html.contains(r#"title="bool">less</span>"#));
+    assert!(html.contains(" &lt; "));
+
+    config.format = HighlightExportFormat::Markdown;
+    config.binding_types = false;
+    let markdown =
+        analysis.export_highlighted(frange.file_id, Some(frange.range), &config).unwrap();
+    assert_eq!(markdown, "```rust\ncount += 1;\n    let less = count < 2;\n```\n");
+}
+
+#[test]
+fn export_markdown_fence_is_longer_than_backtick_runs() {
+    let (analysis, file_id) = fixture::file(
+        r#"
+/// ```
+/// let x = 1;
+/// ```
+fn f() {}
+"#,
+    );
+    let config = HighlightExportConfig {
+        format: HighlightExportFormat::Markdown,
+        theme: HighlightTheme::default(),
+        binding_types: false,
+    };
+    let markdown = analysis.export_highlighted(file_id, None, &config).unwrap();
+    assert!(markdown.starts_with("````rust\n/// ```\n"));
+    assert!(markdown.ends_with("fn f() {}\n````\n"));
+}
+
+#[test]
+fn syntax_only_highlighting() {
+    let (analysis, file_id) = fixture::file(
+        r#"
+struct Foo;
+fn main() {
+    let foo = Foo;
+    foo.bar();
+}
+"#,
+    );
+    let text = analysis.file_text(file_id).unwrap();
+    let config = HighlightConfig { syntax_only: true, ..hl_config() };
+    let highlights: Vec<_> = analysis
+        .highlight(config, file_id)
+        .unwrap()
+        .into_iter()
+        .map(|it| (text[it.range].to_string(), it.highlight.to_string()))
+        .collect();
+
+    let has =
+        |text: &str, highlight: &str| highlights.iter().any(|(t, h)| t == text && h == highlight);
+    assert!(has("Foo", "struct.declaration"));
+    assert!(has("foo", "variable.declaration"));
+    assert!(has("Foo", "struct"));
+    // `bar` does not exist, but is highlighted as a method anyways.
+    assert!(has("bar", "function"));
+    assert!(has("let", "keyword"));
+    assert!(highlights.iter().all(|(_, h)| h != "unresolved_reference"));
+}
+
+#[test]
+fn injected_languages_in_strings() {
+    let (analysis, file_id) = fixture::file(
+        r#"
+//- /main.rs crate:main deps:regex,sqlx
+use regex::Regex;
+
+fn main() {
+    let re = Regex::new(r"^\d+[a-z]*$");
+    let query = sqlx::query!("SELECT name FROM users WHERE id = $1", 5);
+    let plain = "SELECT \\d";
+}
+//- /regex.rs crate:regex
+pub struct Regex;
+impl Regex {
+    pub fn new(re: &str) -> Regex { Regex }
+}
+//- /sqlx.rs crate:sqlx
+#[macro_export]
+macro_rules! query {
+    ($query:literal $(, $args:expr)*) => { ($query, $($args),*) };
+}
+"#,
+    );
+    let text = analysis.file_text(file_id).unwrap();
+    let highlights: Vec<_> = analysis
+        .highlight(hl_config(), file_id)
+        .unwrap()
+        .into_iter()
+        .map(|it| (text[it.range].to_string(), it.highlight.to_string()))
+        .collect();
+
+    let has =
+        |text: &str, highlight: &str| highlights.iter().any(|(t, h)| t == text && h == highlight);
+    assert!(has("\\d", "escape_sequence.injected"));
+    assert!(has("[", "bracket.injected"));
+    assert!(has("SELECT", "keyword.injected"));
+    assert!(has("$1", "value_param.injected"));
+    assert!(!has("FROM", "keyword"));
+    // Strings not passed to an injection target are left alone.
+    assert_eq!(highlights.iter().filter(|(_, h)| h.ends_with(".injected")).count(), 12);
+
+    let config = HighlightConfig { inject_strings: false, ..hl_config() };
+    let highlights = analysis.highlight(config, file_id).unwrap();
+    assert!(highlights.iter().all(|it| !it.highlight.mods.contains(HlMod::Injected)));
+}
+
+#[test]
+fn custom_string_injections() {
+    let (analysis, file_id) = fixture::file(
+        r#"
+//- /main.rs crate:main deps:db
+fn main() {
+    db::Client.execute("ignored", "SELECT 1");
+    db::sql!("ignored", "SELECT 2");
+}
+//- /db.rs crate:db
+pub struct Client;
+impl Client {
+    pub fn execute(&self, name: &str, query: &str) {}
+}
+#[macro_export]
+macro_rules! sql {
+    ($($query:literal),*) => { ($($query),*) };
+}
+"#,
+    );
+    let config = HighlightConfig {
+        string_injections: vec![
+            StringInjection {
+                path: "db::Client::execute".to_owned(),
+                argument: 1,
+                language: InjectedLanguage::Sql,
+            },
+            StringInjection {
+                path: "db::sql".to_owned(),
+                argument: 1,
+                language: InjectedLanguage::Sql,
+            },
+        ],
+        ..hl_config()
+    };
+    let text = analysis.file_text(file_id).unwrap();
+    let injected: Vec<_> = analysis
+        .highlight(config, file_id)
+        .unwrap()
+        .into_iter()
+        .filter(|it| it.highlight.mods.contains(HlMod::Injected))
+        .map(|it| text[it.range].to_string())
+        .collect();
+    assert_eq!(injected, ["SELECT", "1", "SELECT", "2"]);
 }
 
 /// Highlights the code given by the `ra_fixture` argument, renders the
@@ -1069,7 +1261,7 @@ fn benchmark_syntax_highlighting_long_struct() {
     let hash = {
         let _pt = bench("syntax highlighting long struct");
         analysis
-            .highlight(HL_CONFIG, file_id)
+            .highlight(hl_config(), file_id)
             .unwrap()
             .iter()
             .filter(|it| it.highlight.tag == HlTag::Symbol(SymbolKind::Struct))
@@ -1095,7 +1287,7 @@ fn syntax_highlighting_not_quadratic() {
             let time = Instant::now();
 
             let hash = analysis
-                .highlight(HL_CONFIG, file_id)
+                .highlight(hl_config(), file_id)
                 .unwrap()
                 .iter()
                 .filter(|it| it.highlight.tag == HlTag::Symbol(SymbolKind::Struct))
@@ -1120,7 +1312,7 @@ fn benchmark_syntax_highlighting_parser() {
     let hash = {
         let _pt = bench("syntax highlighting parser");
         analysis
-            .highlight(HL_CONFIG, file_id)
+            .highlight(hl_config(), file_id)
             .unwrap()
             .iter()
             .filter(|it| it.highlight.tag == HlTag::Symbol(SymbolKind::Function))
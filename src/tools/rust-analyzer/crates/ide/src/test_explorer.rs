@@ -0,0 +1,392 @@
+//! Discovers the tests of the workspace as a tree of crates, modules and test functions, for
+//! editors providing a test explorer.
+
+use hir::{Crate, Module, ModuleDef, Semantics};
+use ide_db::{
+    base_db::{CrateGraph, CrateId, FileId, SourceDatabase, SourceDatabaseExt},
+    RootDatabase,
+};
+use syntax::TextRange;
+
+use crate::{runnables::runnable_fn, NavigationTarget, Runnable, RunnableKind, TryToNav};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestItemKind {
+    Crate,
+    Module,
+    Function,
+}
+
+/// A node of the test tree.
+///
+/// Items are identified by their path, starting with the display name of their crate, like
+/// `my_crate::tests::it_works`. For test functions, the part after the crate name is exactly the
+/// name `libtest` reports for them, which is what allows mapping test results back to items.
+#[derive(Debug)]
+pub struct TestItem {
+    pub id: String,
+    pub kind: TestItemKind,
+    pub label: String,
+    pub parent: Option<String>,
+    pub file: Option<FileId>,
+    pub text_range: Option<TextRange>,
+    pub runnable: Option<Runnable>,
+}
+
+impl TestItem {
+    fn for_crate(name: String, root_file: FileId) -> TestItem {
+        TestItem {
+            id: name.clone(),
+            kind: TestItemKind::Crate,
+            label: name,
+            parent: None,
+            file: Some(root_file),
+            text_range: None,
+            runnable: None,
+        }
+    }
+}
+
+// Feature: Test Explorer
+//
+// Lists the tests of the workspace crates, grouped by crate and module, so that editors can
+// present them in a test explorer, run them and show their results.
+pub(crate) fn discover_test_roots(db: &RootDatabase) -> Vec<TestItem> {
+    let crate_graph = db.crate_graph();
+    crate_graph
+        .iter()
+        .filter(|&krate| is_workspace_crate(db, &crate_graph, krate))
+        .filter_map(|krate| {
+            let data = &crate_graph[krate];
+            Some(TestItem::for_crate(data.display_name.as_ref()?.to_string(), data.root_file_id))
+        })
+        .collect()
+}
+
+pub(crate) fn discover_tests_in_crate_by_test_id(
+    db: &RootDatabase,
+    crate_test_id: &str,
+) -> Vec<TestItem> {
+    let crate_graph = db.crate_graph();
+    match find_crate_by_test_id(db, &crate_graph, crate_test_id) {
+        Some(krate) => discover_tests_in_crate(db, krate),
+        None => Vec::new(),
+    }
+}
+
+pub(crate) fn discover_tests_in_crate(db: &RootDatabase, krate: CrateId) -> Vec<TestItem> {
+    let crate_graph = db.crate_graph();
+    if !is_workspace_crate(db, &crate_graph, krate) {
+        return Vec::new();
+    }
+    let Some(name) = &crate_graph[krate].display_name else { return Vec::new() };
+    let name = name.to_string();
+    let root_module = Crate::from(krate).root_module(db);
+
+    let mut res = vec![TestItem::for_crate(name.clone(), crate_graph[krate].root_file_id)];
+    res.extend(discover_tests_in_module(db, root_module, name, None));
+    res
+}
+
+/// The tests declared in a file, see [`discover_tests_in_file`].
+#[derive(Debug, Default)]
+pub struct FileTests {
+    /// The tests of the file, preceded by the module of the file and its parents.
+    pub items: Vec<TestItem>,
+    /// The ids of the modules whose children are all declared in the file: the module of the
+    /// file and its inline submodules. Children of these missing from `items` no longer exist.
+    pub scope: Vec<String>,
+}
+
+pub(crate) fn discover_tests_in_file(db: &RootDatabase, file_id: FileId) -> FileTests {
+    let sema = Semantics::new(db);
+    let Some(module) = sema.to_module_def(file_id) else { return FileTests::default() };
+    let crate_graph = db.crate_graph();
+    if !is_workspace_crate(db, &crate_graph, module.krate().into()) {
+        return FileTests::default();
+    }
+    let Some((parents, id)) = module_with_parents(db, module) else {
+        return FileTests::default();
+    };
+    let mut scope = vec![id.clone()];
+    let tests = discover_tests_in_module(db, module, id, Some(&mut scope));
+    let items =
+        if tests.is_empty() { Vec::new() } else { parents.into_iter().chain(tests).collect() };
+    FileTests { items, scope }
+}
+
+/// Crates whose root lives in the workspace, as opposed to dependencies and the sysroot.
+fn is_workspace_crate(db: &RootDatabase, crate_graph: &CrateGraph, krate: CrateId) -> bool {
+    let root_file = crate_graph[krate].root_file_id;
+    !db.source_root(db.file_source_root(root_file)).is_library
+}
+
+fn find_crate_by_test_id(
+    db: &RootDatabase,
+    crate_graph: &CrateGraph,
+    crate_test_id: &str,
+) -> Option<CrateId> {
+    // Display names aren't unique across a crate graph, but they are within the workspace crates
+    // of the targets we show tests for, except for a binary named like its package's library.
+    crate_graph.iter().find(|&krate| {
+        is_workspace_crate(db, crate_graph, krate)
+            && crate_graph[krate].display_name.as_ref().map_or(false, |it| &**it == crate_test_id)
+    })
+}
+
+fn module_name(db: &RootDatabase, module: Module) -> String {
+    module.name(db).map_or_else(|| "[mod without name]".to_owned(), |it| it.to_string())
+}
+
+/// Returns the items for `module` and its parents, up to its crate, and the id of `module`.
+fn module_with_parents(db: &RootDatabase, module: Module) -> Option<(Vec<TestItem>, String)> {
+    let Some(parent) = module.parent(db) else {
+        let krate = module.krate();
+        let name = krate.display_name(db)?.to_string();
+        return Some((vec![TestItem::for_crate(name.clone(), krate.root_file(db))], name));
+    };
+    let (mut res, parent_id) = module_with_parents(db, parent)?;
+    let label = module_name(db, module);
+    let id = format!("{parent_id}::{label}");
+    let nav = NavigationTarget::from_module_to_decl(db, module);
+    res.push(TestItem {
+        id: id.clone(),
+        kind: TestItemKind::Module,
+        label,
+        parent: Some(parent_id),
+        file: Some(nav.file_id),
+        text_range: Some(nav.focus_or_full_range()),
+        runnable: None,
+    });
+    Some((res, id))
+}
+
+/// Returns the test functions of `module` and its submodules, along with the submodules
+/// containing tests.
+///
+/// With a `file_scope`, only the contents of inline submodules are returned, as outline ones
+/// belong to another file, and the ids of the inline submodules are added to the scope.
+fn discover_tests_in_module(
+    db: &RootDatabase,
+    module: Module,
+    module_id: String,
+    mut file_scope: Option<&mut Vec<String>>,
+) -> Vec<TestItem> {
+    let sema = Semantics::new(db);
+
+    let mut res = Vec::new();
+    for def in module.declarations(db) {
+        match def {
+            ModuleDef::Module(child) => {
+                let label = module_name(db, child);
+                let child_id = format!("{module_id}::{label}");
+                let in_this_file = file_scope.is_some() && is_inline(db, child);
+                let child_scope = if in_this_file { file_scope.as_deref_mut() } else { None };
+                let child_tests =
+                    discover_tests_in_module(db, child, child_id.clone(), child_scope);
+                if child_tests.is_empty() {
+                    continue;
+                }
+                let nav = NavigationTarget::from_module_to_decl(db, child);
+                res.push(TestItem {
+                    id: child_id.clone(),
+                    kind: TestItemKind::Module,
+                    label,
+                    parent: Some(module_id.clone()),
+                    file: Some(nav.file_id),
+                    text_range: Some(nav.focus_or_full_range()),
+                    runnable: None,
+                });
+                match &mut file_scope {
+                    Some(scope) if in_this_file => {
+                        scope.push(child_id);
+                        res.extend(child_tests);
+                    }
+                    Some(_) => (),
+                    None => res.extend(child_tests),
+                }
+            }
+            ModuleDef::Function(func) => {
+                let Some(runnable) = runnable_fn(&sema, func) else { continue };
+                if !matches!(runnable.kind, RunnableKind::Test { .. }) {
+                    continue;
+                }
+                let nav = func.try_to_nav(db);
+                let label = func.name(db).to_string();
+                res.push(TestItem {
+                    id: format!("{module_id}::{label}"),
+                    kind: TestItemKind::Function,
+                    label,
+                    parent: Some(module_id.clone()),
+                    file: nav.as_ref().map(|it| it.file_id),
+                    text_range: nav.as_ref().map(|it| it.focus_or_full_range()),
+                    runnable: Some(runnable),
+                });
+            }
+            _ => (),
+        }
+    }
+    res
+}
+
+fn is_inline(db: &RootDatabase, module: Module) -> bool {
+    matches!(module.definition_source(db).value, hir::ModuleSource::Module(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn render(items: Vec<super::TestItem>) -> String {
+        items
+            .into_iter()
+            .map(|it| {
+                let parent = it.parent.map(|it| format!(" in {it}")).unwrap_or_default();
+                format!("{:?} {}{parent}\n", it.kind, it.id)
+            })
+            .collect()
+    }
+
+    fn check_roots(ra_fixture: &str, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        expect.assert_eq(&render(analysis.discover_test_roots().unwrap()));
+    }
+
+    fn check_crate(ra_fixture: &str, crate_test_id: &str, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        let tests = analysis.discover_tests_in_crate_by_test_id(crate_test_id).unwrap();
+        expect.assert_eq(&render(tests));
+    }
+
+    fn check_file(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let tests = analysis.discover_tests_in_file(position.file_id).unwrap();
+        let scope: String = tests.scope.iter().map(|it| format!("scope {it}\n")).collect();
+        expect.assert_eq(&(render(tests.items) + &scope));
+    }
+
+    #[test]
+    fn roots_are_the_workspace_crates() {
+        check_roots(
+            r#"
+//- /main.rs crate:main deps:foo
+fn main() {}
+//- /foo/lib.rs crate:foo
+pub fn foo() {}
+"#,
+            expect![[r#"
+                Crate main
+                Crate foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn crate_tests_are_grouped_by_module() {
+        check_crate(
+            r#"
+//- /lib.rs crate:foo
+mod helpers;
+
+#[test]
+fn at_root() {}
+
+fn not_a_test() {}
+
+mod tests {
+    #[test]
+    fn first() {}
+
+    #[test]
+    #[ignore]
+    fn second() {}
+
+    mod nested {
+        #[test]
+        fn third() {}
+    }
+
+    mod without_tests {
+        fn helper() {}
+    }
+}
+//- /helpers.rs
+#[test]
+fn in_outline_module() {}
+"#,
+            "foo",
+            expect![[r#"
+                Crate foo
+                Module foo::helpers in foo
+                Function foo::helpers::in_outline_module in foo::helpers
+                Function foo::at_root in foo
+                Module foo::tests in foo
+                Function foo::tests::first in foo::tests
+                Function foo::tests::second in foo::tests
+                Module foo::tests::nested in foo::tests
+                Function foo::tests::nested::third in foo::tests::nested
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unknown_crate_has_no_tests() {
+        check_crate(
+            r#"
+//- /lib.rs crate:foo
+#[test]
+fn test() {}
+"#,
+            "bar",
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn file_tests_include_their_parents() {
+        check_file(
+            r#"
+//- /lib.rs crate:foo
+mod outer;
+//- /outer.rs
+mod inner;
+
+mod tests {
+    #[test]
+    fn in_outer$0() {}
+}
+//- /outer/inner.rs
+#[test]
+fn in_inner() {}
+"#,
+            expect![[r#"
+                Crate foo
+                Module foo::outer in foo
+                Module foo::outer::inner in foo::outer
+                Module foo::outer::tests in foo::outer
+                Function foo::outer::tests::in_outer in foo::outer::tests
+                scope foo::outer
+                scope foo::outer::tests
+            "#]],
+        );
+    }
+
+    #[test]
+    fn file_without_tests_only_has_a_scope() {
+        check_file(
+            r#"
+//- /lib.rs crate:foo
+mod outer;
+//- /outer.rs
+mod tests {
+    fn not_a_test$0() {}
+}
+"#,
+            expect![[r#"
+                scope foo::outer
+            "#]],
+        );
+    }
+}
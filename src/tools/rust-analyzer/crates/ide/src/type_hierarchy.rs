@@ -0,0 +1,177 @@
+//! Entry point for type-hierarchy
+
+use either::Either;
+use hir::{HirDisplay, Semantics};
+use ide_db::{
+    defs::{Definition, IdentClass},
+    helpers::pick_best_token,
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{AstNode, SyntaxKind::*, TextRange, T};
+
+use crate::{FilePosition, NavigationTarget, RangeInfo, TryToNav};
+
+// Feature: Type Hierarchy
+//
+// Shows the supertraits and the implementing types of a trait, or the traits implemented by a
+// struct, enum or union. Each level of the hierarchy is only computed once it is expanded.
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantics::new(db);
+    let (range, defs) = hierarchy_defs(&sema, position)?;
+    let navs = defs.into_iter().filter_map(|def| def.try_to_nav(db)).collect();
+    Some(RangeInfo::new(range, navs))
+}
+
+/// The direct supertraits of a trait, or the traits implemented by an ADT.
+pub(crate) fn supertypes(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, defs) = hierarchy_defs(&sema, position)?;
+    let navs = defs
+        .into_iter()
+        .flat_map(|def| match def {
+            Definition::Trait(trait_) => trait_.direct_supertraits(db),
+            Definition::Adt(adt) => hir::Impl::all_for_type(db, adt.ty(db))
+                .into_iter()
+                .filter_map(|impl_| impl_.trait_(db))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .unique()
+        .filter_map(|trait_| trait_.try_to_nav(db))
+        .collect();
+    Some(navs)
+}
+
+/// The types implementing a trait.
+pub(crate) fn subtypes(db: &RootDatabase, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let (_, defs) = hierarchy_defs(&sema, position)?;
+    let navs = defs
+        .into_iter()
+        .flat_map(|def| match def {
+            Definition::Trait(trait_) => hir::Impl::all_for_trait(db, trait_),
+            _ => Vec::new(),
+        })
+        .map(|impl_| match impl_.self_ty(db).as_adt() {
+            Some(adt) => Either::Left(adt),
+            None => Either::Right(impl_),
+        })
+        .unique()
+        .filter_map(|it| match it {
+            Either::Left(adt) => adt.try_to_nav(db),
+            // Types without a definition to navigate to, like `i32` or the type parameter of a
+            // blanket impl, are represented by their impl.
+            Either::Right(impl_) => {
+                let mut nav = impl_.try_to_nav(db)?;
+                nav.name = impl_.self_ty(db).display(db).to_string().into();
+                Some(nav)
+            }
+        })
+        .collect();
+    Some(navs)
+}
+
+/// The traits and ADTs referred to by the token at `position`.
+fn hierarchy_defs(
+    sema: &Semantics<'_, RootDatabase>,
+    position: FilePosition,
+) -> Option<(TextRange, Vec<Definition>)> {
+    let file = sema.parse(position.file_id);
+    let token =
+        pick_best_token(file.syntax().token_at_offset(position.offset), |kind| match kind {
+            IDENT | T![Self] => 1,
+            _ => 0,
+        })?;
+    let range = token.text_range();
+    let defs = sema
+        .descend_into_macros(token)
+        .into_iter()
+        .filter_map(|token| IdentClass::classify_token(sema, &token))
+        .flat_map(IdentClass::definitions)
+        .filter_map(|def| match def {
+            Definition::Trait(_) | Definition::Adt(_) => Some(def),
+            Definition::SelfType(impl_) => impl_.self_ty(sema.db).as_adt().map(Definition::Adt),
+            _ => None,
+        })
+        .unique()
+        .collect();
+    Some((range, defs))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+
+    use crate::{fixture, FilePosition};
+
+    fn check_hierarchy(
+        ra_fixture: &str,
+        expected: Expect,
+        expected_supertypes: Expect,
+        expected_subtypes: Expect,
+    ) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+
+        let mut navs = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        expected.assert_eq(&nav.debug_render());
+
+        let item_pos =
+            FilePosition { file_id: nav.file_id, offset: nav.focus_or_full_range().start() };
+        let supertypes = analysis.supertypes(item_pos).unwrap().unwrap();
+        expected_supertypes
+            .assert_eq(&supertypes.into_iter().map(|it| it.debug_render()).sorted().join("\n"));
+
+        let subtypes = analysis.subtypes(item_pos).unwrap().unwrap();
+        expected_subtypes
+            .assert_eq(&subtypes.into_iter().map(|it| it.debug_render()).sorted().join("\n"));
+    }
+
+    #[test]
+    fn test_type_hierarchy_trait() {
+        check_hierarchy(
+            r#"
+trait Named {}
+trait Shape: Named {}
+struct Square;
+impl Named for Square {}
+impl Shape for Square {}
+impl Shape for i32 {}
+fn draw(_: &dyn Sha$0pe) {}
+"#,
+            expect![["Shape Trait FileId(0) 15..36 21..26"]],
+            expect![["Named Trait FileId(0) 0..14 6..11"]],
+            expect![[r#"
+                Square Struct FileId(0) 37..51 44..50
+                i32 Impl FileId(0) 102..123 117..120"#]],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_struct() {
+        check_hierarchy(
+            r#"
+trait Named {}
+trait Shape {}
+struct Squ$0are;
+impl Named for Square {}
+impl Shape for Square {}
+impl Square {}
+"#,
+            expect![["Square Struct FileId(0) 30..44 37..43"]],
+            expect![[r#"
+                Named Trait FileId(0) 0..14 6..11
+                Shape Trait FileId(0) 15..29 21..26"#]],
+            expect![[]],
+        );
+    }
+}
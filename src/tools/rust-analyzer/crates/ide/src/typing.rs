@@ -22,7 +22,7 @@
 use syntax::{
     algo::{ancestors_at_offset, find_node_at_offset},
     ast::{self, edit::IndentLevel, AstToken},
-    AstNode, Parse, SourceFile, SyntaxKind, TextRange, TextSize, T,
+    AstNode, Parse, SourceFile, SyntaxKind, SyntaxToken, TextRange, TextSize, T,
 };
 
 use text_edit::{Indel, TextEdit};
@@ -290,16 +290,11 @@ fn on_dot_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
     };
 
     let receiver_is_multiline = receiver.syntax().text().find_char('\n').is_some();
-    let target_indent = match (receiver, receiver_is_multiline) {
-        // if receiver is multiline field or method call, just take the previous `.` indentation
-        (ast::Expr::MethodCallExpr(expr), true) => {
-            expr.dot_token().as_ref().map(IndentLevel::from_token)
-        }
-        (ast::Expr::FieldExpr(expr), true) => {
-            expr.dot_token().as_ref().map(IndentLevel::from_token)
-        }
+    let target_indent = match (last_chain_dot(&receiver), receiver_is_multiline) {
+        // if receiver is a multiline chain, just take the previous `.` indentation
+        (Some(dot), true) => Some(IndentLevel::from_token(&dot)),
         // if receiver is multiline expression, just keeps its indentation
-        (_, true) => Some(IndentLevel::from_node(&parent)),
+        (None, true) => Some(IndentLevel::from_node(&parent)),
         _ => None,
     };
     let target_indent = match target_indent {
@@ -316,6 +311,17 @@ fn on_dot_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
     Some(TextEdit::replace(TextRange::new(offset - current_indent_len, offset), target_indent))
 }
 
+/// Returns the `.` of the last link of a chain, looking through `?` like chaining hints do.
+fn last_chain_dot(expr: &ast::Expr) -> Option<SyntaxToken> {
+    match expr {
+        ast::Expr::MethodCallExpr(expr) => expr.dot_token(),
+        ast::Expr::FieldExpr(expr) => expr.dot_token(),
+        ast::Expr::AwaitExpr(expr) => expr.dot_token(),
+        ast::Expr::TryExpr(expr) => last_chain_dot(&expr.expr()?),
+        _ => None,
+    }
+}
+
 /// Add closing `>` for generic arguments/parameters.
 fn on_left_angle_typed(file: &SourceFile, offset: TextSize) -> Option<ExtendedTextEdit> {
     let file_text = file.syntax().text();
@@ -672,6 +678,44 @@ fn main() {
         );
     }
 
+    #[test]
+    fn indents_continued_chain_call_after_try_and_await() {
+        type_char(
+            '.',
+            r#"
+fn main() {
+    xs.foo()
+        .first()?
+    $0
+}
+            "#,
+            r#"
+fn main() {
+    xs.foo()
+        .first()?
+        .
+}
+            "#,
+        );
+        type_char(
+            '.',
+            r#"
+async fn main() {
+    xs.foo()
+        .await
+    $0
+}
+            "#,
+            r#"
+async fn main() {
+    xs.foo()
+        .await
+        .
+}
+            "#,
+        );
+    }
+
     #[test]
     fn indents_middle_of_chain_call() {
         type_char(
@@ -1,12 +1,20 @@
 use std::sync::Arc;
 
-use dot::{Id, LabelText};
+use dot::{Id, LabelText, Style};
 use ide_db::{
-    base_db::{CrateGraph, CrateId, Dependency, SourceDatabase, SourceDatabaseExt},
+    base_db::{CrateGraph, CrateId, Dependency, DependencyKind, SourceDatabase, SourceDatabaseExt},
     RootDatabase,
 };
 use stdx::hash::NoHashHashSet;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewCrateGraphConfig {
+    /// Include *all* crates, not just crates in the workspace.
+    pub full: bool,
+    pub dev_deps: bool,
+    pub build_deps: bool,
+}
+
 // Feature: View Crate Graph
 //
 // Renders the currently loaded crate graph as an SVG graphic. Requires the `dot` tool, which
@@ -14,35 +22,66 @@
 //
 // Only workspace crates are included, no crates.io dependencies or sysroot crates.
 //
+// Dev-dependencies are drawn dashed and build-dependencies dotted. Both can be left out with the
+// `rust-analyzer.crateGraph.devDependencies` and `rust-analyzer.crateGraph.buildDependencies`
+// settings, along with the crates only reachable through them.
+//
 // |===
 // | Editor  | Action Name
 //
 // | VS Code | **rust-analyzer: View Crate Graph**
 // |===
-pub(crate) fn view_crate_graph(db: &RootDatabase, full: bool) -> Result<String, String> {
+pub(crate) fn view_crate_graph(
+    db: &RootDatabase,
+    config: ViewCrateGraphConfig,
+) -> Result<String, String> {
     let crate_graph = db.crate_graph();
-    let crates_to_render = crate_graph
-        .iter()
-        .filter(|krate| {
-            if full {
-                true
-            } else {
-                // Only render workspace crates
-                let root_id = db.file_source_root(crate_graph[*krate].root_file_id);
-                !db.source_root(root_id).is_library
+    let workspace_crates = crate_graph.iter().filter(|krate| {
+        let root_id = db.file_source_root(crate_graph[*krate].root_file_id);
+        !db.source_root(root_id).is_library
+    });
+    let crates_to_render = if !config.full {
+        workspace_crates.collect()
+    } else if config.dev_deps && config.build_deps {
+        crate_graph.iter().collect()
+    } else {
+        // Leave out the crates which are only reachable through the hidden dependencies.
+        let mut reachable = NoHashHashSet::default();
+        let mut stack: Vec<_> = workspace_crates.collect();
+        while let Some(krate) = stack.pop() {
+            if reachable.insert(krate) {
+                stack.extend(
+                    crate_graph[krate]
+                        .dependencies
+                        .iter()
+                        .filter(|dep| config.includes(dep))
+                        .map(|dep| dep.crate_id),
+                );
             }
-        })
-        .collect();
-    let graph = DotCrateGraph { graph: crate_graph, crates_to_render };
+        }
+        reachable
+    };
+    let graph = DotCrateGraph { graph: crate_graph, crates_to_render, config };
 
     let mut dot = Vec::new();
     dot::render(&graph, &mut dot).unwrap();
     Ok(String::from_utf8(dot).unwrap())
 }
 
+impl ViewCrateGraphConfig {
+    fn includes(&self, dep: &Dependency) -> bool {
+        match dep.kind() {
+            DependencyKind::Normal => true,
+            DependencyKind::Dev => self.dev_deps,
+            DependencyKind::Build => self.build_deps,
+        }
+    }
+}
+
 struct DotCrateGraph {
     graph: Arc<CrateGraph>,
     crates_to_render: NoHashHashSet<CrateId>,
+    config: ViewCrateGraphConfig,
 }
 
 type Edge<'a> = (CrateId, &'a Dependency);
@@ -59,7 +98,9 @@ fn edges(&'a self) -> dot::Edges<'a, Edge<'a>> {
                 self.graph[*krate]
                     .dependencies
                     .iter()
-                    .filter(|dep| self.crates_to_render.contains(&dep.crate_id))
+                    .filter(|dep| {
+                        self.config.includes(dep) && self.crates_to_render.contains(&dep.crate_id)
+                    })
                     .map(move |dep| (*krate, dep))
             })
             .collect()
@@ -91,4 +132,12 @@ fn node_label(&'a self, n: &CrateId) -> LabelText<'a> {
         let name = self.graph[*n].display_name.as_ref().map_or("(unnamed crate)", |name| &*name);
         LabelText::LabelStr(name.into())
     }
+
+    fn edge_style(&'a self, edge: &Edge<'a>) -> Style {
+        match edge.1.kind() {
+            DependencyKind::Normal => Style::None,
+            DependencyKind::Dev => Style::Dashed,
+            DependencyKind::Build => Style::Dotted,
+        }
+    }
 }
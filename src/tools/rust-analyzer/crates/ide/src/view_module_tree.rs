@@ -0,0 +1,98 @@
+use dot::{Id, LabelText, Style};
+use hir::{Module, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+
+// Feature: View Module Tree
+//
+// Renders the module tree of the crate containing the current file as an SVG graphic. Requires
+// the `dot` tool, which is part of graphviz, to be installed.
+//
+// The module of the current file is drawn in bold.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: View Module Tree**
+// |===
+pub(crate) fn view_module_tree(db: &RootDatabase, file_id: FileId) -> Result<String, String> {
+    let sema = Semantics::new(db);
+    let current = sema.to_module_def(file_id).ok_or("the file is not part of a crate")?;
+    let krate = current.krate();
+
+    let mut tree = DotModuleTree { labels: Vec::new(), edges: Vec::new(), current: 0 };
+    let mut stack = vec![(None, krate.root_module(db))];
+    while let Some((parent, module)) = stack.pop() {
+        let idx = tree.labels.len();
+        let label = match module.name(db) {
+            Some(name) => name.to_string(),
+            None => krate.display_name(db).map_or_else(|| "crate".to_owned(), |it| it.to_string()),
+        };
+        tree.labels.push(label);
+        if module == current {
+            tree.current = idx;
+        }
+        if let Some(parent) = parent {
+            tree.edges.push((parent, idx));
+        }
+        let mut children: Vec<Module> = module.children(db).collect();
+        children.sort_by_cached_key(|it| it.name(db).map(|name| name.to_string()));
+        // Pushed in reverse so that the children are numbered alphabetically.
+        stack.extend(children.into_iter().rev().map(|child| (Some(idx), child)));
+    }
+
+    let mut dot = Vec::new();
+    dot::render(&tree, &mut dot).unwrap();
+    Ok(String::from_utf8(dot).unwrap())
+}
+
+struct DotModuleTree {
+    labels: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    current: usize,
+}
+
+type Edge = (usize, usize);
+
+impl<'a> dot::GraphWalk<'a, usize, Edge> for DotModuleTree {
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.labels.len()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, Edge> {
+        self.edges.as_slice().into()
+    }
+
+    fn source(&'a self, edge: &Edge) -> usize {
+        edge.0
+    }
+
+    fn target(&'a self, edge: &Edge) -> usize {
+        edge.1
+    }
+}
+
+impl<'a> dot::Labeller<'a, usize, Edge> for DotModuleTree {
+    fn graph_id(&'a self) -> Id<'a> {
+        Id::new("rust_analyzer_module_tree").unwrap()
+    }
+
+    fn node_id(&'a self, n: &usize) -> Id<'a> {
+        Id::new(format!("_{n}")).unwrap()
+    }
+
+    fn node_shape(&'a self, _node: &usize) -> Option<LabelText<'a>> {
+        Some(LabelText::LabelStr("box".into()))
+    }
+
+    fn node_label(&'a self, n: &usize) -> LabelText<'a> {
+        LabelText::LabelStr(self.labels[*n].as_str().into())
+    }
+
+    fn node_style(&'a self, n: &usize) -> Style {
+        if *n == self.current {
+            Style::Bold
+        } else {
+            Style::None
+        }
+    }
+}
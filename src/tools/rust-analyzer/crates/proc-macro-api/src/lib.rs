@@ -38,12 +38,16 @@ pub enum ProcMacroKind {
 
 /// A handle to an external process which load dylibs with macros (.so or .dll)
 /// and runs actual macro expansion functions.
-#[derive(Debug)]
+///
+/// Clones share the same process, which can load dylibs of several workspaces as long as they
+/// were built by a toolchain it supports.
+#[derive(Debug, Clone)]
 pub struct ProcMacroServer {
     /// Currently, the proc macro process expands all procedural macros sequentially.
     ///
     /// That means that concurrent salsa requests may block each other when expanding proc macros,
-    /// which is unfortunate, but simple and good enough for the time being.
+    /// which is unfortunate, but simple and good enough for the time being. As clones share the
+    /// mutex, this includes the expansions of all workspaces using the same server.
     ///
     /// Therefore, we just wrap the `ProcMacroProcessSrv` in a mutex here.
     process: Arc<Mutex<ProcMacroProcessSrv>>,
@@ -115,7 +119,7 @@ impl ProcMacroServer {
     /// Spawns an external process as the proc macro server and returns a client connected to it.
     pub fn spawn(
         process_path: AbsPathBuf,
-        args: impl IntoIterator<Item = impl AsRef<OsStr>> + Clone,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
     ) -> io::Result<ProcMacroServer> {
         let process = ProcMacroProcessSrv::run(process_path, args)?;
         Ok(ProcMacroServer { process: Arc::new(Mutex::new(process)) })
@@ -16,22 +16,33 @@
 
 #[derive(Debug)]
 pub(crate) struct ProcMacroProcessSrv {
-    _process: Process,
+    process: Process,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
     version: u32,
+    /// The command the process was spawned with, to respawn it if it dies.
+    process_path: AbsPathBuf,
+    args: Vec<OsString>,
 }
 
 impl ProcMacroProcessSrv {
     pub(crate) fn run(
         process_path: AbsPathBuf,
-        args: impl IntoIterator<Item = impl AsRef<OsStr>> + Clone,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
     ) -> io::Result<ProcMacroProcessSrv> {
+        let args: Vec<OsString> = args.into_iter().map(|s| s.as_ref().into()).collect();
         let create_srv = |null_stderr| {
-            let mut process = Process::run(process_path.clone(), args.clone(), null_stderr)?;
+            let mut process = Process::run(process_path.clone(), &args, null_stderr)?;
             let (stdin, stdout) = process.stdio().expect("couldn't access child stdio");
 
-            io::Result::Ok(ProcMacroProcessSrv { _process: process, stdin, stdout, version: 0 })
+            io::Result::Ok(ProcMacroProcessSrv {
+                process,
+                stdin,
+                stdout,
+                version: 0,
+                process_path: process_path.clone(),
+                args: args.clone(),
+            })
         };
         let mut srv = create_srv(true)?;
         tracing::info!("sending version check");
@@ -58,7 +69,9 @@ pub(crate) fn run(
 
     pub(crate) fn version_check(&mut self) -> Result<u32, ServerError> {
         let request = Request::ApiVersionCheck {};
-        let response = self.send_task(request)?;
+        // Not `send_task`, as servers not supporting the check may exit instead of answering.
+        let mut buf = String::new();
+        let response = send_request(&mut self.stdin, &mut self.stdout, request, &mut buf)?;
 
         match response {
             Response::ApiVersionCheck(version) => Ok(version),
@@ -86,7 +99,27 @@ pub(crate) fn find_proc_macros(
 
     pub(crate) fn send_task(&mut self, req: Request) -> Result<Response, ServerError> {
         let mut buf = String::new();
-        send_request(&mut self.stdin, &mut self.stdout, req, &mut buf)
+        let res = send_request(&mut self.stdin, &mut self.stdout, req, &mut buf);
+        if res.is_err() {
+            self.respawn_if_exited();
+        }
+        res
+    }
+
+    /// Restarts the server if it died, for example because a macro aborted or overflowed its
+    /// stack, so that the other macros can still be expanded.
+    fn respawn_if_exited(&mut self) {
+        match self.process.child.try_wait() {
+            Ok(Some(status)) => {
+                tracing::warn!(%status, "proc-macro server exited, restarting it");
+                match ProcMacroProcessSrv::run(self.process_path.clone(), &self.args) {
+                    Ok(srv) => *self = srv,
+                    Err(err) => tracing::error!(%err, "failed to restart the proc-macro server"),
+                }
+            }
+            Ok(None) => (),
+            Err(err) => tracing::error!(%err, "failed to query the proc-macro server status"),
+        }
     }
 }
 
@@ -16,7 +16,7 @@
 use cargo_metadata::{camino::Utf8Path, Message};
 use la_arena::ArenaMap;
 use paths::AbsPathBuf;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use semver::Version;
 use serde::Deserialize;
 
@@ -56,7 +56,9 @@ fn is_unchanged(&self) -> bool {
 }
 
 impl WorkspaceBuildScripts {
-    fn build_command(config: &CargoConfig) -> io::Result<Command> {
+    /// The command running the build scripts of `packages`, given as package id specs, or of the
+    /// whole workspace if there are none.
+    fn build_command(config: &CargoConfig, packages: &[String]) -> io::Result<Command> {
         let mut cmd = match config.run_build_script_command.as_deref() {
             Some([program, args @ ..]) => {
                 let mut cmd = Command::new(program);
@@ -66,7 +68,13 @@ fn build_command(config: &CargoConfig) -> io::Result<Command> {
             _ => {
                 let mut cmd = Command::new(toolchain::cargo());
 
-                cmd.args(["check", "--quiet", "--workspace", "--message-format=json"]);
+                cmd.args(["check", "--quiet", "--message-format=json"]);
+                if packages.is_empty() {
+                    cmd.arg("--workspace");
+                }
+                for package in packages {
+                    cmd.args(["--package", package]);
+                }
 
                 // --all-targets includes tests, benches and examples in addition to the
                 // default lib and bins. This is an independent concept from the --target
@@ -115,6 +123,47 @@ pub(crate) fn run_for_workspace(
         workspace: &CargoWorkspace,
         progress: &dyn Fn(String),
         toolchain: &Option<Version>,
+    ) -> io::Result<WorkspaceBuildScripts> {
+        Self::run_for_packages(config, workspace, &[], progress, toolchain)
+    }
+
+    /// Re-runs the build scripts of `packages` and of their dependencies, keeping the outputs of
+    /// the other packages of the workspace.
+    pub(crate) fn rerun_for_packages(
+        &self,
+        config: &CargoConfig,
+        workspace: &CargoWorkspace,
+        packages: &[Package],
+        progress: &dyn Fn(String),
+        toolchain: &Option<Version>,
+    ) -> io::Result<WorkspaceBuildScripts> {
+        let specs: Vec<_> = packages
+            .iter()
+            .map(|&pkg| format!("{}@{}", workspace[pkg].name, workspace[pkg].version))
+            .collect();
+        let mut res = Self::run_for_packages(config, workspace, &specs, progress, toolchain)?;
+
+        let mut rebuilt = FxHashSet::default();
+        let mut stack = packages.to_vec();
+        while let Some(pkg) = stack.pop() {
+            if rebuilt.insert(pkg) {
+                stack.extend(workspace[pkg].dependencies.iter().map(|dep| dep.pkg));
+            }
+        }
+        for package in workspace.packages().filter(|pkg| !rebuilt.contains(pkg)) {
+            if let Some(output) = self.outputs.get(package) {
+                res.outputs.insert(package, output.clone());
+            }
+        }
+        Ok(res)
+    }
+
+    fn run_for_packages(
+        config: &CargoConfig,
+        workspace: &CargoWorkspace,
+        packages: &[String],
+        progress: &dyn Fn(String),
+        toolchain: &Option<Version>,
     ) -> io::Result<WorkspaceBuildScripts> {
         const RUST_1_62: Version = Version::new(1, 62, 0);
 
@@ -126,13 +175,14 @@ pub(crate) fn run_for_workspace(
         }
         .as_ref();
 
-        match Self::run_per_ws(Self::build_command(config)?, workspace, current_dir, progress) {
+        let cmd = Self::build_command(config, packages)?;
+        match Self::run_per_ws(cmd, workspace, current_dir, progress) {
             Ok(WorkspaceBuildScripts { error: Some(error), .. })
                 if toolchain.as_ref().map_or(false, |it| *it >= RUST_1_62) =>
             {
                 // building build scripts failed, attempt to build with --keep-going so
                 // that we potentially get more build data
-                let mut cmd = Self::build_command(config)?;
+                let mut cmd = Self::build_command(config, packages)?;
                 cmd.args(["-Z", "unstable-options", "--keep-going"]).env("RUSTC_BOOTSTRAP", "1");
                 let mut res = Self::run_per_ws(cmd, workspace, current_dir, progress)?;
                 res.error = Some(error);
@@ -160,7 +210,7 @@ pub(crate) fn run_once(
                 ))
             }
         };
-        let cmd = Self::build_command(config)?;
+        let cmd = Self::build_command(config, &[])?;
         // NB: Cargo.toml could have been modified between `cargo metadata` and
         // `cargo check`. We shouldn't assume that package ids we see here are
         // exactly those from `config`.
@@ -6,7 +6,7 @@
 use std::{ops, process::Command};
 
 use anyhow::{Context, Result};
-use base_db::Edition;
+use base_db::{DependencyKind, Edition};
 use cargo_metadata::{CargoOpt, MetadataCommand};
 use la_arena::{Arena, Idx};
 use paths::{AbsPath, AbsPathBuf};
@@ -32,6 +32,7 @@ pub struct CargoWorkspace {
     packages: Arena<PackageData>,
     targets: Arena<TargetData>,
     workspace_root: AbsPathBuf,
+    target_directory: AbsPathBuf,
 }
 
 impl ops::Index<Package> for CargoWorkspace {
@@ -179,7 +180,7 @@ pub struct PackageDependency {
     pub kind: DepKind,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
 pub enum DepKind {
     /// Available to the library, binary, and dev targets in the package (but not the build script).
     Normal,
@@ -189,6 +190,16 @@ pub enum DepKind {
     Build,
 }
 
+impl From<DepKind> for DependencyKind {
+    fn from(kind: DepKind) -> DependencyKind {
+        match kind {
+            DepKind::Normal => DependencyKind::Normal,
+            DepKind::Dev => DependencyKind::Dev,
+            DepKind::Build => DependencyKind::Build,
+        }
+    }
+}
+
 impl DepKind {
     fn iter(list: &[cargo_metadata::DepKindInfo]) -> impl Iterator<Item = Self> + '_ {
         let mut dep_kinds = Vec::new();
@@ -409,7 +420,10 @@ pub fn new(mut meta: cargo_metadata::Metadata) -> CargoWorkspace {
         let workspace_root =
             AbsPathBuf::assert(PathBuf::from(meta.workspace_root.into_os_string()));
 
-        CargoWorkspace { packages, targets, workspace_root }
+        let target_directory =
+            AbsPathBuf::assert(PathBuf::from(meta.target_directory.into_os_string()));
+
+        CargoWorkspace { packages, targets, workspace_root, target_directory }
     }
 
     pub fn packages(&self) -> impl Iterator<Item = Package> + ExactSizeIterator + '_ {
@@ -427,6 +441,10 @@ pub fn workspace_root(&self) -> &AbsPath {
         &self.workspace_root
     }
 
+    pub fn target_directory(&self) -> &AbsPath {
+        &self.target_directory
+    }
+
     pub fn package_flag(&self, package: &PackageData) -> String {
         if self.is_unique(&package.name) {
             package.name.clone()
@@ -112,7 +112,7 @@ pub fn new(base: &AbsPath, data: ProjectJsonData) -> ProjectJson {
                             || crate_data.root_module.starts_with(base)
                     });
                     let root_module = base.join(crate_data.root_module).normalize();
-                    let (include, exclude) = match crate_data.source {
+                    let (mut include, exclude) = match crate_data.source {
                         Some(src) => {
                             let absolutize = |dirs: Vec<PathBuf>| {
                                 dirs.into_iter()
@@ -124,6 +124,20 @@ pub fn new(base: &AbsPath, data: ProjectJsonData) -> ProjectJson {
                         None => (vec![root_module.parent().unwrap().to_path_buf()], Vec::new()),
                     };
 
+                    let mut cfg = crate_data.cfg;
+                    let mut env = crate_data.env;
+                    if let Some(build) = crate_data.build {
+                        // Like the outputs of Cargo build scripts, the build outputs are added to
+                        // the ones of the crate, and the `OUT_DIR` is loaded with the crate.
+                        cfg.extend(build.cfg);
+                        env.extend(build.env);
+                        if let Some(out_dir) = build.out_dir {
+                            let out_dir = base.join(out_dir).normalize();
+                            env.insert("OUT_DIR".to_owned(), out_dir.display().to_string());
+                            include.push(out_dir);
+                        }
+                    }
+
                     Crate {
                         display_name: crate_data
                             .display_name
@@ -138,9 +152,9 @@ pub fn new(base: &AbsPath, data: ProjectJsonData) -> ProjectJson {
                                 Dependency::new(dep_data.name, CrateId(dep_data.krate as u32))
                             })
                             .collect::<Vec<_>>(),
-                        cfg: crate_data.cfg,
+                        cfg,
                         target: crate_data.target,
-                        env: crate_data.env,
+                        env,
                         proc_macro_dylib_path: crate_data
                             .proc_macro_dylib_path
                             .map(|it| base.join(it)),
@@ -198,6 +212,7 @@ struct CrateData {
     is_proc_macro: bool,
     #[serde(default)]
     repository: Option<String>,
+    build: Option<BuildData>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -230,6 +245,16 @@ struct DepData {
     name: CrateName,
 }
 
+/// The outputs of the build script of a crate, as produced by the build system.
+#[derive(Deserialize, Debug, Clone)]
+struct BuildData {
+    out_dir: Option<PathBuf>,
+    #[serde(default)]
+    cfg: Vec<CfgFlag>,
+    #[serde(default)]
+    env: FxHashMap<String, String>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 struct CrateSource {
     include_dirs: Vec<PathBuf>,
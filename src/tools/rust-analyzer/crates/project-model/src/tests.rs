@@ -182,6 +182,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -254,6 +255,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -263,6 +265,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -335,6 +338,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -344,6 +348,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -416,6 +421,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -425,6 +431,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -594,6 +601,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -668,6 +676,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -677,6 +686,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -751,6 +761,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -760,6 +771,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -834,6 +846,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -843,6 +856,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1003,6 +1017,7 @@ fn cargo_hello_world_project_model() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1077,6 +1092,7 @@ fn cargo_hello_world_project_model() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1086,6 +1102,7 @@ fn cargo_hello_world_project_model() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1160,6 +1177,7 @@ fn cargo_hello_world_project_model() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1169,6 +1187,7 @@ fn cargo_hello_world_project_model() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1243,6 +1262,7 @@ fn cargo_hello_world_project_model() {
                                     "hello_world",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1252,6 +1272,7 @@ fn cargo_hello_world_project_model() {
                                     "libc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1388,6 +1409,7 @@ fn rust_project_hello_world_project_model() {
                                     "core",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1546,6 +1568,7 @@ fn rust_project_hello_world_project_model() {
                                     "std",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1630,6 +1653,7 @@ fn rust_project_hello_world_project_model() {
                                     "alloc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1639,6 +1663,7 @@ fn rust_project_hello_world_project_model() {
                                     "panic_unwind",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1648,6 +1673,7 @@ fn rust_project_hello_world_project_model() {
                                     "panic_abort",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1657,6 +1683,7 @@ fn rust_project_hello_world_project_model() {
                                     "core",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1666,6 +1693,7 @@ fn rust_project_hello_world_project_model() {
                                     "profiler_builtins",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1675,6 +1703,7 @@ fn rust_project_hello_world_project_model() {
                                     "unwind",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1684,6 +1713,7 @@ fn rust_project_hello_world_project_model() {
                                     "std_detect",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1693,6 +1723,7 @@ fn rust_project_hello_world_project_model() {
                                     "test",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1851,6 +1882,7 @@ fn rust_project_hello_world_project_model() {
                                     "core",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1860,6 +1892,7 @@ fn rust_project_hello_world_project_model() {
                                     "alloc",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1869,6 +1902,7 @@ fn rust_project_hello_world_project_model() {
                                     "std",
                                 ),
                                 prelude: true,
+                                kind: Normal,
                             },
                             Dependency {
                                 crate_id: CrateId(
@@ -1878,6 +1912,7 @@ fn rust_project_hello_world_project_model() {
                                     "test",
                                 ),
                                 prelude: false,
+                                kind: Normal,
                             },
                         ],
                         proc_macro: Err(
@@ -1907,3 +1942,17 @@ fn rust_project_is_proc_macro_has_proc_macro_dep() {
     // on the proc_macro sysroot crate.
     crate_data.dependencies.iter().find(|&dep| dep.name.deref() == "proc_macro").unwrap();
 }
+
+#[test]
+fn rust_project_build_outputs() {
+    let crate_graph = load_rust_project("build-outputs-project.json");
+    let crate_id = crate_graph.iter().max().unwrap();
+    let crate_data = &crate_graph[crate_id];
+
+    let out_dir = crate_data.env.get("OUT_DIR").unwrap();
+    assert!(Path::new(&out_dir).ends_with("ROOT/out"), "{out_dir}");
+    assert_eq!(crate_data.env.get("TARGET_VERSION").as_deref(), Some("1.0"));
+    assert_eq!(crate_data.env.get("CARGO_PKG_NAME").as_deref(), Some("build_outputs"));
+    assert!(crate_data.cfg_options.get_cfg_keys().any(|it| it == "has_simd"));
+    assert!(crate_data.cfg_options.get_cfg_values("feature").any(|it| it == "std"));
+}
@@ -6,8 +6,8 @@
 
 use anyhow::{format_err, Context, Result};
 use base_db::{
-    CrateDisplayName, CrateGraph, CrateId, CrateName, CrateOrigin, Dependency, Edition, Env,
-    FileId, LangCrateOrigin, ProcMacroLoadResult, TargetLayoutLoadResult,
+    CrateDisplayName, CrateGraph, CrateId, CrateName, CrateOrigin, Dependency, DependencyKind,
+    Edition, Env, FileId, LangCrateOrigin, ProcMacroLoadResult, TargetLayoutLoadResult,
 };
 use cfg::{CfgDiff, CfgOptions};
 use paths::{AbsPath, AbsPathBuf};
@@ -390,6 +390,36 @@ pub fn run_build_scripts(
         }
     }
 
+    /// Re-runs the build scripts of the packages with the given ids and of their dependencies,
+    /// keeping the build script outputs of the other packages.
+    pub fn rerun_build_scripts(
+        &self,
+        config: &CargoConfig,
+        package_ids: &FxHashSet<String>,
+        progress: &dyn Fn(String),
+    ) -> Result<WorkspaceBuildScripts> {
+        match self {
+            ProjectWorkspace::Cargo { cargo, build_scripts, toolchain, .. } => {
+                let packages: Vec<_> =
+                    cargo.packages().filter(|&pkg| package_ids.contains(&cargo[pkg].id)).collect();
+                if packages.is_empty() {
+                    return Ok(build_scripts.clone());
+                }
+                build_scripts
+                    .rerun_for_packages(config, cargo, &packages, progress, toolchain)
+                    .with_context(|| {
+                        format!(
+                            "Failed to run build scripts for {}",
+                            &cargo.workspace_root().display()
+                        )
+                    })
+            }
+            ProjectWorkspace::Json { .. } | ProjectWorkspace::DetachedFiles { .. } => {
+                Ok(WorkspaceBuildScripts::default())
+            }
+        }
+    }
+
     /// Runs the build scripts for the given [`ProjectWorkspace`]s. Depending on the invocation
     /// strategy this may run a single build process for all project workspaces.
     pub fn run_all_build_scripts(
@@ -915,7 +945,7 @@ fn cargo_to_crate_graph(
                         continue;
                     }
 
-                    add_dep(&mut crate_graph, from, name.clone(), to)
+                    add_dep_with_kind(&mut crate_graph, from, name.clone(), to, dep.kind.into())
                 }
             }
         }
@@ -1270,6 +1300,16 @@ fn add_dep_with_prelude(
     add_dep_inner(graph, from, Dependency::with_prelude(name, to, prelude))
 }
 
+fn add_dep_with_kind(
+    graph: &mut CrateGraph,
+    from: CrateId,
+    name: CrateName,
+    to: CrateId,
+    kind: DependencyKind,
+) {
+    add_dep_inner(graph, from, Dependency::with_kind(name, to, kind))
+}
+
 fn add_dep_inner(graph: &mut CrateGraph, from: CrateId, dep: Dependency) {
     if let Err(err) = graph.add_dep(from, dep) {
         tracing::error!("{}", err)
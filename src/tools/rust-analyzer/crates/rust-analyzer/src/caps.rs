@@ -148,10 +148,13 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
             "onEnter": true,
             "openCargoToml": true,
             "parentModule": true,
+            "peekRelated": true,
+            "referenceAccessKindFiltering": true,
             "runnables": {
                 "kinds": [ "cargo" ],
             },
             "ssr": true,
+            "typeHierarchy": true,
             "workspaceSymbolScopeKindFiltering": true,
         })),
     }
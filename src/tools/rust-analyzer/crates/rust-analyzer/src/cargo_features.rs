@@ -0,0 +1,586 @@
+//! Toggling the Cargo features of workspace packages from the client.
+//!
+//! The `rust-analyzer/cargoFeatures` extension request lists the features of the workspace
+//! members, and `rust-analyzer/setCargoFeature` enables or disables one of them. Only the crates
+//! of the changed package are updated in the crate graph: their `feature` cfgs are replaced and
+//! their dependencies on the optional dependencies of the package are removed or added back. Then
+//! the build scripts of the package and of its dependencies are re-run with the selected features.
+//! The workspace is only reloaded when an enabled optional dependency wasn't resolved by Cargo
+//! yet. The selected features are passed to flycheck as well, and kept when the workspace is
+//! reloaded.
+
+use cfg::{CfgAtom, CfgDiff};
+use flycheck::FlycheckConfig;
+use ide::Change;
+use ide_db::base_db::{CrateGraph, CrateId, CrateName, Dependency, FileId, SourceDatabase};
+use project_model::{
+    CargoConfig, CargoFeatures, CargoWorkspace, DepKind, ManifestPath, Package, PackageData,
+    ProjectWorkspace, Target, TargetKind,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use vfs::VfsPath;
+
+use crate::{global_state::GlobalState, lsp_ext};
+
+/// The features selected by the client, replacing the ones resolved by Cargo.
+#[derive(Default)]
+pub(crate) struct FeatureOverrides {
+    /// The requested features by Cargo package id.
+    requested: FxHashMap<String, FxHashSet<String>>,
+}
+
+impl FeatureOverrides {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.requested.is_empty()
+    }
+
+    fn enabled_features(&self, package: &PackageData) -> FxHashSet<String> {
+        match self.requested.get(&package.id) {
+            Some(requested) => with_implied_features(package, requested),
+            None => package.active_features.iter().cloned().collect(),
+        }
+    }
+
+    fn set(&mut self, package: &PackageData, feature: String, enabled: bool) {
+        let requested =
+            self.requested.entry(package.id.clone()).or_insert_with(|| explicit_features(package));
+        if enabled {
+            requested.insert(feature);
+        } else {
+            requested.remove(&feature);
+        }
+    }
+
+    /// The features to pass to Cargo for the members of `cargo`, if the client changed the
+    /// features of one of them.
+    ///
+    /// They are passed with `--no-default-features`, which applies to all members, so the
+    /// features of the other members are listed as well.
+    fn cargo_features(&self, cargo: &CargoWorkspace) -> Option<Vec<String>> {
+        let members = || cargo.packages().map(|pkg| &cargo[pkg]).filter(|it| it.is_member);
+        if !members().any(|package| self.requested.contains_key(&package.id)) {
+            return None;
+        }
+        let mut features: Vec<_> = members()
+            .flat_map(|package| {
+                let enabled: Vec<_> = match self.requested.get(&package.id) {
+                    Some(requested) => requested.iter().collect(),
+                    None => package.active_features.iter().collect(),
+                };
+                enabled.into_iter().map(move |feature| format!("{}/{feature}", package.name))
+            })
+            .collect();
+        features.sort();
+        Some(features)
+    }
+}
+
+/// The features enabled by Cargo which aren't implied by another enabled feature, so that
+/// disabling e.g. `default` also disables the features it enables.
+fn explicit_features(package: &PackageData) -> FxHashSet<String> {
+    let implied: FxHashSet<&str> = package
+        .active_features
+        .iter()
+        .filter_map(|feature| package.features.get(feature))
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    package.active_features.iter().filter(|it| !implied.contains(it.as_str())).cloned().collect()
+}
+
+/// Adds the features enabled by `features`, ignoring the ones of dependencies.
+fn with_implied_features(package: &PackageData, features: &FxHashSet<String>) -> FxHashSet<String> {
+    let mut enabled = FxHashSet::default();
+    let mut stack: Vec<&str> = features.iter().map(String::as_str).collect();
+    while let Some(feature) = stack.pop() {
+        // `name/feature` also enables the implicit feature of the optional dependency `name`,
+        // `name?/feature` doesn't. `dep:name` is not a feature of the package.
+        let feature = match feature.split_once('/') {
+            Some((dep, _)) if !dep.ends_with('?') => dep,
+            Some(_) => continue,
+            None => feature,
+        };
+        let Some(implied) = package.features.get(feature) else { continue };
+        if enabled.insert(feature.to_owned()) {
+            stack.extend(implied.iter().map(String::as_str));
+        }
+    }
+    enabled
+}
+
+/// The optional dependencies of `package`, by their name in the manifest.
+fn optional_dependencies(package: &PackageData) -> FxHashSet<&str> {
+    package.features.values().flatten().filter_map(|it| it.strip_prefix("dep:")).collect()
+}
+
+/// The optional dependencies of `package` enabled by `features`, which include the implied
+/// features.
+fn enabled_optional_dependencies<'a>(
+    package: &'a PackageData,
+    features: &FxHashSet<String>,
+) -> FxHashSet<&'a str> {
+    let optional = optional_dependencies(package);
+    features
+        .iter()
+        .filter_map(|feature| package.features.get(feature))
+        .flatten()
+        .filter_map(|it| match it.split_once('/') {
+            Some((dep, _)) => (!dep.ends_with('?')).then_some(dep),
+            None => it.strip_prefix("dep:"),
+        })
+        .filter(|dep| optional.contains(dep))
+        .collect()
+}
+
+/// Updates the `feature` cfgs of `crates` to the `enabled` features.
+fn update_feature_cfgs(
+    enabled: &FxHashSet<String>,
+    crates: &[CrateId],
+    crate_graph: &mut CrateGraph,
+) {
+    for &krate in crates {
+        let mut cfg_options = crate_graph[krate].cfg_options.clone();
+        let current: FxHashSet<_> =
+            cfg_options.get_cfg_values("feature").map(|it| it.to_string()).collect();
+        let feature_atom = |value: &String| CfgAtom::KeyValue {
+            key: "feature".into(),
+            value: value.as_str().into(),
+        };
+        let enable = enabled.difference(&current).map(feature_atom).collect();
+        let disable = current.difference(enabled).map(feature_atom).collect();
+        if let Some(diff) = CfgDiff::new(enable, disable) {
+            cfg_options.apply_diff(diff);
+        }
+        crate_graph.set_cfg_options(krate, cfg_options);
+    }
+}
+
+/// Removes the dependencies of `crates`, the crates of `package` with the kinds of their targets,
+/// on the optional dependencies which aren't enabled by the `enabled` features, and adds back the
+/// ones on the enabled optional dependencies. `lib_root` is the root file of the library of a
+/// package.
+///
+/// Returns `false` if an enabled optional dependency wasn't resolved by Cargo, so that it has no
+/// crate yet.
+fn update_optional_dependencies(
+    package: &PackageData,
+    enabled: &FxHashSet<String>,
+    crates: &[(CrateId, TargetKind)],
+    lib_root: impl Fn(Package) -> Option<FileId>,
+    crate_graph: &mut CrateGraph,
+) -> bool {
+    let enabled = enabled_optional_dependencies(package, enabled);
+    let mut resolved = true;
+    for optional in optional_dependencies(package) {
+        let name = CrateName::normalize_dashes(optional);
+        if !enabled.contains(optional) {
+            for &(krate, _) in crates {
+                crate_graph.remove_dep(krate, &name);
+            }
+            continue;
+        }
+
+        let mut deps = package
+            .dependencies
+            .iter()
+            .filter(|dep| CrateName::normalize_dashes(&dep.name) == name)
+            .peekable();
+        if deps.peek().is_none() {
+            resolved = false;
+        }
+        for dep in deps {
+            let to = lib_root(dep.pkg).and_then(|it| crate_graph.crate_id_for_crate_root(it));
+            let Some(to) = to else { continue };
+            for &(from, kind) in crates {
+                // Only build scripts depend on build dependencies, like in the crate graph of the
+                // workspace.
+                if (dep.kind == DepKind::Build) != (kind == TargetKind::BuildScript)
+                    || crate_graph[from].dependencies.iter().any(|it| it.name == name)
+                {
+                    continue;
+                }
+                let dependency = Dependency::with_kind(name.clone(), to, dep.kind.into());
+                if let Err(err) = crate_graph.add_dep(from, dependency) {
+                    tracing::error!("{}", err);
+                }
+            }
+        }
+    }
+    resolved
+}
+
+impl GlobalState {
+    pub(crate) fn cargo_features(&self) -> Vec<lsp_ext::PackageFeatures> {
+        cargo_workspaces(&self.workspaces)
+            .flat_map(|cargo| {
+                cargo
+                    .packages()
+                    .filter(|&pkg| cargo[pkg].is_member)
+                    .map(|pkg| self.package_features(&cargo[pkg]))
+            })
+            .collect()
+    }
+
+    /// Enables or disables a feature of a package, updates its crates and re-runs its build
+    /// scripts with the new features.
+    pub(crate) fn set_cargo_feature(
+        &mut self,
+        params: lsp_ext::SetCargoFeatureParams,
+    ) -> Result<lsp_ext::PackageFeatures, String> {
+        let workspaces = self.workspaces.clone();
+        let package = cargo_workspaces(&workspaces)
+            .flat_map(|cargo| cargo.packages().map(move |pkg| &cargo[pkg]))
+            .find(|package| package.id == params.package)
+            .ok_or_else(|| format!("unknown package `{}`", params.package))?;
+        if !package.is_member {
+            return Err(format!("`{}` is not a workspace member", package.name));
+        }
+        if !package.features.contains_key(&params.feature) {
+            return Err(format!("`{}` has no feature `{}`", package.name, params.feature));
+        }
+
+        let cause = format!(
+            "{} feature `{}` of `{}`",
+            if params.enabled { "enabled" } else { "disabled" },
+            params.feature,
+            package.name
+        );
+        self.feature_overrides.set(package, params.feature, params.enabled);
+
+        let mut crate_graph = CrateGraph::clone(&self.analysis_host.raw_database().crate_graph());
+        let resolved = self.apply_feature_overrides(&mut crate_graph, |it| it.id == package.id);
+        let mut change = Change::new();
+        change.set_crate_graph(crate_graph);
+        self.analysis_host.apply_change(change);
+        self.outdate_semantic_tokens();
+
+        if !resolved {
+            // Cargo has to resolve the enabled optional dependency first.
+            self.fetch_workspaces_queue.request_op(cause);
+        } else {
+            self.reload_flycheck();
+            // A fetch of all the build data which is already requested covers the package.
+            let all_requested =
+                self.fetch_build_data_queue.op_requested() && self.build_data_packages.is_none();
+            if self.config.run_build_scripts() && !all_requested {
+                self.build_data_packages
+                    .get_or_insert_with(Default::default)
+                    .insert(package.id.clone());
+                self.fetch_build_data_queue.request_op(cause);
+            }
+        }
+
+        if self.is_quiescent() {
+            self.refresh_views();
+            if self.config.publish_diagnostics() {
+                self.update_diagnostics();
+            }
+        }
+        Ok(self.package_features(package))
+    }
+
+    /// Updates the crates of the packages selected by `filter` to the features requested by the
+    /// client: their `feature` cfgs and their dependencies on optional dependencies.
+    ///
+    /// Returns `false` if an enabled optional dependency wasn't resolved by Cargo, in which case
+    /// the workspace has to be reloaded.
+    pub(crate) fn apply_feature_overrides(
+        &self,
+        crate_graph: &mut CrateGraph,
+        filter: impl Fn(&PackageData) -> bool,
+    ) -> bool {
+        let vfs = &self.vfs.read().0;
+        let mut resolved = true;
+        for cargo in cargo_workspaces(&self.workspaces) {
+            let root_file = |tgt: Target| vfs.file_id(&VfsPath::from(cargo[tgt].root.clone()));
+            for pkg in cargo.packages() {
+                let package = &cargo[pkg];
+                if !filter(package) || !self.feature_overrides.requested.contains_key(&package.id) {
+                    continue;
+                }
+                let roots: FxHashMap<_, _> = package
+                    .targets
+                    .iter()
+                    .filter_map(|&tgt| Some((root_file(tgt)?, cargo[tgt].kind)))
+                    .collect();
+                let crates: Vec<_> = crate_graph
+                    .iter()
+                    .filter_map(|krate| {
+                        Some((krate, *roots.get(&crate_graph[krate].root_file_id)?))
+                    })
+                    .collect();
+                let lib_root = |pkg: Package| {
+                    let lib = cargo[pkg]
+                        .targets
+                        .iter()
+                        .find(|&&tgt| cargo[tgt].kind == TargetKind::Lib)?;
+                    root_file(*lib)
+                };
+
+                let enabled = self.feature_overrides.enabled_features(package);
+                let krates: Vec<_> = crates.iter().map(|&(krate, _)| krate).collect();
+                update_feature_cfgs(&enabled, &krates, crate_graph);
+                resolved &=
+                    update_optional_dependencies(package, &enabled, &crates, lib_root, crate_graph);
+            }
+        }
+        resolved
+    }
+
+    /// The Cargo config for loading the workspace of `manifest`, with the features selected by
+    /// the client.
+    pub(crate) fn cargo_config_for(&self, manifest: &ManifestPath) -> CargoConfig {
+        let config = self.config.cargo();
+        match cargo_workspaces(&self.workspaces)
+            .find(|cargo| cargo.packages().any(|pkg| cargo[pkg].manifest == *manifest))
+        {
+            Some(cargo) => self.with_selected_features(cargo, config),
+            None => config,
+        }
+    }
+
+    /// Replaces the features of `config` with the ones selected by the client for `cargo`.
+    pub(crate) fn with_selected_features(
+        &self,
+        cargo: &CargoWorkspace,
+        mut config: CargoConfig,
+    ) -> CargoConfig {
+        if let Some(features) = self.feature_overrides.cargo_features(cargo) {
+            config.features = CargoFeatures::Selected { features, no_default_features: true };
+        }
+        config
+    }
+
+    /// Replaces the features `cargo check` is run with for `cargo` with the ones selected by the
+    /// client.
+    pub(crate) fn flycheck_config_for(
+        &self,
+        cargo: &CargoWorkspace,
+        mut config: FlycheckConfig,
+    ) -> FlycheckConfig {
+        if let FlycheckConfig::CargoCommand {
+            no_default_features, all_features, features, ..
+        } = &mut config
+        {
+            if let Some(selected) = self.feature_overrides.cargo_features(cargo) {
+                *no_default_features = true;
+                *all_features = false;
+                *features = selected;
+            }
+        }
+        config
+    }
+
+    fn package_features(&self, package: &PackageData) -> lsp_ext::PackageFeatures {
+        let enabled = self.feature_overrides.enabled_features(package);
+        let mut features: Vec<_> = package
+            .features
+            .iter()
+            .map(|(name, implies)| lsp_ext::CargoFeature {
+                name: name.clone(),
+                enabled: enabled.contains(name),
+                implies: implies.clone(),
+            })
+            .collect();
+        features.sort_by(|a, b| a.name.cmp(&b.name));
+        lsp_ext::PackageFeatures {
+            package: package.id.clone(),
+            name: package.name.clone(),
+            manifest_path: package.manifest.display().to_string(),
+            features,
+        }
+    }
+}
+
+fn cargo_workspaces(workspaces: &[ProjectWorkspace]) -> impl Iterator<Item = &CargoWorkspace> {
+    workspaces.iter().filter_map(|ws| match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => Some(cargo),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cfg::{CfgExpr, CfgOptions};
+    use ide_db::base_db::{CrateOrigin, Edition, Env};
+    use vfs::AbsPathBuf;
+
+    use super::*;
+
+    fn package(features: &[(&str, &[&str])], active_features: &[&str]) -> PackageData {
+        let manifest = if cfg!(windows) { "C:\\foo\\Cargo.toml" } else { "/foo/Cargo.toml" };
+        PackageData {
+            version: "0.1.0".parse().unwrap(),
+            name: "foo".to_owned(),
+            repository: None,
+            manifest: ManifestPath::try_from(AbsPathBuf::assert(manifest.into())).unwrap(),
+            targets: Vec::new(),
+            is_local: true,
+            is_member: true,
+            dependencies: Vec::new(),
+            edition: Edition::Edition2021,
+            features: features
+                .iter()
+                .map(|&(name, implies)| {
+                    (name.to_owned(), implies.iter().map(|&it| it.to_owned()).collect())
+                })
+                .collect(),
+            active_features: active_features.iter().map(|&it| it.to_owned()).collect(),
+            id: "foo 0.1.0 (path+file:///foo)".to_owned(),
+            metadata: Default::default(),
+        }
+    }
+
+    fn add_crate(crate_graph: &mut CrateGraph, file_id: u32, cfg_options: CfgOptions) -> CrateId {
+        crate_graph.add_crate_root(
+            FileId(file_id),
+            Edition::Edition2021,
+            None,
+            None,
+            cfg_options,
+            CfgOptions::default(),
+            Env::default(),
+            Ok(Vec::new()),
+            false,
+            CrateOrigin::CratesIo { repo: None, name: None },
+            Err(Arc::from("target layout is not available")),
+        )
+    }
+
+    fn sorted(features: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut features: Vec<_> = features.into_iter().collect();
+        features.sort();
+        features
+    }
+
+    fn feature_set(features: &[&str]) -> FxHashSet<String> {
+        features.iter().map(|&it| it.to_owned()).collect()
+    }
+
+    #[test]
+    fn implied_features() {
+        let package = package(
+            &[
+                ("default", &["std"]),
+                ("std", &["alloc", "dep:libc"]),
+                ("alloc", &[]),
+                ("serde", &["dep:serde"]),
+                ("derive", &["serde/derive"]),
+                ("rayon", &["dep:rayon"]),
+                ("parallel", &["rayon?/std"]),
+            ],
+            &[],
+        );
+
+        let enabled = with_implied_features(&package, &feature_set(&["default"]));
+        assert_eq!(sorted(enabled), ["alloc", "default", "std"]);
+
+        let enabled = with_implied_features(&package, &feature_set(&["derive"]));
+        assert_eq!(sorted(enabled), ["derive", "serde"]);
+
+        let enabled = with_implied_features(&package, &feature_set(&["parallel", "unknown"]));
+        assert_eq!(sorted(enabled), ["parallel"]);
+    }
+
+    #[test]
+    fn optional_dependencies_enabled_by_features() {
+        let package = package(
+            &[
+                ("std", &["dep:libc"]),
+                ("serde", &["dep:serde"]),
+                ("derive", &["serde/derive"]),
+                ("rayon", &["dep:rayon"]),
+                ("parallel", &["rayon?/std"]),
+            ],
+            &[],
+        );
+        let enabled = |features| {
+            let features = with_implied_features(&package, &feature_set(features));
+            let mut deps: Vec<_> = enabled_optional_dependencies(&package, &features)
+                .into_iter()
+                .map(str::to_owned)
+                .collect();
+            deps.sort();
+            deps
+        };
+
+        assert_eq!(enabled(&["std", "derive"]), ["libc", "serde"]);
+        assert_eq!(enabled(&["parallel"]), Vec::<String>::new());
+        assert_eq!(enabled(&["parallel", "rayon"]), ["rayon"]);
+    }
+
+    #[test]
+    fn disabled_optional_dependencies_are_removed() {
+        let package = package(&[("serde", &["dep:serde"]), ("derive", &["serde/derive"])], &[]);
+        let mut crate_graph = CrateGraph::default();
+        let foo = add_crate(&mut crate_graph, 0, CfgOptions::default());
+        let serde = add_crate(&mut crate_graph, 1, CfgOptions::default());
+        let name = CrateName::new("serde").unwrap();
+        crate_graph.add_dep(foo, Dependency::new(name, serde)).unwrap();
+        let crates = [(foo, TargetKind::Lib)];
+
+        let resolved = update_optional_dependencies(
+            &package,
+            &feature_set(&["derive"]),
+            &crates,
+            |_| None,
+            &mut crate_graph,
+        );
+        // Cargo didn't resolve `serde` when the workspace was loaded.
+        assert!(!resolved);
+        assert_eq!(crate_graph[foo].dependencies.len(), 1);
+
+        let resolved = update_optional_dependencies(
+            &package,
+            &FxHashSet::default(),
+            &crates,
+            |_| None,
+            &mut crate_graph,
+        );
+        assert!(resolved);
+        assert!(crate_graph[foo].dependencies.is_empty());
+    }
+
+    #[test]
+    fn disabling_default_disables_implied_features() {
+        let package = package(
+            &[("default", &["std"]), ("std", &[]), ("serde", &[])],
+            &["default", "std", "serde"],
+        );
+        let mut overrides = FeatureOverrides::default();
+        overrides.set(&package, "default".to_owned(), false);
+        assert_eq!(sorted(overrides.enabled_features(&package)), ["serde"]);
+
+        overrides.set(&package, "std".to_owned(), true);
+        assert_eq!(sorted(overrides.enabled_features(&package)), ["serde", "std"]);
+    }
+
+    #[test]
+    fn feature_overrides_update_cfgs() {
+        let package = package(&[("default", &["std"]), ("std", &[]), ("serde", &[])], &[]);
+        let mut overrides = FeatureOverrides::default();
+        overrides.set(&package, "serde".to_owned(), true);
+
+        let mut cfg_options = CfgOptions::default();
+        cfg_options.insert_atom("test".into());
+        cfg_options.insert_key_value("feature".into(), "default".into());
+        cfg_options.insert_key_value("feature".into(), "std".into());
+        let mut crate_graph = CrateGraph::default();
+        let foo = add_crate(&mut crate_graph, 0, cfg_options.clone());
+        let other = add_crate(&mut crate_graph, 1, cfg_options);
+
+        update_feature_cfgs(&overrides.enabled_features(&package), &[foo], &mut crate_graph);
+
+        let features = |krate| {
+            sorted(
+                crate_graph[krate].cfg_options.get_cfg_values("feature").map(|it| it.to_string()),
+            )
+        };
+        assert_eq!(features(foo), ["serde"]);
+        assert_eq!(features(other), ["default", "std"]);
+        let test = CfgExpr::Atom(CfgAtom::Flag("test".into()));
+        assert_eq!(crate_graph[foo].cfg_options.check(&test), Some(true));
+    }
+}
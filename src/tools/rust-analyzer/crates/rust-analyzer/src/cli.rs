@@ -7,6 +7,7 @@
 mod highlight;
 mod analysis_stats;
 mod diagnostics;
+mod dead_code;
 mod ssr;
 mod lsif;
 mod scip;
@@ -3,7 +3,7 @@
 
 use std::{
     env,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use hir::{
@@ -16,10 +16,18 @@
     FunctionId,
 };
 use hir_ty::{Interner, TyExt, TypeFlags};
-use ide::{Analysis, AnalysisHost, LineCol, RootDatabase};
-use ide_db::base_db::{
-    salsa::{self, debug::DebugQueryTable, ParallelDatabase},
-    SourceDatabase, SourceDatabaseExt,
+use ide::{
+    AdjustmentHints, AdjustmentHintsMode, Analysis, AnalysisHost, CallableSnippets,
+    ClosureReturnTypeHints, CompletionConfig, DiscriminantHints, FileId, FilePosition,
+    HighlightConfig, InlayHintsConfig, LifetimeElisionHints, LineCol, RootDatabase,
+    StringInjection,
+};
+use ide_db::{
+    base_db::{
+        salsa::{self, debug::DebugQueryTable, ParallelDatabase},
+        SourceDatabase, SourceDatabaseExt,
+    },
+    imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
 };
 use itertools::Itertools;
 use oorandom::Rand32;
@@ -28,7 +36,7 @@
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
 use stdx::format_to;
-use syntax::{AstNode, SyntaxNode};
+use syntax::{AstNode, SyntaxKind, SyntaxNode, T};
 use vfs::{AbsPathBuf, Vfs, VfsPath};
 
 use crate::cli::{
@@ -49,6 +57,9 @@ fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
 
 impl flags::AnalysisStats {
     pub fn run(self, verbosity: Verbosity) -> Result<()> {
+        if self.output == Some(OutputFormat::Json) {
+            anyhow::bail!("analysis-stats does not support JSON output");
+        }
         let mut rng = {
             let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
             Rand32::new(seed)
@@ -153,6 +164,15 @@ pub fn run(self, verbosity: Verbosity) -> Result<()> {
             self.run_inference(&host, db, &vfs, &funcs, verbosity);
         }
 
+        if self.run_highlighting || self.run_inlay_hints || self.run_completions {
+            let file_ids = visited_modules
+                .iter()
+                .map(|module| module.definition_source(db).file_id.original_file(db))
+                .unique()
+                .collect::<Vec<_>>();
+            self.run_ide_features(&host, &file_ids);
+        }
+
         let total_span = analysis_sw.elapsed();
         eprintln!("{:<20} {total_span}", "Total:");
         report_metric("total time", total_span.time.as_millis() as u64, "ms");
@@ -374,11 +394,148 @@ fn run_inference(
         eprintln!("{:<20} {}", "Inference:", inference_sw.elapsed());
     }
 
+    fn run_ide_features(&self, host: &AnalysisHost, file_ids: &[FileId]) {
+        let analysis = host.analysis();
+        if self.run_highlighting {
+            let config = HighlightConfig {
+                strings: true,
+                punctuation: true,
+                specialize_punctuation: true,
+                operator: true,
+                specialize_operator: true,
+                inject_doc_comment: true,
+                inject_strings: true,
+                string_injections: StringInjection::defaults(),
+                macro_bang: true,
+                syntactic_name_ref_highlighting: false,
+                syntax_only: false,
+            };
+            self.bench_ide_feature("Highlighting", file_ids.iter().copied(), |file_id| {
+                analysis.highlight(config.clone(), file_id).unwrap();
+            });
+        }
+        if self.run_inlay_hints {
+            let config = inlay_hints_config();
+            self.bench_ide_feature("Inlay hints", file_ids.iter().copied(), |file_id| {
+                analysis.inlay_hints(&config, file_id, None).unwrap();
+            });
+        }
+        if self.run_completions {
+            let config = completion_config();
+            let positions = file_ids
+                .iter()
+                .flat_map(|&file_id| {
+                    let source_file = analysis.parse(file_id).unwrap();
+                    source_file
+                        .syntax()
+                        .descendants_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .filter(|token| {
+                            token.kind() == T![.]
+                                && token.parent().map_or(false, |parent| {
+                                    matches!(
+                                        parent.kind(),
+                                        SyntaxKind::FIELD_EXPR | SyntaxKind::METHOD_CALL_EXPR
+                                    )
+                                })
+                        })
+                        .map(|token| FilePosition { file_id, offset: token.text_range().end() })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            self.bench_ide_feature("Completions", positions.into_iter(), |position| {
+                analysis.completions(&config, position, Some('.')).unwrap();
+            });
+        }
+    }
+
+    /// Runs `request` for every input, reporting the total time and memory along with the
+    /// percentiles of the time taken by a single request.
+    fn bench_ide_feature<T>(
+        &self,
+        feature: &str,
+        inputs: impl Iterator<Item = T>,
+        mut request: impl FnMut(T),
+    ) {
+        let mut sw = self.stop_watch();
+        let mut timings = inputs
+            .map(|input| {
+                let start = Instant::now();
+                request(input);
+                start.elapsed()
+            })
+            .collect::<Vec<_>>();
+        let span = sw.elapsed();
+        timings.sort_unstable();
+
+        let percentile = |p: usize| -> Duration {
+            match timings.len() {
+                0 => Duration::ZERO,
+                len => timings[(len - 1) * p / 100],
+            }
+        };
+        eprintln!("{:<20} {span}", format!("{feature}:"));
+        eprintln!(
+            "  requests: {}, p50: {:.2?}, p90: {:.2?}, p99: {:.2?}, max: {:.2?}",
+            timings.len(),
+            percentile(50),
+            percentile(90),
+            percentile(99),
+            percentile(100),
+        );
+        let metric = feature.to_lowercase();
+        report_metric(&format!("{metric} time"), span.time.as_millis() as u64, "ms");
+        report_metric(&format!("{metric} p99"), percentile(99).as_micros() as u64, "us");
+    }
+
     fn stop_watch(&self) -> StopWatch {
         StopWatch::start().memory(self.memory_usage)
     }
 }
 
+fn inlay_hints_config() -> InlayHintsConfig {
+    InlayHintsConfig {
+        render_colons: true,
+        type_hints: true,
+        discriminant_hints: DiscriminantHints::Fieldless,
+        parameter_hints: true,
+        chaining_hints: true,
+        adjustment_hints: AdjustmentHints::ReborrowOnly,
+        adjustment_hints_mode: AdjustmentHintsMode::Prefix,
+        adjustment_hints_hide_outside_unsafe: false,
+        closure_return_type_hints: ClosureReturnTypeHints::WithBlock,
+        binding_mode_hints: true,
+        lifetime_elision_hints: LifetimeElisionHints::SkipTrivial,
+        param_names_for_lifetime_elision_hints: true,
+        hide_named_constructor_hints: false,
+        hide_closure_initialization_hints: false,
+        max_length: Some(25),
+        closing_brace_hints_min_lines: Some(25),
+    }
+}
+
+fn completion_config() -> CompletionConfig {
+    CompletionConfig {
+        enable_postfix_completions: true,
+        enable_imports_on_the_fly: true,
+        enable_self_on_the_fly: true,
+        enable_private_editable: false,
+        callable: Some(CallableSnippets::FillArguments),
+        snippet_cap: None,
+        insert_use: InsertUseConfig {
+            granularity: ImportGranularity::Crate,
+            enforce_granularity: false,
+            prefix_kind: PrefixKind::Plain,
+            group: true,
+            skip_glob_imports: true,
+        },
+        prefer_no_std: false,
+        prefer_defining_crate: false,
+        snippets: Vec::new(),
+        limit: None,
+    }
+}
+
 fn location_csv(
     db: &RootDatabase,
     analysis: &Analysis,
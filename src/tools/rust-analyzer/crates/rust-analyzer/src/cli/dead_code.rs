@@ -0,0 +1,92 @@
+//! Reports the items of the workspace crates that are never referred to in the workspace.
+
+use ide::{Analysis, LineCol, NavigationTarget};
+use serde::Serialize;
+use vfs::Vfs;
+
+use crate::cli::{
+    flags::{self, OutputFormat},
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+};
+
+impl flags::DeadCode {
+    pub fn run(self) -> anyhow::Result<()> {
+        let json = match self.output {
+            None => false,
+            Some(OutputFormat::Json) => true,
+            Some(OutputFormat::Csv) => anyhow::bail!("dead-code does not support CSV output"),
+        };
+
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let analysis = host.analysis();
+
+        let mut report = Vec::new();
+        for krate in analysis.dead_code()? {
+            let items = krate
+                .items
+                .into_iter()
+                .map(|item| {
+                    let (file, LineCol { line, col }) = location(&analysis, &vfs, &item.nav)?;
+                    Ok(JsonDeadItem {
+                        path: item.path,
+                        public: item.is_public,
+                        file,
+                        line: line + 1,
+                        column: col + 1,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            report.push(JsonDeadCrate { name: krate.name, items });
+        }
+
+        if json {
+            println!("{}", serde_json::to_string(&report)?);
+            return Ok(());
+        }
+        for krate in &report {
+            println!("crate {}: {} unused item(s)", krate.name, krate.items.len());
+            for item in &krate.items {
+                let visibility = if item.public { "pub " } else { "" };
+                println!(
+                    "  {visibility}{} ({}:{}:{})",
+                    item.path, item.file, item.line, item.column
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDeadCrate {
+    name: String,
+    items: Vec<JsonDeadItem>,
+}
+
+/// An unused item as printed by `--output json`. Lines and columns are 1-based, columns count
+/// UTF-8 bytes.
+#[derive(Serialize)]
+struct JsonDeadItem {
+    path: String,
+    public: bool,
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+fn location(
+    analysis: &Analysis,
+    vfs: &Vfs,
+    nav: &NavigationTarget,
+) -> anyhow::Result<(String, LineCol)> {
+    let line_index = analysis.file_line_index(nav.file_id)?;
+    let line_col = line_index.line_col(nav.focus_or_full_range().start());
+    Ok((vfs.file_path(nav.file_id).to_string(), line_col))
+}
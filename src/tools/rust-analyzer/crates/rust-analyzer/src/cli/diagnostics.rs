@@ -2,30 +2,41 @@
 //! status code if any errors are found.
 
 use rustc_hash::FxHashSet;
+use serde::Serialize;
 
 use hir::{db::HirDatabase, Crate, Module};
-use ide::{AssistResolveStrategy, DiagnosticsConfig, Severity};
+use ide::{AssistResolveStrategy, Diagnostic, DiagnosticsConfig, LineCol, LineIndex, Severity};
 use ide_db::base_db::SourceDatabaseExt;
 
 use crate::cli::{
-    flags,
+    flags::{self, OutputFormat},
     load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
 };
 
 impl flags::Diagnostics {
     pub fn run(self) -> anyhow::Result<()> {
+        let json = match self.output {
+            None => false,
+            Some(OutputFormat::Json) => true,
+            Some(OutputFormat::Csv) => anyhow::bail!("diagnostics does not support CSV output"),
+        };
+
         let cargo_config = Default::default();
         let load_cargo_config = LoadCargoConfig {
             load_out_dirs_from_check: !self.disable_build_scripts,
             with_proc_macro_server: ProcMacroServerChoice::Sysroot,
             prefill_caches: false,
         };
-        let (host, _vfs, _proc_macro) =
+        let (host, vfs, _proc_macro) =
             load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
         let db = host.raw_database();
         let analysis = host.analysis();
 
-        let mut found_error = false;
+        let mut config = DiagnosticsConfig::test_sample();
+        config.overly_public_items.enable = self.lints;
+
+        let mut error_count = 0;
+        let mut warning_count = 0;
         let mut visited_files = FxHashSet::default();
 
         let work = all_modules(db).into_iter().filter(|module| {
@@ -37,41 +48,99 @@ pub fn run(self) -> anyhow::Result<()> {
 
         for module in work {
             let file_id = module.definition_source(db).file_id.original_file(db);
-            if !visited_files.contains(&file_id) {
+            if !visited_files.insert(file_id) {
+                continue;
+            }
+            let path = vfs.file_path(file_id).to_string();
+            if !json {
                 let crate_name =
                     module.krate().display_name(db).as_deref().unwrap_or("unknown").to_string();
-                println!("processing crate: {crate_name}, module: {}", _vfs.file_path(file_id));
-                for diagnostic in analysis
-                    .diagnostics(
-                        &DiagnosticsConfig::test_sample(),
-                        AssistResolveStrategy::None,
-                        file_id,
-                    )
-                    .unwrap()
-                {
-                    if matches!(diagnostic.severity, Severity::Error) {
-                        found_error = true;
-                    }
-
-                    println!("{diagnostic:?}");
+                println!("processing crate: {crate_name}, module: {path}");
+            }
+
+            let line_index = analysis.file_line_index(file_id)?;
+            for diagnostic in analysis.diagnostics(&config, AssistResolveStrategy::None, file_id)? {
+                match diagnostic.severity {
+                    Severity::Error => error_count += 1,
+                    Severity::WeakWarning => warning_count += 1,
                 }
 
-                visited_files.insert(file_id);
+                let diagnostic = JsonDiagnostic::new(&path, &line_index, &diagnostic);
+                if json {
+                    println!("{}", serde_json::to_string(&diagnostic)?);
+                } else {
+                    println!(
+                        "{}:{}:{}: {}[{}]: {}",
+                        diagnostic.file,
+                        diagnostic.start.line,
+                        diagnostic.start.column,
+                        diagnostic.severity,
+                        diagnostic.code,
+                        diagnostic.message
+                    );
+                }
             }
         }
 
-        println!();
-        println!("diagnostic scan complete");
-
-        if found_error {
+        // Keep stdout machine readable when printing JSON.
+        let summary =
+            format!("diagnostic scan complete: {error_count} error(s), {warning_count} warning(s)");
+        if json {
+            eprintln!("{summary}");
+        } else {
             println!();
+            println!("{summary}");
+        }
+
+        if error_count > 0 {
             anyhow::bail!("diagnostic error detected")
         }
+        if self.deny_warnings && warning_count > 0 {
+            anyhow::bail!("diagnostic warning detected")
+        }
 
         Ok(())
     }
 }
 
+/// A diagnostic as printed by `--output json`. Lines and columns are 1-based, columns count UTF-8
+/// bytes.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    file: &'a str,
+    start: JsonPosition,
+    end: JsonPosition,
+    severity: &'static str,
+    code: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonPosition {
+    line: u32,
+    column: u32,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new(file: &'a str, line_index: &LineIndex, diagnostic: &'a Diagnostic) -> Self {
+        let position = |offset| {
+            let LineCol { line, col } = line_index.line_col(offset);
+            JsonPosition { line: line + 1, column: col + 1 }
+        };
+        JsonDiagnostic {
+            file,
+            start: position(diagnostic.range.start()),
+            end: position(diagnostic.range.end()),
+            severity: match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::WeakWarning => "warning",
+            },
+            code: diagnostic.code.as_str(),
+            message: &diagnostic.message,
+        }
+    }
+}
+
 fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
     let mut worklist: Vec<_> =
         Crate::all(db).into_iter().map(|krate| krate.root_module(db)).collect();
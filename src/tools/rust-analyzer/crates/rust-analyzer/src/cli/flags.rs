@@ -80,16 +80,43 @@
             optional --disable-proc-macros
             /// Only resolve names, don't run type inference.
             optional --skip-inference
+
+            /// Benchmark syntax highlighting of every file.
+            optional --run-highlighting
+            /// Benchmark inlay hints, including chaining hints, of every file.
+            optional --run-inlay-hints
+            /// Benchmark completions after the `.` of every field access and method call.
+            optional --run-completions
         }
 
+        /// Batch check project for diagnostics, exiting with a non-zero status code if errors are found.
         cmd diagnostics {
             /// Directory with Cargo.toml.
             required path: PathBuf
 
+            /// Output format, `json` prints one JSON object per diagnostic and line.
+            optional --output format: OutputFormat
+
             /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
             optional --disable-build-scripts
             /// Don't use expand proc macros.
             optional --disable-proc-macros
+            /// Also run the lints that are disabled by default, like the overly public items lint.
+            optional --lints
+            /// Exit with a non-zero status code if warnings are found too.
+            optional --deny-warnings
+        }
+
+        /// Report the items of the workspace crates that are never referred to in the workspace.
+        cmd dead-code {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+
+            /// Output format, `json` prints the report as a single JSON array of crates.
+            optional --output format: OutputFormat
+
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
         }
 
         cmd ssr {
@@ -112,6 +139,9 @@
 
         cmd scip {
             required path: PathBuf
+
+            /// The output path where the SCIP file will be written to. Defaults to `index.scip`.
+            optional --output path: PathBuf
         }
     }
 }
@@ -137,6 +167,7 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     Diagnostics(Diagnostics),
+    DeadCode(DeadCode),
     Ssr(Ssr),
     Search(Search),
     ProcMacro(ProcMacro),
@@ -178,14 +209,28 @@ pub struct AnalysisStats {
     pub disable_build_scripts: bool,
     pub disable_proc_macros: bool,
     pub skip_inference: bool,
+    pub run_highlighting: bool,
+    pub run_inlay_hints: bool,
+    pub run_completions: bool,
 }
 
 #[derive(Debug)]
 pub struct Diagnostics {
     pub path: PathBuf,
 
+    pub output: Option<OutputFormat>,
     pub disable_build_scripts: bool,
     pub disable_proc_macros: bool,
+    pub lints: bool,
+    pub deny_warnings: bool,
+}
+
+#[derive(Debug)]
+pub struct DeadCode {
+    pub path: PathBuf,
+
+    pub output: Option<OutputFormat>,
+    pub disable_build_scripts: bool,
 }
 
 #[derive(Debug)]
@@ -211,6 +256,8 @@ pub struct Lsif {
 #[derive(Debug)]
 pub struct Scip {
     pub path: PathBuf,
+
+    pub output: Option<PathBuf>,
 }
 
 impl RustAnalyzer {
@@ -234,6 +281,7 @@ pub fn from_vec(args: Vec<std::ffi::OsString>) -> xflags::Result<Self> {
 #[derive(Debug, PartialEq, Eq)]
 pub enum OutputFormat {
     Csv,
+    Json,
 }
 
 impl RustAnalyzer {
@@ -255,6 +303,7 @@ impl FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
             _ => Err(format!("unknown output format `{s}`")),
         }
     }
@@ -92,7 +92,7 @@ pub fn load_workspace(
         extra_env,
     );
 
-    let project_folders = ProjectFolders::new(&[ws], &[]);
+    let project_folders = ProjectFolders::new(&[ws], &[], &[]);
     loader.set_config(vfs::loader::Config {
         load: project_folders.load,
         watch: vec![],
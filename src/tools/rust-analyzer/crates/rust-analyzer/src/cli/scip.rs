@@ -70,107 +70,135 @@ pub fn run(self) -> Result<()> {
             text_document_encoding: scip_types::TextEncoding::UTF8.into(),
             special_fields: Default::default(),
         };
-        let mut documents = Vec::new();
-
-        let mut symbols_emitted: HashSet<TokenId> = HashSet::default();
-        let mut tokens_to_symbol: HashMap<TokenId, String> = HashMap::new();
-
-        for StaticIndexedFile { file_id, tokens, .. } in si.files {
-            let mut local_count = 0;
-            let mut new_local_symbol = || {
-                let new_symbol = scip::types::Symbol::new_local(local_count);
-                local_count += 1;
-
-                new_symbol
-            };
-
-            let relative_path = match get_relative_filepath(&vfs, &rootpath, file_id) {
-                Some(relative_path) => relative_path,
-                None => continue,
-            };
-
-            let line_index = LineIndex {
-                index: db.line_index(file_id),
-                encoding: PositionEncoding::Utf8,
-                endings: LineEndings::Unix,
-            };
-
-            let mut occurrences = Vec::new();
-            let mut symbols = Vec::new();
-
-            tokens.into_iter().for_each(|(text_range, id)| {
-                let token = si.tokens.get(id).unwrap();
-
-                let range = text_range_to_scip_range(&line_index, text_range);
-                let symbol = tokens_to_symbol
-                    .entry(id)
-                    .or_insert_with(|| {
-                        let symbol = token_to_symbol(token).unwrap_or_else(&mut new_local_symbol);
-                        scip::symbol::format_symbol(symbol)
-                    })
-                    .clone();
-
-                let mut symbol_roles = Default::default();
-
-                if let Some(def) = token.definition {
-                    if def.range == text_range {
-                        symbol_roles |= scip_types::SymbolRole::Definition as i32;
-                    }
+        let (documents, external_symbols) =
+            index_documents(db, si, &|file_id| get_relative_filepath(&vfs, &rootpath, file_id));
+
+        let index = scip_types::Index {
+            metadata: Some(metadata).into(),
+            documents,
+            external_symbols,
+            special_fields: Default::default(),
+        };
+
+        let output = self.output.unwrap_or_else(|| "index.scip".into());
+        scip::write_message_to_file(&output, index)
+            .map_err(|err| anyhow::anyhow!("Failed to write scip to file: {}", err))?;
+
+        eprintln!("Generating SCIP finished {:?}", now.elapsed());
+        Ok(())
+    }
+}
 
+/// Indexes the files for which `file_path` returns a relative path, collecting the symbols they
+/// use which are defined outside of them as external symbols.
+fn index_documents(
+    db: &ide_db::RootDatabase,
+    si: StaticIndex<'_>,
+    file_path: &dyn Fn(ide::FileId) -> Option<String>,
+) -> (Vec<scip_types::Document>, Vec<scip_types::SymbolInformation>) {
+    let mut documents = Vec::new();
+
+    let mut symbols_emitted: HashSet<TokenId> = HashSet::default();
+    let mut tokens_to_symbol: HashMap<TokenId, String> = HashMap::new();
+    let mut external_symbols: HashMap<TokenId, String> = HashMap::new();
+
+    for StaticIndexedFile { file_id, tokens, .. } in si.files {
+        let mut local_count = 0;
+        let mut new_local_symbol = || {
+            let new_symbol = scip::types::Symbol::new_local(local_count);
+            local_count += 1;
+
+            new_symbol
+        };
+
+        let relative_path = match file_path(file_id) {
+            Some(relative_path) => relative_path,
+            None => continue,
+        };
+
+        let line_index = LineIndex {
+            index: db.line_index(file_id),
+            encoding: PositionEncoding::Utf8,
+            endings: LineEndings::Unix,
+        };
+
+        let mut occurrences = Vec::new();
+        let mut symbols = Vec::new();
+
+        tokens.into_iter().for_each(|(text_range, id)| {
+            let token = si.tokens.get(id).unwrap();
+
+            let range = text_range_to_scip_range(&line_index, text_range);
+            let symbol = tokens_to_symbol
+                .entry(id)
+                .or_insert_with(|| {
+                    let symbol = token_to_symbol(token).unwrap_or_else(&mut new_local_symbol);
+                    scip::symbol::format_symbol(symbol)
+                })
+                .clone();
+
+            let mut symbol_roles = Default::default();
+
+            if let Some(def) = token.definition {
+                if def.range == text_range {
+                    symbol_roles |= scip_types::SymbolRole::Definition as i32;
+                }
+
+                // Symbol information belongs to the document defining the symbol, symbols
+                // defined outside of the project are collected as external symbols instead.
+                if def.file_id == file_id {
                     if symbols_emitted.insert(id) {
-                        let documentation = token
-                            .hover
-                            .as_ref()
-                            .map(|hover| hover.markup.as_str())
-                            .filter(|it| !it.is_empty())
-                            .map(|it| vec![it.to_owned()]);
-                        let symbol_info = scip_types::SymbolInformation {
-                            symbol: symbol.clone(),
-                            documentation: documentation.unwrap_or_default(),
-                            relationships: Vec::new(),
-                            special_fields: Default::default(),
-                        };
-
-                        symbols.push(symbol_info)
+                        symbols.push(symbol_information(token, symbol.clone()));
                     }
+                } else if file_path(def.file_id).is_none() {
+                    external_symbols.entry(id).or_insert_with(|| symbol.clone());
                 }
-
-                occurrences.push(scip_types::Occurrence {
-                    range,
-                    symbol,
-                    symbol_roles,
-                    override_documentation: Vec::new(),
-                    syntax_kind: Default::default(),
-                    diagnostics: Vec::new(),
-                    special_fields: Default::default(),
-                });
-            });
-
-            if occurrences.is_empty() {
-                continue;
             }
 
-            documents.push(scip_types::Document {
-                relative_path,
-                language: "rust".to_string(),
-                occurrences,
-                symbols,
+            occurrences.push(scip_types::Occurrence {
+                range,
+                symbol,
+                symbol_roles,
+                override_documentation: Vec::new(),
+                syntax_kind: Default::default(),
+                diagnostics: Vec::new(),
                 special_fields: Default::default(),
             });
+        });
+
+        if occurrences.is_empty() {
+            continue;
         }
 
-        let index = scip_types::Index {
-            metadata: Some(metadata).into(),
-            documents,
-            external_symbols: Vec::new(),
+        documents.push(scip_types::Document {
+            relative_path,
+            language: "rust".to_string(),
+            occurrences,
+            symbols,
             special_fields: Default::default(),
-        };
+        });
+    }
 
-        scip::write_message_to_file("index.scip", index)
-            .map_err(|err| anyhow::anyhow!("Failed to write scip to file: {}", err))?;
+    let mut external_symbols = external_symbols
+        .into_iter()
+        .map(|(id, symbol)| symbol_information(si.tokens.get(id).unwrap(), symbol))
+        .collect::<Vec<_>>();
+    external_symbols.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    (documents, external_symbols)
+}
 
-        eprintln!("Generating SCIP finished {:?}", now.elapsed());
-        Ok(())
+fn symbol_information(token: &TokenStaticData, symbol: String) -> scip_types::SymbolInformation {
+    let documentation = token
+        .hover
+        .as_ref()
+        .map(|hover| hover.markup.as_str())
+        .filter(|it| !it.is_empty())
+        .map(|it| vec![it.to_owned()]);
+    scip_types::SymbolInformation {
+        symbol,
+        documentation: documentation.unwrap_or_default(),
+        relationships: Vec::new(),
+        special_fields: Default::default(),
     }
 }
 
@@ -477,4 +505,52 @@ pub struct Bar {
             "rust-analyzer cargo main . foo/Bar#",
         );
     }
+
+    #[test]
+    fn external_symbols() {
+        let (host, position) = position(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::St;
+pub struct Local;
+fn main() {
+    let _ = St$0 { a: 0 };
+    let _ = St { a: 1 };
+    let _ = Local;
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+/// Docs of `St`.
+pub struct St {
+    pub a: i32,
+}
+"#,
+        );
+        let analysis = host.analysis();
+        let si = StaticIndex::compute(&analysis);
+        let main_file = position.file_id;
+        let (documents, external_symbols) = index_documents(host.raw_database(), si, &|file_id| {
+            (file_id == main_file).then(|| "lib.rs".to_owned())
+        });
+
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].relative_path, "lib.rs");
+        assert!(documents[0]
+            .symbols
+            .iter()
+            .any(|it| it.symbol == "rust-analyzer cargo main . Local#"));
+
+        let symbols: Vec<_> = external_symbols.iter().map(|it| it.symbol.as_str()).collect();
+        assert!(
+            symbols.iter().all(|it| it.starts_with("rust-analyzer cargo foo 0.1.0 ")),
+            "{symbols:?}"
+        );
+        // Symbols used several times are only emitted once.
+        let st: Vec<_> = external_symbols
+            .iter()
+            .filter(|it| it.symbol == "rust-analyzer cargo foo 0.1.0 St#")
+            .collect();
+        assert_eq!(st.len(), 1);
+        assert!(st[0].documentation.iter().any(|it| it.contains("Docs of `St`.")));
+        assert!(symbols.contains(&"rust-analyzer cargo foo 0.1.0 St#a."));
+    }
 }
@@ -10,10 +10,12 @@
 use std::{fmt, iter, path::PathBuf};
 
 use flycheck::FlycheckConfig;
+use hir::db::ExpansionLimits;
 use ide::{
-    AssistConfig, CallableSnippets, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode,
-    HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayHintsConfig,
-    JoinLinesConfig, Snippet, SnippetScope,
+    AssistConfig, AsyncRuntime, CallableSnippets, CompletionConfig, DiagnosticsConfig,
+    ExprFillDefaultMode, HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat,
+    InjectedLanguage, InlayHintsConfig, JoinLinesConfig, OverlyPublicItemsConfig, RunnableKind,
+    Snippet, SnippetScope, StringInjection,
 };
 use ide_db::{
     imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -56,6 +58,12 @@
 // parsing the old name.
 config_data! {
     struct ConfigData {
+        /// Additional mappings from paths of blocking std functions to the paths of their async
+        /// equivalents, used by the "Convert to async IO" assist. For example,
+        /// `{ "std::fs::read": "smol::fs::read" }`.
+        assist_asyncIo_mappings: FxHashMap<String, String> = "{}",
+        /// Async runtime whose IO functions replace blocking std ones in the "Convert to async IO" assist.
+        assist_asyncIo_runtime: AsyncRuntimeDef = "\"tokio\"",
         /// Whether to insert #[must_use] when generating `as_` methods
         /// for enum variants.
         assist_emitMustUse: bool               = "false",
@@ -177,6 +185,11 @@ struct ConfigData {
         /// each of them, with the working directory being the project root
         /// (i.e., the folder containing the `Cargo.toml`).
         ///
+        /// An argument equal to `$saved_file` is replaced by the path of the saved
+        /// file, allowing to check only that file. Commands using it are not run
+        /// when the check is not triggered by a file. When several files are
+        /// saved in quick succession, the last one is checked.
+        ///
         /// An example command would be:
         ///
         /// ```bash
@@ -191,6 +204,10 @@ struct ConfigData {
         ///
         /// Aliased as `"checkOnSave.targets"`.
         check_targets | checkOnSave_targets | checkOnSave_target: Option<CheckOnSaveTargets> = "null",
+        /// Whether `--workspace` should be passed to `cargo check`.
+        /// If false, `-p <package>` is passed instead, for the packages containing the saved file
+        /// and the ones depending on them.
+        check_workspace: bool = "true",
 
         /// Toggles the additional completions that automatically add imports when completed.
         /// Note that your client must specify the `additionalTextEdits` LSP client capability to truly have this feature enabled.
@@ -257,6 +274,12 @@ struct ConfigData {
         /// Whether to show experimental rust-analyzer diagnostics that might
         /// have more false positives than usual.
         diagnostics_experimental_enable: bool    = "false",
+        /// Paths of modules, such as `my_crate::api`, whose items are intentionally public and
+        /// are never reported by the `overly-public-item` diagnostic.
+        diagnostics_overlyPublicItems_allowedModules: Vec<String> = "[]",
+        /// Whether to report `pub` items of workspace crates that are never used by any of the
+        /// workspace crates depending on them.
+        diagnostics_overlyPublicItems_enable: bool = "false",
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = "{}",
@@ -271,10 +294,25 @@ struct ConfigData {
         /// and a blue icon in the `Problems Panel`.
         diagnostics_warningsAsInfo: Vec<String> = "[]",
 
+        /// Whether evaluating a selected expression may compile it with `rustc` and run the
+        /// resulting program. The program runs in a sandbox without network access, which is `bwrap` on
+        /// Linux and `sandbox-exec` on macOS, and isn't run on other platforms. Constants are always
+        /// evaluated.
+        evaluateExpression_runPrograms_enable: bool = "false",
+
         /// These directories will be ignored by rust-analyzer. They are
         /// relative to the workspace root, and globs are not supported. You may
         /// also need to add the folders to Code's `files.watcherExclude`.
         files_excludeDirs: Vec<PathBuf> = "[]",
+        /// Glob patterns of files and directories, relative to the workspace root, which
+        /// are loaded but excluded from analysis. Navigating into them works, but
+        /// diagnostics, inlay hints and references are not shown for them, and renames
+        /// don't edit them, which is useful for large generated modules.
+        files_excludeFromAnalysis: Vec<String> = "[]",
+        /// Files larger than this many bytes are only highlighted syntactically, and
+        /// features requiring type information are disabled for them. The default
+        /// `null` means no limit.
+        files_maxAnalysisSize: Option<usize> = "null",
         /// Controls file watching implementation.
         files_watcher: FilesWatcherDef = "\"client\"",
 
@@ -321,6 +359,10 @@ struct ConfigData {
         imports_group_enable: bool                           = "true",
         /// Whether to allow import insertion to merge new imports into single path glob imports like `use std::fmt::*;`.
         imports_merge_glob: bool           = "true",
+        /// Prefer import paths through the crate defining an item over shorter paths through crates
+        /// re-exporting it. Paths into the core, alloc and std crates are chosen by
+        /// `#rust-analyzer.imports.prefer.no.std#` instead.
+        imports_prefer_definingCrate: bool = "false",
         /// Prefer to unconditionally use imports of the core and alloc crate, over the std crate.
         imports_prefer_no_std: bool                     = "false",
         /// The path structure for newly inserted paths to use.
@@ -371,6 +413,13 @@ struct ConfigData {
         joinLines_joinAssignments: bool = "true",
         /// Join lines inserts else between consecutive ifs.
         joinLines_joinElseIf: bool = "true",
+        /// Join lines joins all the lines of a method chain when joining a line before one of its
+        /// `.`s.
+        joinLines_joinMethodChains: bool = "true",
+        /// Join lines merges consecutive `if let`s with the same path and body into one `if let`
+        /// with the patterns separated by `|`, if the patterns don't bind anything and don't
+        /// overlap.
+        joinLines_mergeIfLetPatterns: bool = "true",
         /// Join lines removes trailing commas.
         joinLines_removeTrailingComma: bool = "true",
         /// Join lines unwraps trivial blocks.
@@ -388,6 +437,13 @@ struct ConfigData {
         /// Whether to show `Implementations` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
         lens_implementations_enable: bool  = "true",
+        /// Whether to show `Implementations` lens for Struct, Enum, and Union, counting the trait
+        /// impls of the type. Only applies when
+        /// `#rust-analyzer.lens.implementations.enable#` is set.
+        lens_implementations_adt_enable: bool = "true",
+        /// Whether to show `Implementations` lens for Trait. Only applies when
+        /// `#rust-analyzer.lens.implementations.enable#` is set.
+        lens_implementations_trait_enable: bool = "true",
         /// Where to render annotations.
         lens_location: AnnotationLocation = "\"above_name\"",
         /// Whether to show `References` lens for Struct, Enum, and Union.
@@ -415,6 +471,24 @@ struct ConfigData {
 
         /// Number of syntax trees rust-analyzer keeps in memory. Defaults to 128.
         lru_capacity: Option<usize>                 = "null",
+        /// Memory usage in megabytes above which rust-analyzer drops the syntax trees, macro
+        /// expansions, bodies and inference results it keeps in memory. They are recomputed when
+        /// needed, so this should be set well above the memory needed to analyze the workspace.
+        /// If dropping them doesn't get below the budget, they are dropped again once the memory
+        /// usage grew by a quarter of the budget.
+        lru_memoryBudget: Option<usize>             = "null",
+        /// Sets the LRU capacity of the specified queries, by the names listed by the
+        /// **rust-analyzer: Memory Usage** command, for example `{ "InferQueryQuery": 4096 }`.
+        /// Supports `ParseQuery`, `ParseMacroExpansionQuery`, `MacroExpandQuery`,
+        /// `BodyWithSourceMapQuery`, `BodyQuery` and `InferQueryQuery`.
+        lru_query_capacities: FxHashMap<Box<str>, usize> = "{}",
+
+        /// Maximum depth of macro invocations expanding to further macro invocations.
+        /// Deeper expansions are not analyzed.
+        macroExpansion_maxDepth: usize = "128",
+        /// Maximum number of tokens a single macro invocation may expand to. Larger
+        /// expansions are discarded with an error.
+        macroExpansion_maxOutputTokens: usize = "1048576",
 
         /// Whether to show `can't find Cargo.toml` error message.
         notifications_cargoTomlNotFound: bool      = "true",
@@ -442,6 +516,12 @@ struct ConfigData {
         /// Additional arguments to be passed to cargo for runnables such as
         /// tests or binaries. For example, it may be `--release`.
         runnables_extraArgs: Vec<String>   = "[]",
+        /// Overrides applied to the commands of matching runnables. Each override may
+        /// match on the runnable's `kind` (`"test"`, `"test_mod"`, `"bench"`, `"doc_test"`
+        /// or `"bin"`) and on a `path`, the test path or binary name, which also matches
+        /// everything nested within it. The `env`, `features` and `extraArgs` of all
+        /// matching overrides are added to the command.
+        runnables_overrides: Vec<RunnableOverride> = "[]",
 
         /// Path to the Cargo.toml of the rust compiler workspace, for usage in rustc_private
         /// projects, or "discover" to try to automatically find it if the `rustc-dev` component
@@ -497,15 +577,33 @@ struct ConfigData {
         /// By disabling semantic tokens for strings, other grammars can be used to highlight
         /// their contents.
         semanticHighlighting_strings_enable: bool = "true",
+        /// Use semantic tokens to highlight other languages in strings, like the regex passed
+        /// to `Regex::new` or the query passed to `sqlx::query!`.
+        semanticHighlighting_strings_injections_enable: bool = "true",
+        /// Other functions and macros whose string arguments are highlighted as another
+        /// language, in addition to `regex::Regex::new`, `regex::RegexBuilder::new` and the
+        /// `sqlx::query` functions and macros. The `path` is `krate::name`, or
+        /// `krate::Type::name` for an associated function, without the modules. `argument` is
+        /// the index of the argument of the function, or of the string literal among the
+        /// arguments of the macro. `language` is either `regex` or `sql`.
+        semanticHighlighting_strings_injections_custom: Vec<StringInjectionDef> = "[]",
 
         /// Show full signature of the callable. Only shows parameters if disabled.
         signatureInfo_detail: SignatureDetail                           = "\"full\"",
         /// Show documentation.
         signatureInfo_documentation_enable: bool                       = "true",
 
+        /// Whether to let the client show the tests of the workspace in a test explorer, and send it updates of
+        /// the tests as files change.
+        testExplorer_enable: bool = "false",
+
         /// Whether to insert closing angle brackets when typing an opening angle bracket of a generic argument list.
         typing_autoClosingAngleBrackets_enable: bool = "false",
 
+        /// Whether items declared inside of function bodies of dependencies are found by workspace
+        /// symbol search. Disabling this avoids lowering all of these bodies when searching
+        /// dependencies, reducing memory usage.
+        workspace_symbol_search_dependencyBodies: bool = "true",
         /// Workspace symbol search kind.
         workspace_symbol_search_kind: WorkspaceSymbolSearchKindDef = "\"only_types\"",
         /// Limits the number of items returned from a workspace symbol search (Defaults to 128).
@@ -566,7 +664,8 @@ pub struct LensConfig {
     pub debug: bool,
 
     // implementations
-    pub implementations: bool,
+    pub impls_adt: bool,
+    pub impls_trait: bool,
 
     // references
     pub method_refs: bool,
@@ -598,7 +697,8 @@ impl LensConfig {
     pub fn any(&self) -> bool {
         self.run
             || self.debug
-            || self.implementations
+            || self.impls_adt
+            || self.impls_trait
             || self.method_refs
             || self.refs_adt
             || self.refs_trait
@@ -653,6 +753,8 @@ pub fn runnable(&self) -> bool {
 pub struct FilesConfig {
     pub watcher: FilesWatcher,
     pub exclude: Vec<AbsPathBuf>,
+    /// Absolute glob patterns of the files and directories excluded from analysis.
+    pub exclude_from_analysis: Vec<AbsPathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -679,6 +781,53 @@ pub struct RunnablesConfig {
     pub override_cargo: Option<String>,
     /// Additional arguments for the `cargo`, e.g. `--release`.
     pub cargo_extra_args: Vec<String>,
+    /// Additions to the commands of matching runnables.
+    pub overrides: Vec<RunnableOverride>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnableOverride {
+    #[serde(default)]
+    pub kind: Option<RunnableOverrideKind>,
+    /// Matches the runnable's path and all paths nested within it.
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub env: FxHashMap<String, String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunnableOverrideKind {
+    Test,
+    TestMod,
+    Bench,
+    DocTest,
+    Bin,
+}
+
+impl RunnableOverride {
+    pub fn matches(&self, kind: &RunnableKind, path: &str) -> bool {
+        let kind_matches = match (self.kind, kind) {
+            (None, _)
+            | (Some(RunnableOverrideKind::Test), RunnableKind::Test { .. })
+            | (Some(RunnableOverrideKind::TestMod), RunnableKind::TestMod { .. })
+            | (Some(RunnableOverrideKind::Bench), RunnableKind::Bench { .. })
+            | (Some(RunnableOverrideKind::DocTest), RunnableKind::DocTest { .. })
+            | (Some(RunnableOverrideKind::Bin), RunnableKind::Bin) => true,
+            _ => false,
+        };
+        let path_matches = self.path.as_deref().map_or(true, |prefix| {
+            path.strip_prefix(prefix)
+                .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+        });
+        kind_matches && path_matches
+    }
 }
 
 /// Configuration for workspace symbol search requests.
@@ -1018,6 +1167,11 @@ pub fn server_status_notification(&self) -> bool {
         self.experimental("serverStatusNotification")
     }
 
+    /// Whether the client accepts local links to the documentation in `experimental/externalDocs`.
+    pub fn local_docs(&self) -> bool {
+        self.experimental("localDocs")
+    }
+
     /// Whether the client supports colored output for full diagnostics from `checkOnSave`.
     pub fn color_diagnostic_output(&self) -> bool {
         self.experimental("colorDiagnosticOutput")
@@ -1039,6 +1193,11 @@ pub fn diagnostics(&self) -> DiagnosticsConfig {
             },
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
+            prefer_defining_crate: self.data.imports_prefer_definingCrate,
+            overly_public_items: OverlyPublicItemsConfig {
+                enable: self.data.diagnostics_overlyPublicItems_enable,
+                allowed_modules: self.data.diagnostics_overlyPublicItems_allowedModules.clone(),
+            },
         }
     }
 
@@ -1064,6 +1223,25 @@ pub fn lru_capacity(&self) -> Option<usize> {
         self.data.lru_capacity
     }
 
+    pub fn lru_query_capacities(&self) -> &FxHashMap<Box<str>, usize> {
+        &self.data.lru_query_capacities
+    }
+
+    pub fn lru_memory_budget(&self) -> Option<usize> {
+        self.data.lru_memoryBudget
+    }
+
+    pub fn max_analysis_file_size(&self) -> Option<usize> {
+        self.data.files_maxAnalysisSize
+    }
+
+    pub fn expansion_limits(&self) -> ExpansionLimits {
+        ExpansionLimits {
+            max_output_tokens: self.data.macroExpansion_maxOutputTokens,
+            max_depth: self.data.macroExpansion_maxDepth,
+        }
+    }
+
     pub fn proc_macro_srv(&self) -> Option<(AbsPathBuf, /* is path explicitly set */ bool)> {
         if !self.data.procMacro_enable {
             return None;
@@ -1085,6 +1263,10 @@ pub fn expand_proc_attr_macros(&self) -> bool {
         self.data.procMacro_enable && self.data.procMacro_attributes_enable
     }
 
+    pub fn evaluate_expression_run_programs(&self) -> bool {
+        self.data.evaluateExpression_runPrograms_enable
+    }
+
     pub fn files(&self) -> FilesConfig {
         FilesConfig {
             watcher: match self.data.files_watcher {
@@ -1094,6 +1276,12 @@ pub fn files(&self) -> FilesConfig {
                 _ => FilesWatcher::Server,
             },
             exclude: self.data.files_excludeDirs.iter().map(|it| self.root_path.join(it)).collect(),
+            exclude_from_analysis: self
+                .data
+                .files_excludeFromAnalysis
+                .iter()
+                .map(|it| self.root_path.join(it))
+                .collect(),
         }
     }
 
@@ -1233,10 +1421,15 @@ pub fn check_on_save(&self) -> bool {
         self.data.checkOnSave
     }
 
+    pub fn check_workspace(&self) -> bool {
+        self.data.check_workspace
+    }
+
     pub fn runnables(&self) -> RunnablesConfig {
         RunnablesConfig {
             override_cargo: self.data.runnables_command.clone(),
             cargo_extra_args: self.data.runnables_extraArgs.clone(),
+            overrides: self.data.runnables_overrides.clone(),
         }
     }
 
@@ -1330,6 +1523,7 @@ pub fn completion(&self) -> CompletionConfig {
             },
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
+            prefer_defining_crate: self.data.imports_prefer_definingCrate,
             snippet_cap: SnippetCap::new(try_or_def!(
                 self.caps
                     .text_document
@@ -1359,7 +1553,18 @@ pub fn assist(&self) -> AssistConfig {
             allowed: None,
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
+            prefer_defining_crate: self.data.imports_prefer_definingCrate,
             assist_emit_must_use: self.data.assist_emitMustUse,
+            async_runtime: match self.data.assist_asyncIo_runtime {
+                AsyncRuntimeDef::Tokio => AsyncRuntime::Tokio,
+                AsyncRuntimeDef::AsyncStd => AsyncRuntime::AsyncStd,
+            },
+            async_io_mappings: self
+                .data
+                .assist_asyncIo_mappings
+                .iter()
+                .map(|(std_path, async_path)| (std_path.clone(), async_path.clone()))
+                .collect(),
         }
     }
 
@@ -1369,6 +1574,8 @@ pub fn join_lines(&self) -> JoinLinesConfig {
             remove_trailing_comma: self.data.joinLines_removeTrailingComma,
             unwrap_trivial_blocks: self.data.joinLines_unwrapTrivialBlock,
             join_assignments: self.data.joinLines_joinAssignments,
+            join_method_chains: self.data.joinLines_joinMethodChains,
+            merge_if_let_patterns: self.data.joinLines_mergeIfLetPatterns,
         }
     }
 
@@ -1383,7 +1590,12 @@ pub fn lens(&self) -> LensConfig {
         LensConfig {
             run: self.data.lens_enable && self.data.lens_run_enable,
             debug: self.data.lens_enable && self.data.lens_debug_enable,
-            implementations: self.data.lens_enable && self.data.lens_implementations_enable,
+            impls_adt: self.data.lens_enable
+                && self.data.lens_implementations_enable
+                && self.data.lens_implementations_adt_enable,
+            impls_trait: self.data.lens_enable
+                && self.data.lens_implementations_enable
+                && self.data.lens_implementations_trait_enable,
             method_refs: self.data.lens_enable && self.data.lens_references_method_enable,
             refs_adt: self.data.lens_enable && self.data.lens_references_adt_enable,
             refs_trait: self.data.lens_enable && self.data.lens_references_trait_enable,
@@ -1415,7 +1627,22 @@ pub fn highlighting_config(&self) -> HighlightConfig {
             operator: self.data.semanticHighlighting_operator_enable,
             specialize_operator: self.data.semanticHighlighting_operator_specialization_enable,
             inject_doc_comment: self.data.semanticHighlighting_doc_comment_inject_enable,
+            inject_strings: self.data.semanticHighlighting_strings_injections_enable,
+            string_injections: StringInjection::defaults()
+                .into_iter()
+                .chain(self.data.semanticHighlighting_strings_injections_custom.iter().map(|def| {
+                    StringInjection {
+                        path: def.path.clone(),
+                        argument: def.argument,
+                        language: match def.language {
+                            InjectedLanguageDef::Regex => InjectedLanguage::Regex,
+                            InjectedLanguageDef::Sql => InjectedLanguage::Sql,
+                        },
+                    }
+                }))
+                .collect(),
             syntactic_name_ref_highlighting: false,
+            syntax_only: false,
         }
     }
 
@@ -1460,6 +1687,10 @@ pub fn workspace_symbol(&self) -> WorkspaceSymbolConfig {
         }
     }
 
+    pub fn index_library_bodies(&self) -> bool {
+        self.data.workspace_symbol_search_dependencyBodies
+    }
+
     pub fn semantic_tokens_refresh(&self) -> bool {
         try_or_def!(self.caps.workspace.as_ref()?.semantic_tokens.as_ref()?.refresh_support?)
     }
@@ -1524,6 +1755,10 @@ pub fn main_loop_num_threads(&self) -> usize {
         self.data.numThreads.unwrap_or(num_cpus::get_physical().try_into().unwrap_or(1))
     }
 
+    pub fn test_explorer(&self) -> bool {
+        self.data.testExplorer_enable
+    }
+
     pub fn typing_autoclose_angle(&self) -> bool {
         self.data.typing_autoClosingAngleBrackets_enable
     }
@@ -1646,6 +1881,21 @@ fn default() -> Self {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct StringInjectionDef {
+    path: String,
+    #[serde(default)]
+    argument: usize,
+    language: InjectedLanguageDef,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum InjectedLanguageDef {
+    Regex,
+    Sql,
+}
+
 #[derive(Deserialize, Debug, Clone, Default)]
 #[serde(default)]
 struct SnippetDef {
@@ -1699,6 +1949,13 @@ enum ManifestOrProjectJson {
     ProjectJson(ProjectJsonData),
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum AsyncRuntimeDef {
+    Tokio,
+    AsyncStd,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum ExprFillDefaultDef {
@@ -1993,6 +2250,37 @@ macro_rules! set {
         "FxHashMap<String, String>" => set! {
             "type": "object",
         },
+        "FxHashMap<Box<str>, usize>" => set! {
+            "type": "object",
+        },
+        "Vec<StringInjectionDef>" => set! {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "argument": { "type": "integer", "minimum": 0 },
+                    "language": { "type": "string", "enum": ["regex", "sql"] },
+                },
+                "required": ["path", "language"],
+            },
+        },
+        "Vec<RunnableOverride>" => set! {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": ["test", "test_mod", "bench", "doc_test", "bin"],
+                    },
+                    "path": { "type": "string" },
+                    "env": { "type": "object" },
+                    "features": { "type": "array", "items": { "type": "string" } },
+                    "extraArgs": { "type": "array", "items": { "type": "string" } },
+                },
+            },
+        },
         "Option<usize>" => set! {
             "type": ["null", "integer"],
             "minimum": 0,
@@ -2010,6 +2298,14 @@ macro_rules! set {
             "type": ["null", "array"],
             "items": { "type": "string" },
         },
+        "AsyncRuntimeDef" => set! {
+            "type": "string",
+            "enum": ["tokio", "async_std"],
+            "enumDescriptions": [
+                "Use the IO functions of `tokio`.",
+                "Use the IO functions of `async-std`."
+            ],
+        },
         "ExprFillDefaultDef" => set! {
             "type": "string",
             "enum": ["todo", "default"],
@@ -2357,4 +2653,21 @@ fn generate_config_documentation() {
     fn remove_ws(text: &str) -> String {
         text.replace(char::is_whitespace, "")
     }
+
+    #[test]
+    fn runnable_override_matches() {
+        let override_: RunnableOverride = serde_json::from_value(serde_json::json!({
+            "kind": "doc_test",
+            "path": "db",
+            "env": { "DATABASE_URL": "postgres://localhost" },
+        }))
+        .unwrap();
+        let doc_test =
+            |path: &str| RunnableKind::DocTest { test_id: ide::TestId::Path(path.to_owned()) };
+
+        assert!(override_.matches(&doc_test("db"), "db"));
+        assert!(override_.matches(&doc_test("db::Pool::connect"), "db::Pool::connect"));
+        assert!(!override_.matches(&doc_test("dbx"), "dbx"));
+        assert!(!override_.matches(&RunnableKind::TestMod { path: "db".to_owned() }, "db"));
+    }
 }
@@ -1,5 +1,5 @@
 //! See [RequestDispatcher].
-use std::{fmt, panic, thread};
+use std::{fmt, panic, sync::Arc, thread};
 
 use ide::Cancelled;
 use lsp_server::ExtractError;
@@ -48,10 +48,11 @@ pub(crate) fn on_sync_mut<R>(
             Some(it) => it,
             None => return self,
         };
-        let result = {
+        let request_trace = self.global_state.request_trace.clone();
+        let result = request_trace.record_handler(&req.id, || {
             let _pctx = stdx::panic_context::enter(panic_context);
             f(self.global_state, params)
-        };
+        });
         if let Ok(response) = result_to_response::<R>(req.id, result) {
             self.global_state.respond(response);
         }
@@ -75,9 +76,11 @@ pub(crate) fn on_sync<R>(
         };
         let global_state_snapshot = self.global_state.snapshot();
 
-        let result = panic::catch_unwind(move || {
-            let _pctx = stdx::panic_context::enter(panic_context);
-            f(global_state_snapshot, params)
+        let result = self.global_state.request_trace.record_handler(&req.id, || {
+            panic::catch_unwind(move || {
+                let _pctx = stdx::panic_context::enter(panic_context);
+                f(global_state_snapshot, params)
+            })
         });
 
         if let Ok(response) = thread_result_to_response::<R>(req.id, result) {
@@ -104,10 +107,53 @@ pub(crate) fn on<R>(
 
         self.global_state.task_pool.handle.spawn({
             let world = self.global_state.snapshot();
+            let request_trace = self.global_state.request_trace.clone();
+            move || {
+                let result = request_trace.record_handler(&req.id, || {
+                    panic::catch_unwind(move || {
+                        let _pctx = stdx::panic_context::enter(panic_context);
+                        f(world, params)
+                    })
+                });
+                match thread_result_to_response::<R>(req.id.clone(), result) {
+                    Ok(response) => Task::Response(response),
+                    Err(_) => Task::Retry(req),
+                }
+            }
+        });
+
+        self
+    }
+
+    /// Dispatches the request onto a dedicated thread, for requests which
+    /// block for a long time without doing any analysis.
+    pub(crate) fn on_thread<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params) -> Result<R::Result>,
+    ) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug,
+        R::Result: Serialize,
+    {
+        let (req, params, panic_context) = match self.parse::<R>() {
+            Some(it) => it,
+            None => return self,
+        };
+
+        let id = req.id.clone();
+        let world = self.global_state.snapshot();
+        self.global_state
+            .thread_request_cancellations
+            .insert(id.clone(), Arc::clone(&world.request_cancelled));
+        let spawned = self.global_state.task_pool.handle.spawn_thread(R::METHOD.to_owned(), {
+            let request_trace = self.global_state.request_trace.clone();
             move || {
-                let result = panic::catch_unwind(move || {
-                    let _pctx = stdx::panic_context::enter(panic_context);
-                    f(world, params)
+                let result = request_trace.record_handler(&req.id, || {
+                    panic::catch_unwind(move || {
+                        let _pctx = stdx::panic_context::enter(panic_context);
+                        f(world, params)
+                    })
                 });
                 match thread_result_to_response::<R>(req.id.clone(), result) {
                     Ok(response) => Task::Response(response),
@@ -115,6 +161,14 @@ pub(crate) fn on<R>(
                 }
             }
         });
+        if let Err(err) = spawned {
+            let response = lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::InternalError as i32,
+                format!("failed to spawn a thread: {err}"),
+            );
+            self.global_state.respond(response);
+        }
 
         self
     }
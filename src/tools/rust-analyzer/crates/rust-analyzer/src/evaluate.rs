@@ -0,0 +1,199 @@
+//! Compiles and runs the programs generated to evaluate a selected expression.
+//!
+//! This is only done when `rust-analyzer.evaluateExpression.runPrograms.enable` is set. The
+//! programs run in a sandbox without network access which can only write to their own temporary
+//! directory: `bwrap` on Linux and `sandbox-exec` on macOS. Their time, memory and output size are
+//! limited as well. When no sandbox is available, the programs are not run at all.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{self, Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context};
+use ide::{Cancellable, Edition};
+
+/// How long the program may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// How much memory the program may map.
+#[cfg(unix)]
+const MEMORY_LIMIT: u64 = 512 * 1024 * 1024;
+/// How large the files written by the program may get.
+#[cfg(unix)]
+const FILE_SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Compiles `source` as a binary crate in a fresh temporary directory, runs it in a sandbox and
+/// returns what it printed. The program runs with an empty environment and is killed after
+/// [`TIMEOUT`].
+///
+/// `check_cancelled` is polled while waiting for `rustc` and the program. When it fails, both are
+/// killed and the [`Cancelled`](ide::Cancelled) error is returned.
+pub(crate) fn compile_and_run(
+    source: &str,
+    edition: Edition,
+    check_cancelled: &dyn Fn() -> Cancellable<()>,
+) -> anyhow::Result<String> {
+    let dir = create_temp_dir().context("failed to create a temporary directory")?;
+    let res = compile_and_run_in(&dir, source, edition, check_cancelled);
+    _ = fs::remove_dir_all(&dir);
+    res
+}
+
+/// Creates a directory with a random name in the temporary directory, which only the current user
+/// can access. This fails instead of reusing a directory which already exists.
+fn create_temp_dir() -> io::Result<PathBuf> {
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |it| it.as_nanos());
+    let mut rng = oorandom::Rand64::new(seed ^ u128::from(process::id()));
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    loop {
+        let dir = env::temp_dir().join(format!("rust-analyzer-eval-{:016x}", rng.rand_u64()));
+        match builder.create(&dir) {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            res => return res.map(|()| dir),
+        }
+    }
+}
+
+fn compile_and_run_in(
+    dir: &Path,
+    source: &str,
+    edition: Edition,
+    check_cancelled: &dyn Fn() -> Cancellable<()>,
+) -> anyhow::Result<String> {
+    // The output goes to files so that a chatty process can't block on a full pipe.
+    let stdout_path = dir.join("stdout");
+    let stderr_path = dir.join("stderr");
+    let spawn = |cmd: &mut Command| -> anyhow::Result<Child> {
+        Ok(cmd
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(fs::File::create(&stdout_path)?)
+            .stderr(fs::File::create(&stderr_path)?)
+            .spawn()?)
+    };
+
+    let executable = dir.join(if cfg!(windows) { "eval.exe" } else { "eval" });
+    let mut program = sandboxed(&executable, dir)?;
+    fs::write(dir.join("main.rs"), source)?;
+    let rustc = spawn(
+        Command::new(toolchain::rustc())
+            .args(["--edition", &edition.to_string(), "--crate-name", "eval", "-o"])
+            .arg(&executable)
+            .arg("main.rs"),
+    )
+    .context("failed to run rustc")?;
+    if !wait(rustc, None, check_cancelled)?.success() {
+        bail!("failed to compile the expression:\n{}", fs::read_to_string(&stderr_path)?);
+    }
+
+    limit_resources(program.env_clear());
+    let program = spawn(&mut program).context("failed to run the compiled expression")?;
+    let status = wait(program, Some(TIMEOUT), check_cancelled)?;
+    if !status.success() {
+        bail!("the evaluation failed with {status}:\n{}", fs::read_to_string(&stderr_path)?);
+    }
+    Ok(fs::read_to_string(&stdout_path)?.trim_end().to_owned())
+}
+
+/// The command running `executable` in a sandbox which can only write to `dir`, or an error if no
+/// sandbox is available.
+fn sandboxed(executable: &Path, dir: &Path) -> anyhow::Result<Command> {
+    if cfg!(target_os = "linux") {
+        let bwrap = find_in_path("bwrap")
+            .context("`bwrap` is required to run the evaluated expression in a sandbox")?;
+        let mut cmd = Command::new(bwrap);
+        cmd.args(["--unshare-all", "--die-with-parent", "--new-session"])
+            .args(["--ro-bind", "/usr", "/usr"])
+            .args(["--ro-bind-try", "/lib", "/lib"])
+            .args(["--ro-bind-try", "/lib64", "/lib64"])
+            .args(["--ro-bind-try", "/etc/ld.so.cache", "/etc/ld.so.cache"])
+            .args(["--proc", "/proc", "--dev", "/dev"])
+            .arg("--bind")
+            .args([dir, dir])
+            .arg("--chdir")
+            .arg(dir)
+            .arg("--")
+            .arg(executable);
+        Ok(cmd)
+    } else if cfg!(target_os = "macos") {
+        // The profile has to use the real paths, the temporary directory is behind a symlink.
+        let dir = dir.canonicalize()?;
+        let executable = dir.join(executable.file_name().unwrap_or_default());
+        let profile = format!(
+            "(version 1)\
+             (deny default)\
+             (allow process-exec (literal \"{executable}\"))\
+             (allow file-read* (subpath \"/usr/lib\") (subpath \"/System\") (subpath \"{dir}\"))\
+             (allow file-write* (subpath \"{dir}\"))\
+             (allow sysctl-read)",
+            executable = executable.display(),
+            dir = dir.display(),
+        );
+        let mut cmd = Command::new("/usr/bin/sandbox-exec");
+        cmd.arg("-p").arg(profile).arg(&executable);
+        Ok(cmd)
+    } else {
+        bail!("no sandbox is available on this platform to run the evaluated expression")
+    }
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths).map(|dir| dir.join(name)).find(|it| it.is_file())
+}
+
+/// Limits the CPU time, the memory and the size of the files of the sandboxed process.
+#[cfg(unix)]
+fn limit_resources(cmd: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    let limits = [
+        (libc::RLIMIT_CPU, TIMEOUT.as_secs()),
+        (libc::RLIMIT_AS, MEMORY_LIMIT),
+        (libc::RLIMIT_FSIZE, FILE_SIZE_LIMIT),
+    ];
+    // SAFETY: `setrlimit` is async-signal-safe and nothing is allocated between fork and exec.
+    unsafe {
+        cmd.pre_exec(move || {
+            for (resource, limit) in limits {
+                let rlimit = libc::rlimit { rlim_cur: limit as _, rlim_max: limit as _ };
+                if libc::setrlimit(resource, &rlimit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_resources(_cmd: &mut Command) {}
+
+/// Waits for `child` to exit, killing it if it runs longer than `timeout` or the request is
+/// cancelled.
+fn wait(
+    mut child: Child,
+    timeout: Option<Duration>,
+    check_cancelled: &dyn Fn() -> Cancellable<()>,
+) -> anyhow::Result<ExitStatus> {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        let cancelled = check_cancelled();
+        let timed_out = timeout.map_or(false, |timeout| start.elapsed() > timeout);
+        if cancelled.is_err() || timed_out {
+            _ = child.kill();
+            _ = child.wait();
+            cancelled?;
+            bail!("the evaluation timed out after {TIMEOUT:?}");
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
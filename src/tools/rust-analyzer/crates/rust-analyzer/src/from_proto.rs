@@ -1,6 +1,6 @@
 //! Conversion lsp_types types to rust-analyzer specific ones.
 use anyhow::format_err;
-use ide::{Annotation, AnnotationKind, AssistKind, LineCol};
+use ide::{Annotation, AnnotationKind, AssistKind, LineCol, ReferenceCategory};
 use ide_db::{
     base_db::{FileId, FilePosition, FileRange},
     line_index::WideLineCol,
@@ -95,6 +95,14 @@ pub(crate) fn assist_kind(kind: lsp_types::CodeActionKind) -> Option<AssistKind>
     Some(assist_kind)
 }
 
+pub(crate) fn reference_category(kind: lsp_ext::ReferenceAccessKind) -> ReferenceCategory {
+    match kind {
+        lsp_ext::ReferenceAccessKind::Read => ReferenceCategory::Read,
+        lsp_ext::ReferenceAccessKind::Write => ReferenceCategory::Write,
+        lsp_ext::ReferenceAccessKind::Import => ReferenceCategory::Import,
+    }
+}
+
 pub(crate) fn annotation(
     snap: &GlobalStateSnapshot,
     code_lens: lsp_types::CodeLens,
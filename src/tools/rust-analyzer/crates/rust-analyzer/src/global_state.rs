@@ -3,21 +3,28 @@
 //!
 //! Each tick provides an immutable snapshot of the state as `WorldSnapshot`.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
 use ide::{Analysis, AnalysisHost, Cancellable, Change, FileId};
 use ide_db::base_db::{CrateId, FileLoader, SourceDatabase};
-use lsp_types::{SemanticTokens, Url};
+use lsp_types::Url;
 use parking_lot::{Mutex, RwLock};
 use proc_macro_api::ProcMacroServer;
 use project_model::{CargoWorkspace, ProjectWorkspace, Target, WorkspaceBuildScripts};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::hash::NoHashHashMap;
 use vfs::AnchoredPathBuf;
 
 use crate::{
+    cargo_features::FeatureOverrides,
     config::Config,
     diagnostics::{CheckFixes, DiagnosticCollection},
     from_proto,
@@ -26,8 +33,11 @@
     main_loop::Task,
     mem_docs::MemDocs,
     op_queue::OpQueue,
-    reload::{self, SourceRootConfig},
+    reload::{self, ProcMacroFallback, SourceRootConfig},
+    request_trace::RequestTrace,
+    semantic_tokens::CachedSemanticTokens,
     task_pool::TaskPool,
+    test_runner::TestRunSession,
     to_proto::url_from_abs_path,
     Result,
 };
@@ -57,17 +67,27 @@ pub(crate) struct GlobalState {
     pub(crate) analysis_host: AnalysisHost,
     pub(crate) diagnostics: DiagnosticCollection,
     pub(crate) mem_docs: MemDocs,
-    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, CachedSemanticTokens>>>,
+    pub(crate) request_trace: Arc<RequestTrace>,
+    /// The cancellation flags of the requests running on their own thread, which are set when the
+    /// client cancels the request.
+    pub(crate) thread_request_cancellations: FxHashMap<lsp_server::RequestId, Arc<AtomicBool>>,
     pub(crate) shutdown_requested: bool,
     pub(crate) proc_macro_changed: bool,
     pub(crate) last_reported_status: Option<lsp_ext::ServerStatusParams>,
     pub(crate) source_root_config: SourceRootConfig,
     pub(crate) proc_macro_clients: Vec<Result<ProcMacroServer, String>>,
+    /// The proc-macro servers of the toolchains of the workspaces by toolchain version, which load
+    /// the dylibs built by that toolchain in any workspace.
+    pub(crate) proc_macro_toolchain_clients: FxHashMap<(usize, usize, usize), ProcMacroServer>,
+    pub(crate) proc_macro_fallback: Option<ProcMacroFallback>,
 
     pub(crate) flycheck: Arc<[FlycheckHandle]>,
     pub(crate) flycheck_sender: Sender<flycheck::Message>,
     pub(crate) flycheck_receiver: Receiver<flycheck::Message>,
 
+    pub(crate) test_run_session: Option<TestRunSession>,
+
     pub(crate) vfs: Arc<RwLock<(vfs::Vfs, NoHashHashMap<FileId, LineEndings>)>>,
     pub(crate) vfs_config_version: u32,
     pub(crate) vfs_progress_config_version: u32,
@@ -103,8 +123,17 @@ pub(crate) struct GlobalState {
     pub(crate) fetch_workspaces_queue: OpQueue<Option<Vec<anyhow::Result<ProjectWorkspace>>>>,
     pub(crate) fetch_build_data_queue:
         OpQueue<(Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)>,
+    /// The ids of the packages whose build scripts the requested fetch of the build data re-runs,
+    /// or `None` if it runs all of them.
+    pub(crate) build_data_packages: Option<FxHashSet<String>>,
 
     pub(crate) prime_caches_queue: OpQueue<()>,
+
+    pub(crate) feature_overrides: FeatureOverrides,
+
+    /// The memory usage in megabytes after the caches were last dropped to stay within
+    /// `rust-analyzer.lru.memoryBudget`.
+    pub(crate) memory_after_eviction: Option<isize>,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -113,11 +142,14 @@ pub(crate) struct GlobalStateSnapshot {
     pub(crate) analysis: Analysis,
     pub(crate) check_fixes: CheckFixes,
     mem_docs: MemDocs,
-    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, CachedSemanticTokens>>>,
     vfs: Arc<RwLock<(vfs::Vfs, NoHashHashMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
     pub(crate) proc_macros_loaded: bool,
     pub(crate) flycheck: Arc<[FlycheckHandle]>,
+    /// Set when the client cancels the request this snapshot was taken for. Only tracked for
+    /// requests dispatched onto their own thread.
+    pub(crate) request_cancelled: Arc<AtomicBool>,
 }
 
 impl std::panic::UnwindSafe for GlobalStateSnapshot {}
@@ -150,16 +182,22 @@ pub(crate) fn new(sender: Sender<lsp_server::Message>, config: Config) -> Global
             diagnostics: Default::default(),
             mem_docs: MemDocs::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            request_trace: Arc::new(Default::default()),
+            thread_request_cancellations: FxHashMap::default(),
             shutdown_requested: false,
             proc_macro_changed: false,
             last_reported_status: None,
             source_root_config: SourceRootConfig::default(),
             proc_macro_clients: vec![],
+            proc_macro_toolchain_clients: FxHashMap::default(),
+            proc_macro_fallback: None,
 
             flycheck: Arc::new([]),
             flycheck_sender,
             flycheck_receiver,
 
+            test_run_session: None,
+
             vfs: Arc::new(RwLock::new((vfs::Vfs::default(), NoHashHashMap::default()))),
             vfs_config_version: 0,
             vfs_progress_config_version: 0,
@@ -170,9 +208,14 @@ pub(crate) fn new(sender: Sender<lsp_server::Message>, config: Config) -> Global
             fetch_workspaces_queue: OpQueue::default(),
             prime_caches_queue: OpQueue::default(),
 
+            feature_overrides: FeatureOverrides::default(),
+            memory_after_eviction: None,
+
             fetch_build_data_queue: OpQueue::default(),
+            build_data_packages: None,
         };
         // Apply any required database inputs from the config.
+        this.update_lru_capacities();
         this.update_configuration(config);
         this
     }
@@ -295,9 +338,19 @@ pub(crate) fn process_changes(&mut self) -> bool {
                 });
         }
 
+        if self.config.test_explorer() {
+            self.update_tests(&changed_files);
+        }
+
         true
     }
 
+    /// Marks the cached semantic tokens as outdated. They are kept around to compute the next
+    /// deltas against.
+    pub(crate) fn outdate_semantic_tokens(&self) {
+        self.semantic_tokens_cache.lock().values_mut().for_each(|it| it.version = None);
+    }
+
     pub(crate) fn snapshot(&self) -> GlobalStateSnapshot {
         GlobalStateSnapshot {
             config: Arc::clone(&self.config),
@@ -309,6 +362,7 @@ pub(crate) fn snapshot(&self) -> GlobalStateSnapshot {
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
             proc_macros_loaded: !self.fetch_build_data_queue.last_op_result().0.is_empty(),
             flycheck: self.flycheck.clone(),
+            request_cancelled: Arc::default(),
         }
     }
 
@@ -349,6 +403,7 @@ pub(crate) fn register_request(
     }
 
     pub(crate) fn respond(&mut self, response: lsp_server::Response) {
+        self.thread_request_cancellations.remove(&response.id);
         if let Some((method, start)) = self.req_queue.incoming.complete(response.id.clone()) {
             if let Some(err) = &response.error {
                 if err.message.starts_with("server panicked") {
@@ -358,11 +413,15 @@ pub(crate) fn respond(&mut self, response: lsp_server::Response) {
 
             let duration = start.elapsed();
             tracing::debug!("handled {} - ({}) in {:0.2?}", method, response.id, duration);
+            self.request_trace.record_response(&response.id, &method, start);
             self.send(response.into());
         }
     }
 
     pub(crate) fn cancel(&mut self, request_id: lsp_server::RequestId) {
+        if let Some(cancelled) = self.thread_request_cancellations.remove(&request_id) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
         if let Some(response) = self.req_queue.incoming.cancel(request_id) {
             self.send(response.into());
         }
@@ -384,6 +443,11 @@ fn drop(&mut self) {
 }
 
 impl GlobalStateSnapshot {
+    /// Whether the client cancelled the request or a change to the analysis is pending.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.request_cancelled.load(Ordering::Relaxed) || self.analysis.check_cancelled().is_err()
+    }
+
     pub(crate) fn url_to_file_id(&self, url: &Url) -> Result<FileId> {
         url_to_file_id(&self.vfs.read().0, url)
     }
@@ -399,6 +463,15 @@ pub(crate) fn file_line_index(&self, file_id: FileId) -> Cancellable<LineIndex>
         Ok(res)
     }
 
+    /// Whether the file is too large to be analyzed beyond its syntax, in which case
+    /// features requiring type information are disabled for it.
+    pub(crate) fn exceeds_analysis_size(&self, file_id: FileId) -> Cancellable<bool> {
+        match self.config.max_analysis_file_size() {
+            Some(max_size) => Ok(self.analysis.file_text(file_id)?.len() > max_size),
+            None => Ok(false),
+        }
+    }
+
     pub(crate) fn url_file_version(&self, url: &Url) -> Option<i32> {
         let path = from_proto::vfs_path(url).ok()?;
         Some(self.mem_docs.get(&path)?.version)
@@ -3,15 +3,18 @@
 //! `ide` crate.
 
 use std::{
-    io::Write as _,
+    collections::HashMap,
+    io::{self, Write as _},
     process::{self, Stdio},
 };
 
 use anyhow::Context;
 use ide::{
-    AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, FileId, FilePosition,
-    FileRange, HoverAction, HoverGotoTypeData, Query, RangeInfo, ReferenceCategory, Runnable,
-    RunnableKind, SingleResolve, SourceChange, TextEdit,
+    AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, Cancelled, ExpandMacroConfig,
+    ExpressionEvaluation, FileId, FilePosition, FileRange, HighlightExportConfig,
+    HighlightExportFormat, HighlightTheme, HoverAction, HoverGotoTypeData, NavigationTarget,
+    PeekRelatedConfig, Query, QueryOperators, RangeInfo, ReferenceCategory, Runnable, RunnableKind,
+    SingleResolve, SourceChange, TextEdit, ViewCrateGraphConfig,
 };
 use ide_db::SymbolKind;
 use lsp_server::ErrorCode;
@@ -20,7 +23,7 @@
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeLens, CompletionItem, Diagnostic, DiagnosticTag, DocumentFormattingParams, FoldingRange,
     FoldingRangeParams, HoverContents, InlayHint, InlayHintParams, Location, LocationLink,
-    NumberOrString, Position, PrepareRenameResponse, Range, RenameParams,
+    NumberOrString, Position, PrepareRenameResponse, Range, RenameParams, SemanticTokensDelta,
     SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
     SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
     SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
@@ -35,12 +38,16 @@
     cargo_target_spec::CargoTargetSpec,
     config::{RustfmtConfig, WorkspaceSymbolConfig},
     diff::diff,
-    from_proto,
+    evaluate, from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
     line_index::LineEndings,
-    lsp_ext::{self, PositionOrRange, ViewCrateGraphParams, WorkspaceSymbolParams},
+    lsp_ext::{
+        self, ExternalDocsPair, ExternalDocsResponse, PositionOrRange, ViewCrateGraphParams,
+        WorkspaceSymbolParams,
+    },
     lsp_utils::{all_edits_are_disjoint, invalid_params_error},
-    to_proto, LspError, Result,
+    semantic_tokens::{self, CachedSemanticTokens},
+    test_runner, to_proto, LspError, Result,
 };
 
 pub(crate) fn handle_workspace_reload(state: &mut GlobalState, _: ()) -> Result<()> {
@@ -48,6 +55,7 @@ pub(crate) fn handle_workspace_reload(state: &mut GlobalState, _: ()) -> Result<
     state.proc_macro_changed = false;
     state.fetch_workspaces_queue.request_op("reload workspace request".to_string());
     state.fetch_build_data_queue.request_op("reload workspace request".to_string());
+    state.build_data_packages = None;
     Ok(())
 }
 
@@ -107,11 +115,44 @@ pub(crate) fn handle_memory_usage(state: &mut GlobalState, _: ()) -> Result<Stri
     Ok(out)
 }
 
+pub(crate) fn handle_request_trace(
+    state: &mut GlobalState,
+    params: lsp_ext::RequestTraceParams,
+) -> Result<String> {
+    let _p = profile::span("handle_request_trace");
+    if let Some(enable) = params.enable {
+        state.request_trace.set_enabled(enable);
+    }
+
+    let mut buf = String::new();
+    if let Some(path) = params.chrome_trace_path {
+        std::fs::write(&path, state.request_trace.chrome_trace())?;
+        format_to!(buf, "Chrome trace written to {}\n\n", path.display());
+    }
+    buf.push_str(&state.request_trace.report());
+    Ok(buf)
+}
+
 pub(crate) fn handle_shuffle_crate_graph(state: &mut GlobalState, _: ()) -> Result<()> {
     state.analysis_host.shuffle_crate_graph();
     Ok(())
 }
 
+pub(crate) fn handle_cargo_features(
+    state: &mut GlobalState,
+    _: (),
+) -> Result<Vec<lsp_ext::PackageFeatures>> {
+    Ok(state.cargo_features())
+}
+
+pub(crate) fn handle_set_cargo_feature(
+    state: &mut GlobalState,
+    params: lsp_ext::SetCargoFeatureParams,
+) -> Result<lsp_ext::PackageFeatures> {
+    let _p = profile::span("handle_set_cargo_feature");
+    state.set_cargo_feature(params).map_err(|message| invalid_params_error(message).into())
+}
+
 pub(crate) fn handle_syntax_tree(
     snap: GlobalStateSnapshot,
     params: lsp_ext::SyntaxTreeParams,
@@ -152,12 +193,110 @@ pub(crate) fn handle_view_item_tree(
     Ok(res)
 }
 
+pub(crate) fn handle_dead_code(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> Result<Vec<lsp_ext::DeadCodeCrate>> {
+    let _p = profile::span("handle_dead_code");
+    let mut res = Vec::new();
+    for krate in snap.analysis.dead_code()? {
+        let items = krate
+            .items
+            .into_iter()
+            .map(|item| {
+                let frange =
+                    FileRange { file_id: item.nav.file_id, range: item.nav.focus_or_full_range() };
+                Ok(lsp_ext::DeadCodeItem {
+                    path: item.path,
+                    is_public: item.is_public,
+                    location: to_proto::location(&snap, frange)?,
+                })
+            })
+            .collect::<Cancellable<Vec<_>>>()?;
+        res.push(lsp_ext::DeadCodeCrate { name: krate.name, items });
+    }
+    Ok(res)
+}
+
+pub(crate) fn handle_evaluate_expression(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::EvaluateExpressionParams,
+) -> Result<String> {
+    let _p = profile::span("handle_evaluate_expression");
+    let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
+    let (source, edition) = match snap.analysis.evaluate_expression(frange)? {
+        Some(ExpressionEvaluation::Value(value)) => return Ok(value),
+        Some(ExpressionEvaluation::Program { source, edition }) => (source, edition),
+        None => return Err(invalid_params_error("no expression selected".to_owned()).into()),
+    };
+    if !snap.config.evaluate_expression_run_programs() {
+        return Err(LspError::new(
+            ErrorCode::RequestFailed as i32,
+            "only constants can be evaluated, set `rust-analyzer.evaluateExpression.runPrograms.enable` \
+             to compile and run other expressions"
+                .to_owned(),
+        )
+        .into());
+    }
+    evaluate::compile_and_run(&source, edition, &|| snap.analysis.check_cancelled()).map_err(
+        |err| match err.downcast::<Cancelled>() {
+            Ok(cancelled) => cancelled.into(),
+            Err(err) => LspError::new(ErrorCode::RequestFailed as i32, format!("{err:#}")).into(),
+        },
+    )
+}
+
+pub(crate) fn handle_export_highlighted(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ExportHighlightedParams,
+) -> Result<String> {
+    let _p = profile::span("handle_export_highlighted");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let range = params.range.map(|range| from_proto::text_range(&line_index, range)).transpose()?;
+    let config = HighlightExportConfig {
+        format: match params.format {
+            lsp_ext::ExportHighlightedFormat::Html => HighlightExportFormat::Html,
+            lsp_ext::ExportHighlightedFormat::Markdown => HighlightExportFormat::Markdown,
+        },
+        theme: HighlightTheme::default(),
+        binding_types: params.binding_types.unwrap_or(false),
+    };
+    let res = snap.analysis.export_highlighted(file_id, range, &config)?;
+    Ok(res)
+}
+
+pub(crate) fn handle_macro_expansion_costs(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::MacroExpansionCostsParams,
+) -> Result<String> {
+    let _p = profile::span("handle_macro_expansion_costs");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let res = snap.analysis.macro_expansion_costs(file_id)?;
+    Ok(res)
+}
+
 pub(crate) fn handle_view_crate_graph(
     snap: GlobalStateSnapshot,
     params: ViewCrateGraphParams,
 ) -> Result<String> {
     let _p = profile::span("handle_view_crate_graph");
-    let dot = snap.analysis.view_crate_graph(params.full)??;
+    let config = ViewCrateGraphConfig {
+        full: params.full,
+        dev_deps: params.dev_deps.unwrap_or(true),
+        build_deps: params.build_deps.unwrap_or(true),
+    };
+    let dot = snap.analysis.view_crate_graph(config)??;
+    Ok(dot)
+}
+
+pub(crate) fn handle_view_module_tree(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ViewModuleTreeParams,
+) -> Result<String> {
+    let _p = profile::span("handle_view_module_tree");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let dot = snap.analysis.view_module_tree(file_id)??;
     Ok(dot)
 }
 
@@ -170,7 +309,11 @@ pub(crate) fn handle_expand_macro(
     let line_index = snap.file_line_index(file_id)?;
     let offset = from_proto::offset(&line_index, params.position)?;
 
-    let res = snap.analysis.expand_macro(FilePosition { file_id, offset })?;
+    let config = ExpandMacroConfig {
+        depth: params.depth.map(|depth| depth as usize),
+        annotate_hygiene: params.annotate_hygiene.unwrap_or(false),
+    };
+    let res = snap.analysis.expand_macro(&config, FilePosition { file_id, offset })?;
     Ok(res.map(|it| lsp_ext::ExpandedMacro { name: it.name, expansion: it.expansion }))
 }
 
@@ -416,25 +559,19 @@ pub(crate) fn handle_workspace_symbol(
     let _p = profile::span("handle_workspace_symbol");
 
     let config = snap.config.workspace_symbol();
-    let (all_symbols, libs) = decide_search_scope_and_kind(&params, &config);
-    let limit = config.search_limit;
+    let (mut query, operators) = Query::parse(&params.query);
+    let (all_symbols, libs) = decide_search_scope_and_kind(&params, &config, operators);
+    if libs {
+        query.libs();
+    }
+    query.limit(config.search_limit);
 
-    let query = {
-        let query: String = params.query.chars().filter(|&c| c != '#' && c != '*').collect();
-        let mut q = Query::new(query);
-        if !all_symbols {
-            q.only_types();
-        }
-        if libs {
-            q.libs();
-        }
-        q.limit(limit);
-        q
-    };
-    let mut res = exec_query(&snap, query)?;
+    let mut kind_query = query.clone();
+    if !all_symbols {
+        kind_query.only_types();
+    }
+    let mut res = exec_query(&snap, kind_query)?;
     if res.is_empty() && !all_symbols {
-        let mut query = Query::new(params.query);
-        query.limit(limit);
         res = exec_query(&snap, query)?;
     }
 
@@ -443,10 +580,11 @@ pub(crate) fn handle_workspace_symbol(
     fn decide_search_scope_and_kind(
         params: &WorkspaceSymbolParams,
         config: &WorkspaceSymbolConfig,
+        operators: QueryOperators,
     ) -> (bool, bool) {
-        // Support old-style parsing of markers in the query.
-        let mut all_symbols = params.query.contains('#');
-        let mut libs = params.query.contains('*');
+        // Operators in the query take precedence.
+        let mut all_symbols = operators.all_symbols;
+        let mut libs = operators.libs;
 
         // If no explicit marker was set, check request params. If that's also empty
         // use global config.
@@ -566,6 +704,9 @@ pub(crate) fn handle_goto_definition(
 ) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
     let _p = profile::span("handle_goto_definition");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let nav_info = match snap.analysis.goto_definition(position)? {
         None => return Ok(None),
         Some(it) => it,
@@ -581,6 +722,9 @@ pub(crate) fn handle_goto_declaration(
 ) -> Result<Option<lsp_types::request::GotoDeclarationResponse>> {
     let _p = profile::span("handle_goto_declaration");
     let position = from_proto::file_position(&snap, params.text_document_position_params.clone())?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let nav_info = match snap.analysis.goto_declaration(position)? {
         None => return handle_goto_definition(snap, params),
         Some(it) => it,
@@ -596,6 +740,9 @@ pub(crate) fn handle_goto_implementation(
 ) -> Result<Option<lsp_types::request::GotoImplementationResponse>> {
     let _p = profile::span("handle_goto_implementation");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let nav_info = match snap.analysis.goto_implementation(position)? {
         None => return Ok(None),
         Some(it) => it,
@@ -611,6 +758,9 @@ pub(crate) fn handle_goto_type_definition(
 ) -> Result<Option<lsp_types::request::GotoTypeDefinitionResponse>> {
     let _p = profile::span("handle_goto_type_definition");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let nav_info = match snap.analysis.goto_type_definition(position)? {
         None => return Ok(None),
         Some(it) => it,
@@ -744,6 +894,7 @@ pub(crate) fn handle_runnables(
                         cargo_extra_args: config.cargo_extra_args.clone(),
                         executable_args: Vec::new(),
                         expect_test: None,
+                        environment: Default::default(),
                     },
                 })
             }
@@ -768,6 +919,7 @@ pub(crate) fn handle_runnables(
                         cargo_extra_args: config.cargo_extra_args,
                         executable_args: Vec::new(),
                         expect_test: None,
+                        environment: Default::default(),
                     },
                 });
             }
@@ -802,6 +954,105 @@ pub(crate) fn handle_related_tests(
     Ok(res)
 }
 
+pub(crate) fn handle_debug_configuration(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DebugConfigurationParams,
+) -> Result<lsp_ext::DebugConfigurationResult> {
+    let _p = profile::span("handle_debug_configuration");
+    let lsp_ext::Runnable { label, args, .. } = params.runnable;
+    let cwd = match args.workspace_root {
+        Some(it) => it,
+        None => {
+            return Err(invalid_params_error("runnable has no workspace root".to_owned()).into())
+        }
+    };
+
+    // Build the executable instead of running it, like the VS Code extension does.
+    let mut cargo_args = args.cargo_args;
+    let tests_only = match cargo_args.first().map(String::as_str) {
+        Some("run") => {
+            cargo_args[0] = "build".to_owned();
+            false
+        }
+        Some("test" | "bench") => {
+            if !cargo_args.iter().any(|it| it == "--no-run") {
+                cargo_args.push("--no-run".to_owned());
+            }
+            true
+        }
+        _ => false,
+    };
+    // Split on spaces to allow overrides like "wrapper cargo".
+    let mut cmd = match args.override_cargo.as_deref().map(str::split_whitespace) {
+        Some(mut words) => {
+            let mut cmd = process::Command::new(words.next().unwrap_or("cargo"));
+            cmd.args(words);
+            cmd
+        }
+        None => process::Command::new(toolchain::cargo()),
+    };
+    cmd.current_dir(&cwd);
+    cmd.envs(snap.config.extra_env());
+    cmd.envs(&args.environment);
+    cmd.args(&cargo_args);
+    cmd.args(&args.cargo_extra_args);
+    let is_cancelled = || snap.is_cancelled();
+    let program = match flycheck::build_executable(cmd, tests_only, &is_cancelled) {
+        Ok(it) => it,
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+            // A pending change retries the request, a cancelled request was already answered.
+            snap.analysis.check_cancelled()?;
+            return Err(err.into());
+        }
+        Err(err) => Err(err).context("failed to build the executable to debug")?,
+    };
+
+    let mut env: HashMap<_, _> =
+        snap.config.extra_env().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    env.extend(args.environment);
+    Ok(lsp_ext::DebugConfigurationResult {
+        name: label,
+        program: program.into(),
+        args: args.executable_args,
+        cwd,
+        env,
+        source_languages: vec!["rust".to_owned()],
+    })
+}
+
+pub(crate) fn handle_discover_test(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DiscoverTestParams,
+) -> Result<lsp_ext::DiscoverTestResults> {
+    let _p = profile::span("handle_discover_test");
+    let (tests, scope) = match params.test_id {
+        Some(id) => (snap.analysis.discover_tests_in_crate_by_test_id(&id)?, vec![id]),
+        None => (snap.analysis.discover_test_roots()?, vec![]),
+    };
+    let tests = tests
+        .into_iter()
+        .map(|it| to_proto::test_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+    Ok(lsp_ext::DiscoverTestResults { tests, scope })
+}
+
+pub(crate) fn handle_run_test(
+    state: &mut GlobalState,
+    params: lsp_ext::RunTestParams,
+) -> Result<()> {
+    let _p = profile::span("handle_run_test");
+    let snap = state.snapshot();
+    let runs = test_runner::test_runs(&snap, params)?;
+    state.start_test_run(runs);
+    Ok(())
+}
+
+pub(crate) fn handle_abort_run_test(state: &mut GlobalState, _: ()) -> Result<()> {
+    let _p = profile::span("handle_abort_run_test");
+    state.abort_test_run();
+    Ok(())
+}
+
 pub(crate) fn handle_completion(
     snap: GlobalStateSnapshot,
     params: lsp_types::CompletionParams,
@@ -809,6 +1060,9 @@ pub(crate) fn handle_completion(
     let _p = profile::span("handle_completion");
     let text_document_position = params.text_document_position.clone();
     let position = from_proto::file_position(&snap, params.text_document_position)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let completion_trigger_character =
         params.context.and_then(|ctx| ctx.trigger_character).and_then(|s| s.chars().next());
 
@@ -909,6 +1163,9 @@ pub(crate) fn handle_signature_help(
 ) -> Result<Option<lsp_types::SignatureHelp>> {
     let _p = profile::span("handle_signature_help");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let help = match snap.analysis.signature_help(position)? {
         Some(it) => it,
         None => return Ok(None),
@@ -929,6 +1186,9 @@ pub(crate) fn handle_hover(
     };
 
     let file_range = from_proto::file_range(&snap, params.text_document, range)?;
+    if snap.exceeds_analysis_size(file_range.file_id)? {
+        return Ok(None);
+    }
     let info = match snap.analysis.hover(&snap.config.hover(), file_range)? {
         None => return Ok(None),
         Some(info) => info,
@@ -994,10 +1254,17 @@ pub(crate) fn handle_rename(
 
 pub(crate) fn handle_references(
     snap: GlobalStateSnapshot,
-    params: lsp_types::ReferenceParams,
+    params: lsp_ext::ReferenceParams,
 ) -> Result<Option<Vec<Location>>> {
     let _p = profile::span("handle_references");
+    let access_kinds: Option<Vec<ReferenceCategory>> = params
+        .access_kinds
+        .map(|kinds| kinds.into_iter().map(from_proto::reference_category).collect());
+    let params = params.params;
     let position = from_proto::file_position(&snap, params.text_document_position)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
 
     let exclude_imports = snap.config.find_all_refs_exclude_imports();
 
@@ -1023,7 +1290,13 @@ pub(crate) fn handle_references(
                 .flat_map(|(file_id, refs)| {
                     refs.into_iter()
                         .filter(|&(_, category)| {
-                            !exclude_imports || category != Some(ReferenceCategory::Import)
+                            // Only usages of locals and fields are told apart by access, every
+                            // other non-import usage counts as a read.
+                            let category = category.unwrap_or(ReferenceCategory::Read);
+                            match &access_kinds {
+                                Some(kinds) => kinds.contains(&category),
+                                None => !exclude_imports || category != ReferenceCategory::Import,
+                            }
                         })
                         .map(move |(range, _)| FileRange { file_id, range })
                 })
@@ -1208,6 +1481,9 @@ pub(crate) fn handle_code_lens(
     }
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    if snap.exceeds_analysis_size(file_id)? {
+        return Ok(Some(Vec::default()));
+    }
     let cargo_target_spec = CargoTargetSpec::for_file(&snap, file_id)?;
 
     let annotations = snap.analysis.annotations(
@@ -1221,7 +1497,8 @@ pub(crate) fn handle_code_lens(
                 })
                 .unwrap_or(false),
             annotate_runnables: lens_config.runnable(),
-            annotate_impls: lens_config.implementations,
+            annotate_trait_impls: lens_config.impls_trait,
+            annotate_adt_impls: lens_config.impls_adt,
             annotate_references: lens_config.refs_adt,
             annotate_method_references: lens_config.method_refs,
             annotate_enum_variant_references: lens_config.enum_variant_refs,
@@ -1265,6 +1542,9 @@ pub(crate) fn handle_document_highlight(
 ) -> Result<Option<Vec<lsp_types::DocumentHighlight>>> {
     let _p = profile::span("handle_document_highlight");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
     let line_index = snap.file_line_index(position.file_id)?;
 
     let refs = match snap.analysis.highlight_related(snap.config.highlight_related(), position)? {
@@ -1306,6 +1586,9 @@ pub(crate) fn publish_diagnostics(
     file_id: FileId,
 ) -> Result<Vec<Diagnostic>> {
     let _p = profile::span("publish_diagnostics");
+    if snap.exceeds_analysis_size(file_id)? {
+        return Ok(Vec::new());
+    }
     let line_index = snap.file_line_index(file_id)?;
 
     let diagnostics: Vec<Diagnostic> = snap
@@ -1344,6 +1627,9 @@ pub(crate) fn handle_inlay_hints(
         TextDocumentIdentifier::new(document_uri.to_owned()),
         params.range,
     )?;
+    if snap.exceeds_analysis_size(file_id)? {
+        return Ok(None);
+    }
     let line_index = snap.file_line_index(file_id)?;
     let inlay_hints_config = snap.config.inlay_hints();
     Ok(Some(
@@ -1371,6 +1657,9 @@ pub(crate) fn handle_call_hierarchy_prepare(
 ) -> Result<Option<Vec<CallHierarchyItem>>> {
     let _p = profile::span("handle_call_hierarchy_prepare");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
 
     let nav_info = match snap.analysis.call_hierarchy(position)? {
         None => return Ok(None),
@@ -1457,6 +1746,119 @@ pub(crate) fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_type_hierarchy_prepare(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyPrepareParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_prepare");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
+
+    let nav_info = match snap.analysis.type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let RangeInfo { range: _, info: navs } = nav_info;
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_supertypes");
+    let fpos = type_hierarchy_item_position(&snap, params.item)?;
+
+    let navs = match snap.analysis.supertypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_subtypes");
+    let fpos = type_hierarchy_item_position(&snap, params.item)?;
+
+    let navs = match snap.analysis.subtypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Cancellable<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+/// The position of the name of a type hierarchy item, from which its next level is computed.
+fn type_hierarchy_item_position(
+    snap: &GlobalStateSnapshot,
+    item: lsp_ext::TypeHierarchyItem,
+) -> Result<FilePosition> {
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(snap, doc, item.selection_range)?;
+    Ok(FilePosition { file_id: frange.file_id, offset: frange.range.start() })
+}
+
+pub(crate) fn handle_peek_related(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::PeekRelatedParams,
+) -> Result<Option<lsp_ext::PeekRelatedResult>> {
+    let _p = profile::span("handle_peek_related");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    if snap.exceeds_analysis_size(position.file_id)? {
+        return Ok(None);
+    }
+
+    // Without a limit requested by the client, enough results are returned to fill a peek view.
+    const DEFAULT_LIMIT: u32 = 100;
+    let config = PeekRelatedConfig {
+        implementations_limit: params.implementations_limit.unwrap_or(DEFAULT_LIMIT) as usize,
+        references_limit: params.references_limit.unwrap_or(DEFAULT_LIMIT) as usize,
+    };
+    let related = match snap.analysis.peek_related(&config, position)? {
+        None => return Ok(None),
+        Some(it) => it.info,
+    };
+
+    let locations_from_navs = |navs: Vec<NavigationTarget>| {
+        navs.into_iter()
+            .map(|nav| to_proto::location_from_nav(&snap, nav))
+            .collect::<Cancellable<Vec<_>>>()
+    };
+    let res = lsp_ext::PeekRelatedResult {
+        definitions: locations_from_navs(related.definitions)?,
+        declarations: locations_from_navs(related.declarations)?,
+        implementations: locations_from_navs(related.implementations)?,
+        implementations_total: related.implementations_total as u32,
+        references: related
+            .references
+            .into_iter()
+            .map(|frange| to_proto::location(&snap, frange))
+            .collect::<Cancellable<Vec<_>>>()?,
+        references_total: related.references_total as u32,
+    };
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_semantic_tokens_full(
     snap: GlobalStateSnapshot,
     params: SemanticTokensParams,
@@ -1464,6 +1866,12 @@ pub(crate) fn handle_semantic_tokens_full(
     let _p = profile::span("handle_semantic_tokens_full");
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let version = snap.url_file_version(&params.text_document.uri);
+    if let Some(cached) = snap.semantic_tokens_cache.lock().get(&params.text_document.uri) {
+        if cached.is_current(version) {
+            return Ok(Some(cached.tokens.clone().into()));
+        }
+    }
     let text = snap.analysis.file_text(file_id)?;
     let line_index = snap.file_line_index(file_id)?;
 
@@ -1471,12 +1879,15 @@ pub(crate) fn handle_semantic_tokens_full(
     // Avoid flashing a bunch of unresolved references when the proc-macro servers haven't been spawned yet.
     highlight_config.syntactic_name_ref_highlighting =
         snap.workspaces.is_empty() || !snap.proc_macros_loaded;
+    // Only highlight files exceeding the analysis size limit by their syntax.
+    highlight_config.syntax_only = snap.exceeds_analysis_size(file_id)?;
 
     let highlights = snap.analysis.highlight(highlight_config, file_id)?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
 
     // Unconditionally cache the tokens
-    snap.semantic_tokens_cache.lock().insert(params.text_document.uri, semantic_tokens.clone());
+    let cached = CachedSemanticTokens { version, tokens: semantic_tokens.clone() };
+    snap.semantic_tokens_cache.lock().insert(params.text_document.uri, cached);
 
     Ok(Some(semantic_tokens.into()))
 }
@@ -1488,6 +1899,14 @@ pub(crate) fn handle_semantic_tokens_full_delta(
     let _p = profile::span("handle_semantic_tokens_full_delta");
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let version = snap.url_file_version(&params.text_document.uri);
+    if let Some(cached) = snap.semantic_tokens_cache.lock().get(&params.text_document.uri) {
+        let result_id = cached.tokens.result_id.as_ref();
+        if cached.is_current(version) && result_id == Some(&params.previous_result_id) {
+            let delta = SemanticTokensDelta { result_id: result_id.cloned(), edits: Vec::new() };
+            return Ok(Some(delta.into()));
+        }
+    }
     let text = snap.analysis.file_text(file_id)?;
     let line_index = snap.file_line_index(file_id)?;
 
@@ -1495,22 +1914,25 @@ pub(crate) fn handle_semantic_tokens_full_delta(
     // Avoid flashing a bunch of unresolved references when the proc-macro servers haven't been spawned yet.
     highlight_config.syntactic_name_ref_highlighting =
         snap.workspaces.is_empty() || !snap.proc_macros_loaded;
+    // Only highlight files exceeding the analysis size limit by their syntax.
+    highlight_config.syntax_only = snap.exceeds_analysis_size(file_id)?;
 
     let highlights = snap.analysis.highlight(highlight_config, file_id)?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
 
     let mut cache = snap.semantic_tokens_cache.lock();
-    let cached_tokens = cache.entry(params.text_document.uri).or_default();
+    let cached = cache.entry(params.text_document.uri).or_default();
+    cached.version = version;
 
-    if let Some(prev_id) = &cached_tokens.result_id {
+    if let Some(prev_id) = &cached.tokens.result_id {
         if *prev_id == params.previous_result_id {
-            let delta = to_proto::semantic_token_delta(cached_tokens, &semantic_tokens);
-            *cached_tokens = semantic_tokens;
+            let delta = to_proto::semantic_token_delta(&cached.tokens, &semantic_tokens);
+            cached.tokens = semantic_tokens;
             return Ok(Some(delta.into()));
         }
     }
 
-    *cached_tokens = semantic_tokens.clone();
+    cached.tokens = semantic_tokens.clone();
 
     Ok(Some(semantic_tokens.into()))
 }
@@ -1521,6 +1943,12 @@ pub(crate) fn handle_semantic_tokens_range(
 ) -> Result<Option<SemanticTokensRangeResult>> {
     let _p = profile::span("handle_semantic_tokens_range");
 
+    let version = snap.url_file_version(&params.text_document.uri);
+    if let Some(cached) = snap.semantic_tokens_cache.lock().get(&params.text_document.uri) {
+        if cached.is_current(version) {
+            return Ok(Some(semantic_tokens::tokens_in_range(&cached.tokens, params.range).into()));
+        }
+    }
     let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
     let text = snap.analysis.file_text(frange.file_id)?;
     let line_index = snap.file_line_index(frange.file_id)?;
@@ -1529,6 +1957,8 @@ pub(crate) fn handle_semantic_tokens_range(
     // Avoid flashing a bunch of unresolved references when the proc-macro servers haven't been spawned yet.
     highlight_config.syntactic_name_ref_highlighting =
         snap.workspaces.is_empty() || !snap.proc_macros_loaded;
+    // Only highlight files exceeding the analysis size limit by their syntax.
+    highlight_config.syntax_only = snap.exceeds_analysis_size(frange.file_id)?;
 
     let highlights = snap.analysis.highlight_range(highlight_config, frange)?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
@@ -1538,13 +1968,48 @@ pub(crate) fn handle_semantic_tokens_range(
 pub(crate) fn handle_open_docs(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentPositionParams,
-) -> Result<Option<lsp_types::Url>> {
+) -> Result<ExternalDocsResponse> {
     let _p = profile::span("handle_open_docs");
     let position = from_proto::file_position(&snap, params)?;
 
-    let remote = snap.analysis.external_docs(position)?;
+    // The local documentation is built into the target directory of the workspace owning the
+    // file, which has either a package or the sysroot containing the root of the file's crate.
+    let crate_root = match snap.analysis.crates_for(position.file_id)?.first() {
+        Some(&crate_id) => snap.file_id_to_file_path(snap.analysis.crate_root(crate_id)?),
+        None => snap.file_id_to_file_path(position.file_id),
+    };
+    let (target_dir, sysroot) = crate_root
+        .as_path()
+        .and_then(|crate_root| {
+            snap.workspaces.iter().find_map(|ws| match ws {
+                ProjectWorkspace::Cargo { cargo, sysroot, .. } => {
+                    let sysroot = sysroot.as_ref().map(|it| it.root());
+                    let owns_file = cargo.packages().any(|pkg| {
+                        cargo[pkg].targets.iter().any(|&it| cargo[it].root.as_path() == crate_root)
+                    }) || sysroot.map_or(false, |it| crate_root.starts_with(it));
+                    owns_file.then(|| {
+                        (
+                            Some(cargo.target_directory().as_os_str()),
+                            sysroot.map(|it| it.as_os_str()),
+                        )
+                    })
+                }
+                _ => None,
+            })
+        })
+        .unwrap_or_default();
+
+    let Some(links) = snap.analysis.external_docs(position, target_dir, sysroot)? else {
+        return Ok(ExternalDocsResponse::default());
+    };
+    let web = links.web_url.and_then(|it| Url::parse(&it).ok());
+    let local = links.local_url.and_then(|it| Url::parse(&it).ok());
 
-    Ok(remote.and_then(|remote| Url::parse(&remote).ok()))
+    if snap.config.local_docs() {
+        Ok(ExternalDocsResponse::WithLocal(ExternalDocsPair { web, local }))
+    } else {
+        Ok(ExternalDocsResponse::Simple(web))
+    }
 }
 
 pub(crate) fn handle_open_cargo_toml(
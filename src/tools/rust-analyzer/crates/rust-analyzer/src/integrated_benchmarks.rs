@@ -146,6 +146,7 @@ fn integrated_completion_benchmark() {
             },
             snippets: Vec::new(),
             prefer_no_std: false,
+            prefer_defining_crate: false,
             limit: None,
         };
         let position =
@@ -185,6 +186,7 @@ fn integrated_completion_benchmark() {
             },
             snippets: Vec::new(),
             prefer_no_std: false,
+            prefer_defining_crate: false,
             limit: None,
         };
         let position =
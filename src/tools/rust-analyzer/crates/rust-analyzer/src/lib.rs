@@ -19,10 +19,12 @@ macro_rules! eprintln {
 }
 
 mod caps;
+mod cargo_features;
 mod cargo_target_spec;
 mod diagnostics;
 mod diff;
 mod dispatch;
+mod evaluate;
 mod from_proto;
 mod global_state;
 mod handlers;
@@ -33,8 +35,10 @@ macro_rules! eprintln {
 mod mem_docs;
 mod op_queue;
 mod reload;
+mod request_trace;
 mod semantic_tokens;
 mod task_pool;
+mod test_runner;
 mod to_proto;
 mod version;
 
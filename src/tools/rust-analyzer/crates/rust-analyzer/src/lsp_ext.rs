@@ -35,6 +35,21 @@ impl Request for MemoryUsage {
     const METHOD: &'static str = "rust-analyzer/memoryUsage";
 }
 
+pub enum RequestTrace {}
+
+impl Request for RequestTrace {
+    type Params = RequestTraceParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/requestTrace";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestTraceParams {
+    pub enable: Option<bool>,
+    pub chrome_trace_path: Option<PathBuf>,
+}
+
 pub enum ShuffleCrateGraph {}
 
 impl Request for ShuffleCrateGraph {
@@ -51,6 +66,51 @@ impl Request for ReloadWorkspace {
     const METHOD: &'static str = "rust-analyzer/reloadWorkspace";
 }
 
+pub enum CargoFeatures {}
+
+impl Request for CargoFeatures {
+    type Params = ();
+    type Result = Vec<PackageFeatures>;
+    const METHOD: &'static str = "rust-analyzer/cargoFeatures";
+}
+
+pub enum SetCargoFeature {}
+
+impl Request for SetCargoFeature {
+    type Params = SetCargoFeatureParams;
+    type Result = PackageFeatures;
+    const METHOD: &'static str = "rust-analyzer/setCargoFeature";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCargoFeatureParams {
+    /// The Cargo package id of the package.
+    pub package: String,
+    pub feature: String,
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFeatures {
+    /// The Cargo package id of the package.
+    pub package: String,
+    pub name: String,
+    pub manifest_path: String,
+    /// The features of the package, sorted by name.
+    pub features: Vec<CargoFeature>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CargoFeature {
+    pub name: String,
+    pub enabled: bool,
+    /// The features and optional dependencies enabled along with this feature.
+    pub implies: Vec<String>,
+}
+
 pub enum SyntaxTree {}
 
 impl Request for SyntaxTree {
@@ -87,6 +147,10 @@ impl Request for ViewFileText {
 pub struct ViewCrateGraphParams {
     /// Include *all* crates, not just crates in the workspace.
     pub full: bool,
+    /// Include dev-dependencies, `true` if not set.
+    pub dev_deps: Option<bool>,
+    /// Include build-dependencies, `true` if not set.
+    pub build_deps: Option<bool>,
 }
 
 pub enum ViewCrateGraph {}
@@ -111,6 +175,96 @@ impl Request for ViewItemTree {
     const METHOD: &'static str = "rust-analyzer/viewItemTree";
 }
 
+pub enum DeadCode {}
+
+impl Request for DeadCode {
+    type Params = ();
+    type Result = Vec<DeadCodeCrate>;
+    const METHOD: &'static str = "rust-analyzer/deadCode";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCodeCrate {
+    pub name: String,
+    pub items: Vec<DeadCodeItem>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCodeItem {
+    pub path: String,
+    pub is_public: bool,
+    pub location: lsp_types::Location,
+}
+
+pub enum EvaluateExpression {}
+
+impl Request for EvaluateExpression {
+    type Params = EvaluateExpressionParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/evaluateExpression";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateExpressionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+}
+
+pub enum ExportHighlighted {}
+
+impl Request for ExportHighlighted {
+    type Params = ExportHighlightedParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/exportHighlighted";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHighlightedParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Option<Range>,
+    pub format: ExportHighlightedFormat,
+    pub binding_types: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportHighlightedFormat {
+    Html,
+    Markdown,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroExpansionCostsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+pub enum MacroExpansionCosts {}
+
+impl Request for MacroExpansionCosts {
+    type Params = MacroExpansionCostsParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/macroExpansionCosts";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewModuleTreeParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+pub enum ViewModuleTree {}
+
+impl Request for ViewModuleTree {
+    type Params = ViewModuleTreeParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewModuleTree";
+}
+
 pub enum ExpandMacro {}
 
 impl Request for ExpandMacro {
@@ -124,6 +278,8 @@ impl Request for ExpandMacro {
 pub struct ExpandMacroParams {
     pub text_document: TextDocumentIdentifier,
     pub position: Position,
+    pub depth: Option<u32>,
+    pub annotate_hygiene: Option<bool>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -259,6 +415,35 @@ pub struct CargoRunnable {
     pub executable_args: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expect_test: Option<bool>,
+    // environment variables to set in addition to the client's own
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environment: HashMap<String, String>,
+}
+
+pub enum DebugConfiguration {}
+
+impl Request for DebugConfiguration {
+    type Params = DebugConfigurationParams;
+    type Result = DebugConfigurationResult;
+    const METHOD: &'static str = "rust-analyzer/debugConfiguration";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugConfigurationParams {
+    pub runnable: Runnable,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugConfigurationResult {
+    pub name: String,
+    // the executable built for the runnable
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    pub source_languages: Vec<String>,
 }
 
 pub enum RelatedTests {}
@@ -274,6 +459,118 @@ pub struct TestInfo {
     pub runnable: Runnable,
 }
 
+pub enum DiscoverTest {}
+
+impl Request for DiscoverTest {
+    type Params = DiscoverTestParams;
+    type Result = DiscoverTestResults;
+    const METHOD: &'static str = "experimental/discoverTest";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverTestParams {
+    pub test_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverTestResults {
+    pub tests: Vec<TestItem>,
+    // ids of the items whose children were all discovered, children of these missing from `tests`
+    // are gone
+    pub scope: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestItem {
+    pub id: String,
+    pub label: String,
+    pub kind: TestItemKind,
+    // crates are discovered without their tests, which the client asks for when needed
+    pub can_resolve_children: bool,
+    pub parent: Option<String>,
+    pub text_document: Option<TextDocumentIdentifier>,
+    pub range: Option<Range>,
+    pub runnable: Option<Runnable>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum TestItemKind {
+    Package,
+    Module,
+    Test,
+}
+
+pub enum DiscoveredTests {}
+
+impl Notification for DiscoveredTests {
+    type Params = DiscoverTestResults;
+    const METHOD: &'static str = "experimental/discoveredTests";
+}
+
+pub enum RunTest {}
+
+impl Request for RunTest {
+    type Params = RunTestParams;
+    type Result = ();
+    const METHOD: &'static str = "experimental/runTest";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTestParams {
+    // all the tests of the workspace when `None`
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+pub enum AbortRunTest {}
+
+impl Notification for AbortRunTest {
+    type Params = ();
+    const METHOD: &'static str = "experimental/abortRunTest";
+}
+
+pub enum EndRunTest {}
+
+impl Notification for EndRunTest {
+    type Params = ();
+    const METHOD: &'static str = "experimental/endRunTest";
+}
+
+pub enum ChangeTestState {}
+
+impl Notification for ChangeTestState {
+    type Params = ChangeTestStateParams;
+    const METHOD: &'static str = "experimental/changeTestState";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeTestStateParams {
+    pub test_id: String,
+    pub state: TestState,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase", tag = "tag")]
+pub enum TestState {
+    Started,
+    Passed,
+    Failed { message: String },
+    Skipped,
+}
+
+pub enum AppendOutputToRunTest {}
+
+impl Notification for AppendOutputToRunTest {
+    type Params = String;
+    const METHOD: &'static str = "experimental/appendOutputToRunTest";
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintsParams {
@@ -458,10 +755,30 @@ pub enum ExternalDocs {}
 
 impl Request for ExternalDocs {
     type Params = lsp_types::TextDocumentPositionParams;
-    type Result = Option<lsp_types::Url>;
+    type Result = ExternalDocsResponse;
     const METHOD: &'static str = "experimental/externalDocs";
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExternalDocsResponse {
+    Simple(Option<lsp_types::Url>),
+    WithLocal(ExternalDocsPair),
+}
+
+impl Default for ExternalDocsResponse {
+    fn default() -> Self {
+        ExternalDocsResponse::Simple(None)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalDocsPair {
+    pub web: Option<lsp_types::Url>,
+    pub local: Option<lsp_types::Url>,
+}
+
 pub enum OpenCargoToml {}
 
 impl Request for OpenCargoToml {
@@ -564,6 +881,106 @@ pub enum WorkspaceSymbolSearchKind {
     AllSymbols,
 }
 
+pub enum References {}
+
+impl Request for References {
+    type Params = ReferenceParams;
+    type Result = Option<Vec<lsp_types::Location>>;
+    const METHOD: &'static str = "textDocument/references";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceParams {
+    #[serde(flatten)]
+    pub params: lsp_types::ReferenceParams,
+
+    pub access_kinds: Option<Vec<ReferenceAccessKind>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceAccessKind {
+    Read,
+    Write,
+    Import,
+}
+
+/// The type hierarchy requests of LSP 3.17, which `lsp_types` doesn't provide yet.
+pub enum TypeHierarchyPrepare {}
+
+impl Request for TypeHierarchyPrepare {
+    type Params = TypeHierarchyPrepareParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "textDocument/prepareTypeHierarchy";
+}
+
+pub enum TypeHierarchySupertypes {}
+
+impl Request for TypeHierarchySupertypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/supertypes";
+}
+
+pub enum TypeHierarchySubtypes {}
+
+impl Request for TypeHierarchySubtypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/subtypes";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyPrepareParams {
+    #[serde(flatten)]
+    pub text_document_position_params: lsp_types::TextDocumentPositionParams,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyItemParams {
+    pub item: TypeHierarchyItem,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+/// Has the same fields as a call hierarchy item.
+pub type TypeHierarchyItem = lsp_types::CallHierarchyItem;
+
+pub enum PeekRelated {}
+
+impl Request for PeekRelated {
+    type Params = PeekRelatedParams;
+    type Result = Option<PeekRelatedResult>;
+    const METHOD: &'static str = "experimental/peekRelated";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PeekRelatedParams {
+    #[serde(flatten)]
+    pub text_document_position_params: lsp_types::TextDocumentPositionParams,
+    pub implementations_limit: Option<u32>,
+    pub references_limit: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PeekRelatedResult {
+    pub definitions: Vec<lsp_types::Location>,
+    pub declarations: Vec<lsp_types::Location>,
+    pub implementations: Vec<lsp_types::Location>,
+    pub implementations_total: u32,
+    pub references: Vec<lsp_types::Location>,
+    pub references_total: u32,
+}
+
 /// The document on type formatting request is sent from the client to
 /// the server to format parts of the document during typing.  This is
 /// almost same as lsp_types::request::OnTypeFormatting, but the
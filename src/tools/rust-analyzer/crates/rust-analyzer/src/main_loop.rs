@@ -8,9 +8,11 @@
 };
 
 use always_assert::always;
-use crossbeam_channel::{select, Receiver};
-use flycheck::FlycheckHandle;
-use ide_db::base_db::{SourceDatabaseExt, VfsPath};
+use crossbeam_channel::{never, select, Receiver};
+use ide_db::{
+    base_db::{SourceDatabaseExt, VfsPath},
+    helpers::is_analyzed,
+};
 use itertools::Itertools;
 use lsp_server::{Connection, Notification, Request};
 use lsp_types::notification::Notification as _;
@@ -58,6 +60,7 @@ enum Event {
     Task(Task),
     Vfs(vfs::loader::Message),
     Flycheck(flycheck::Message),
+    TestResult(flycheck::CargoTestMessage),
 }
 
 #[derive(Debug)]
@@ -68,6 +71,7 @@ pub(crate) enum Task {
     PrimeCaches(PrimeCachesProgress),
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
+    DiscoverTest(lsp_ext::DiscoverTestResults),
 }
 
 #[derive(Debug)]
@@ -105,6 +109,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Event::Task(it) => fmt::Debug::fmt(it, f),
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Flycheck(it) => fmt::Debug::fmt(it, f),
+            Event::TestResult(it) => fmt::Debug::fmt(it, f),
         }
     }
 }
@@ -171,6 +176,9 @@ fn run(mut self, inbox: Receiver<lsp_server::Message>) -> Result<()> {
     }
 
     fn next_event(&self, inbox: &Receiver<lsp_server::Message>) -> Option<Event> {
+        let never = never();
+        let test_results =
+            self.test_run_session.as_ref().and_then(|it| it.receiver()).unwrap_or(&never);
         select! {
             recv(inbox) -> msg =>
                 msg.ok().map(Event::Lsp),
@@ -183,6 +191,9 @@ fn next_event(&self, inbox: &Receiver<lsp_server::Message>) -> Option<Event> {
 
             recv(self.flycheck_receiver) -> task =>
                 Some(Event::Flycheck(task.unwrap())),
+
+            recv(test_results) -> msg =>
+                Some(Event::TestResult(msg.unwrap_or(flycheck::CargoTestMessage::Finished))),
         }
     }
 
@@ -276,9 +287,16 @@ fn handle_event(&mut self, event: Event) -> Result<()> {
                     self.handle_flycheck_msg(message);
                 }
             }
+            Event::TestResult(message) => {
+                let _p = profile::span("GlobalState::handle_event/test_result");
+                self.handle_cargo_test_msg(message);
+            }
         }
 
         let state_changed = self.process_changes();
+        if state_changed {
+            self.outdate_semantic_tokens();
+        }
         let memdocs_added_or_removed = self.mem_docs.take_changes();
 
         if self.is_quiescent() {
@@ -289,29 +307,19 @@ fn handle_event(&mut self, event: Event) -> Result<()> {
             if became_quiescent {
                 if self.config.check_on_save() {
                     // Project has loaded properly, kick off initial flycheck
-                    self.flycheck.iter().for_each(FlycheckHandle::restart);
+                    self.flycheck.iter().for_each(|flycheck| flycheck.restart(None));
                 }
                 if self.config.prefill_caches() {
                     self.prime_caches_queue.request_op("became quiescent".to_string());
                 }
             }
 
-            if !was_quiescent || state_changed {
-                // Refresh semantic tokens if the client supports it.
-                if self.config.semantic_tokens_refresh() {
-                    self.semantic_tokens_cache.lock().clear();
-                    self.send_request::<lsp_types::request::SemanticTokensRefresh>((), |_, _| ());
-                }
-
-                // Refresh code lens if the client supports it.
-                if self.config.code_lens_refresh() {
-                    self.send_request::<lsp_types::request::CodeLensRefresh>((), |_, _| ());
-                }
+            if became_quiescent || state_changed {
+                self.enforce_memory_budget();
+            }
 
-                // Refresh inlay hints if the client supports it.
-                if self.config.inlay_hints_refresh() {
-                    self.send_request::<lsp_types::request::InlayHintRefreshRequest>((), |_, _| ());
-                }
+            if !was_quiescent || state_changed {
+                self.refresh_views();
             }
 
             if (!was_quiescent || state_changed || memdocs_added_or_removed)
@@ -463,6 +471,7 @@ fn handle_task(&mut self, prime_caches_progress: &mut Vec<PrimeCachesProgress>,
 
                         if self.config.run_build_scripts() && workspaces_updated {
                             self.fetch_build_data_queue.request_op(format!("workspace updated"));
+                            self.build_data_packages = None;
                         }
 
                         (Progress::End, None)
@@ -488,6 +497,9 @@ fn handle_task(&mut self, prime_caches_progress: &mut Vec<PrimeCachesProgress>,
                     self.report_progress("Loading", state, msg, None, None);
                 }
             }
+            Task::DiscoverTest(results) => {
+                self.send_notification::<lsp_ext::DiscoveredTests>(results);
+            }
         }
     }
 
@@ -538,8 +550,10 @@ fn handle_flycheck_msg(&mut self, message: flycheck::Message) {
                     &workspace_root,
                     &snap,
                 );
+                let db = self.analysis_host.raw_database();
                 for diag in diagnostics {
                     match url_to_file_id(&self.vfs.read().0, &diag.url) {
+                        Ok(file_id) if !is_analyzed(db, file_id) => (),
                         Ok(file_id) => self.diagnostics.add_check_diagnostic(
                             id,
                             file_id,
@@ -597,6 +611,33 @@ fn handle_flycheck_msg(&mut self, message: flycheck::Message) {
         }
     }
 
+    fn handle_cargo_test_msg(&mut self, message: flycheck::CargoTestMessage) {
+        match message {
+            flycheck::CargoTestMessage::Test { name, state } => {
+                let Some(test_id) = self.test_run_session.as_ref().and_then(|it| it.test_id(&name))
+                else {
+                    return;
+                };
+                let state = match state {
+                    flycheck::TestState::Started => lsp_ext::TestState::Started,
+                    flycheck::TestState::Ok => lsp_ext::TestState::Passed,
+                    flycheck::TestState::Ignored => lsp_ext::TestState::Skipped,
+                    flycheck::TestState::Failed { stdout } => {
+                        lsp_ext::TestState::Failed { message: stdout }
+                    }
+                };
+                self.send_notification::<lsp_ext::ChangeTestState>(
+                    lsp_ext::ChangeTestStateParams { test_id, state },
+                );
+            }
+            flycheck::CargoTestMessage::Suite => (),
+            flycheck::CargoTestMessage::Finished => self.start_next_test_run(),
+            flycheck::CargoTestMessage::Custom { text } => {
+                self.send_notification::<lsp_ext::AppendOutputToRunTest>(text);
+            }
+        }
+    }
+
     /// Registers and handles a request. This should only be called once per incoming request.
     fn on_new_request(&mut self, request_received: Instant, req: Request) {
         self.register_request(&req, request_received);
@@ -626,7 +667,11 @@ fn on_request(&mut self, req: Request) {
         dispatcher
             .on_sync_mut::<lsp_ext::ReloadWorkspace>(handlers::handle_workspace_reload)
             .on_sync_mut::<lsp_ext::MemoryUsage>(handlers::handle_memory_usage)
+            .on_sync_mut::<lsp_ext::RequestTrace>(handlers::handle_request_trace)
             .on_sync_mut::<lsp_ext::ShuffleCrateGraph>(handlers::handle_shuffle_crate_graph)
+            .on_sync_mut::<lsp_ext::CargoFeatures>(handlers::handle_cargo_features)
+            .on_sync_mut::<lsp_ext::SetCargoFeature>(handlers::handle_set_cargo_feature)
+            .on_sync_mut::<lsp_ext::RunTest>(handlers::handle_run_test)
             .on_sync::<lsp_ext::JoinLines>(handlers::handle_join_lines)
             .on_sync::<lsp_ext::OnEnter>(handlers::handle_on_enter)
             .on_sync::<lsp_types::request::SelectionRangeRequest>(handlers::handle_selection_range)
@@ -637,10 +682,17 @@ fn on_request(&mut self, req: Request) {
             .on::<lsp_ext::ViewFileText>(handlers::handle_view_file_text)
             .on::<lsp_ext::ViewCrateGraph>(handlers::handle_view_crate_graph)
             .on::<lsp_ext::ViewItemTree>(handlers::handle_view_item_tree)
+            .on::<lsp_ext::DeadCode>(handlers::handle_dead_code)
+            .on_thread::<lsp_ext::EvaluateExpression>(handlers::handle_evaluate_expression)
+            .on::<lsp_ext::ExportHighlighted>(handlers::handle_export_highlighted)
+            .on::<lsp_ext::MacroExpansionCosts>(handlers::handle_macro_expansion_costs)
+            .on_thread::<lsp_ext::DebugConfiguration>(handlers::handle_debug_configuration)
+            .on::<lsp_ext::ViewModuleTree>(handlers::handle_view_module_tree)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)
             .on::<lsp_ext::RelatedTests>(handlers::handle_related_tests)
+            .on::<lsp_ext::DiscoverTest>(handlers::handle_discover_test)
             .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)
             .on::<lsp_ext::CodeActionResolveRequest>(handlers::handle_code_action_resolve)
             .on::<lsp_ext::HoverRequest>(handlers::handle_hover)
@@ -664,7 +716,7 @@ fn on_request(&mut self, req: Request) {
             .on::<lsp_types::request::SignatureHelpRequest>(handlers::handle_signature_help)
             .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)
             .on::<lsp_types::request::Rename>(handlers::handle_rename)
-            .on::<lsp_types::request::References>(handlers::handle_references)
+            .on::<lsp_ext::References>(handlers::handle_references)
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)
             .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)
             .on::<lsp_types::request::DocumentHighlightRequest>(handlers::handle_document_highlight)
@@ -675,6 +727,10 @@ fn on_request(&mut self, req: Request) {
             .on::<lsp_types::request::CallHierarchyOutgoingCalls>(
                 handlers::handle_call_hierarchy_outgoing,
             )
+            .on::<lsp_ext::TypeHierarchyPrepare>(handlers::handle_type_hierarchy_prepare)
+            .on::<lsp_ext::TypeHierarchySupertypes>(handlers::handle_type_hierarchy_supertypes)
+            .on::<lsp_ext::TypeHierarchySubtypes>(handlers::handle_type_hierarchy_subtypes)
+            .on::<lsp_ext::PeekRelated>(handlers::handle_peek_related)
             .on::<lsp_types::request::SemanticTokensFullRequest>(
                 handlers::handle_semantic_tokens_full,
             )
@@ -696,6 +752,7 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
             let file_id = this.vfs.read().0.file_id(&vfs_path);
             if let Some(file_id) = file_id {
                 let world = this.snapshot();
+                let saved_file = vfs_path.as_path().map(|it| it.to_owned());
                 let mut updated = false;
                 let task = move || -> std::result::Result<(), ide::Cancelled> {
                     // Trigger flychecks for all workspaces that depend on the saved file
@@ -728,28 +785,44 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
                     let crate_root_paths: Vec<_> =
                         crate_root_paths.iter().map(Deref::deref).collect();
 
-                    // Find all workspaces that have at least one target containing the saved file
-                    let workspace_ids =
-                        world.workspaces.iter().enumerate().filter(|(_, ws)| match ws {
+                    // Find all workspaces that have at least one target containing the saved file,
+                    // along with the packages of these targets
+                    let workspace_ids: Vec<_> = world
+                        .workspaces
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(id, ws)| match ws {
                             project_model::ProjectWorkspace::Cargo { cargo, .. } => {
-                                cargo.packages().any(|pkg| {
-                                    cargo[pkg].targets.iter().any(|&it| {
-                                        crate_root_paths.contains(&cargo[it].root.as_path())
+                                let packages: Vec<_> = cargo
+                                    .packages()
+                                    .filter(|&pkg| {
+                                        cargo[pkg].targets.iter().any(|&it| {
+                                            crate_root_paths.contains(&cargo[it].root.as_path())
+                                        })
                                     })
-                                })
+                                    .map(|pkg| cargo.package_flag(&cargo[pkg]))
+                                    .collect();
+                                (!packages.is_empty()).then_some((id, packages))
                             }
                             project_model::ProjectWorkspace::Json { project, .. } => project
                                 .crates()
-                                .any(|(c, _)| crate_ids.iter().any(|&crate_id| crate_id == c)),
-                            project_model::ProjectWorkspace::DetachedFiles { .. } => false,
-                        });
+                                .any(|(c, _)| crate_ids.iter().any(|&crate_id| crate_id == c))
+                                .then_some((id, Vec::new())),
+                            project_model::ProjectWorkspace::DetachedFiles { .. } => None,
+                        })
+                        .collect();
 
                     // Find and trigger corresponding flychecks
                     for flycheck in world.flycheck.iter() {
-                        for (id, _) in workspace_ids.clone() {
-                            if id == flycheck.id() {
+                        for (id, packages) in &workspace_ids {
+                            if *id == flycheck.id() {
                                 updated = true;
-                                flycheck.restart();
+                                if world.config.check_workspace() {
+                                    flycheck.restart(saved_file.clone());
+                                } else {
+                                    flycheck
+                                        .restart_for_packages(packages.clone(), saved_file.clone());
+                                }
                                 continue;
                             }
                         }
@@ -757,7 +830,7 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
                     // No specific flycheck was triggered, so let's trigger all of them.
                     if !updated {
                         for flycheck in world.flycheck.iter() {
-                            flycheck.restart();
+                            flycheck.restart(saved_file.clone());
                         }
                     }
                     Ok(())
@@ -814,6 +887,7 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
                 Ok(())
             })?
             .on::<lsp_ext::CancelFlycheck>(handlers::handle_cancel_flycheck)?
+            .on::<lsp_ext::AbortRunTest>(handlers::handle_abort_run_test)?
             .on::<lsp_types::notification::DidChangeTextDocument>(|this, params| {
                 if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
                     match this.mem_docs.get_mut(&path) {
@@ -868,7 +942,7 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
                 }
                 // No specific flycheck was triggered, so let's trigger all of them.
                 for flycheck in this.flycheck.iter() {
-                    flycheck.restart();
+                    flycheck.restart(None);
                 }
                 Ok(())
             })?
@@ -888,7 +962,7 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
                 } else if this.config.check_on_save() {
                     // No specific flycheck was triggered, so let's trigger all of them.
                     for flycheck in this.flycheck.iter() {
-                        flycheck.restart();
+                        flycheck.restart(None);
                     }
                 }
                 Ok(())
@@ -970,7 +1044,54 @@ fn run_flycheck(this: &mut GlobalState, vfs_path: VfsPath) -> bool {
         Ok(())
     }
 
-    fn update_diagnostics(&mut self) {
+    /// Drops the expensive caches if the memory usage exceeds `rust-analyzer.lru.memoryBudget`.
+    ///
+    /// If that doesn't get the memory usage below the budget, the caches are only dropped again
+    /// once the memory usage grew by a quarter of the budget, instead of every time the state
+    /// changes.
+    fn enforce_memory_budget(&mut self) {
+        let Some(budget) = self.config.lru_memory_budget() else { return };
+        let budget = budget as isize;
+        let allocated = profile::memory_usage().allocated;
+        let threshold = match self.memory_after_eviction {
+            Some(after_eviction) if after_eviction > budget => after_eviction + budget / 4,
+            _ => budget,
+        };
+        if allocated.megabytes() <= threshold {
+            if allocated.megabytes() <= budget {
+                self.memory_after_eviction = None;
+            }
+            return;
+        }
+
+        self.analysis_host.evict_lru_queries();
+        let after_eviction = profile::memory_usage().allocated;
+        tracing::info!(
+            before = %allocated,
+            after = %after_eviction,
+            budget,
+            "memory budget exceeded, evicted caches"
+        );
+        self.memory_after_eviction = Some(after_eviction.megabytes());
+    }
+
+    /// Asks the client to refresh the semantic tokens, code lenses and inlay hints, if it supports
+    /// that.
+    pub(crate) fn refresh_views(&mut self) {
+        if self.config.semantic_tokens_refresh() {
+            self.send_request::<lsp_types::request::SemanticTokensRefresh>((), |_, _| ());
+        }
+
+        if self.config.code_lens_refresh() {
+            self.send_request::<lsp_types::request::CodeLensRefresh>((), |_, _| ());
+        }
+
+        if self.config.inlay_hints_refresh() {
+            self.send_request::<lsp_types::request::InlayHintRefreshRequest>((), |_, _| ());
+        }
+    }
+
+    pub(crate) fn update_diagnostics(&mut self) {
         let subscriptions = self
             .mem_docs
             .iter()
@@ -12,17 +12,21 @@
 //! correct. Instead, we try to provide a best-effort service. Even if the
 //! project is currently loading and we don't have a full project model, we
 //! still want to respond to various  requests.
-use std::{mem, sync::Arc};
+use std::{mem, path::Path, sync::Arc};
 
 use flycheck::{FlycheckConfig, FlycheckHandle};
-use hir::db::DefDatabase;
+use hir::db::{AstDatabase, DefDatabase};
 use ide::Change;
-use ide_db::base_db::{
-    CrateGraph, Env, ProcMacro, ProcMacroExpander, ProcMacroExpansionError, ProcMacroKind,
-    ProcMacroLoadResult, SourceRoot, VfsPath,
+use ide_db::{
+    base_db::{
+        AnalysisPolicy, CrateGraph, Env, ProcMacro, ProcMacroExpander, ProcMacroExpansionError,
+        ProcMacroKind, ProcMacroLoadResult, SourceRoot, VfsPath,
+    },
+    symbol_index::SymbolsDatabase,
 };
 use proc_macro_api::{MacroDylib, ProcMacroServer};
-use project_model::{ProjectWorkspace, WorkspaceBuildScripts};
+use project_model::{ProjectManifest, ProjectWorkspace, WorkspaceBuildScripts};
+use rustc_hash::FxHashMap;
 use syntax::SmolStr;
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
@@ -61,8 +65,11 @@ pub(crate) fn is_quiescent(&self) -> bool {
     pub(crate) fn update_configuration(&mut self, config: Config) {
         let _p = profile::span("GlobalState::update_configuration");
         let old_config = mem::replace(&mut self.config, Arc::new(config));
-        if self.config.lru_capacity() != old_config.lru_capacity() {
-            self.analysis_host.update_lru_capacity(self.config.lru_capacity());
+        self.outdate_semantic_tokens();
+        if self.config.lru_capacity() != old_config.lru_capacity()
+            || self.config.lru_query_capacities() != old_config.lru_query_capacities()
+        {
+            self.update_lru_capacities();
         }
         if self.config.linked_projects() != old_config.linked_projects() {
             self.fetch_workspaces_queue.request_op("linked projects changed".to_string())
@@ -77,6 +84,35 @@ pub(crate) fn update_configuration(&mut self, config: Config) {
                 .raw_database_mut()
                 .set_enable_proc_attr_macros(self.config.expand_proc_attr_macros());
         }
+        if self.analysis_host.raw_database().index_library_bodies()
+            != self.config.index_library_bodies()
+        {
+            self.analysis_host
+                .raw_database_mut()
+                .set_index_library_bodies(self.config.index_library_bodies());
+        }
+        if self.analysis_host.raw_database().expansion_limits() != self.config.expansion_limits() {
+            self.analysis_host
+                .raw_database_mut()
+                .set_expansion_limits(self.config.expansion_limits());
+        }
+    }
+
+    /// Sets the LRU capacities of the queries to the configured ones, reporting the names of
+    /// unknown queries.
+    pub(crate) fn update_lru_capacities(&mut self) {
+        let unknown = self
+            .analysis_host
+            .update_lru_capacities(self.config.lru_capacity(), self.config.lru_query_capacities());
+        if !unknown.is_empty() {
+            self.show_and_log_error(
+                format!(
+                    "`rust-analyzer.lru.query.capacities` contains unknown queries: {}",
+                    unknown.join(", ")
+                ),
+                None,
+            );
+        }
     }
 
     pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
@@ -86,6 +122,21 @@ pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
             message: None,
         };
 
+        if let Some(max_size) = self.config.max_analysis_file_size() {
+            let vfs = &self.vfs.read().0;
+            let oversized = self.mem_docs.iter().find(|path| {
+                vfs.file_id(path)
+                    .map_or(false, |file_id| vfs.file_contents(file_id).len() > max_size)
+            });
+            if let Some(path) = oversized {
+                status.health = lsp_ext::Health::Warning;
+                status.message = Some(format!(
+                    "{path} exceeds the analysis size limit of {max_size} bytes, \
+                     only syntax-based features are available for it."
+                ));
+            }
+        }
+
         if self.proc_macro_changed {
             status.health = lsp_ext::Health::Warning;
             status.message =
@@ -123,7 +174,20 @@ pub(crate) fn fetch_workspaces(&mut self, cause: Cause) {
         tracing::info!(%cause, "will fetch workspaces");
 
         self.task_pool.handle.spawn_with_sender({
-            let linked_projects = self.config.linked_projects();
+            let linked_projects: Vec<_> = self
+                .config
+                .linked_projects()
+                .into_iter()
+                .map(|project| {
+                    let cargo_config = match &project {
+                        LinkedProject::ProjectManifest(ProjectManifest::CargoToml(manifest)) => {
+                            self.cargo_config_for(manifest)
+                        }
+                        _ => self.config.cargo(),
+                    };
+                    (project, cargo_config)
+                })
+                .collect();
             let detached_files = self.config.detached_files().to_vec();
             let cargo_config = self.config.cargo();
 
@@ -141,11 +205,11 @@ pub(crate) fn fetch_workspaces(&mut self, cause: Cause) {
 
                 let mut workspaces = linked_projects
                     .iter()
-                    .map(|project| match project {
+                    .map(|(project, cargo_config)| match project {
                         LinkedProject::ProjectManifest(manifest) => {
                             project_model::ProjectWorkspace::load(
                                 manifest.clone(),
-                                &cargo_config,
+                                cargo_config,
                                 &progress,
                             )
                         }
@@ -178,6 +242,22 @@ pub(crate) fn fetch_build_data(&mut self, cause: Cause) {
         tracing::info!(%cause, "will fetch build data");
         let workspaces = Arc::clone(&self.workspaces);
         let config = self.config.cargo();
+        let packages = self.build_data_packages.take();
+        // Each workspace is built with the features selected for its members, which a single
+        // custom build command can't do.
+        let selected_features_configs = (!self.feature_overrides.is_empty()
+            && config.run_build_script_command.is_none())
+        .then(|| {
+            workspaces
+                .iter()
+                .map(|ws| match ws {
+                    ProjectWorkspace::Cargo { cargo, .. } => {
+                        self.with_selected_features(cargo, config.clone())
+                    }
+                    _ => config.clone(),
+                })
+                .collect::<Vec<_>>()
+        });
         self.task_pool.handle.spawn_with_sender(move |sender| {
             sender.send(Task::FetchBuildData(BuildDataProgress::Begin)).unwrap();
 
@@ -187,7 +267,23 @@ pub(crate) fn fetch_build_data(&mut self, cause: Cause) {
                     sender.send(Task::FetchBuildData(BuildDataProgress::Report(msg))).unwrap()
                 }
             };
-            let res = ProjectWorkspace::run_all_build_scripts(&workspaces, &config, &progress);
+            let res = match (&packages, &selected_features_configs) {
+                // Changing the features of a member only affects its build scripts and the ones
+                // of its dependencies.
+                (Some(packages), Some(configs)) => workspaces
+                    .iter()
+                    .zip(configs)
+                    .map(|(ws, config)| ws.rerun_build_scripts(config, packages, &progress))
+                    .collect(),
+                (_, Some(configs)) => workspaces
+                    .iter()
+                    .zip(configs)
+                    .map(|(ws, config)| ws.run_build_scripts(config, &progress))
+                    .collect(),
+                (_, None) => {
+                    ProjectWorkspace::run_all_build_scripts(&workspaces, &config, &progress)
+                }
+            };
 
             sender.send(Task::FetchBuildData(BuildDataProgress::End((workspaces, res)))).unwrap();
         });
@@ -288,11 +384,19 @@ pub(crate) fn switch_workspaces(&mut self, cause: Cause) {
         let mut change = Change::new();
 
         let files_config = self.config.files();
-        let project_folders = ProjectFolders::new(&self.workspaces, &files_config.exclude);
+        let project_folders = ProjectFolders::new(
+            &self.workspaces,
+            &files_config.exclude,
+            &files_config.exclude_from_analysis,
+        );
 
         if self.proc_macro_clients.is_empty() {
             if let Some((path, path_manually_set)) = self.config.proc_macro_srv() {
                 tracing::info!("Spawning proc-macro servers");
+                // Workspaces using the same server, usually because they use the same toolchain,
+                // share a single process.
+                let mut servers = FxHashMap::default();
+                let mut toolchain_servers = FxHashMap::default();
                 self.proc_macro_clients = self
                     .workspaces
                     .iter()
@@ -310,18 +414,33 @@ pub(crate) fn switch_workspaces(&mut self, cause: Cause) {
                             }
                         };
 
-                        tracing::info!(?args, "Using proc-macro server at {}", path.display(),);
-                        ProcMacroServer::spawn(path.clone(), args).map_err(|err| {
-                            let error = format!(
-                                "Failed to run proc-macro server from path {}, error: {:?}",
-                                path.display(),
-                                err
+                        let server = servers
+                            .entry((path.clone(), args))
+                            .or_insert_with(|| spawn_proc_macro_server(&path, args))
+                            .clone();
+                        if let (
+                            ProjectWorkspace::Cargo { toolchain: Some(toolchain), .. },
+                            Ok(server),
+                            [],
+                        ) = (ws, &server, args)
+                        {
+                            let version = (
+                                toolchain.major as usize,
+                                toolchain.minor as usize,
+                                toolchain.patch as usize,
                             );
-                            tracing::error!(error);
-                            error
-                        })
+                            toolchain_servers.entry(version).or_insert_with(|| server.clone());
+                        }
+                        server
                     })
-                    .collect()
+                    .collect();
+                if path_manually_set {
+                    self.proc_macro_toolchain_clients = FxHashMap::default();
+                    self.proc_macro_fallback = None;
+                } else {
+                    self.proc_macro_toolchain_clients = toolchain_servers;
+                    self.proc_macro_fallback = Some(ProcMacroFallback::NotSpawned(path));
+                }
             };
         }
 
@@ -337,7 +456,7 @@ pub(crate) fn switch_workspaces(&mut self, cause: Cause) {
         });
 
         // Create crate graph from all the workspaces
-        let crate_graph = {
+        let mut crate_graph = {
             let dummy_replacements = self.config.dummy_replacements();
 
             let vfs = &mut self.vfs.write().0;
@@ -358,17 +477,40 @@ pub(crate) fn switch_workspaces(&mut self, cause: Cause) {
             };
 
             let mut crate_graph = CrateGraph::default();
+            let toolchain_clients = &self.proc_macro_toolchain_clients;
+            let fallback = &mut self.proc_macro_fallback;
             for (idx, ws) in self.workspaces.iter().enumerate() {
                 let proc_macro_client = match self.proc_macro_clients.get(idx) {
                     Some(res) => res.as_ref().map_err(|e| &**e),
                     None => Err("Proc macros are disabled"),
                 };
-                let mut load_proc_macro = move |crate_name: &str, path: &AbsPath| {
-                    load_proc_macro(
-                        proc_macro_client,
+                let client_is_bundled = ws.find_sysroot_proc_macro_srv().is_none();
+                let mut load_proc_macro = |crate_name: &str, path: &AbsPath| {
+                    let dummy_replace =
+                        dummy_replacements.get(crate_name).map(|v| &**v).unwrap_or_default();
+                    // Dylibs built by the toolchain of another workspace are loaded by its server,
+                    // which supports their ABI.
+                    let toolchain_client = proc_macro_api::read_dylib_info(path)
+                        .ok()
+                        .and_then(|info| toolchain_clients.get(&info.version));
+                    let res = load_proc_macro(
+                        toolchain_client.map_or(proc_macro_client, Ok),
                         path,
-                        dummy_replacements.get(crate_name).map(|v| &**v).unwrap_or_default(),
-                    )
+                        dummy_replace,
+                    );
+                    match fallback.as_mut() {
+                        Some(fallback)
+                            if res.is_err()
+                                && (toolchain_client.is_some() || !client_is_bundled) =>
+                        {
+                            tracing::info!(
+                                "retrying to load {} with the bundled proc-macro server",
+                                path.display()
+                            );
+                            load_proc_macro(fallback.get_or_spawn(), path, dummy_replace)
+                        }
+                        _ => res,
+                    }
                 };
                 crate_graph.extend(ws.to_crate_graph(
                     &mut load_proc_macro,
@@ -378,11 +520,13 @@ pub(crate) fn switch_workspaces(&mut self, cause: Cause) {
             }
             crate_graph
         };
+        self.apply_feature_overrides(&mut crate_graph, |_| true);
         change.set_crate_graph(crate_graph);
 
         self.source_root_config = project_folders.source_root_config;
 
         self.analysis_host.apply_change(change);
+        self.outdate_semantic_tokens();
         self.process_changes();
         self.reload_flycheck();
         tracing::info!("did switch workspaces");
@@ -430,7 +574,7 @@ fn fetch_build_data_error(&self) -> Result<(), String> {
         }
     }
 
-    fn reload_flycheck(&mut self) {
+    pub(crate) fn reload_flycheck(&mut self) {
         let _p = profile::span("GlobalState::reload_flycheck");
         let config = self.config.flycheck();
         let sender = self.flycheck_sender.clone();
@@ -451,23 +595,29 @@ fn reload_flycheck(&mut self) {
                     .iter()
                     .enumerate()
                     .filter_map(|(id, w)| match w {
-                        ProjectWorkspace::Cargo { cargo, .. } => Some((id, cargo.workspace_root())),
+                        ProjectWorkspace::Cargo { cargo, .. } => Some((
+                            id,
+                            cargo.workspace_root(),
+                            self.flycheck_config_for(cargo, config.clone()),
+                        )),
                         ProjectWorkspace::Json { project, .. } => {
                             // Enable flychecks for json projects if a custom flycheck command was supplied
                             // in the workspace configuration.
                             match config {
-                                FlycheckConfig::CustomCommand { .. } => Some((id, project.path())),
+                                FlycheckConfig::CustomCommand { .. } => {
+                                    Some((id, project.path(), config.clone()))
+                                }
                                 _ => None,
                             }
                         }
                         ProjectWorkspace::DetachedFiles { .. } => None,
                     })
-                    .map(|(id, root)| {
+                    .map(|(id, root, config)| {
                         let sender = sender.clone();
                         FlycheckHandle::spawn(
                             id,
                             Box::new(move |msg| sender.send(msg).unwrap()),
-                            config.clone(),
+                            config,
                             root.to_path_buf(),
                         )
                     })
@@ -489,6 +639,7 @@ impl ProjectFolders {
     pub(crate) fn new(
         workspaces: &[ProjectWorkspace],
         global_excludes: &[AbsPathBuf],
+        exclude_from_analysis: &[AbsPathBuf],
     ) -> ProjectFolders {
         let mut res = ProjectFolders::default();
         let mut fsc = FileSetConfig::builder();
@@ -528,7 +679,11 @@ pub(crate) fn new(
         }
 
         let fsc = fsc.build();
-        res.source_root_config = SourceRootConfig { fsc, local_filesets };
+        res.source_root_config = SourceRootConfig {
+            fsc,
+            local_filesets,
+            exclude_from_analysis: exclude_from_analysis.to_vec(),
+        };
 
         res
     }
@@ -538,6 +693,8 @@ pub(crate) fn new(
 pub(crate) struct SourceRootConfig {
     pub(crate) fsc: FileSetConfig,
     pub(crate) local_filesets: Vec<usize>,
+    /// Glob patterns of local files and directories to exclude from analysis.
+    pub(crate) exclude_from_analysis: Vec<AbsPathBuf>,
 }
 
 impl SourceRootConfig {
@@ -550,7 +707,19 @@ pub(crate) fn partition(&self, vfs: &vfs::Vfs) -> Vec<SourceRoot> {
             .map(|(idx, file_set)| {
                 let is_local = self.local_filesets.contains(&idx);
                 if is_local {
+                    let excluded = file_set
+                        .iter()
+                        .filter(|file_id| {
+                            let path = file_set.path_for_file(file_id).and_then(|it| it.as_path());
+                            path.map_or(false, |path| {
+                                self.exclude_from_analysis
+                                    .iter()
+                                    .any(|glob| glob_matches(glob.as_ref(), path.as_ref()))
+                            })
+                        })
+                        .collect::<Vec<_>>();
                     SourceRoot::new_local(file_set)
+                        .with_analysis_policy(AnalysisPolicy::excluding(excluded))
                 } else {
                     SourceRoot::new_library(file_set)
                 }
@@ -559,6 +728,78 @@ pub(crate) fn partition(&self, vfs: &vfs::Vfs) -> Vec<SourceRoot> {
     }
 }
 
+/// The proc-macro server bundled with rust-analyzer, which loads the dylibs the server of their
+/// workspace fails to load. It is only spawned when it is needed.
+pub(crate) enum ProcMacroFallback {
+    NotSpawned(AbsPathBuf),
+    Spawned(Result<ProcMacroServer, String>),
+}
+
+impl ProcMacroFallback {
+    fn get_or_spawn(&mut self) -> Result<&ProcMacroServer, &str> {
+        if let ProcMacroFallback::NotSpawned(path) = self {
+            *self = ProcMacroFallback::Spawned(spawn_proc_macro_server(path, &["proc-macro"]));
+        }
+        match self {
+            ProcMacroFallback::Spawned(res) => res.as_ref().map_err(|e| &**e),
+            ProcMacroFallback::NotSpawned(_) => unreachable!(),
+        }
+    }
+}
+
+fn spawn_proc_macro_server(path: &AbsPathBuf, args: &[&str]) -> Result<ProcMacroServer, String> {
+    tracing::info!(?args, "Using proc-macro server at {}", path.display(),);
+    ProcMacroServer::spawn(path.clone(), args).map_err(|err| {
+        let error = format!(
+            "Failed to run proc-macro server from path {}, error: {:?}",
+            path.display(),
+            err
+        );
+        tracing::error!(error);
+        error
+    })
+}
+
+/// Whether `path`, or one of the directories containing it, matches the glob `pattern`.
+///
+/// `?` and `*` match any character, respectively any sequence of characters, within a path
+/// component, while a `**` component matches any number of components.
+fn glob_matches(pattern: &Path, path: &Path) -> bool {
+    fn components(path: &Path) -> Vec<Vec<char>> {
+        path.components().map(|it| it.as_os_str().to_string_lossy().chars().collect()).collect()
+    }
+
+    fn matches_components(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((first, rest)) if first[..] == ['*', '*'] => {
+                (0..=path.len()).any(|skip| matches_components(rest, &path[skip..]))
+            }
+            Some((first, rest)) => match path.split_first() {
+                Some((component, path)) => {
+                    matches_component(first, component) && matches_components(rest, path)
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn matches_component(pattern: &[char], text: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => {
+                (0..=text.len()).any(|skip| matches_component(rest, &text[skip..]))
+            }
+            Some(('?', rest)) => !text.is_empty() && matches_component(rest, &text[1..]),
+            Some((c, rest)) => text.first() == Some(c) && matches_component(rest, &text[1..]),
+        }
+    }
+
+    let pattern = components(pattern);
+    let path = components(path);
+    (1..=path.len()).any(|len| matches_components(&pattern, &path[..len]))
+}
+
 /// Load the proc-macros for the given lib path, replacing all expanders whose names are in `dummy_replace`
 /// with an identity dummy expander.
 pub(crate) fn load_proc_macro(
@@ -710,3 +951,27 @@ pub(crate) fn should_refresh_for_change(path: &AbsPath, change_kind: ChangeKind)
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::glob_matches;
+
+    #[test]
+    fn test_glob_matches() {
+        let matches = |pattern: &str, path: &str| glob_matches(Path::new(pattern), Path::new(path));
+        assert!(matches("/ws/src/generated.rs", "/ws/src/generated.rs"));
+        assert!(matches("/ws/src/gen?rated.rs", "/ws/src/generated.rs"));
+        assert!(matches("/ws/src/gen?rated.rs", "/ws/src/genérated.rs"));
+        assert!(matches("/ws/src/*.rs", "/ws/src/generated.rs"));
+        assert!(!matches("/ws/src/*.rs", "/ws/src/nested/generated.rs"));
+        assert!(matches("/ws/**/*_generated.rs", "/ws/src/nested/bindings_generated.rs"));
+        assert!(matches("/ws/**/*_generated.rs", "/ws/bindings_generated.rs"));
+        // Directories exclude everything within them.
+        assert!(matches("/ws/src/generated", "/ws/src/generated/mod.rs"));
+        assert!(matches("/ws/**/gen", "/ws/crates/foo/gen/bindings/mod.rs"));
+        assert!(!matches("/ws/src/generated", "/ws/src/generated_not.rs"));
+        assert!(!matches("/ws/src/lib.rs", "/ws/src"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Opt-in recording of per-request timings, used to investigate performance issues.
+//!
+//! For every request we record when it was received by the main loop, when its handler started
+//! and finished, and when the response was sent, together with the salsa queries the handler
+//! executed. Recording is toggled by the `rust-analyzer/requestTrace` extension request, which
+//! also renders a report and can dump the recorded requests in the Chrome trace event format.
+
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use ide_db::query_stats::{self, QueryStats};
+use lsp_server::RequestId;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use stdx::format_to;
+
+/// The maximum number of requests kept, older ones are discarded first.
+const CAPACITY: usize = 512;
+/// The number of queries shown per request in the report.
+const TOP_QUERIES: usize = 5;
+
+pub(crate) struct RequestTrace {
+    enabled: AtomicBool,
+    epoch: Instant,
+    /// Handler timings of requests whose response hasn't been sent yet.
+    pending: Mutex<FxHashMap<RequestId, HandlerTiming>>,
+    completed: Mutex<VecDeque<RequestTiming>>,
+}
+
+struct HandlerTiming {
+    start: Instant,
+    end: Instant,
+    queries: QueryStats,
+}
+
+struct RequestTiming {
+    id: RequestId,
+    method: String,
+    received: Instant,
+    responded: Instant,
+    handler: Option<HandlerTiming>,
+}
+
+impl RequestTiming {
+    fn wall_time(&self) -> Duration {
+        self.responded - self.received
+    }
+
+    fn queue_latency(&self) -> Option<Duration> {
+        self.handler.as_ref().map(|it| it.start - self.received)
+    }
+
+    fn handler_time(&self) -> Option<Duration> {
+        self.handler.as_ref().map(|it| it.end - it.start)
+    }
+}
+
+impl Default for RequestTrace {
+    fn default() -> Self {
+        RequestTrace {
+            enabled: AtomicBool::new(false),
+            epoch: Instant::now(),
+            pending: Default::default(),
+            completed: Default::default(),
+        }
+    }
+}
+
+impl RequestTrace {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables recording, discarding what was recorded so far when enabling.
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        if enabled && !self.is_enabled() {
+            self.pending.lock().clear();
+            self.completed.lock().clear();
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Runs the handler of the given request, recording its timings if tracing is enabled.
+    pub(crate) fn record_handler<T>(&self, id: &RequestId, f: impl FnOnce() -> T) -> T {
+        if !self.is_enabled() {
+            return f();
+        }
+        let start = Instant::now();
+        let (res, queries) = query_stats::collect(f);
+        let end = Instant::now();
+        self.pending.lock().insert(id.clone(), HandlerTiming { start, end, queries });
+        res
+    }
+
+    /// Records that the response to the given request has been sent.
+    pub(crate) fn record_response(&self, id: &RequestId, method: &str, received: Instant) {
+        if !self.is_enabled() {
+            return;
+        }
+        let handler = self.pending.lock().remove(id);
+        let timing = RequestTiming {
+            id: id.clone(),
+            method: method.to_owned(),
+            received,
+            responded: Instant::now(),
+            handler,
+        };
+        let mut completed = self.completed.lock();
+        if completed.len() == CAPACITY {
+            completed.pop_front();
+        }
+        completed.push_back(timing);
+    }
+
+    /// Renders the recorded requests, slowest first, followed by the most executed queries.
+    pub(crate) fn report(&self) -> String {
+        let completed = self.completed.lock();
+        let mut buf = format!(
+            "Request tracing is {}, {} request{} recorded.\n",
+            if self.is_enabled() { "enabled" } else { "disabled" },
+            completed.len(),
+            if completed.len() == 1 { "" } else { "s" },
+        );
+        if completed.is_empty() {
+            return buf;
+        }
+
+        let fmt_duration = |it: Option<Duration>| match it {
+            Some(it) => format!("{:0.2?}", it),
+            None => "-".to_owned(),
+        };
+        let mut by_wall_time = completed.iter().collect::<Vec<_>>();
+        by_wall_time.sort_by_key(|it| std::cmp::Reverse(it.wall_time()));
+
+        format_to!(buf, "\n{:>10} {:>10} {:>10}  request\n", "wall", "queue", "handler");
+        for timing in by_wall_time {
+            format_to!(
+                buf,
+                "{:>10} {:>10} {:>10}  {} ({})\n",
+                fmt_duration(Some(timing.wall_time())),
+                fmt_duration(timing.queue_latency()),
+                fmt_duration(timing.handler_time()),
+                timing.method,
+                timing.id,
+            );
+            let queries = timing.handler.iter().flat_map(|it| &it.queries.counts);
+            for (name, count) in queries.take(TOP_QUERIES) {
+                format_to!(buf, "{:34}{:>6} {}\n", "", count, name);
+            }
+        }
+
+        let mut totals = FxHashMap::<&str, u32>::default();
+        let queries = completed.iter().flat_map(|it| &it.handler).flat_map(|it| &it.queries.counts);
+        for (name, count) in queries {
+            *totals.entry(name).or_default() += count;
+        }
+        let mut totals = totals.into_iter().collect::<Vec<_>>();
+        totals.sort_by(|(name_a, count_a), (name_b, count_b)| {
+            count_b.cmp(count_a).then_with(|| name_a.cmp(name_b))
+        });
+        buf.push_str("\nMost executed queries:\n");
+        for (name, count) in totals.into_iter().take(20) {
+            format_to!(buf, "{:>10} {}\n", count, name);
+        }
+        buf
+    }
+
+    /// Renders the recorded requests in the Chrome trace event format, viewable with
+    /// `chrome://tracing` or Perfetto.
+    pub(crate) fn chrome_trace(&self) -> String {
+        let micros = |instant: Instant| instant.saturating_duration_since(self.epoch).as_micros();
+        let mut events = Vec::new();
+        // Requests are handled concurrently, so give each its own track.
+        for (tid, timing) in self.completed.lock().iter().enumerate() {
+            events.push(serde_json::json!({
+                "name": timing.method,
+                "cat": "request",
+                "ph": "X",
+                "ts": micros(timing.received),
+                "dur": timing.wall_time().as_micros(),
+                "pid": 1,
+                "tid": tid,
+                "args": { "id": timing.id.to_string() },
+            }));
+            if let Some(handler) = &timing.handler {
+                let mut queries = String::new();
+                for (name, count) in &handler.queries.counts {
+                    format_to!(queries, "{count} {name}\n");
+                }
+                events.push(serde_json::json!({
+                    "name": "handler",
+                    "cat": "handler",
+                    "ph": "X",
+                    "ts": micros(handler.start),
+                    "dur": (handler.end - handler.start).as_micros(),
+                    "pid": 1,
+                    "tid": tid,
+                    "args": { "queries": queries },
+                }));
+            }
+        }
+        serde_json::Value::Array(events).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_when_enabled() {
+        let trace = RequestTrace::default();
+        let id = RequestId::from(1);
+
+        trace.record_handler(&id, || ());
+        trace.record_response(&id, "textDocument/hover", Instant::now());
+        assert!(trace.completed.lock().is_empty());
+
+        trace.set_enabled(true);
+        let received = Instant::now();
+        trace.record_handler(&id, || ());
+        trace.record_response(&id, "textDocument/hover", received);
+        assert!(trace.pending.lock().is_empty());
+        assert_eq!(trace.completed.lock().len(), 1);
+        assert!(trace.report().contains("textDocument/hover (1)"));
+
+        let events: serde_json::Value = serde_json::from_str(&trace.chrome_trace()).unwrap();
+        assert_eq!(events.as_array().unwrap().len(), 2);
+    }
+}
@@ -3,7 +3,7 @@
 use std::ops;
 
 use lsp_types::{
-    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    Position, Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
     SemanticTokensEdit,
 };
 
@@ -193,6 +193,46 @@ pub(crate) fn build(self) -> SemanticTokens {
     }
 }
 
+/// The last tokens sent for a whole document, which the deltas of the next requests are computed
+/// against.
+#[derive(Debug, Default)]
+pub(crate) struct CachedSemanticTokens {
+    /// The version of the document the tokens were computed for, `None` once they may be out of
+    /// date, because the document or anything its highlighting depends on changed.
+    pub(crate) version: Option<i32>,
+    pub(crate) tokens: SemanticTokens,
+}
+
+impl CachedSemanticTokens {
+    /// Whether the tokens are still those of the given version of the document.
+    pub(crate) fn is_current(&self, version: Option<i32>) -> bool {
+        self.version.is_some() && self.version == version
+    }
+}
+
+/// Returns the tokens overlapping `range`, re-encoded relative to each other.
+pub(crate) fn tokens_in_range(tokens: &SemanticTokens, range: Range) -> SemanticTokens {
+    let mut builder = SemanticTokensBuilder::new(tokens.result_id.clone().unwrap_or_default());
+    let (mut line, mut start) = (0, 0);
+    for token in &tokens.data {
+        if token.delta_line == 0 {
+            start += token.delta_start;
+        } else {
+            line += token.delta_line;
+            start = token.delta_start;
+        }
+        let token_range =
+            Range::new(Position::new(line, start), Position::new(line, start + token.length));
+        if token_range.start >= range.end {
+            break;
+        }
+        if token_range.end > range.start {
+            builder.push(token_range, token.token_type, token.token_modifiers_bitset);
+        }
+    }
+    builder.build()
+}
+
 pub(crate) fn diff_tokens(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
     let offset = new.iter().zip(old.iter()).take_while(|&(n, p)| n == p).count();
 
@@ -237,6 +277,28 @@ fn from(t: (u32, u32, u32, u32, u32)) -> SemanticToken {
         }
     }
 
+    #[test]
+    fn test_tokens_in_range() {
+        let tokens = SemanticTokens {
+            result_id: Some("1".to_owned()),
+            data: vec![
+                from((0, 0, 2, 1, 0)),
+                from((0, 4, 3, 2, 0)),
+                from((2, 2, 5, 3, 0)),
+                from((1, 0, 1, 4, 1)),
+                from((0, 6, 2, 5, 0)),
+            ],
+        };
+        let range = Range::new(Position::new(0, 5), Position::new(3, 2));
+        let in_range = tokens_in_range(&tokens, range);
+
+        assert_eq!(in_range.result_id.as_deref(), Some("1"));
+        assert_eq!(
+            in_range.data,
+            vec![from((0, 4, 3, 2, 0)), from((2, 2, 5, 3, 0)), from((1, 0, 1, 4, 1))]
+        );
+    }
+
     #[test]
     fn test_diff_insert_at_end() {
         let before = [from((1, 2, 3, 4, 5)), from((6, 7, 8, 9, 10))];
@@ -1,5 +1,7 @@
 //! A thin wrapper around `ThreadPool` to make sure that we join all things
 //! properly.
+use std::{io, thread};
+
 use crossbeam_channel::Sender;
 
 pub(crate) struct TaskPool<T> {
@@ -41,6 +43,19 @@ pub(crate) fn spawn_with_sender<F>(&mut self, task: F)
         })
     }
 
+    /// Runs `task` on a new thread instead of the pool, for tasks that spend most of their time
+    /// waiting on other processes and would otherwise keep a worker busy.
+    pub(crate) fn spawn_thread<F>(&mut self, name: String, task: F) -> io::Result<()>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let sender = self.sender.clone();
+        // The receiver is gone if the server shuts down before the task finishes.
+        thread::Builder::new().name(name).spawn(move || _ = sender.send(task()))?;
+        Ok(())
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.inner.queued_count()
     }
@@ -0,0 +1,181 @@
+//! Runs the tests selected in the test explorer of the client, one `cargo test` per crate, and
+//! keeps track of the run in progress.
+
+use std::collections::VecDeque;
+
+use crossbeam_channel::Receiver;
+use flycheck::{CargoTestHandle, CargoTestMessage};
+use ide::{Cancellable, FileTests, RunnableKind};
+use rustc_hash::FxHashMap;
+use stdx::format_to;
+use vfs::AbsPathBuf;
+
+use crate::{
+    cargo_target_spec::CargoTargetSpec,
+    global_state::{GlobalState, GlobalStateSnapshot},
+    lsp_ext,
+    main_loop::Task,
+    to_proto,
+};
+
+/// A `cargo test` invocation for the selected tests of one crate.
+#[derive(Debug)]
+pub(crate) struct TestRun {
+    crate_id: String,
+    workspace_root: AbsPathBuf,
+    cargo_args: Vec<String>,
+    test_args: Vec<String>,
+}
+
+/// The test runs requested by the last `experimental/runTest`, the first one being in progress.
+pub(crate) struct TestRunSession {
+    current: Option<(String, CargoTestHandle)>,
+    pending: VecDeque<TestRun>,
+}
+
+impl TestRunSession {
+    pub(crate) fn receiver(&self) -> Option<&Receiver<CargoTestMessage>> {
+        self.current.as_ref().map(|(_, handle)| handle.receiver())
+    }
+
+    /// Maps the name `libtest` reports for a test of the current run to its test id.
+    pub(crate) fn test_id(&self, name: &str) -> Option<String> {
+        self.current.as_ref().map(|(crate_id, _)| format!("{crate_id}::{name}"))
+    }
+}
+
+/// Splits a test id into the id of its crate and the path of the item within the crate.
+fn split_test_id(test_id: &str) -> (&str, Option<&str>) {
+    match test_id.split_once("::") {
+        Some((krate, path)) => (krate, Some(path)),
+        None => (test_id, None),
+    }
+}
+
+pub(crate) fn test_runs(
+    snap: &GlobalStateSnapshot,
+    params: lsp_ext::RunTestParams,
+) -> Cancellable<Vec<TestRun>> {
+    let roots = snap.analysis.discover_test_roots()?;
+    let include = match params.include {
+        Some(include) => include,
+        None => roots.iter().map(|it| it.id.clone()).collect(),
+    };
+
+    // `None` runs all tests of the crate.
+    let mut filters: FxHashMap<&str, Option<Vec<&str>>> = FxHashMap::default();
+    let mut crate_order = Vec::new();
+    for test_id in &include {
+        let (krate, path) = split_test_id(test_id);
+        let entry = filters.entry(krate).or_insert_with(|| {
+            crate_order.push(krate);
+            Some(Vec::new())
+        });
+        match (entry, path) {
+            (Some(paths), Some(path)) => paths.push(path),
+            (entry, _) => *entry = None,
+        }
+    }
+
+    let mut res = Vec::new();
+    for krate in crate_order {
+        let Some(root) = roots.iter().find(|it| it.id == krate) else { continue };
+        let Some(file_id) = root.file else { continue };
+        let Some(spec) = CargoTargetSpec::for_file(snap, file_id)? else { continue };
+        let workspace_root = spec.workspace_root.clone();
+        let kind = RunnableKind::TestMod { path: String::new() };
+        let (cargo_args, _) = CargoTargetSpec::runnable_args(snap, Some(spec), &kind, &None);
+
+        let mut test_args: Vec<String> =
+            filters[krate].iter().flatten().map(|&it| it.to_owned()).collect();
+        for test_id in params.exclude.iter().flatten() {
+            if let (exclude_krate, Some(path)) = split_test_id(test_id) {
+                if exclude_krate == krate {
+                    test_args.extend(["--skip".to_owned(), path.to_owned()]);
+                }
+            }
+        }
+        res.push(TestRun { crate_id: krate.to_owned(), workspace_root, cargo_args, test_args });
+    }
+    Ok(res)
+}
+
+impl GlobalState {
+    /// Starts the given runs one after the other, aborting the previous session if any.
+    pub(crate) fn start_test_run(&mut self, runs: Vec<TestRun>) {
+        self.abort_test_run();
+        self.test_run_session = Some(TestRunSession { current: None, pending: runs.into() });
+        self.start_next_test_run();
+    }
+
+    /// Finishes the current run of the session and starts the next one, ending the session once
+    /// all runs are done.
+    pub(crate) fn start_next_test_run(&mut self) {
+        let Some(session) = &mut self.test_run_session else { return };
+        if let Some((_, handle)) = session.current.take() {
+            handle.cancel();
+        }
+        let mut errors = String::new();
+        while let Some(run) = session.pending.pop_front() {
+            match CargoTestHandle::spawn(
+                &run.workspace_root,
+                &run.cargo_args,
+                &run.test_args,
+                self.config.extra_env(),
+            ) {
+                Ok(handle) => {
+                    session.current = Some((run.crate_id, handle));
+                    break;
+                }
+                Err(err) => {
+                    format_to!(errors, "failed to run the tests of {}: {err}\n", run.crate_id)
+                }
+            }
+        }
+        let finished = session.current.is_none();
+        if !errors.is_empty() {
+            self.send_notification::<lsp_ext::AppendOutputToRunTest>(errors);
+        }
+        if finished {
+            self.test_run_session = None;
+            self.send_notification::<lsp_ext::EndRunTest>(());
+        }
+    }
+
+    /// Sends the tests of the changed files to the client, replacing the ones it knows about.
+    pub(crate) fn update_tests(&mut self, changed_files: &[vfs::ChangedFile]) {
+        let files: Vec<_> =
+            changed_files.iter().filter(|it| it.exists()).map(|it| it.file_id).collect();
+        if files.is_empty() {
+            return;
+        }
+        let snapshot = self.snapshot();
+        self.task_pool.handle.spawn_with_sender(move |sender| {
+            for file_id in files {
+                let Ok(FileTests { items, scope }) =
+                    snapshot.analysis.discover_tests_in_file(file_id)
+                else {
+                    return;
+                };
+                if scope.is_empty() {
+                    continue;
+                }
+                let tests = items
+                    .into_iter()
+                    .map(|it| to_proto::test_item(&snapshot, it))
+                    .collect::<Cancellable<_>>();
+                let Ok(tests) = tests else { return };
+                let results = lsp_ext::DiscoverTestResults { tests, scope };
+                sender.send(Task::DiscoverTest(results)).unwrap();
+            }
+        });
+    }
+
+    pub(crate) fn abort_test_run(&mut self) {
+        let Some(session) = self.test_run_session.take() else { return };
+        if let Some((_, handle)) = session.current {
+            handle.cancel();
+        }
+        self.send_notification::<lsp_ext::EndRunTest>(());
+    }
+}
@@ -1,5 +1,6 @@
 //! Conversion of rust-analyzer specific types to lsp_types equivalents.
 use std::{
+    collections::HashMap,
     iter::once,
     path,
     sync::atomic::{AtomicU32, Ordering},
@@ -739,7 +740,8 @@ pub(crate) fn folding_range(
         | FoldKind::WhereClause
         | FoldKind::ReturnType
         | FoldKind::Array
-        | FoldKind::MatchArm => None,
+        | FoldKind::MatchArm
+        | FoldKind::String => None,
     };
 
     let range = range(line_index, fold.range);
@@ -1099,6 +1101,26 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn type_hierarchy_item(
+    snap: &GlobalStateSnapshot,
+    target: NavigationTarget,
+) -> Cancellable<lsp_ext::TypeHierarchyItem> {
+    let name = target.name.to_string();
+    let detail = target.description.clone();
+    let kind = target.kind.map(symbol_kind).unwrap_or(lsp_types::SymbolKind::STRUCT);
+    let (uri, range, selection_range) = location_info(snap, target)?;
+    Ok(lsp_ext::TypeHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail,
+        uri,
+        range,
+        selection_range,
+        data: None,
+    })
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -1154,6 +1176,25 @@ pub(crate) fn runnable(
     let target = spec.as_ref().map(|s| s.target.clone());
     let (cargo_args, executable_args) =
         CargoTargetSpec::runnable_args(snap, spec, &runnable.kind, &runnable.cfg);
+
+    let path = match &runnable.kind {
+        ide::RunnableKind::Test { test_id, .. }
+        | ide::RunnableKind::Bench { test_id }
+        | ide::RunnableKind::DocTest { test_id } => test_id.to_string(),
+        ide::RunnableKind::TestMod { path } => path.clone(),
+        ide::RunnableKind::Bin => target.clone().unwrap_or_default(),
+    };
+    let mut cargo_extra_args = config.cargo_extra_args;
+    let mut environment = HashMap::default();
+    for it in config.overrides.iter().filter(|it| it.matches(&runnable.kind, &path)) {
+        environment.extend(it.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if !it.features.is_empty() {
+            cargo_extra_args.push("--features".to_owned());
+            cargo_extra_args.push(it.features.join(","));
+        }
+        cargo_extra_args.extend(it.extra_args.iter().cloned());
+    }
+
     let label = runnable.label(target);
     let location = location_link(snap, None, runnable.nav)?;
 
@@ -1165,10 +1206,39 @@ pub(crate) fn runnable(
             workspace_root: workspace_root.map(|it| it.into()),
             override_cargo: config.override_cargo,
             cargo_args,
-            cargo_extra_args: config.cargo_extra_args,
+            cargo_extra_args,
             executable_args,
             expect_test: None,
+            environment,
+        },
+    })
+}
+
+pub(crate) fn test_item(
+    snap: &GlobalStateSnapshot,
+    test_item: ide::TestItem,
+) -> Cancellable<lsp_ext::TestItem> {
+    let range = match (test_item.file, test_item.text_range) {
+        (Some(file_id), Some(text_range)) => {
+            Some(range(&snap.file_line_index(file_id)?, text_range))
+        }
+        _ => None,
+    };
+    Ok(lsp_ext::TestItem {
+        id: test_item.id,
+        label: test_item.label,
+        kind: match test_item.kind {
+            ide::TestItemKind::Crate => lsp_ext::TestItemKind::Package,
+            ide::TestItemKind::Module => lsp_ext::TestItemKind::Module,
+            ide::TestItemKind::Function => lsp_ext::TestItemKind::Test,
         },
+        can_resolve_children: matches!(test_item.kind, ide::TestItemKind::Crate),
+        parent: test_item.parent,
+        text_document: test_item
+            .file
+            .map(|file_id| lsp_types::TextDocumentIdentifier { uri: url(snap, file_id) }),
+        range,
+        runnable: test_item.runnable.map(|it| runnable(snap, it)).transpose()?,
     })
 }
 
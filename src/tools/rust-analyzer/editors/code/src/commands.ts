@@ -8,7 +8,7 @@ import { applySnippetWorkspaceEdit, applySnippetTextEdits } from "./snippets";
 import { spawnSync } from "child_process";
 import { RunnableQuickPick, selectRunnable, createTask, createArgs } from "./run";
 import { AstInspector } from "./ast_inspector";
-import { isRustDocument, isCargoTomlDocument, sleep, isRustEditor } from "./util";
+import { isRustDocument, isCargoTomlDocument, sleep, isRustEditor, log } from "./util";
 import { startDebugSession, makeDebugConfig } from "./debug";
 import { LanguageClient } from "vscode-languageclient/node";
 import { LINKED_COMMANDS } from "./client";
@@ -81,6 +81,100 @@ export function memoryUsage(ctx: CtxInit): Cmd {
     };
 }
 
+export function deadCode(ctx: CtxInit): Cmd {
+    return async () => {
+        const report = await vscode.window.withProgress(
+            {
+                location: vscode.ProgressLocation.Notification,
+                title: "Searching for dead code",
+            },
+            () => ctx.client.sendRequest(ra.deadCode)
+        );
+        const document = await vscode.workspace.openTextDocument({
+            language: "json",
+            content: JSON.stringify(report, null, 2),
+        });
+        await vscode.window.showTextDocument(document, vscode.ViewColumn.Two, true);
+    };
+}
+
+export function evaluateExpression(ctx: CtxInit): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const client = ctx.client;
+        const value = await client.sendRequest(ra.evaluateExpression, {
+            textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+            range: client.code2ProtocolConverter.asRange(editor.selection),
+        });
+        await vscode.window.showInformationMessage(value);
+    };
+}
+
+function copyHighlighted(ctx: CtxInit, format: "html" | "markdown"): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const client = ctx.client;
+        const text = await client.sendRequest(ra.exportHighlighted, {
+            textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+            range: editor.selection.isEmpty
+                ? undefined
+                : client.code2ProtocolConverter.asRange(editor.selection),
+            format,
+            bindingTypes: format === "html",
+        });
+        await vscode.env.clipboard.writeText(text);
+    };
+}
+
+export function copyHighlightedHtml(ctx: CtxInit): Cmd {
+    return copyHighlighted(ctx, "html");
+}
+
+export function copyMarkdown(ctx: CtxInit): Cmd {
+    return copyHighlighted(ctx, "markdown");
+}
+
+export function macroExpansionCosts(ctx: CtxInit): Cmd {
+    const tdcp = new (class implements vscode.TextDocumentContentProvider {
+        readonly uri = vscode.Uri.parse("rust-analyzer-macro-costs://macroExpansionCosts");
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+
+        provideTextDocumentContent(
+            _uri: vscode.Uri,
+            ct: vscode.CancellationToken
+        ): vscode.ProviderResult<string> {
+            const rustEditor = ctx.activeRustEditor;
+            if (!rustEditor) return "";
+            const client = ctx.client;
+
+            const params = {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(
+                    rustEditor.document
+                ),
+            };
+            return client.sendRequest(ra.macroExpansionCosts, params, ct);
+        }
+
+        get onDidChange(): vscode.Event<vscode.Uri> {
+            return this.eventEmitter.event;
+        }
+    })();
+
+    ctx.pushExtCleanup(
+        vscode.workspace.registerTextDocumentContentProvider("rust-analyzer-macro-costs", tdcp)
+    );
+
+    return async () => {
+        tdcp.eventEmitter.fire(tdcp.uri);
+        const document = await vscode.workspace.openTextDocument(tdcp.uri);
+        return vscode.window.showTextDocument(document, vscode.ViewColumn.Two, true);
+    };
+}
+
 export function shuffleCrateGraph(ctx: CtxInit): Cmd {
     return async () => {
         return ctx.client.sendRequest(ra.shuffleCrateGraph);
@@ -602,66 +696,66 @@ export function viewItemTree(ctx: CtxInit): Cmd {
     };
 }
 
+function showDotGraph(ctx: CtxInit, viewType: string, title: string, dot: string) {
+    const nodeModulesPath = vscode.Uri.file(path.join(ctx.extensionPath, "node_modules"));
+
+    const panel = vscode.window.createWebviewPanel(viewType, title, vscode.ViewColumn.Two, {
+        enableScripts: true,
+        retainContextWhenHidden: true,
+        localResourceRoots: [nodeModulesPath],
+    });
+    const uri = panel.webview.asWebviewUri(nodeModulesPath);
+
+    const html = `
+        <!DOCTYPE html>
+        <meta charset="utf-8">
+        <head>
+            <style>
+                /* Fill the entire view */
+                html, body { margin:0; padding:0; overflow:hidden }
+                svg { position:fixed; top:0; left:0; height:100%; width:100% }
+
+                /* Disable the graphviz background and fill the polygons */
+                .graph > polygon { display:none; }
+                :is(.node,.edge) polygon { fill: white; }
+
+                /* Invert the line colours for dark themes */
+                body:not(.vscode-light) .edge path { stroke: white; }
+            </style>
+        </head>
+        <body>
+            <script type="text/javascript" src="${uri}/d3/dist/d3.min.js"></script>
+            <script type="text/javascript" src="${uri}/@hpcc-js/wasm/dist/index.min.js"></script>
+            <script type="text/javascript" src="${uri}/d3-graphviz/build/d3-graphviz.min.js"></script>
+            <div id="graph"></div>
+            <script>
+                let graph = d3.select("#graph")
+                              .graphviz()
+                              .fit(true)
+                              .zoomScaleExtent([0.1, Infinity])
+                              .renderDot(\`${dot}\`);
+
+                d3.select(window).on("click", (event) => {
+                    if (event.ctrlKey) {
+                        graph.resetZoom(d3.transition().duration(100));
+                    }
+                });
+            </script>
+        </body>
+        `;
+
+    panel.webview.html = html;
+}
+
 function crateGraph(ctx: CtxInit, full: boolean): Cmd {
     return async () => {
-        const nodeModulesPath = vscode.Uri.file(path.join(ctx.extensionPath, "node_modules"));
-
-        const panel = vscode.window.createWebviewPanel(
-            "rust-analyzer.crate-graph",
-            "rust-analyzer crate graph",
-            vscode.ViewColumn.Two,
-            {
-                enableScripts: true,
-                retainContextWhenHidden: true,
-                localResourceRoots: [nodeModulesPath],
-            }
-        );
         const params = {
             full: full,
+            devDeps: ctx.config.crateGraph.devDependencies,
+            buildDeps: ctx.config.crateGraph.buildDependencies,
         };
-        const client = ctx.client;
-        const dot = await client.sendRequest(ra.viewCrateGraph, params);
-        const uri = panel.webview.asWebviewUri(nodeModulesPath);
-
-        const html = `
-            <!DOCTYPE html>
-            <meta charset="utf-8">
-            <head>
-                <style>
-                    /* Fill the entire view */
-                    html, body { margin:0; padding:0; overflow:hidden }
-                    svg { position:fixed; top:0; left:0; height:100%; width:100% }
-
-                    /* Disable the graphviz background and fill the polygons */
-                    .graph > polygon { display:none; }
-                    :is(.node,.edge) polygon { fill: white; }
-
-                    /* Invert the line colours for dark themes */
-                    body:not(.vscode-light) .edge path { stroke: white; }
-                </style>
-            </head>
-            <body>
-                <script type="text/javascript" src="${uri}/d3/dist/d3.min.js"></script>
-                <script type="text/javascript" src="${uri}/@hpcc-js/wasm/dist/index.min.js"></script>
-                <script type="text/javascript" src="${uri}/d3-graphviz/build/d3-graphviz.min.js"></script>
-                <div id="graph"></div>
-                <script>
-                    let graph = d3.select("#graph")
-                                  .graphviz()
-                                  .fit(true)
-                                  .zoomScaleExtent([0.1, Infinity])
-                                  .renderDot(\`${dot}\`);
-
-                    d3.select(window).on("click", (event) => {
-                        if (event.ctrlKey) {
-                            graph.resetZoom(d3.transition().duration(100));
-                        }
-                    });
-                </script>
-            </body>
-            `;
-
-        panel.webview.html = html;
+        const dot = await ctx.client.sendRequest(ra.viewCrateGraph, params);
+        showDotGraph(ctx, "rust-analyzer.crate-graph", "rust-analyzer crate graph", dot);
     };
 }
 
@@ -673,6 +767,21 @@ export function viewFullCrateGraph(ctx: CtxInit): Cmd {
     return crateGraph(ctx, true);
 }
 
+export function viewModuleTree(ctx: CtxInit): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const params = {
+            textDocument: ctx.client.code2ProtocolConverter.asTextDocumentIdentifier(
+                editor.document
+            ),
+        };
+        const dot = await ctx.client.sendRequest(ra.viewModuleTree, params);
+        showDotGraph(ctx, "rust-analyzer.module-tree", "rust-analyzer module tree", dot);
+    };
+}
+
 // Opens the virtual file that will show the syntax tree
 //
 // The contents of the file come from the `TextDocumentContentProvider`
@@ -728,6 +837,55 @@ export function reloadWorkspace(ctx: CtxInit): Cmd {
     return async () => ctx.client.sendRequest(ra.reloadWorkspace);
 }
 
+export function toggleCargoFeatures(ctx: CtxInit): Cmd {
+    return async () => {
+        const client = ctx.client;
+        const packages = await client.sendRequest(ra.cargoFeatures);
+        const withFeatures = packages.filter((it) => it.features.length > 0);
+        if (withFeatures.length === 0) {
+            await vscode.window.showInformationMessage("No workspace package has features");
+            return;
+        }
+
+        const pkg =
+            withFeatures.length === 1
+                ? withFeatures[0]
+                : (
+                      await vscode.window.showQuickPick(
+                          withFeatures.map((it) => ({
+                              label: it.name,
+                              description: it.manifestPath,
+                              pkg: it,
+                          })),
+                          { placeHolder: "Select the package" }
+                      )
+                  )?.pkg;
+        if (!pkg) return;
+
+        const selected = await vscode.window.showQuickPick(
+            pkg.features.map((it) => ({
+                label: it.name,
+                description: it.implies.join(", "),
+                picked: it.enabled,
+                feature: it,
+            })),
+            { canPickMany: true, placeHolder: `Features of ${pkg.name}` }
+        );
+        if (!selected) return;
+
+        const enabled = new Set(selected.map((it) => it.feature.name));
+        for (const feature of pkg.features) {
+            if (feature.enabled !== enabled.has(feature.name)) {
+                await client.sendRequest(ra.setCargoFeature, {
+                    package: pkg.package,
+                    feature: feature.name,
+                    enabled: !feature.enabled,
+                });
+            }
+        }
+    };
+}
+
 async function showReferencesImpl(
     client: LanguageClient | undefined,
     uri: string,
@@ -784,10 +942,25 @@ export function openDocs(ctx: CtxInit): Cmd {
         const position = editor.selection.active;
         const textDocument = { uri: editor.document.uri.toString() };
 
-        const doclink = await client.sendRequest(ra.openDocs, { position, textDocument });
+        const docLinks = await client.sendRequest(ra.openDocs, { position, textDocument });
+
+        let fileType = vscode.FileType.Unknown;
+        if (typeof docLinks === "object" && docLinks.local !== undefined) {
+            try {
+                fileType = (await vscode.workspace.fs.stat(vscode.Uri.parse(docLinks.local)))
+                    .type;
+            } catch (e) {
+                log.debug("stat() threw error. Falling back to web version", e);
+            }
+        }
+
+        let docLink = typeof docLinks === "string" ? docLinks : docLinks?.web;
+        if (typeof docLinks === "object" && fileType & vscode.FileType.File) {
+            docLink = docLinks.local;
+        }
 
-        if (doclink != null) {
-            await vscode.commands.executeCommand("vscode.open", vscode.Uri.parse(doclink));
+        if (docLink) {
+            await vscode.commands.executeCommand("vscode.open", vscode.Uri.parse(docLink));
         }
     };
 }
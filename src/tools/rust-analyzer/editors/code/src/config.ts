@@ -244,6 +244,13 @@ export class Config {
         return this.get<boolean>("typing.continueCommentsOnNewline");
     }
 
+    get crateGraph() {
+        return {
+            devDependencies: this.get<boolean>("crateGraph.devDependencies"),
+            buildDependencies: this.get<boolean>("crateGraph.buildDependencies"),
+        };
+    }
+
     get debug() {
         let sourceFileMap = this.get<Record<string, string> | "auto">("debug.sourceFileMap");
         if (sourceFileMap !== "auto") {
@@ -33,16 +33,35 @@ export const analyzerStatus = new lc.RequestType<AnalyzerStatusParams, string, v
     "rust-analyzer/analyzerStatus"
 );
 export const cancelFlycheck = new lc.NotificationType0("rust-analyzer/cancelFlycheck");
+export const cargoFeatures = new lc.RequestType0<PackageFeatures[], void>(
+    "rust-analyzer/cargoFeatures"
+);
 export const clearFlycheck = new lc.NotificationType0("rust-analyzer/clearFlycheck");
 export const expandMacro = new lc.RequestType<ExpandMacroParams, ExpandedMacro | null, void>(
     "rust-analyzer/expandMacro"
 );
+export const deadCode = new lc.RequestType0<DeadCodeCrate[], void>("rust-analyzer/deadCode");
+export const evaluateExpression = new lc.RequestType<EvaluateExpressionParams, string, void>(
+    "rust-analyzer/evaluateExpression"
+);
+export const exportHighlighted = new lc.RequestType<ExportHighlightedParams, string, void>(
+    "rust-analyzer/exportHighlighted"
+);
+export const macroExpansionCosts = new lc.RequestType<MacroExpansionCostsParams, string, void>(
+    "rust-analyzer/macroExpansionCosts"
+);
 export const memoryUsage = new lc.RequestType0<string, void>("rust-analyzer/memoryUsage");
 export const openServerLogs = new lc.NotificationType0("rust-analyzer/openServerLogs");
 export const relatedTests = new lc.RequestType<lc.TextDocumentPositionParams, TestInfo[], void>(
     "rust-analyzer/relatedTests"
 );
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
+export const requestTrace = new lc.RequestType<RequestTraceParams, string, void>(
+    "rust-analyzer/requestTrace"
+);
+export const setCargoFeature = new lc.RequestType<SetCargoFeatureParams, PackageFeatures, void>(
+    "rust-analyzer/setCargoFeature"
+);
 export const runFlycheck = new lc.NotificationType<{
     textDocument: lc.TextDocumentIdentifier | null;
 }>("rust-analyzer/runFlycheck");
@@ -62,12 +81,27 @@ export const viewHir = new lc.RequestType<lc.TextDocumentPositionParams, string,
 export const viewItemTree = new lc.RequestType<ViewItemTreeParams, string, void>(
     "rust-analyzer/viewItemTree"
 );
+export const viewModuleTree = new lc.RequestType<ViewModuleTreeParams, string, void>(
+    "rust-analyzer/viewModuleTree"
+);
 
 export type AnalyzerStatusParams = { textDocument?: lc.TextDocumentIdentifier };
 
+export type ExternalDocsResponse = string | void | { web?: string; local?: string };
+export type CargoFeature = { name: string; enabled: boolean; implies: string[] };
+export type PackageFeatures = {
+    package: string;
+    name: string;
+    manifestPath: string;
+    features: CargoFeature[];
+};
+export type SetCargoFeatureParams = { package: string; feature: string; enabled: boolean };
+export type RequestTraceParams = { enable?: boolean; chromeTracePath?: string };
 export type ExpandMacroParams = {
     textDocument: lc.TextDocumentIdentifier;
     position: lc.Position;
+    depth?: number;
+    annotateHygiene?: boolean;
 };
 export type ExpandedMacro = {
     name: string;
@@ -78,11 +112,46 @@ export type SyntaxTreeParams = {
     textDocument: lc.TextDocumentIdentifier;
     range: lc.Range | null;
 };
-export type ViewCrateGraphParams = { full: boolean };
+export type ViewCrateGraphParams = { full: boolean; devDeps?: boolean; buildDeps?: boolean };
+export type DeadCodeCrate = {
+    name: string;
+    items: DeadCodeItem[];
+};
+export type DeadCodeItem = {
+    path: string;
+    isPublic: boolean;
+    location: lc.Location;
+};
+export type EvaluateExpressionParams = {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+};
+export type ExportHighlightedParams = {
+    textDocument: lc.TextDocumentIdentifier;
+    range?: lc.Range;
+    format: "html" | "markdown";
+    bindingTypes?: boolean;
+};
+export type MacroExpansionCostsParams = { textDocument: lc.TextDocumentIdentifier };
 export type ViewItemTreeParams = { textDocument: lc.TextDocumentIdentifier };
+export type ViewModuleTreeParams = { textDocument: lc.TextDocumentIdentifier };
 
 // experimental extensions
 
+export const abortRunTest = new lc.NotificationType0("experimental/abortRunTest");
+export const appendOutputToRunTest = new lc.NotificationType<string>(
+    "experimental/appendOutputToRunTest"
+);
+export const changeTestState = new lc.NotificationType<ChangeTestStateParams>(
+    "experimental/changeTestState"
+);
+export const discoverTest = new lc.RequestType<DiscoverTestParams, DiscoverTestResults, void>(
+    "experimental/discoverTest"
+);
+export const discoveredTests = new lc.NotificationType<DiscoverTestResults>(
+    "experimental/discoveredTests"
+);
+export const endRunTest = new lc.NotificationType0("experimental/endRunTest");
 export const joinLines = new lc.RequestType<JoinLinesParams, lc.TextEdit[], void>(
     "experimental/joinLines"
 );
@@ -98,22 +167,31 @@ export const onEnter = new lc.RequestType<lc.TextDocumentPositionParams, lc.Text
 export const openCargoToml = new lc.RequestType<OpenCargoTomlParams, lc.Location, void>(
     "experimental/openCargoToml"
 );
-export const openDocs = new lc.RequestType<lc.TextDocumentPositionParams, string | void, void>(
-    "experimental/externalDocs"
-);
+export const openDocs = new lc.RequestType<
+    lc.TextDocumentPositionParams,
+    ExternalDocsResponse,
+    void
+>("experimental/externalDocs");
 export const parentModule = new lc.RequestType<
     lc.TextDocumentPositionParams,
     lc.LocationLink[] | null,
     void
 >("experimental/parentModule");
+export const peekRelated = new lc.RequestType<PeekRelatedParams, PeekRelatedResult | null, void>(
+    "experimental/peekRelated"
+);
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>(
     "experimental/runnables"
 );
+export const runTest = new lc.RequestType<RunTestParams, void, void>("experimental/runTest");
 export const serverStatus = new lc.NotificationType<ServerStatusParams>(
     "experimental/serverStatus"
 );
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>("experimental/ssr");
 
+export type ChangeTestStateParams = { testId: string; state: TestState };
+export type DiscoverTestParams = { testId?: string | undefined };
+export type DiscoverTestResults = { tests: TestItem[]; scope: string[] };
 export type JoinLinesParams = {
     textDocument: lc.TextDocumentIdentifier;
     ranges: lc.Range[];
@@ -131,6 +209,18 @@ export type Direction = "Up" | "Down";
 export type OpenCargoTomlParams = {
     textDocument: lc.TextDocumentIdentifier;
 };
+export type PeekRelatedParams = lc.TextDocumentPositionParams & {
+    implementationsLimit?: number;
+    referencesLimit?: number;
+};
+export type PeekRelatedResult = {
+    definitions: lc.Location[];
+    declarations: lc.Location[];
+    implementations: lc.Location[];
+    implementationsTotal: number;
+    references: lc.Location[];
+    referencesTotal: number;
+};
 export type Runnable = {
     label: string;
     location?: lc.LocationLink;
@@ -142,12 +232,17 @@ export type Runnable = {
         executableArgs: string[];
         expectTest?: boolean;
         overrideCargo?: string;
+        environment?: Record<string, string>;
     };
 };
 export type RunnablesParams = {
     textDocument: lc.TextDocumentIdentifier;
     position: lc.Position | null;
 };
+export type RunTestParams = {
+    include?: string[] | undefined;
+    exclude?: string[] | undefined;
+};
 export type ServerStatusParams = {
     health: "ok" | "warning" | "error";
     quiescent: boolean;
@@ -160,3 +255,19 @@ export type SsrParams = {
     position: lc.Position;
     selections: readonly lc.Range[];
 };
+export type TestItemKind = "package" | "module" | "test";
+export type TestItem = {
+    id: string;
+    label: string;
+    kind: TestItemKind;
+    canResolveChildren: boolean;
+    parent?: string | undefined;
+    textDocument?: lc.TextDocumentIdentifier | undefined;
+    range?: lc.Range | undefined;
+    runnable?: Runnable | undefined;
+};
+export type TestState =
+    | { tag: "failed"; message: string }
+    | { tag: "passed" }
+    | { tag: "started" }
+    | { tag: "skipped" };
@@ -151,8 +151,14 @@ function createCommands(): Record<string, CommandFactory> {
 
         analyzerStatus: { enabled: commands.analyzerStatus },
         memoryUsage: { enabled: commands.memoryUsage },
+        deadCode: { enabled: commands.deadCode },
+        evaluateExpression: { enabled: commands.evaluateExpression },
+        copyHighlightedHtml: { enabled: commands.copyHighlightedHtml },
+        copyMarkdown: { enabled: commands.copyMarkdown },
+        macroExpansionCosts: { enabled: commands.macroExpansionCosts },
         shuffleCrateGraph: { enabled: commands.shuffleCrateGraph },
         reloadWorkspace: { enabled: commands.reloadWorkspace },
+        toggleCargoFeatures: { enabled: commands.toggleCargoFeatures },
         matchingBrace: { enabled: commands.matchingBrace },
         joinLines: { enabled: commands.joinLines },
         parentModule: { enabled: commands.parentModule },
@@ -162,6 +168,7 @@ function createCommands(): Record<string, CommandFactory> {
         viewItemTree: { enabled: commands.viewItemTree },
         viewCrateGraph: { enabled: commands.viewCrateGraph },
         viewFullCrateGraph: { enabled: commands.viewFullCrateGraph },
+        viewModuleTree: { enabled: commands.viewModuleTree },
         expandMacro: { enabled: commands.expandMacro },
         run: { enabled: commands.run },
         copyRunCommandLine: { enabled: commands.copyRunCommandLine },
@@ -122,6 +122,10 @@ export function prepareEnv(
         }
     }
 
+    if (runnable.args.environment) {
+        Object.assign(env, runnable.args.environment);
+    }
+
     return env;
 }
 